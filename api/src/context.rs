@@ -42,7 +42,7 @@ use aptos_types::{
     state_store::{
         state_key::{inner::StateKeyInner, prefix::StateKeyPrefix, StateKey},
         state_value::StateValue,
-        TStateView,
+        StateView, TStateView,
     },
     transaction::{
         block_epilogue::BlockEndInfo,
@@ -68,6 +68,7 @@ use std::{
     },
     time::Instant,
 };
+use tokio::sync::watch;
 
 // Context holds application scope context
 #[derive(Clone)]
@@ -83,6 +84,8 @@ pub struct Context {
     simulate_txn_stats: Arc<FunctionStats>,
     pub indexer_reader: Option<Arc<dyn IndexerReader>>,
     pub wait_for_hash_active_connections: Arc<AtomicUsize>,
+    pub stream_active_connections: Arc<AtomicUsize>,
+    committed_version_receiver: Option<watch::Receiver<Version>>,
 }
 
 impl std::fmt::Debug for Context {
@@ -135,9 +138,21 @@ impl Context {
             simulate_txn_stats,
             indexer_reader,
             wait_for_hash_active_connections: Arc::new(AtomicUsize::new(0)),
+            stream_active_connections: Arc::new(AtomicUsize::new(0)),
+            committed_version_receiver: None,
         }
     }
 
+    /// Sets the receiver used to notify streaming clients of newly committed versions
+    pub fn set_committed_version_receiver(&mut self, receiver: watch::Receiver<Version>) {
+        self.committed_version_receiver = Some(receiver);
+    }
+
+    /// Returns a receiver for newly committed versions (if one has been set)
+    pub fn committed_version_receiver(&self) -> Option<watch::Receiver<Version>> {
+        self.committed_version_receiver.clone()
+    }
+
     pub fn max_transactions_page_size(&self) -> u16 {
         self.node_config.api.max_transactions_page_size
     }
@@ -154,6 +169,10 @@ impl Context {
         self.node_config.api.max_account_modules_page_size
     }
 
+    pub fn max_resource_history_page_size(&self) -> u16 {
+        self.node_config.api.max_resource_history_page_size
+    }
+
     pub fn latest_state_view(&self) -> Result<DbStateView> {
         Ok(self.db.latest_state_checkpoint_view()?)
     }
@@ -778,6 +797,21 @@ impl Context {
         }
 
         let state_view = self.latest_state_view_poem(ledger_info)?;
+        self.render_transactions_non_sequential_with_state_view(ledger_info, &state_view, data)
+    }
+
+    /// Renders the given transactions using the given state view (e.g., a state
+    /// view that contains state that has not been committed to storage).
+    pub fn render_transactions_non_sequential_with_state_view<E: InternalError>(
+        &self,
+        ledger_info: &LedgerInfo,
+        state_view: &impl StateView,
+        data: Vec<TransactionOnChainData>,
+    ) -> Result<Vec<aptos_api_types::Transaction>, E> {
+        if data.is_empty() {
+            return Ok(vec![]);
+        }
+
         let converter = state_view.as_converter(self.db.clone(), self.indexer_reader.clone());
         let txns: Vec<aptos_api_types::Transaction> = data
             .into_iter()
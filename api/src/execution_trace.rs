@@ -0,0 +1,52 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_api_types::{
+    ExecutionTrace, ExecutionTraceAbort, ExecutionTraceCall, ExecutionTraceEvent,
+};
+use aptos_gas_profiling::{AbortTrace, CallTrace, EventTrace, TransactionGasLog};
+use aptos_types::transaction::ExecutionStatus;
+
+/// Builds the API execution trace from the gas log recorded by the gas profiler
+pub fn create_execution_trace(
+    gas_log: &TransactionGasLog,
+    status: &ExecutionStatus,
+) -> ExecutionTrace {
+    let trace = aptos_gas_profiling::ExecutionTrace::new(gas_log, status);
+    ExecutionTrace {
+        root: convert_call_trace(trace.root),
+        events: trace.events.into_iter().map(convert_event_trace).collect(),
+        abort: trace.abort.map(convert_abort_trace),
+    }
+}
+
+fn convert_call_trace(call: CallTrace) -> ExecutionTraceCall {
+    ExecutionTraceCall {
+        function: call.function,
+        type_arguments: call.type_args,
+        arguments: call.args,
+        return_values: call.return_values,
+        is_native: call.is_native,
+        returned: call.returned,
+        execution_gas: call.execution_gas.into(),
+        calls: call.calls.into_iter().map(convert_call_trace).collect(),
+    }
+}
+
+fn convert_event_trace(event: EventTrace) -> ExecutionTraceEvent {
+    ExecutionTraceEvent {
+        event_type: event.event_type,
+        emitted_by: event.emitted_by,
+        data: event.data,
+    }
+}
+
+fn convert_abort_trace(abort: AbortTrace) -> ExecutionTraceAbort {
+    ExecutionTraceAbort {
+        location: abort.location,
+        function: abort.function,
+        code: abort.code.map(Into::into),
+        code_offset: abort.code_offset,
+        reason: abort.reason,
+    }
+}
@@ -13,6 +13,7 @@ mod check_size;
 pub mod context;
 mod error_converter;
 mod events;
+mod execution_trace;
 mod failpoint;
 mod index;
 mod log;
@@ -23,6 +24,8 @@ mod runtime;
 mod set_failpoints;
 pub mod spec;
 mod state;
+mod state_overrides;
+mod stream;
 #[cfg(test)]
 pub mod tests;
 mod transactions;
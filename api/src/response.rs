@@ -518,6 +518,17 @@ macro_rules! generate_success_response {
                 self
             }
 
+            pub fn with_version_cursor(mut self, new_cursor: Option<u64>) -> Self {
+                match self {
+                    $(
+                    [<$enum_name>]::$name(_, _, _, _, _, _, _, _, _, ref mut cursor) => {
+                        *cursor = new_cursor.map(|c| c.to_string());
+                    }
+                    )*
+                }
+                self
+            }
+
             pub fn with_gas_used(mut self, new_gas_used: Option<u64>) -> Self {
                 match self {
                     $(
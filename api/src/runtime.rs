@@ -15,16 +15,18 @@ use crate::{
     set_failpoints,
     spec::{spec_endpoint_json, spec_endpoint_yaml},
     state::StateApi,
+    stream::StreamApi,
     transactions::TransactionsApi,
     view_function::ViewFunctionApi,
 };
 use anyhow::{anyhow, Context as AnyhowContext};
 use aptos_config::config::{ApiConfig, NodeConfig};
+use aptos_event_notifications::CommitNotificationListener;
 use aptos_logger::info;
 use aptos_mempool::MempoolClientSender;
 use aptos_storage_interface::DbReader;
 use aptos_types::{chain_id::ChainId, indexer::indexer_db_reader::IndexerReader};
-use futures::channel::oneshot;
+use futures::{channel::oneshot, StreamExt};
 use poem::{
     handler,
     http::Method,
@@ -35,7 +37,10 @@ use poem::{
 };
 use poem_openapi::{ContactObject, LicenseObject, OpenApiService};
 use std::{net::SocketAddr, sync::Arc};
-use tokio::runtime::{Handle, Runtime};
+use tokio::{
+    runtime::{Handle, Runtime},
+    sync::watch,
+};
 
 const VERSION: &str = include_str!("../doc/.version");
 
@@ -47,11 +52,24 @@ pub fn bootstrap(
     mp_sender: MempoolClientSender,
     indexer_reader: Option<Arc<dyn IndexerReader>>,
     port_tx: Option<oneshot::Sender<u16>>,
+    commit_notification_listener: Option<CommitNotificationListener>,
 ) -> anyhow::Result<Runtime> {
     let max_runtime_workers = get_max_runtime_workers(&config.api);
     let runtime = aptos_runtimes::spawn_named_runtime("api".into(), Some(max_runtime_workers));
 
-    let context = Context::new(chain_id, db, mp_sender, config.clone(), indexer_reader);
+    let mut context = Context::new(chain_id, db, mp_sender, config.clone(), indexer_reader);
+
+    // Forward commit notifications to the streaming endpoints (if enabled)
+    if let Some(mut commit_notification_listener) = commit_notification_listener {
+        let synced_version = context.db.get_synced_version()?.unwrap_or(0);
+        let (committed_version_sender, committed_version_receiver) = watch::channel(synced_version);
+        context.set_committed_version_receiver(committed_version_receiver);
+        runtime.spawn(async move {
+            while let Some(commit_notification) = commit_notification_listener.next().await {
+                committed_version_sender.send_replace(commit_notification.version);
+            }
+        });
+    }
 
     attach_poem_to_runtime(runtime.handle(), context.clone(), config, false, port_tx)
         .context("Failed to attach poem to runtime")?;
@@ -117,6 +135,7 @@ pub fn get_api_service(
         EventsApi,
         IndexApi,
         StateApi,
+        StreamApi,
         TransactionsApi,
         ViewFunctionApi,
     ),
@@ -142,6 +161,9 @@ pub fn get_api_service(
         StateApi {
             context: context.clone(),
         },
+        StreamApi {
+            context: context.clone(),
+        },
         TransactionsApi {
             context: context.clone(),
         },
@@ -5,18 +5,20 @@ use crate::{
     accept_type::AcceptType,
     context::api_spawn_blocking,
     failpoint::fail_point_poem,
+    page::Page,
     response::{
         api_forbidden, build_not_found, module_not_found, resource_not_found, table_item_not_found,
-        BadRequestError, BasicErrorWith404, BasicResponse, BasicResponseStatus, BasicResultWith404,
-        InternalError,
+        version_not_found, version_pruned, BadRequestError, BasicErrorWith404, BasicResponse,
+        BasicResponseStatus, BasicResultWith404, InternalError,
     },
     ApiTags, Context,
 };
 use anyhow::Context as AnyhowContext;
 use aptos_api_types::{
     verify_module_identifier, Address, AptosErrorCode, AsConverter, IdentifierWrapper,
-    MoveModuleBytecode, MoveResource, MoveStructTag, MoveValue, RawStateValueRequest,
-    RawTableItemRequest, TableItemRequest, VerifyInput, VerifyInputWithRecursion, U64,
+    MoveModuleBytecode, MoveResource, MoveResourceChange, MoveStructTag, MoveValue,
+    RawStateValueRequest, RawTableItemRequest, TableItemRequest, VerifyInput,
+    VerifyInputWithRecursion, U64,
 };
 use aptos_types::state_store::{state_key::StateKey, table::TableHandle, TStateView};
 use move_core_types::language_storage::StructTag;
@@ -83,6 +85,74 @@ impl StateApi {
         .await
     }
 
+    /// Get account resource history
+    ///
+    /// Retrieves the history of changes to an individual resource from a given account, i.e.,
+    /// the ledger versions at which the resource was created, modified or deleted, and the
+    /// resource after each change. Versions are scanned in ascending order, from the `start`
+    /// version up to the given ledger version. If the ledger version is not specified in the
+    /// request, the latest ledger version is used.
+    ///
+    /// Each request scans a bounded number of versions. If the scan ends before the ledger
+    /// version, the next version to scan is returned in the X-Aptos-Cursor header.
+    ///
+    /// The Aptos nodes prune transaction history, via a configurable time window.
+    /// If the requested start version has been pruned, the server responds with a 410.
+    #[oai(
+        path = "/accounts/:address/resource/:resource_type/history",
+        method = "get",
+        operation_id = "get_account_resource_history",
+        tag = "ApiTags::Accounts"
+    )]
+    async fn get_account_resource_history(
+        &self,
+        accept_type: AcceptType,
+        /// Address of account with or without a `0x` prefix
+        address: Path<Address>,
+        /// Name of struct to retrieve e.g. `0x1::account::Account`
+        resource_type: Path<MoveStructTag>,
+        /// Ledger version to start scanning for changes from
+        ///
+        /// If not provided, it will be the oldest non-pruned version. To get the next
+        /// page of results, use the cursor returned in the X-Aptos-Cursor header.
+        start: Query<Option<U64>>,
+        /// Max number of resource changes to retrieve
+        ///
+        /// If not provided, defaults to default page size.
+        limit: Query<Option<u16>>,
+        /// Ledger version to stop scanning for changes at (inclusive)
+        ///
+        /// If not provided, it will be the latest version
+        ledger_version: Query<Option<U64>>,
+    ) -> BasicResultWith404<Vec<MoveResourceChange>> {
+        resource_type
+            .0
+            .verify(0)
+            .context("'resource_type' invalid")
+            .map_err(|err| {
+                BasicErrorWith404::bad_request_with_code_no_info(err, AptosErrorCode::InvalidInput)
+            })?;
+        fail_point_poem("endpoint_get_account_resource_history")?;
+        self.context
+            .check_api_output_enabled("Get account resource history", &accept_type)?;
+
+        let api = self.clone();
+        api_spawn_blocking(move || {
+            api.resource_history(
+                &accept_type,
+                address.0,
+                resource_type.0,
+                Page::new(
+                    start.0.map(|inner| inner.0),
+                    limit.0,
+                    api.context.max_resource_history_page_size(),
+                ),
+                ledger_version.0.map(|inner| inner.0),
+            )
+        })
+        .await
+    }
+
     /// Get account module
     ///
     /// Retrieves an individual module from a given account and at a specific ledger version. If the
@@ -327,6 +397,173 @@ impl StateApi {
         }
     }
 
+    /// Read the history of changes to a resource, by scanning the write sets
+    /// of committed transactions (starting at the page start version).
+    ///
+    /// JSON: Convert each change to a MoveResourceChange
+    /// BCS: Leave each resource encoded, i.e., (version, Option<resource bytes>)
+    fn resource_history(
+        &self,
+        accept_type: &AcceptType,
+        address: Address,
+        resource_type: MoveStructTag,
+        page: Page,
+        ledger_version: Option<u64>,
+    ) -> BasicResultWith404<Vec<MoveResourceChange>> {
+        let tag: StructTag = (&resource_type)
+            .try_into()
+            .context("Failed to parse given resource type")
+            .map_err(|err| {
+                BasicErrorWith404::bad_request_with_code_no_info(err, AptosErrorCode::InvalidInput)
+            })?;
+
+        let (ledger_info, ledger_version, state_view) = self.context.state_view(ledger_version)?;
+        let limit = page.limit(&ledger_info)?;
+
+        // Verify the start version of the scan
+        let oldest_version = ledger_info.oldest_ledger_version.0;
+        let start_version = page.start_option().unwrap_or(oldest_version);
+        if start_version < oldest_version {
+            return Err(version_pruned(start_version, &ledger_info));
+        }
+        if start_version > ledger_version {
+            return Err(version_not_found(start_version, &ledger_info));
+        }
+
+        // Identify the state key that holds the resource
+        let converter =
+            state_view.as_converter(self.context.db.clone(), self.context.indexer_reader.clone());
+        let (state_key, is_group_member) = converter
+            .find_resource_state_key(address, &tag)
+            .context("Failed to identify the state key for the resource")
+            .map_err(|err| {
+                BasicErrorWith404::internal_with_code(
+                    err,
+                    AptosErrorCode::InternalError,
+                    &ledger_info,
+                )
+            })?;
+
+        // A resource group can be written without the resource changing, so we
+        // track the previous resource to only report actual changes. If the
+        // previous resource is unavailable (e.g., pruned), the first write is reported.
+        let mut previous_resource = if is_group_member && start_version > 0 {
+            self.context
+                .db
+                .get_state_value_by_version(&state_key, start_version - 1)
+                .ok()
+                .and_then(|state_value| match state_value {
+                    Some(state_value) => converter
+                        .find_resource_group_member(&tag, state_value.bytes())
+                        .ok(),
+                    None => Some(None),
+                })
+        } else {
+            None
+        };
+
+        // Scan the write sets for changes to the resource
+        let scan_size = std::cmp::min(
+            self.context.node_config.api.max_resource_history_scan_size,
+            ledger_version - start_version + 1,
+        );
+        let write_sets = self
+            .context
+            .db
+            .get_write_set_iterator(start_version, scan_size)
+            .context("Failed to read write sets from DB")
+            .map_err(|err| {
+                BasicErrorWith404::internal_with_code(
+                    err,
+                    AptosErrorCode::InternalError,
+                    &ledger_info,
+                )
+            })?;
+        let mut changes = vec![];
+        let mut next_version = start_version;
+        for write_set in write_sets {
+            let write_set = write_set
+                .context("Failed to read write set from DB")
+                .map_err(|err| {
+                    BasicErrorWith404::internal_with_code(
+                        err,
+                        AptosErrorCode::InternalError,
+                        &ledger_info,
+                    )
+                })?;
+            let version = next_version;
+            next_version += 1;
+
+            let write_op = match write_set.get_write_op(&state_key) {
+                Some(write_op) => write_op,
+                None => continue,
+            };
+            let resource = match write_op.bytes() {
+                Some(bytes) if is_group_member => converter
+                    .find_resource_group_member(&tag, bytes)
+                    .context("Failed to deserialize resource group from DB")
+                    .map_err(|err| {
+                        BasicErrorWith404::internal_with_code(
+                            err,
+                            AptosErrorCode::InternalError,
+                            &ledger_info,
+                        )
+                    })?,
+                bytes => bytes.cloned(),
+            };
+            if previous_resource.as_ref() != Some(&resource) {
+                changes.push((version, resource.clone()));
+                previous_resource = Some(resource);
+            }
+            if changes.len() >= limit as usize {
+                break;
+            }
+        }
+
+        // If the scan ended early, return the next version to scan as the cursor
+        let cursor = if next_version <= ledger_version {
+            Some(next_version)
+        } else {
+            None
+        };
+
+        match accept_type {
+            AcceptType::Json => {
+                let changes = changes
+                    .into_iter()
+                    .map(|(version, resource)| {
+                        let resource = resource
+                            .map(|bytes| converter.try_into_resource(&tag, &bytes))
+                            .transpose()?;
+                        Ok(MoveResourceChange {
+                            version: version.into(),
+                            resource,
+                        })
+                    })
+                    .collect::<anyhow::Result<Vec<_>>>()
+                    .context("Failed to deserialize resource data retrieved from DB")
+                    .map_err(|err| {
+                        BasicErrorWith404::internal_with_code(
+                            err,
+                            AptosErrorCode::InternalError,
+                            &ledger_info,
+                        )
+                    })?;
+
+                BasicResponse::try_from_json((changes, &ledger_info, BasicResponseStatus::Ok))
+                    .map(|response| response.with_version_cursor(cursor))
+            },
+            AcceptType::Bcs => {
+                let changes: Vec<(u64, Option<Vec<u8>>)> = changes
+                    .into_iter()
+                    .map(|(version, resource)| (version, resource.map(|bytes| bytes.to_vec())))
+                    .collect();
+                BasicResponse::try_from_bcs((changes, &ledger_info, BasicResponseStatus::Ok))
+                    .map(|response| response.with_version_cursor(cursor))
+            },
+        }
+    }
+
     /// Retrieve the module
     ///
     /// JSON: Parse ABI and bytecode
@@ -0,0 +1,113 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::context::Context;
+use anyhow::{anyhow, Context as AnyhowContext};
+use aptos_api_types::{AsConverter, ResourceOverride, StateOverrides};
+use aptos_storage_interface::state_store::state_view::db_state_view::DbStateView;
+use aptos_transaction_simulation::{DeltaStateStore, SimulationStateStore};
+use aptos_types::{
+    on_chain_config::CurrentTimeMicroseconds,
+    state_store::{state_value::StateValue, TStateView},
+};
+use bytes::Bytes;
+use move_binary_format::CompiledModule;
+use move_core_types::language_storage::{StructTag, TypeTag};
+use std::collections::BTreeMap;
+
+/// A state view with state overrides applied on top of the on-chain state
+pub type StateViewWithOverrides = DeltaStateStore<DbStateView>;
+
+/// Creates a state view that applies the given state overrides on top of the given state
+/// view. Modules are injected first, so that resource overrides can use the injected types.
+pub fn create_state_view_with_overrides(
+    context: &Context,
+    state_view: DbStateView,
+    state_overrides: &StateOverrides,
+) -> anyhow::Result<StateViewWithOverrides> {
+    let state_store = DeltaStateStore::new_with_base(state_view);
+
+    // Inject the module overrides
+    for module_bytes in &state_overrides.modules {
+        let module = CompiledModule::deserialize(module_bytes.inner())
+            .context("Failed to deserialize module override")?;
+        state_store.add_module_blob(&module.self_id(), module_bytes.inner().to_vec())?;
+    }
+
+    // Apply the resource overrides
+    for resource_override in &state_overrides.resources {
+        apply_resource_override(context, &state_store, resource_override).with_context(|| {
+            format!(
+                "Failed to override resource {} at {}",
+                resource_override.resource_type, resource_override.address
+            )
+        })?;
+    }
+
+    // Apply the balance overrides
+    for balance_override in &state_overrides.balances {
+        state_store.set_apt_balance(balance_override.address.into(), balance_override.amount.0)?;
+    }
+
+    // Apply the timestamp override
+    if let Some(timestamp_usecs) = state_overrides.timestamp_usecs {
+        state_store.set_on_chain_config(&CurrentTimeMicroseconds {
+            microseconds: timestamp_usecs.0,
+        })?;
+    }
+
+    Ok(state_store)
+}
+
+/// Replaces (or deletes) the resource in the state store. If the resource
+/// is a member of a resource group, only the member in the group is replaced.
+fn apply_resource_override(
+    context: &Context,
+    state_store: &StateViewWithOverrides,
+    resource_override: &ResourceOverride,
+) -> anyhow::Result<()> {
+    let tag: StructTag = (&resource_override.resource_type).try_into()?;
+    let converter = state_store.as_converter(context.db.clone(), context.indexer_reader.clone());
+
+    // Serialize the new resource (if any)
+    let resource_bytes: Option<Bytes> = match &resource_override.data {
+        Some(data) => {
+            let value = converter
+                .try_into_vm_value(&TypeTag::Struct(Box::new(tag.clone())), data.clone())
+                .context("Failed to convert the resource data")?;
+            let bytes = value
+                .simple_serialize()
+                .ok_or_else(|| anyhow!("Failed to serialize the resource data"))?;
+            Some(bytes.into())
+        },
+        None => None,
+    };
+
+    let (state_key, is_group_member) =
+        converter.find_resource_state_key(resource_override.address, &tag)?;
+    let state_bytes = if is_group_member {
+        let mut group: BTreeMap<StructTag, Bytes> =
+            match state_store.get_state_value_bytes(&state_key)? {
+                Some(group_bytes) => bcs::from_bytes(&group_bytes)?,
+                None => BTreeMap::new(),
+            };
+        match resource_bytes {
+            Some(resource_bytes) => group.insert(tag, resource_bytes),
+            None => group.remove(&tag),
+        };
+        if group.is_empty() {
+            None
+        } else {
+            Some(bcs::to_bytes(&group)?.into())
+        }
+    } else {
+        resource_bytes
+    };
+
+    match state_bytes {
+        Some(state_bytes) => {
+            state_store.set_state_value(state_key, StateValue::new_legacy(state_bytes))
+        },
+        None => state_store.remove_state_value(&state_key),
+    }
+}
@@ -0,0 +1,548 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    context::{api_spawn_blocking, Context},
+    failpoint::fail_point_poem,
+    response::{api_disabled, version_pruned, BasicError, BasicErrorWith404},
+    ApiTags,
+};
+use anyhow::Context as AnyhowContext;
+use aptos_api_types::{
+    Address, AptosErrorCode, AsConverter, EntryFunctionId, LedgerInfo, MoveStructTag, Transaction,
+    TransactionOnChainData, VersionedEvent, U64,
+};
+use aptos_infallible::Mutex;
+use aptos_logger::warn;
+use aptos_types::{
+    contract_event::EventWithVersion,
+    language_storage::TypeTag,
+    transaction::{TransactionExecutableRef, Version},
+};
+use futures::stream::{self, BoxStream, StreamExt};
+use poem::web::sse::Event;
+use poem_openapi::{
+    param::{Header, Query},
+    payload::EventStream,
+    OpenApi,
+};
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::sync::watch;
+
+/// The interval at which keep-alive messages are sent to idle streams
+const STREAM_KEEP_ALIVE_INTERVAL_SECS: u64 = 15;
+
+/// API for streaming committed transactions and events
+#[derive(Clone)]
+pub struct StreamApi {
+    pub context: Arc<Context>,
+}
+
+#[OpenApi]
+impl StreamApi {
+    /// Stream committed transactions
+    ///
+    /// Streams committed transactions (as server-sent events) starting at the
+    /// given version, and continues to stream new transactions as they are
+    /// committed. Each event id is the transaction version, so clients can resume
+    /// a dropped stream using the `Last-Event-ID` header (or `start_version`).
+    ///
+    /// If a filter is provided, only matching user transactions are streamed.
+    /// The entry function filter also matches multisig and (new format)
+    /// payloads that execute the entry function.
+    #[oai(
+        path = "/stream/transactions",
+        method = "get",
+        operation_id = "stream_transactions",
+        tag = "ApiTags::Experimental"
+    )]
+    async fn stream_transactions(
+        &self,
+        /// Ledger version to start streaming from (defaults to the next committed version)
+        start_version: Query<Option<U64>>,
+        /// Only stream transactions sent by this account
+        sender: Query<Option<Address>>,
+        /// Only stream transactions calling this entry function, e.g., `0x1::coin::transfer`
+        entry_function: Query<Option<EntryFunctionId>>,
+        /// The id of the last event received (i.e., the stream resumes at the next version)
+        #[oai(name = "Last-Event-ID")]
+        last_event_id: Header<Option<u64>>,
+    ) -> Result<EventStream<BoxStream<'static, Transaction>>, BasicErrorWith404> {
+        fail_point_poem("endpoint_stream_transactions")?;
+        let stream_filter = StreamFilter {
+            sender: sender.0,
+            entry_function: entry_function.0,
+            event_type: None,
+            last_event: None,
+        };
+        let resume_version = last_event_id
+            .0
+            .map(|last_version| last_version.saturating_add(1));
+        let stream_state = self
+            .create_stream_state(start_version.0, resume_version, stream_filter)
+            .await?;
+
+        let transaction_stream = stream_committed_items(stream_state, render_transactions);
+        Ok(create_event_stream(transaction_stream.boxed()))
+    }
+
+    /// Stream committed events
+    ///
+    /// Streams events (as server-sent events) emitted by committed transactions,
+    /// starting at the given version, and continues to stream new events as they
+    /// are committed. Each event id has the form `version:event_index`, where the
+    /// event index is the position of the event in the transaction that emitted it.
+    /// Clients can resume a dropped stream using the `Last-Event-ID` header (or
+    /// `start_version`).
+    ///
+    /// If a filter is provided, only matching events are streamed.
+    #[oai(
+        path = "/stream/events",
+        method = "get",
+        operation_id = "stream_events",
+        tag = "ApiTags::Experimental"
+    )]
+    async fn stream_events(
+        &self,
+        /// Ledger version to start streaming from (defaults to the next committed version)
+        start_version: Query<Option<U64>>,
+        /// Only stream events emitted by transactions sent by this account
+        sender: Query<Option<Address>>,
+        /// Only stream events of this type, e.g., `0x1::coin::CoinDeposit`
+        event_type: Query<Option<MoveStructTag>>,
+        /// The id of the last event received, i.e., `version:event_index` (the stream
+        /// resumes at the next event)
+        #[oai(name = "Last-Event-ID")]
+        last_event_id: Header<Option<String>>,
+    ) -> Result<EventStream<BoxStream<'static, VersionedEvent>>, BasicErrorWith404> {
+        fail_point_poem("endpoint_stream_events")?;
+        let last_event = last_event_id.0.as_deref().map(parse_event_id).transpose()?;
+        let stream_filter = StreamFilter {
+            sender: sender.0,
+            entry_function: None,
+            event_type: event_type.0,
+            last_event,
+        };
+        let resume_version = last_event.map(|(last_version, _)| last_version);
+        let stream_state = self
+            .create_stream_state(start_version.0, resume_version, stream_filter)
+            .await?;
+
+        let event_stream = stream_committed_items(stream_state, render_events);
+        Ok(create_event_stream(event_stream.boxed()))
+    }
+}
+
+impl StreamApi {
+    /// Verifies the stream request and creates the state for a new stream. The
+    /// resume version (derived from the Last-Event-ID header) takes precedence
+    /// over the start version, as it is set by clients on reconnection.
+    async fn create_stream_state(
+        &self,
+        start_version: Option<U64>,
+        resume_version: Option<Version>,
+        stream_filter: StreamFilter,
+    ) -> Result<StreamState, BasicErrorWith404> {
+        if !self.context.node_config.api.stream_enabled {
+            return Err(api_disabled("Stream"));
+        }
+
+        // Reserve a stream connection (if the limit hasn't been reached)
+        let connection_guard = StreamConnectionGuard::new(
+            self.context.stream_active_connections.clone(),
+            self.context.node_config.api.stream_max_active_connections,
+        )
+        .ok_or_else(|| {
+            BasicErrorWith404::service_unavailable_with_code_no_info(
+                "The maximum number of active streams has been reached",
+                AptosErrorCode::InternalError,
+            )
+        })?;
+
+        // Determine the version to start streaming from
+        let context = self.context.clone();
+        let latest_ledger_info =
+            api_spawn_blocking(move || context.get_latest_ledger_info::<BasicErrorWith404>())
+                .await?;
+        let next_version = match (resume_version, start_version) {
+            (Some(resume_version), _) => resume_version,
+            (None, Some(start_version)) => start_version.0,
+            (None, None) => latest_ledger_info.version().saturating_add(1),
+        };
+        if next_version < latest_ledger_info.oldest_ledger_version.0 {
+            return Err(version_pruned(next_version, &latest_ledger_info));
+        }
+
+        Ok(StreamState {
+            context: self.context.clone(),
+            next_version,
+            stream_filter,
+            committed_version_receiver: self.context.committed_version_receiver(),
+            _connection_guard: connection_guard,
+        })
+    }
+}
+
+/// Parses an event id of the form `version:event_index`
+fn parse_event_id(event_id: &str) -> Result<(Version, u64), BasicErrorWith404> {
+    event_id
+        .split_once(':')
+        .and_then(|(version, event_index)| Some((version.parse().ok()?, event_index.parse().ok()?)))
+        .ok_or_else(|| {
+            BasicErrorWith404::bad_request_with_code_no_info(
+                format!(
+                    "Invalid Last-Event-ID: {}. Expected the form `version:event_index`",
+                    event_id
+                ),
+                AptosErrorCode::InvalidInput,
+            )
+        })
+}
+
+/// The filter applied to the committed transactions (and events) before streaming
+#[derive(Clone, Debug)]
+struct StreamFilter {
+    sender: Option<Address>,
+    entry_function: Option<EntryFunctionId>,
+    event_type: Option<MoveStructTag>,
+    last_event: Option<(Version, u64)>, // The (version, event index) of the last event received
+}
+
+impl StreamFilter {
+    /// Returns true iff the transaction matches the sender and entry function filters
+    fn matches_transaction(&self, transaction_data: &TransactionOnChainData) -> bool {
+        if self.sender.is_none() && self.entry_function.is_none() {
+            return true; // No transaction filters are set
+        }
+
+        // Only user transactions have a sender and entry function
+        let signed_transaction = match transaction_data.transaction.try_as_signed_user_txn() {
+            Some(signed_transaction) => signed_transaction,
+            None => return false,
+        };
+        if let Some(sender) = &self.sender {
+            if signed_transaction.sender() != *sender.inner() {
+                return false;
+            }
+        }
+        if let Some(entry_function_id) = &self.entry_function {
+            match signed_transaction.executable_ref() {
+                Ok(TransactionExecutableRef::EntryFunction(entry_function)) => {
+                    let transaction_entry_function_id = EntryFunctionId {
+                        module: entry_function.module().clone().into(),
+                        name: entry_function.function().into(),
+                    };
+                    if transaction_entry_function_id != *entry_function_id {
+                        return false;
+                    }
+                },
+                _ => return false,
+            }
+        }
+
+        true
+    }
+
+    /// Returns true iff the event comes after the last event received (if any)
+    fn is_after_last_event(&self, version: Version, event_index: u64) -> bool {
+        match self.last_event {
+            Some(last_event) => (version, event_index) > last_event,
+            None => true,
+        }
+    }
+
+    /// Returns true iff the event type matches the event type filter
+    fn matches_event_type(&self, type_tag: &TypeTag) -> bool {
+        match (&self.event_type, type_tag) {
+            (None, _) => true,
+            (Some(event_type), TypeTag::Struct(struct_tag)) => {
+                MoveStructTag::from(struct_tag.as_ref()) == *event_type
+            },
+            _ => false,
+        }
+    }
+}
+
+/// A guard that holds an active stream connection, and releases it on drop
+struct StreamConnectionGuard {
+    active_connections: Arc<AtomicUsize>,
+}
+
+impl StreamConnectionGuard {
+    /// Reserves an active connection, or returns None if the limit has been reached
+    fn new(active_connections: Arc<AtomicUsize>, max_active_connections: usize) -> Option<Self> {
+        if active_connections.fetch_add(1, Ordering::Relaxed) >= max_active_connections {
+            active_connections.fetch_sub(1, Ordering::Relaxed);
+            return None;
+        }
+        Some(Self { active_connections })
+    }
+}
+
+impl Drop for StreamConnectionGuard {
+    fn drop(&mut self) {
+        self.active_connections.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// The state of a single stream
+struct StreamState {
+    context: Arc<Context>,
+    next_version: Version,
+    stream_filter: StreamFilter,
+    committed_version_receiver: Option<watch::Receiver<Version>>,
+    _connection_guard: StreamConnectionGuard,
+}
+
+impl StreamState {
+    /// Waits until new versions are committed. If no commit notifications are
+    /// available, this falls back to polling at the configured interval.
+    async fn wait_for_new_commits(&mut self) {
+        let poll_interval =
+            Duration::from_millis(self.context.node_config.api.stream_poll_interval_ms);
+        wait_for_new_commits(&mut self.committed_version_receiver, poll_interval).await
+    }
+}
+
+/// Waits until the committed version receiver is notified of new commits (or
+/// the poll interval elapses). If the receiver is missing, or the commit
+/// notifications have stopped (e.g., the sender was dropped on shutdown), this
+/// sleeps for the poll interval instead.
+async fn wait_for_new_commits(
+    committed_version_receiver: &mut Option<watch::Receiver<Version>>,
+    poll_interval: Duration,
+) {
+    match committed_version_receiver.as_mut() {
+        Some(receiver) => {
+            let result = tokio::time::timeout(poll_interval, receiver.changed()).await;
+            if let Ok(Err(_)) = result {
+                // Fall back to polling, otherwise every wait returns immediately
+                *committed_version_receiver = None;
+                tokio::time::sleep(poll_interval).await;
+            }
+        },
+        None => tokio::time::sleep(poll_interval).await,
+    }
+}
+
+/// A function that filters and renders a batch of committed transactions into
+/// stream items. Each item is returned with its server-sent event id.
+type RenderFn<T> = fn(
+    &Context,
+    &LedgerInfo,
+    &StreamFilter,
+    Vec<TransactionOnChainData>,
+) -> anyhow::Result<Vec<(String, T)>>;
+
+/// Creates a stream of items rendered from committed transactions, starting at the
+/// next version in the stream state. The stream ends if an unexpected error occurs
+/// (e.g., the next version is pruned), in which case the client should reconnect.
+fn stream_committed_items<T: Send + 'static>(
+    stream_state: StreamState,
+    render_fn: RenderFn<T>,
+) -> impl futures::Stream<Item = (String, T)> + Send + 'static {
+    stream::unfold(
+        (stream_state, VecDeque::new()),
+        move |(mut stream_state, mut pending_items)| async move {
+            loop {
+                // Return any pending items first
+                if let Some(item) = pending_items.pop_front() {
+                    return Some((item, (stream_state, pending_items)));
+                }
+
+                // Otherwise, fetch the next batch of committed transactions
+                match fetch_next_batch(&stream_state, render_fn).await {
+                    Ok(Some((next_version, items))) => {
+                        stream_state.next_version = next_version;
+                        pending_items.extend(items);
+                    },
+                    Ok(None) => stream_state.wait_for_new_commits().await,
+                    Err(error) => {
+                        warn!(
+                            "Terminating the stream at version {}! Error: {:?}",
+                            stream_state.next_version, error
+                        );
+                        return None;
+                    },
+                }
+            }
+        },
+    )
+}
+
+/// Fetches and renders the next batch of committed transactions. Returns the
+/// next version to fetch and the rendered items, or None if there are no new
+/// committed transactions.
+async fn fetch_next_batch<T: Send + 'static>(
+    stream_state: &StreamState,
+    render_fn: RenderFn<T>,
+) -> anyhow::Result<Option<(Version, Vec<(String, T)>)>> {
+    let context = stream_state.context.clone();
+    let next_version = stream_state.next_version;
+    let stream_filter = stream_state.stream_filter.clone();
+    tokio::task::spawn_blocking(move || {
+        let latest_ledger_info = context.get_latest_ledger_info_wrapped()?;
+        let ledger_version = latest_ledger_info.version();
+        if next_version > ledger_version {
+            return Ok(None); // There are no new committed transactions
+        }
+
+        let transactions = context.get_transactions(
+            next_version,
+            context.max_transactions_page_size(),
+            ledger_version,
+        )?;
+        let next_version = match transactions.last() {
+            Some(transaction) => transaction.version + 1,
+            None => return Ok(None),
+        };
+        let items = render_fn(&context, &latest_ledger_info, &stream_filter, transactions)?;
+
+        Ok(Some((next_version, items)))
+    })
+    .await
+    .context("Failed to join the stream fetch task")?
+}
+
+/// Filters and renders the given transactions. The event id of each
+/// transaction is its version.
+fn render_transactions(
+    context: &Context,
+    latest_ledger_info: &LedgerInfo,
+    stream_filter: &StreamFilter,
+    transactions: Vec<TransactionOnChainData>,
+) -> anyhow::Result<Vec<(String, Transaction)>> {
+    let transactions: Vec<_> = transactions
+        .into_iter()
+        .filter(|transaction| stream_filter.matches_transaction(transaction))
+        .collect();
+    let event_ids: Vec<_> = transactions
+        .iter()
+        .map(|transaction| transaction.version.to_string())
+        .collect();
+
+    let transactions = context
+        .render_transactions_non_sequential::<BasicError>(latest_ledger_info, transactions)
+        .map_err(anyhow::Error::from)?;
+    Ok(event_ids.into_iter().zip(transactions).collect())
+}
+
+/// Filters the given transactions, and renders the events they emitted. The
+/// event id of each event is `version:event_index`.
+fn render_events(
+    context: &Context,
+    latest_ledger_info: &LedgerInfo,
+    stream_filter: &StreamFilter,
+    transactions: Vec<TransactionOnChainData>,
+) -> anyhow::Result<Vec<(String, VersionedEvent)>> {
+    let (event_ids, events): (Vec<String>, Vec<EventWithVersion>) = transactions
+        .into_iter()
+        .filter(|transaction| stream_filter.matches_transaction(transaction))
+        .flat_map(|transaction| {
+            let version = transaction.version;
+            transaction
+                .events
+                .into_iter()
+                .enumerate()
+                .map(move |(event_index, event)| (version, event_index as u64, event))
+        })
+        .filter(|(version, event_index, event)| {
+            stream_filter.is_after_last_event(*version, *event_index)
+                && stream_filter.matches_event_type(event.type_tag())
+        })
+        .map(|(version, event_index, event)| {
+            (
+                format!("{}:{}", version, event_index),
+                EventWithVersion::new(version, event),
+            )
+        })
+        .unzip();
+    if events.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let events = context
+        .latest_state_view_poem::<BasicError>(latest_ledger_info)
+        .map_err(anyhow::Error::from)?
+        .as_converter(context.db.clone(), context.indexer_reader.clone())
+        .try_into_versioned_events(&events)
+        .context("Failed to convert events from storage into response")?;
+    Ok(event_ids.into_iter().zip(events).collect())
+}
+
+/// Creates a server-sent event stream from the given items and their event ids
+fn create_event_stream<T>(
+    item_stream: BoxStream<'static, (String, T)>,
+) -> EventStream<BoxStream<'static, T>>
+where
+    T: poem_openapi::types::ToJSON + Send + 'static,
+{
+    // The event stream converts each item to an event (in stream order) as soon
+    // as it is yielded, so the event ids are handed over using a shared queue.
+    let event_ids = Arc::new(Mutex::new(VecDeque::new()));
+    let pending_event_ids = event_ids.clone();
+    let item_stream = item_stream
+        .map(move |(event_id, item)| {
+            pending_event_ids.lock().push_back(event_id);
+            item
+        })
+        .boxed();
+
+    EventStream::new(item_stream)
+        .keep_alive(Duration::from_secs(STREAM_KEEP_ALIVE_INTERVAL_SECS))
+        .to_event(move |item| {
+            let event = Event::message(item.to_json_string());
+            match event_ids.lock().pop_front() {
+                Some(event_id) => event.id(event_id),
+                None => event,
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::wait_for_new_commits;
+    use std::time::{Duration, Instant};
+    use tokio::sync::watch;
+
+    #[tokio::test]
+    async fn test_wait_for_new_commits_after_sender_dropped() {
+        // Create a committed version receiver, and drop its sender
+        let (committed_version_sender, receiver) = watch::channel(0);
+        let mut committed_version_receiver = Some(receiver);
+        drop(committed_version_sender);
+
+        // Verify that every wait still lasts for the poll interval
+        let poll_interval = Duration::from_millis(50);
+        for _ in 0..3 {
+            let start_time = Instant::now();
+            wait_for_new_commits(&mut committed_version_receiver, poll_interval).await;
+            assert!(start_time.elapsed() >= poll_interval);
+        }
+
+        // Verify that the waits have fallen back to polling
+        assert!(committed_version_receiver.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_wait_for_new_commits_notified() {
+        // Create a committed version receiver, and notify it of a new commit
+        let (committed_version_sender, receiver) = watch::channel(0);
+        let mut committed_version_receiver = Some(receiver);
+        committed_version_sender.send_replace(1);
+
+        // Verify that the wait returns before the poll interval elapses
+        let poll_interval = Duration::from_secs(30);
+        let start_time = Instant::now();
+        wait_for_new_commits(&mut committed_version_receiver, poll_interval).await;
+        assert!(start_time.elapsed() < poll_interval);
+        assert!(committed_version_receiver.is_some());
+    }
+}
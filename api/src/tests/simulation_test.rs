@@ -1,8 +1,11 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::tests::{new_test_context, new_test_context_with_orderless_flags};
+use crate::tests::{
+    new_test_context, new_test_context_with_config, new_test_context_with_orderless_flags,
+};
 use aptos_api_test_context::{current_function_name, pretty, TestContext};
+use aptos_config::config::NodeConfig;
 use aptos_crypto::ed25519::Ed25519Signature;
 use aptos_types::{
     account_address::AccountAddress,
@@ -745,3 +748,222 @@ async fn test_simulate_txn_with_randomness() {
         unreachable!("Simulation uses Ed25519 authenticator.");
     }
 }
+
+/// Creates a JSON transfer request (with a dummy signature) from a newly funded account
+async fn create_transfer_request(context: &mut TestContext, amount: u64) -> serde_json::Value {
+    let alice = &mut context.gen_account();
+    let bob = &mut context.gen_account();
+    let txn = context.mint_user_account(alice).await;
+    context.commit_block(&vec![txn]).await;
+
+    let txn = context.account_transfer_to(alice, bob.address(), amount);
+    let public_key = match txn.authenticator_ref() {
+        TransactionAuthenticator::Ed25519 { public_key, .. } => public_key.to_string(),
+        _ => unreachable!("Simulation uses Ed25519 authenticator."),
+    };
+    json!({
+        "sender": txn.sender().to_string(),
+        "sequence_number": txn.sequence_number().to_string(),
+        "max_gas_amount": txn.max_gas_amount().to_string(),
+        "gas_unit_price": txn.gas_unit_price().to_string(),
+        "expiration_timestamp_secs": txn.expiration_timestamp_secs().to_string(),
+        "payload": {
+            "type": "entry_function_payload",
+            "function": "0x1::aptos_account::transfer",
+            "type_arguments": [],
+            "arguments": [
+                bob.address().to_standard_string(), amount.to_string(),
+            ]
+        },
+        "signature": {
+            "type": "ed25519_signature",
+            "public_key": public_key,
+            "signature": Ed25519Signature::dummy_signature().to_string(),
+        },
+    })
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_simulate_transaction_with_state_overrides() {
+    let mut context = new_test_context(current_function_name!());
+    let transaction = create_transfer_request(&mut context, LARGE_TRANSFER_AMOUNT).await;
+    let sender = transaction["sender"].clone();
+
+    // Without overrides, the sender has insufficient balance
+    let resp = context
+        .expect_status_code(200)
+        .post(
+            "/transactions/simulate_with_overrides",
+            json!({ "transaction": transaction.clone() }),
+        )
+        .await;
+    assert!(!resp[0]["success"].as_bool().is_some_and(|v| v));
+
+    // With a balance override, the transfer succeeds
+    let resp = context
+        .expect_status_code(200)
+        .post(
+            "/transactions/simulate_with_overrides",
+            json!({
+                "transaction": transaction.clone(),
+                "state_overrides": {
+                    "balances": [{
+                        "address": sender,
+                        "amount": (10 * LARGE_TRANSFER_AMOUNT).to_string(),
+                    }],
+                },
+            }),
+        )
+        .await;
+    assert!(resp[0]["success"].as_bool().is_some_and(|v| v));
+
+    // The override is never persisted
+    let resp = context
+        .expect_status_code(200)
+        .post(
+            "/transactions/simulate_with_overrides",
+            json!({ "transaction": transaction.clone() }),
+        )
+        .await;
+    assert!(!resp[0]["success"].as_bool().is_some_and(|v| v));
+
+    // Invalid overrides are rejected
+    context
+        .expect_status_code(400)
+        .post(
+            "/transactions/simulate_with_overrides",
+            json!({
+                "transaction": transaction,
+                "state_overrides": {
+                    "modules": ["0x0102"],
+                },
+            }),
+        )
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_simulate_transaction_with_state_overrides_and_gas_estimation() {
+    let mut context = new_test_context(current_function_name!());
+    let transaction = create_transfer_request(&mut context, 1).await;
+    let gas_unit_price: u64 = transaction["gas_unit_price"]
+        .as_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(gas_unit_price > 0);
+
+    // The max gas amount is estimated using the overridden balance
+    let max_gas_amount = 5_000;
+    let resp = context
+        .expect_status_code(200)
+        .post(
+            "/transactions/simulate_with_overrides?estimate_max_gas_amount=true",
+            json!({
+                "transaction": transaction,
+                "state_overrides": {
+                    "balances": [{
+                        "address": transaction["sender"],
+                        "amount": (max_gas_amount * gas_unit_price).to_string(),
+                    }],
+                },
+            }),
+        )
+        .await;
+    assert_eq!(resp[0]["max_gas_amount"], max_gas_amount.to_string());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_simulate_transaction_with_trace() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.transaction_simulation_trace_enabled = true;
+    let mut context =
+        new_test_context_with_config(current_function_name!(), node_config, false, false);
+
+    // Simulate a successful transfer, and verify the call tree and events
+    let transaction = create_transfer_request(&mut context, SMALL_TRANSFER_AMOUNT).await;
+    let resp = context
+        .expect_status_code(200)
+        .post("/transactions/simulate?trace=true", transaction)
+        .await;
+    assert!(resp[0]["success"].as_bool().is_some_and(|v| v));
+    let trace = &resp[0]["trace"];
+    assert_eq!(trace["root"]["function"], "0x1::aptos_account::transfer");
+    assert!(trace["root"]["returned"].as_bool().unwrap());
+    assert!(!trace["root"]["calls"].as_array().unwrap().is_empty());
+    assert!(!trace["events"].as_array().unwrap().is_empty());
+    assert!(trace.get("abort").is_none());
+
+    // Simulate a failing transfer, and verify the abort location
+    let transaction = create_transfer_request(&mut context, LARGE_TRANSFER_AMOUNT).await;
+    let resp = context
+        .expect_status_code(200)
+        .post("/transactions/simulate?trace=true", transaction.clone())
+        .await;
+    assert!(!resp[0]["success"].as_bool().is_some_and(|v| v));
+    let abort = &resp[0]["trace"]["abort"];
+    assert!(abort["code"].as_str().is_some());
+    assert!(abort["function"].as_str().is_some());
+
+    // The trace is only included when requested
+    let resp = context
+        .expect_status_code(200)
+        .post("/transactions/simulate", transaction)
+        .await;
+    assert!(resp[0].get("trace").is_none());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_simulate_transaction_with_trace_disabled() {
+    // Execution traces are disabled by default
+    let mut context = new_test_context(current_function_name!());
+    let transaction = create_transfer_request(&mut context, SMALL_TRANSFER_AMOUNT).await;
+    let resp = context
+        .expect_status_code(403)
+        .post("/transactions/simulate?trace=true", transaction)
+        .await;
+    assert_eq!(resp["error_code"], "api_disabled");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_simulate_transaction_bundle() {
+    let mut context = new_test_context(current_function_name!());
+
+    // Create two transfers from the same account, where the second
+    // transfer depends on the sequence number bump of the first.
+    let first_transaction = create_transfer_request(&mut context, SMALL_TRANSFER_AMOUNT).await;
+    let sequence_number: u64 = first_transaction["sequence_number"]
+        .as_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    let mut second_transaction = first_transaction.clone();
+    second_transaction["sequence_number"] = json!((sequence_number + 1).to_string());
+
+    // The second transfer fails when simulated on its own
+    let resp = context
+        .expect_status_code(200)
+        .post("/transactions/simulate", second_transaction.clone())
+        .await;
+    assert!(!resp[0]["success"].as_bool().is_some_and(|v| v));
+
+    // The second transfer succeeds when simulated after the first
+    let resp = context
+        .expect_status_code(200)
+        .post(
+            "/transactions/simulate_bundle",
+            json!([first_transaction, second_transaction]),
+        )
+        .await;
+    let outputs = resp.as_array().unwrap();
+    assert_eq!(outputs.len(), 2);
+    assert!(outputs
+        .iter()
+        .all(|output| output["success"].as_bool().is_some_and(|v| v)));
+
+    // Empty bundles are rejected
+    context
+        .expect_status_code(400)
+        .post("/transactions/simulate_bundle", json!([]))
+        .await;
+}
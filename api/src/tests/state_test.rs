@@ -406,3 +406,62 @@ async fn assert_table_item<T: Serialize, U: Serialize>(
     let response = api_get_table_item(ctx, table, key_type, value_type, key).await;
     assert_eq!(response, json!(value));
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_account_resource_history() {
+    let mut context = new_test_context(current_function_name!());
+    let root_address = "0xA550C18";
+    let resource_type = "0x1::account::Account";
+
+    // Get the root account sequence number and the current ledger version
+    let resp = context
+        .get(&get_account_resource(root_address, resource_type))
+        .await;
+    let sequence_number: u64 = resp["data"]["sequence_number"]
+        .as_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    let ledger_version: u64 = context.get("/").await["ledger_version"]
+        .as_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+
+    // Commit a transaction sent by the root account
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account).await;
+    context.commit_block(&vec![txn]).await;
+
+    // Verify that the history contains the sequence number change
+    let resp = context
+        .get(&get_account_resource_history(
+            root_address,
+            resource_type,
+            ledger_version + 1,
+        ))
+        .await;
+    let changes = resp.as_array().unwrap();
+    assert_eq!(changes.len(), 1);
+    assert_eq!(
+        changes[0]["resource"]["data"]["sequence_number"],
+        json!((sequence_number + 1).to_string())
+    );
+
+    // Verify that a start version beyond the ledger version is rejected
+    context
+        .expect_status_code(404)
+        .get(&get_account_resource_history(
+            root_address,
+            resource_type,
+            u64::MAX,
+        ))
+        .await;
+}
+
+fn get_account_resource_history(address: &str, struct_tag: &str, start: u64) -> String {
+    format!(
+        "/accounts/{}/resource/{}/history?start={}",
+        address, struct_tag, start
+    )
+}
@@ -0,0 +1,200 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{new_test_context, new_test_context_with_config};
+use aptos_api_test_context::{current_function_name, ApiSpecificConfig, TestContext};
+use aptos_config::config::NodeConfig;
+use rstest::rstest;
+use serde_json::Value;
+use std::time::Duration;
+
+// Useful test constants
+const ROOT_ADDRESS: &str = "0xa550c18";
+const STREAM_READ_TIMEOUT_SECS: u64 = 30;
+const TRANSFER_FUNCTION: &str = "0x1::aptos_account::transfer";
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_stream_disabled() {
+    // Streaming is disabled by default
+    let mut context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(403)
+        .get("/stream/transactions")
+        .await;
+    assert_eq!(resp["error_code"], "api_disabled");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+#[rstest(use_txn_payload_v2_format, case(false), case(true))]
+async fn test_stream_transactions(use_txn_payload_v2_format: bool) {
+    let mut context = new_stream_test_context(current_function_name!(), use_txn_payload_v2_format);
+
+    // Commit a transfer from the root account
+    context.create_account().await;
+
+    // Stream the transfers sent by the root account, and verify the first transfer
+    let path = format!(
+        "/stream/transactions?start_version=0&sender={}&entry_function={}",
+        ROOT_ADDRESS, TRANSFER_FUNCTION
+    );
+    let mut stream = SseStream::open(&context, &path, None).await;
+    let (event_id, transaction) = stream.next_event().await;
+    verify_transfer_transaction(&event_id, &transaction);
+    let first_version: u64 = event_id.parse().unwrap();
+
+    // Commit another transfer, and verify that it is streamed
+    context.create_account().await;
+    let (event_id, transaction) = stream.next_event().await;
+    verify_transfer_transaction(&event_id, &transaction);
+    let second_version: u64 = event_id.parse().unwrap();
+    assert!(second_version > first_version);
+
+    // Resume the stream after the first transfer, and verify the second transfer is streamed
+    let mut stream = SseStream::open(&context, &path, Some(&first_version.to_string())).await;
+    let (event_id, _) = stream.next_event().await;
+    assert_eq!(event_id, second_version.to_string());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_stream_events() {
+    let mut context = new_stream_test_context(current_function_name!(), false);
+
+    // Commit a transfer (which emits several events)
+    let start_version = context.get_latest_ledger_info().version() + 1;
+    context.create_account().await;
+
+    // Stream the events, and verify that the ids are ordered by version and event index
+    let path = format!("/stream/events?start_version={}", start_version);
+    let mut stream = SseStream::open(&context, &path, None).await;
+    let mut event_ids = vec![];
+    for _ in 0..3 {
+        let (event_id, event) = stream.next_event().await;
+        let (version, event_index) = parse_event_id(&event_id);
+        assert!(version >= start_version);
+        assert_eq!(event["version"], version.to_string());
+        if let Some(last_event_id) = event_ids.last() {
+            assert!((version, event_index) > parse_event_id(last_event_id));
+        }
+        event_ids.push(event_id);
+    }
+
+    // Resume the stream after the first event, and verify the next events are streamed
+    let mut stream = SseStream::open(&context, &path, Some(&event_ids[0])).await;
+    for expected_event_id in &event_ids[1..] {
+        let (event_id, _) = stream.next_event().await;
+        assert_eq!(event_id, *expected_event_id);
+    }
+
+    // Stream the fee statement events, and verify that only those are streamed
+    let path = format!(
+        "/stream/events?start_version={}&event_type=0x1::transaction_fee::FeeStatement",
+        start_version
+    );
+    let mut stream = SseStream::open(&context, &path, None).await;
+    let (_, event) = stream.next_event().await;
+    assert_eq!(event["type"], "0x1::transaction_fee::FeeStatement");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_stream_events_invalid_last_event_id() {
+    let context = new_stream_test_context(current_function_name!(), false);
+
+    // Verify that a Last-Event-ID without an event index is rejected
+    let response = send_stream_request(&context, "/stream/events", Some("10")).await;
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+}
+
+/// Creates a test context with the streaming endpoints enabled
+fn new_stream_test_context(test_name: String, use_txn_payload_v2_format: bool) -> TestContext {
+    let mut node_config = NodeConfig::default();
+    node_config.api.stream_enabled = true;
+    node_config.api.stream_poll_interval_ms = 10;
+    new_test_context_with_config(test_name, node_config, use_txn_payload_v2_format, false)
+}
+
+/// Parses an event id of the form `version:event_index`
+fn parse_event_id(event_id: &str) -> (u64, u64) {
+    let (version, event_index) = event_id.split_once(':').unwrap();
+    (version.parse().unwrap(), event_index.parse().unwrap())
+}
+
+/// Sends a stream request directly to the API server (i.e., without
+/// buffering the response body), and returns the response.
+async fn send_stream_request(
+    context: &TestContext,
+    path: &str,
+    last_event_id: Option<&str>,
+) -> reqwest::Response {
+    let ApiSpecificConfig::V1(address) = &context.api_specific_config;
+    let mut request = reqwest::Client::new().get(format!("http://{}/v1{}", address, path));
+    if let Some(last_event_id) = last_event_id {
+        request = request.header("Last-Event-ID", last_event_id);
+    }
+    request.send().await.unwrap()
+}
+
+/// Verifies that the streamed transaction is a transfer from the root account
+fn verify_transfer_transaction(event_id: &str, transaction: &Value) {
+    assert_eq!(transaction["type"], "user_transaction");
+    assert_eq!(transaction["version"], event_id);
+    assert_eq!(transaction["sender"], ROOT_ADDRESS);
+    assert_eq!(transaction["payload"]["function"], TRANSFER_FUNCTION);
+}
+
+/// A simple reader for server-sent event streams
+struct SseStream {
+    response: reqwest::Response,
+    buffer: String,
+}
+
+impl SseStream {
+    /// Opens the stream at the given path
+    async fn open(context: &TestContext, path: &str, last_event_id: Option<&str>) -> Self {
+        let response = send_stream_request(context, path, last_event_id).await;
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        Self {
+            response,
+            buffer: String::new(),
+        }
+    }
+
+    /// Returns the id and (JSON) data of the next event in the stream
+    async fn next_event(&mut self) -> (String, Value) {
+        tokio::time::timeout(
+            Duration::from_secs(STREAM_READ_TIMEOUT_SECS),
+            self.read_next_event(),
+        )
+        .await
+        .expect("Timed out waiting for the next stream event!")
+    }
+
+    async fn read_next_event(&mut self) -> (String, Value) {
+        loop {
+            // Parse the next complete event in the buffer (skipping keep-alive messages)
+            while let Some(event_end) = self.buffer.find("\n\n") {
+                let event: String = self.buffer.drain(..event_end + 2).collect();
+                let mut event_id = None;
+                let mut event_data = None;
+                for line in event.lines() {
+                    if let Some(id) = line.strip_prefix("id: ") {
+                        event_id = Some(id.to_string());
+                    } else if let Some(data) = line.strip_prefix("data: ") {
+                        event_data = Some(serde_json::from_str(data).unwrap());
+                    }
+                }
+                if let (Some(event_id), Some(event_data)) = (event_id, event_data) {
+                    return (event_id, event_data);
+                }
+            }
+
+            // Otherwise, read more of the stream
+            let chunk = self
+                .response
+                .chunk()
+                .await
+                .unwrap()
+                .expect("The stream ended unexpectedly!");
+            self.buffer.push_str(std::str::from_utf8(&chunk).unwrap());
+        }
+    }
+}
@@ -476,3 +476,92 @@ async fn test_view_aggregator(use_txn_payload_v2_format: bool, use_orderless_tra
         .await;
     context.check_golden_output_no_prune(resp);
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_view_batch() {
+    let mut context = new_test_context(current_function_name!());
+    let creator = &mut context.gen_account();
+    let owner = &mut context.gen_account();
+    let txn1 = context.mint_user_account(creator).await;
+    let txn2 = context.account_transfer(creator, owner, 100_000);
+
+    context.commit_block(&vec![txn1, txn2]).await;
+
+    // Execute a batch containing both valid and invalid view functions
+    let resp = context
+        .post(
+            "/view/batch",
+            json!(vec![
+                build_coin_balance_request(&owner.address()),
+                json!({
+                    "function":"0x1::aptos_account::fake_function",
+                    "arguments": vec![owner.address().to_string()],
+                    "type_arguments": [],
+                }),
+                build_coin_decimals_request(),
+            ]),
+        )
+        .await;
+
+    // Verify the result of each view function
+    let results = resp.as_array().unwrap();
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0]["values"], json!(["100000"]));
+    assert_eq!(results[0]["error"], Value::Null);
+    assert_eq!(results[1]["values"], Value::Null);
+    assert!(results[1]["error"].is_string());
+    assert_eq!(results[2]["values"], json!([8]));
+    assert_eq!(results[2]["error"], Value::Null);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_view_batch_gas_budget() {
+    // Only allow a single gas unit for the entire batch
+    let mut node_config = NodeConfig::default();
+    node_config.api.max_gas_view_function_batch = 1;
+    let mut context =
+        new_test_context_with_config(current_function_name!(), node_config, false, false);
+
+    let creator = &mut context.gen_account();
+    let owner = &mut context.gen_account();
+    let txn1 = context.mint_user_account(creator).await;
+    let txn2 = context.account_transfer(creator, owner, 100_000);
+
+    context.commit_block(&vec![txn1, txn2]).await;
+
+    // Verify that neither view function can execute within the budget
+    let resp = context
+        .post(
+            "/view/batch",
+            json!(vec![
+                build_coin_balance_request(&owner.address()),
+                build_coin_balance_request(&owner.address()),
+            ]),
+        )
+        .await;
+    let results = resp.as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    for result in results {
+        assert_eq!(result["values"], Value::Null);
+        assert!(result["error"].is_string());
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_view_batch_too_large() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.max_view_function_batch_size = 1;
+    let context = new_test_context_with_config(current_function_name!(), node_config, false, false);
+
+    // Verify that batches exceeding the size limit are rejected
+    context
+        .expect_status_code(400)
+        .post(
+            "/view/batch",
+            json!(vec![
+                build_coin_decimals_request(),
+                build_coin_decimals_request()
+            ]),
+        )
+        .await;
+}
@@ -7,6 +7,7 @@ use crate::{
     accounts::Account,
     bcs_payload::Bcs,
     context::{api_spawn_blocking, Context, FunctionStats},
+    execution_trace::create_execution_trace,
     failpoint::fail_point_poem,
     generate_error_response, generate_success_response, metrics,
     metrics::WAIT_TRANSACTION_GAUGE,
@@ -17,6 +18,7 @@ use crate::{
         BasicErrorWith404, BasicResponse, BasicResponseStatus, BasicResult, BasicResultWith404,
         ForbiddenError, InsufficientStorageError, InternalError,
     },
+    state_overrides::create_state_view_with_overrides,
     view_function::convert_view_function_error,
     ApiTags,
 };
@@ -25,19 +27,24 @@ use aptos_api_types::{
     transaction::TransactionSummary, verify_function_identifier, verify_module_identifier, Address,
     AptosError, AptosErrorCode, AsConverter, EncodeSubmissionRequest, GasEstimation,
     GasEstimationBcs, HashValue, HexEncodedBytes, LedgerInfo, MoveType, PendingTransaction,
-    SubmitTransactionRequest, Transaction, TransactionData, TransactionOnChainData,
-    TransactionsBatchSingleSubmissionFailure, TransactionsBatchSubmissionResult, UserTransaction,
-    VerifyInput, VerifyInputWithRecursion, U64,
+    SimulateTransactionWithOverridesRequest, SubmitTransactionRequest, Transaction,
+    TransactionData, TransactionOnChainData, TransactionsBatchSingleSubmissionFailure,
+    TransactionsBatchSubmissionResult, UserTransaction, VerifyInput, VerifyInputWithRecursion, U64,
 };
 use aptos_crypto::{hash::CryptoHash, signing_message};
+use aptos_gas_profiling::GasProfiler;
 use aptos_logger::error;
+use aptos_transaction_filters::transaction_filter::API_FILTER_LABEL;
+use aptos_transaction_simulation::{DeltaStateStore, SimulationStateStore};
 use aptos_types::{
     account_address::AccountAddress,
     mempool_status::MempoolStatusCode,
+    state_store::StateView,
     transaction::{
         EntryFunction, ExecutionStatus, MultisigTransactionPayload, RawTransaction,
         RawTransactionWithData, Script, SignedTransaction, TransactionExecutable,
-        TransactionPayload, TransactionPayloadInner,
+        TransactionExecutableRef, TransactionOutput, TransactionPayload, TransactionPayloadInner,
+        Version,
     },
     vm_status::StatusCode,
     AptosCoinType, CoinType,
@@ -558,6 +565,11 @@ impl TransactionsApi {
         /// If set to true, the transaction will use a higher price than the original
         /// estimate.
         estimate_prioritized_gas_unit_price: Query<Option<bool>>,
+        /// If set to true, the response will include a structured execution trace
+        /// of the transaction (i.e., the call tree, events and abort location).
+        /// This is only supported for JSON responses, and only if traces are
+        /// enabled on the node.
+        trace: Query<Option<bool>>,
         data: SubmitTransactionPost,
     ) -> SimulateTransactionResult<Vec<UserTransaction>> {
         data.verify()
@@ -579,117 +591,199 @@ impl TransactionsApi {
         let context = self.context.clone();
         api_spawn_blocking(move || {
             let ledger_info = context.get_latest_ledger_info()?;
-            let mut signed_transaction = api.get_signed_transaction(&ledger_info, data)?;
+            let signed_transaction = api.get_signed_transaction(&ledger_info, data)?;
 
             // Confirm the API simulation filter allows the transaction
-            let api_filter = &context.node_config.transaction_filters.api_filter;
-            if api_filter.is_enabled()
-                && !api_filter
-                    .transaction_filter()
-                    .allows_transaction(&signed_transaction)
-            {
-                return Err(SubmitTransactionError::forbidden_with_code(
-                    "Transaction not allowed by simulation filter",
-                    AptosErrorCode::InvalidInput,
-                    &ledger_info,
-                ));
-            }
+            api.check_simulation_filter(&ledger_info, &signed_transaction)?;
 
-            let estimated_gas_unit_price = match (
+            // Replace the gas parameters with estimated values (if requested)
+            let state_view = context.latest_state_view_poem(&ledger_info)?;
+            let signed_transaction = api.estimate_gas_parameters(
+                &ledger_info,
+                signed_transaction,
+                &state_view,
+                estimate_max_gas_amount.0.unwrap_or_default(),
                 estimate_gas_unit_price.0.unwrap_or_default(),
                 estimate_prioritized_gas_unit_price.0.unwrap_or_default(),
-            ) {
-                (_, true) => {
-                    let gas_estimation = context.estimate_gas_price(&ledger_info)?;
-                    // The prioritized gas estimate should always be set, but if it's not use the gas estimate
-                    Some(
-                        gas_estimation
-                            .prioritized_gas_estimate
-                            .unwrap_or(gas_estimation.gas_estimate),
-                    )
-                },
-                (true, false) => Some(context.estimate_gas_price(&ledger_info)?.gas_estimate),
-                (false, false) => None,
-            };
+            )?;
 
-            // If estimate max gas amount is provided, we will just make it the maximum value
-            let estimated_max_gas_amount = if estimate_max_gas_amount.0.unwrap_or_default() {
-                // Retrieve max possible gas units
-                let (_, gas_params) = context.get_gas_schedule(&ledger_info)?;
-                let min_number_of_gas_units =
-                    u64::from(gas_params.vm.txn.min_transaction_gas_units)
-                        / u64::from(gas_params.vm.txn.gas_unit_scaling_factor);
-                let max_number_of_gas_units =
-                    u64::from(gas_params.vm.txn.maximum_number_of_gas_units);
-
-                // Retrieve account balance to determine max gas available, right now this is using
-                // a view function, but we may want to re-evaluate this based on performance
-                let (_, _, state_view) = context
-                    .state_view::<BasicErrorWith404>(Option::None)
+            api.simulate(
+                &accept_type,
+                ledger_info,
+                signed_transaction,
+                trace.0.unwrap_or_default(),
+            )
+        })
+        .await
+    }
+
+    /// Simulate transaction with state overrides
+    ///
+    /// Simulates a transaction in the same way as the `/transactions/simulate` endpoint,
+    /// but on top of a modified copy of the latest ledger state. The state overrides can be
+    /// used to replace (or delete) resources, inject modules that are not yet published,
+    /// set the APT balances of accounts, and change the on-chain timestamp. The overrides
+    /// are only applied to the simulation, and are never persisted.
+    ///
+    /// Modules are injected before the resources are overridden, so resource overrides
+    /// may use types defined in the injected modules. The gas parameters are estimated
+    /// (if requested) against the overridden state.
+    ///
+    /// This endpoint only accepts JSON requests.
+    #[oai(
+        path = "/transactions/simulate_with_overrides",
+        method = "post",
+        operation_id = "simulate_transaction_with_overrides",
+        tag = "ApiTags::Transactions"
+    )]
+    async fn simulate_transaction_with_overrides(
+        &self,
+        accept_type: AcceptType,
+        /// If set to true, the max gas value in the transaction will be ignored
+        /// and the maximum possible gas will be used (based on the overridden balance)
+        estimate_max_gas_amount: Query<Option<bool>>,
+        /// If set to true, the gas unit price in the transaction will be ignored
+        /// and the estimated value will be used
+        estimate_gas_unit_price: Query<Option<bool>>,
+        /// If set to true, the transaction will use a higher price than the original
+        /// estimate.
+        estimate_prioritized_gas_unit_price: Query<Option<bool>>,
+        /// If set to true, the response will include a structured execution trace
+        /// of the transaction (i.e., the call tree, events and abort location).
+        /// This is only supported for JSON responses, and only if traces are
+        /// enabled on the node.
+        trace: Query<Option<bool>>,
+        data: Json<SimulateTransactionWithOverridesRequest>,
+    ) -> SimulateTransactionResult<Vec<UserTransaction>> {
+        data.0
+            .verify()
+            .context("Simulated transaction invalid")
+            .map_err(|err| {
+                SubmitTransactionError::bad_request_with_code_no_info(
+                    err,
+                    AptosErrorCode::InvalidInput,
+                )
+            })?;
+        fail_point_poem("endpoint_simulate_transaction_with_overrides")?;
+        if !self.context.node_config.api.transaction_simulation_enabled {
+            return Err(api_disabled("Simulate transaction"));
+        }
+        self.context
+            .check_api_output_enabled("Simulate transaction", &accept_type)?;
+
+        let api = self.clone();
+        let context = self.context.clone();
+        api_spawn_blocking(move || {
+            let SimulateTransactionWithOverridesRequest {
+                transaction,
+                state_overrides,
+            } = data.0;
+            let ledger_info = context.get_latest_ledger_info()?;
+            let signed_transaction = api.get_signed_transaction(
+                &ledger_info,
+                SubmitTransactionPost::Json(Json(transaction)),
+            )?;
+
+            // Confirm the API simulation filter allows the transaction
+            api.check_simulation_filter(&ledger_info, &signed_transaction)?;
+
+            // Apply the state overrides on top of the latest state
+            let state_view = context.latest_state_view_poem(&ledger_info)?;
+            let state_view =
+                create_state_view_with_overrides(&context, state_view, &state_overrides)
+                    .context("Invalid state overrides")
                     .map_err(|err| {
-                        SubmitTransactionError::bad_request_with_code_no_info(
+                        SubmitTransactionError::bad_request_with_code(
                             err,
                             AptosErrorCode::InvalidInput,
+                            &ledger_info,
                         )
                     })?;
-                let output = AptosVM::execute_view_function(
-                    &state_view,
-                    ModuleId::new(AccountAddress::ONE, ident_str!("coin").into()),
-                    ident_str!("balance").into(),
-                    vec![AptosCoinType::type_tag()],
-                    vec![signed_transaction.sender().to_vec()],
-                    context.node_config.api.max_gas_view_function,
-                );
-                let values = output.values.map_err(|status| {
-                    let (err_string, vm_error_code) =
-                        convert_view_function_error(&status, &state_view, &context);
-                    SubmitTransactionError::bad_request_with_optional_vm_status_and_ledger_info(
-                        anyhow::anyhow!(err_string),
-                        AptosErrorCode::InvalidInput,
-                        vm_error_code,
-                        Some(&ledger_info),
-                    )
-                })?;
-                let balance: u64 = bcs::from_bytes(&values[0]).map_err(|err| {
-                    SubmitTransactionError::bad_request_with_code_no_info(
-                        err,
-                        AptosErrorCode::InvalidInput,
-                    )
-                })?;
 
-                let gas_unit_price =
-                    estimated_gas_unit_price.unwrap_or_else(|| signed_transaction.gas_unit_price());
+            // Replace the gas parameters with estimated values (if requested)
+            let signed_transaction = api.estimate_gas_parameters(
+                &ledger_info,
+                signed_transaction,
+                &state_view,
+                estimate_max_gas_amount.0.unwrap_or_default(),
+                estimate_gas_unit_price.0.unwrap_or_default(),
+                estimate_prioritized_gas_unit_price.0.unwrap_or_default(),
+            )?;
 
-                // With 0 gas price, we set it to max gas units, since we can't divide by 0
-                let max_account_gas_units = if gas_unit_price == 0 {
-                    balance
-                } else {
-                    balance / gas_unit_price
-                };
+            api.simulate_with_state_view(
+                &accept_type,
+                ledger_info,
+                signed_transaction,
+                &state_view,
+                trace.0.unwrap_or_default(),
+            )
+        })
+        .await
+    }
 
-                // To give better error messaging, we should not go below the minimum number of gas units
-                let max_account_gas_units =
-                    std::cmp::max(min_number_of_gas_units, max_account_gas_units);
+    /// Simulate transaction bundle
+    ///
+    /// Simulates an ordered bundle of transactions, where each transaction is executed on
+    /// top of the state produced by all previous transactions in the bundle (e.g., to
+    /// simulate an approval followed by a swap). As with `/transactions/simulate`, none
+    /// of the transactions may have a valid signature, and nothing is persisted.
+    ///
+    /// The response contains the output of each transaction, in the same order as the
+    /// request. Transactions are executed even if a previous transaction in the bundle
+    /// failed (in which case, only the gas charges of the failed transaction are applied).
+    ///
+    /// To use this endpoint with BCS, you must submit a list of SignedTransactions
+    /// encoded as BCS. See SignedTransaction in types/src/transaction/mod.rs.
+    #[oai(
+        path = "/transactions/simulate_bundle",
+        method = "post",
+        operation_id = "simulate_transaction_bundle",
+        tag = "ApiTags::Transactions"
+    )]
+    async fn simulate_transaction_bundle(
+        &self,
+        accept_type: AcceptType,
+        data: SubmitTransactionsBatchPost,
+    ) -> SimulateTransactionResult<Vec<UserTransaction>> {
+        data.verify()
+            .context("Simulated transactions invalid")
+            .map_err(|err| {
+                SubmitTransactionError::bad_request_with_code_no_info(
+                    err,
+                    AptosErrorCode::InvalidInput,
+                )
+            })?;
+        fail_point_poem("endpoint_simulate_transaction_bundle")?;
+        if !self.context.node_config.api.transaction_simulation_enabled {
+            return Err(api_disabled("Simulate transaction bundle"));
+        }
+        self.context
+            .check_api_output_enabled("Simulate transaction bundle", &accept_type)?;
 
-                // Minimum of the max account and the max total needs to be used for estimation
-                Some(std::cmp::min(
-                    max_account_gas_units,
-                    max_number_of_gas_units,
-                ))
-            } else {
-                None
-            };
+        let api = self.clone();
+        let context = self.context.clone();
+        api_spawn_blocking(move || {
+            let ledger_info = context.get_latest_ledger_info()?;
+            let signed_transactions = api.get_signed_transactions_batch(&ledger_info, data)?;
+            let max_bundle_size = context.node_config.api.max_simulate_transaction_bundle_size;
+            if signed_transactions.is_empty() || signed_transactions.len() > max_bundle_size {
+                return Err(SubmitTransactionError::bad_request_with_code(
+                    format!(
+                        "Simulated bundles must contain between 1 and {} transactions, but got: {}",
+                        max_bundle_size,
+                        signed_transactions.len(),
+                    ),
+                    AptosErrorCode::InvalidInput,
+                    &ledger_info,
+                ));
+            }
 
-            // If there is an estimation of either, replace the values
-            if estimated_max_gas_amount.is_some() || estimated_gas_unit_price.is_some() {
-                signed_transaction = override_gas_parameters(
-                    &signed_transaction,
-                    estimated_max_gas_amount,
-                    estimated_gas_unit_price,
-                );
+            // Confirm the API simulation filter allows each transaction
+            for signed_transaction in &signed_transactions {
+                api.check_simulation_filter(&ledger_info, signed_transaction)?;
             }
 
-            api.simulate(&accept_type, ledger_info, signed_transaction)
+            api.simulate_bundle(&accept_type, ledger_info, signed_transactions)
         })
         .await
     }
@@ -1175,6 +1269,126 @@ impl TransactionsApi {
         Ok(())
     }
 
+    /// Confirms that the API simulation filter allows the given transaction
+    fn check_simulation_filter(
+        &self,
+        ledger_info: &LedgerInfo,
+        signed_transaction: &SignedTransaction,
+    ) -> Result<(), SubmitTransactionError> {
+        let api_filter = &self.context.node_config.transaction_filters.api_filter;
+        if !api_filter.is_enabled() {
+            return Ok(());
+        }
+
+        let transaction_filter = api_filter.transaction_filter();
+        transaction_filter.record_shadow_rule_match(API_FILTER_LABEL, signed_transaction);
+        if !transaction_filter.allows_transaction(signed_transaction) {
+            return Err(SubmitTransactionError::forbidden_with_code(
+                "Transaction not allowed by simulation filter",
+                AptosErrorCode::InvalidInput,
+                ledger_info,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Replaces the gas parameters of a simulated transaction with estimated values (if
+    /// requested). The max gas amount is estimated using the sender's balance in the
+    /// given state view.
+    fn estimate_gas_parameters(
+        &self,
+        ledger_info: &LedgerInfo,
+        signed_transaction: SignedTransaction,
+        state_view: &impl StateView,
+        estimate_max_gas_amount: bool,
+        estimate_gas_unit_price: bool,
+        estimate_prioritized_gas_unit_price: bool,
+    ) -> Result<SignedTransaction, SubmitTransactionError> {
+        let estimated_gas_unit_price =
+            match (estimate_gas_unit_price, estimate_prioritized_gas_unit_price) {
+                (_, true) => {
+                    let gas_estimation = self.context.estimate_gas_price(ledger_info)?;
+                    // The prioritized gas estimate should always be set, but if it's not use the gas estimate
+                    Some(
+                        gas_estimation
+                            .prioritized_gas_estimate
+                            .unwrap_or(gas_estimation.gas_estimate),
+                    )
+                },
+                (true, false) => Some(self.context.estimate_gas_price(ledger_info)?.gas_estimate),
+                (false, false) => None,
+            };
+
+        // If estimate max gas amount is provided, we will just make it the maximum value
+        let estimated_max_gas_amount = if estimate_max_gas_amount {
+            // Retrieve max possible gas units
+            let (_, gas_params) = self.context.get_gas_schedule(ledger_info)?;
+            let min_number_of_gas_units = u64::from(gas_params.vm.txn.min_transaction_gas_units)
+                / u64::from(gas_params.vm.txn.gas_unit_scaling_factor);
+            let max_number_of_gas_units = u64::from(gas_params.vm.txn.maximum_number_of_gas_units);
+
+            // Retrieve account balance to determine max gas available, right now this is using
+            // a view function, but we may want to re-evaluate this based on performance
+            let output = AptosVM::execute_view_function(
+                state_view,
+                ModuleId::new(AccountAddress::ONE, ident_str!("coin").into()),
+                ident_str!("balance").into(),
+                vec![AptosCoinType::type_tag()],
+                vec![signed_transaction.sender().to_vec()],
+                self.context.node_config.api.max_gas_view_function,
+            );
+            let values = output.values.map_err(|status| {
+                let (err_string, vm_error_code) =
+                    convert_view_function_error(&status, state_view, &self.context);
+                SubmitTransactionError::bad_request_with_optional_vm_status_and_ledger_info(
+                    anyhow::anyhow!(err_string),
+                    AptosErrorCode::InvalidInput,
+                    vm_error_code,
+                    Some(ledger_info),
+                )
+            })?;
+            let balance: u64 = bcs::from_bytes(&values[0]).map_err(|err| {
+                SubmitTransactionError::bad_request_with_code_no_info(
+                    err,
+                    AptosErrorCode::InvalidInput,
+                )
+            })?;
+
+            let gas_unit_price =
+                estimated_gas_unit_price.unwrap_or_else(|| signed_transaction.gas_unit_price());
+
+            // With 0 gas price, we set it to max gas units, since we can't divide by 0
+            let max_account_gas_units = if gas_unit_price == 0 {
+                balance
+            } else {
+                balance / gas_unit_price
+            };
+
+            // To give better error messaging, we should not go below the minimum number of gas units
+            let max_account_gas_units =
+                std::cmp::max(min_number_of_gas_units, max_account_gas_units);
+
+            // Minimum of the max account and the max total needs to be used for estimation
+            Some(std::cmp::min(
+                max_account_gas_units,
+                max_number_of_gas_units,
+            ))
+        } else {
+            None
+        };
+
+        // If there is an estimation of either, replace the values
+        if estimated_max_gas_amount.is_some() || estimated_gas_unit_price.is_some() {
+            Ok(override_gas_parameters(
+                &signed_transaction,
+                estimated_max_gas_amount,
+                estimated_gas_unit_price,
+            ))
+        } else {
+            Ok(signed_transaction)
+        }
+    }
+
     /// Parses a single signed transaction
     fn get_signed_transaction(
         &self,
@@ -1533,6 +1747,22 @@ impl TransactionsApi {
         accept_type: &AcceptType,
         ledger_info: LedgerInfo,
         txn: SignedTransaction,
+        trace: bool,
+    ) -> SimulateTransactionResult<Vec<UserTransaction>> {
+        let state_view = self.context.latest_state_view_poem(&ledger_info)?;
+        self.simulate_with_state_view(accept_type, ledger_info, txn, &state_view, trace)
+    }
+
+    /// Simulate a transaction in the VM, on top of the given state view. If `trace`
+    /// is set, the transaction is simulated with the gas profiler, and the execution
+    /// trace is included in the response.
+    pub fn simulate_with_state_view(
+        &self,
+        accept_type: &AcceptType,
+        ledger_info: LedgerInfo,
+        txn: SignedTransaction,
+        state_view: &impl StateView,
+        trace: bool,
     ) -> SimulateTransactionResult<Vec<UserTransaction>> {
         // The caller must ensure that the signature is not valid, as otherwise
         // a malicious actor could execute the transaction without their knowledge
@@ -1544,12 +1774,174 @@ impl TransactionsApi {
             ));
         }
 
-        // Simulate transaction
-        let state_view = self.context.latest_state_view_poem(&ledger_info)?;
-        let (vm_status, output) =
-            AptosSimulationVM::create_vm_and_simulate_signed_transaction(&txn, &state_view);
-        let version = ledger_info.version();
+        if trace {
+            if !self
+                .context
+                .node_config
+                .api
+                .transaction_simulation_trace_enabled
+            {
+                return Err(SubmitTransactionError::forbidden_with_code(
+                    "Execution traces are disabled on this node",
+                    AptosErrorCode::ApiDisabled,
+                    &ledger_info,
+                ));
+            }
+            if accept_type == &AcceptType::Bcs {
+                return Err(SubmitTransactionError::bad_request_with_code(
+                    "Execution traces are only supported for JSON responses",
+                    AptosErrorCode::BcsNotSupported,
+                    &ledger_info,
+                ));
+            }
+            if !matches!(
+                txn.executable_ref(),
+                Ok(TransactionExecutableRef::Script(_))
+                    | Ok(TransactionExecutableRef::EntryFunction(_))
+            ) {
+                return Err(SubmitTransactionError::bad_request_with_code(
+                    "Execution traces are only supported for entry function and script payloads",
+                    AptosErrorCode::InvalidInput,
+                    &ledger_info,
+                ));
+            }
+        }
+
+        // Simulate transaction (using the gas profiler to record the call graph, if requested)
+        let (vm_status, output, gas_log) = if trace {
+            let (vm_status, output, gas_profiler) =
+                AptosSimulationVM::create_vm_and_simulate_signed_transaction_with_modified_gas_meter(
+                    &txn,
+                    state_view,
+                    |gas_meter| {
+                        let gas_profiler = match txn.executable_ref() {
+                            Ok(TransactionExecutableRef::EntryFunction(entry_function)) => {
+                                GasProfiler::new_function(
+                                    gas_meter,
+                                    entry_function.module().clone(),
+                                    entry_function.function().to_owned(),
+                                    entry_function.ty_args().to_vec(),
+                                )
+                            },
+                            _ => GasProfiler::new_script(gas_meter),
+                        };
+                        gas_profiler.with_value_tracing()
+                    },
+                );
+            (vm_status, output, gas_profiler.map(GasProfiler::finish))
+        } else {
+            let (vm_status, output) =
+                AptosSimulationVM::create_vm_and_simulate_signed_transaction(&txn, state_view);
+            (vm_status, output, None)
+        };
+
+        let simulated_txn = self.to_simulated_transaction(ledger_info.version(), txn, &output);
+        let result = match accept_type {
+            AcceptType::Json => {
+                let execution_trace = gas_log
+                    .map(|gas_log| create_execution_trace(&gas_log, simulated_txn.info.status()));
+                let mut user_transactions = self.render_simulated_transactions(
+                    &ledger_info,
+                    state_view,
+                    vec![(simulated_txn, vm_status)],
+                )?;
+                for user_txn in user_transactions.iter_mut() {
+                    user_txn.trace = execution_trace.clone();
+                }
+                BasicResponse::try_from_json((
+                    user_transactions,
+                    &ledger_info,
+                    BasicResponseStatus::Ok,
+                ))
+            },
+            AcceptType::Bcs => {
+                BasicResponse::try_from_bcs((simulated_txn, &ledger_info, BasicResponseStatus::Ok))
+            },
+        };
 
+        result.map(|r| r.with_gas_used(Some(output.gas_used())))
+    }
+
+    /// Simulates a bundle of transactions in the VM, in order, where each transaction is
+    /// executed on top of the state produced by all previous transactions in the bundle.
+    /// Nothing is persisted. The response contains the output of each transaction.
+    pub fn simulate_bundle(
+        &self,
+        accept_type: &AcceptType,
+        ledger_info: LedgerInfo,
+        txns: Vec<SignedTransaction>,
+    ) -> SimulateTransactionResult<Vec<UserTransaction>> {
+        // The caller must ensure that the signatures are not valid, as otherwise
+        // a malicious actor could execute the transactions without their knowledge
+        for (index, txn) in txns.iter().enumerate() {
+            if txn.verify_signature().is_ok() {
+                return Err(SubmitTransactionError::bad_request_with_code(
+                    format!(
+                        "Simulated transactions must not have a valid signature. Transaction at position {} has a valid signature",
+                        index
+                    ),
+                    AptosErrorCode::InvalidInput,
+                    &ledger_info,
+                ));
+            }
+        }
+
+        // Simulate each transaction on top of the write sets of the previous transactions
+        let state_view =
+            DeltaStateStore::new_with_base(self.context.latest_state_view_poem(&ledger_info)?);
+        let mut simulated_txns = vec![];
+        let mut total_gas_used = 0;
+        for txn in txns {
+            let (vm_status, output) =
+                AptosSimulationVM::create_vm_and_simulate_signed_transaction(&txn, &state_view);
+            state_view
+                .apply_write_set(output.write_set())
+                .context("Failed to apply the write set of a simulated transaction")
+                .map_err(|err| {
+                    SubmitTransactionError::internal_with_code(
+                        err,
+                        AptosErrorCode::InternalError,
+                        &ledger_info,
+                    )
+                })?;
+
+            total_gas_used += output.gas_used();
+            simulated_txns.push((
+                self.to_simulated_transaction(ledger_info.version(), txn, &output),
+                vm_status,
+            ));
+        }
+
+        let result = match accept_type {
+            AcceptType::Json => {
+                let user_transactions =
+                    self.render_simulated_transactions(&ledger_info, &state_view, simulated_txns)?;
+                BasicResponse::try_from_json((
+                    user_transactions,
+                    &ledger_info,
+                    BasicResponseStatus::Ok,
+                ))
+            },
+            AcceptType::Bcs => {
+                let simulated_txns: Vec<TransactionOnChainData> = simulated_txns
+                    .into_iter()
+                    .map(|(simulated_txn, _)| simulated_txn)
+                    .collect();
+                BasicResponse::try_from_bcs((simulated_txns, &ledger_info, BasicResponseStatus::Ok))
+            },
+        };
+
+        result.map(|r| r.with_gas_used(Some(total_gas_used)))
+    }
+
+    /// Builds up a transaction from the outputs of a simulated transaction, and records
+    /// the simulation stats. All state hashes are invalid, and will be filled with 0s.
+    fn to_simulated_transaction(
+        &self,
+        version: Version,
+        txn: SignedTransaction,
+        output: &TransactionOutput,
+    ) -> TransactionOnChainData {
         // Ensure that all known statuses return their values in the output (even if they aren't supposed to)
         let exe_status = ExecutionStatus::conmbine_vm_status_for_simulation(
             output.auxiliary_data(),
@@ -1608,8 +2000,6 @@ impl TransactionsApi {
             .simulate_txn_stats()
             .increment(stats_key, output.gas_used());
 
-        // Build up a transaction from the outputs
-        // All state hashes are invalid, and will be filled with 0s
         let txn = aptos_types::transaction::Transaction::UserTransaction(txn);
         let zero_hash = aptos_crypto::HashValue::zero();
         let info = aptos_types::transaction::TransactionInfo::new(
@@ -1626,63 +2016,63 @@ impl TransactionsApi {
             .context
             .translate_v2_to_v1_events_for_simulation(&mut events);
 
-        let simulated_txn = TransactionOnChainData {
+        TransactionOnChainData {
             version,
             transaction: txn,
             info,
             events,
             accumulator_root_hash: zero_hash,
             changes: output.write_set().clone(),
-        };
+        }
+    }
 
-        let result = match accept_type {
-            AcceptType::Json => {
-                let transactions = self
-                    .context
-                    .render_transactions_non_sequential(&ledger_info, vec![simulated_txn])?;
-
-                // Users can only make requests to simulate UserTransactions, so unpack
-                // the Vec<Transaction> into Vec<UserTransaction>.
-                let mut user_transactions = Vec::new();
-                for transaction in transactions.into_iter() {
-                    match transaction {
-                        Transaction::UserTransaction(mut user_txn) => {
-                            match &vm_status {
-                                VMStatus::Error {
-                                    message: Some(msg), ..
-                                }
-                                | VMStatus::ExecutionFailure {
-                                    message: Some(msg), ..
-                                } => {
-                                    user_txn.info.vm_status +=
-                                        format!("\nExecution failed with message: {}", msg)
-                                            .as_str();
-                                },
-                                _ => (),
-                            }
-                            user_transactions.push(user_txn);
-                        },
-                        _ => {
-                            return Err(SubmitTransactionError::internal_with_code(
-                                "Simulation transaction resulted in a non-UserTransaction",
-                                AptosErrorCode::InternalError,
-                                &ledger_info,
-                            ))
+    /// Renders simulated transactions (using the given state view) as user transactions.
+    /// If a transaction failed, the failure message is appended to its VM status.
+    fn render_simulated_transactions(
+        &self,
+        ledger_info: &LedgerInfo,
+        state_view: &impl StateView,
+        simulated_txns: Vec<(TransactionOnChainData, VMStatus)>,
+    ) -> Result<Vec<UserTransaction>, SubmitTransactionError> {
+        let (simulated_txns, vm_statuses): (Vec<_>, Vec<_>) = simulated_txns.into_iter().unzip();
+        let transactions = self
+            .context
+            .render_transactions_non_sequential_with_state_view(
+                ledger_info,
+                state_view,
+                simulated_txns,
+            )?;
+
+        // Users can only make requests to simulate UserTransactions, so unpack
+        // the Vec<Transaction> into Vec<UserTransaction>.
+        let mut user_transactions = Vec::new();
+        for (transaction, vm_status) in transactions.into_iter().zip(vm_statuses) {
+            match transaction {
+                Transaction::UserTransaction(mut user_txn) => {
+                    match &vm_status {
+                        VMStatus::Error {
+                            message: Some(msg), ..
+                        }
+                        | VMStatus::ExecutionFailure {
+                            message: Some(msg), ..
+                        } => {
+                            user_txn.info.vm_status +=
+                                format!("\nExecution failed with message: {}", msg).as_str();
                         },
+                        _ => (),
                     }
-                }
-                BasicResponse::try_from_json((
-                    user_transactions,
-                    &ledger_info,
-                    BasicResponseStatus::Ok,
-                ))
-            },
-            AcceptType::Bcs => {
-                BasicResponse::try_from_bcs((simulated_txn, &ledger_info, BasicResponseStatus::Ok))
-            },
-        };
-
-        result.map(|r| r.with_gas_used(Some(output.gas_used())))
+                    user_transactions.push(user_txn);
+                },
+                _ => {
+                    return Err(SubmitTransactionError::internal_with_code(
+                        "Simulation transaction resulted in a non-UserTransaction",
+                        AptosErrorCode::InternalError,
+                        ledger_info,
+                    ))
+                },
+            }
+        }
+        Ok(user_transactions)
     }
 
     /// Encode message as BCS
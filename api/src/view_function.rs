@@ -7,17 +7,18 @@ use crate::{
     context::{api_spawn_blocking, FunctionStats},
     failpoint::fail_point_poem,
     response::{
-        BadRequestError, BasicErrorWith404, BasicResponse, BasicResponseStatus, BasicResultWith404,
-        ForbiddenError, InternalError,
+        api_forbidden, BadRequestError, BasicErrorWith404, BasicResponse, BasicResponseStatus,
+        BasicResultWith404, ForbiddenError, InternalError,
     },
     ApiTags, Context,
 };
 use anyhow::Context as anyhowContext;
 use aptos_api_types::{
-    AptosErrorCode, AsConverter, MoveValue, ViewFunction, ViewRequest, MAX_RECURSIVE_TYPES_ALLOWED,
-    U64,
+    AptosErrorCode, AsConverter, MoveValue, ViewFunction, ViewFunctionResult, ViewRequest,
+    MAX_RECURSIVE_TYPES_ALLOWED, U64,
 };
 use aptos_bcs_utils::serialize_uleb128;
+use aptos_storage_interface::state_store::state_view::db_state_view::DbStateView;
 use aptos_types::{state_store::StateView, transaction::ViewFunctionError, vm_status::StatusCode};
 use aptos_vm::AptosVM;
 use itertools::Itertools;
@@ -90,6 +91,47 @@ impl ViewFunctionApi {
         api_spawn_blocking(move || view_request(context, accept_type, request, ledger_version))
             .await
     }
+
+    /// Execute a batch of view functions
+    ///
+    /// Execute each of the given Move view functions against the same ledger version,
+    /// and return the execution result of each function (in order). A failed function
+    /// does not fail the batch; instead, the error is returned in its result.
+    ///
+    /// The total gas used by the batch is limited by an aggregate gas budget. Once the
+    /// budget has been exhausted, the remaining functions are not executed.
+    ///
+    /// The Aptos nodes prune account state history, via a configurable time window.
+    /// If the requested ledger version has been pruned, the server responds with a 410.
+    #[oai(
+        path = "/view/batch",
+        method = "post",
+        operation_id = "view_batch",
+        tag = "ApiTags::View"
+    )]
+    async fn view_function_batch(
+        &self,
+        accept_type: AcceptType,
+        /// View function requests with type and position arguments
+        requests: Json<Vec<ViewRequest>>,
+        /// Ledger version to get state of account
+        ///
+        /// If not provided, it will be the latest version
+        ledger_version: Query<Option<U64>>,
+    ) -> BasicResultWith404<Vec<ViewFunctionResult>> {
+        fail_point_poem("endpoint_view_function_batch")?;
+        self.context
+            .check_api_output_enabled("View function batch", &accept_type)?;
+        if accept_type == AcceptType::Bcs {
+            return Err(api_forbidden(
+                "View function batch",
+                "Only JSON is supported as an AcceptType.",
+            ));
+        }
+
+        let context = self.context.clone();
+        api_spawn_blocking(move || view_batch_request(context, requests.0, ledger_version)).await
+    }
 }
 
 fn view_request(
@@ -137,11 +179,7 @@ fn view_request(
     };
 
     // Reject the request if it's not allowed by the filter.
-    if !context.node_config.api.view_filter.allows(
-        view_function.module.address(),
-        view_function.module.name().as_str(),
-        view_function.function.as_str(),
-    ) {
+    if !is_view_function_allowed(&context, &view_function) {
         return Err(BasicErrorWith404::forbidden_with_code_no_info(
             format!(
                 "Function {}::{} is not allowed",
@@ -193,31 +231,7 @@ fn view_request(
             BasicResponse::try_from_encoded((ret, &ledger_info, BasicResponseStatus::Ok))
         },
         AcceptType::Json => {
-            let return_types = state_view
-                .as_converter(context.db.clone(), context.indexer_reader.clone())
-                .function_return_types(&view_function)
-                .and_then(|tys| {
-                    tys.iter()
-                        .map(TypeTag::try_from)
-                        .collect::<anyhow::Result<Vec<_>>>()
-                })
-                .map_err(|err| {
-                    BasicErrorWith404::bad_request_with_code(
-                        err,
-                        AptosErrorCode::InternalError,
-                        &ledger_info,
-                    )
-                })?;
-
-            let move_vals = values
-                .into_iter()
-                .zip(return_types.into_iter())
-                .map(|(v, ty)| {
-                    state_view
-                        .as_converter(context.db.clone(), context.indexer_reader.clone())
-                        .try_into_move_value(&ty, &v)
-                })
-                .collect::<anyhow::Result<Vec<_>>>()
+            let move_vals = convert_return_values(&context, &state_view, &view_function, values)
                 .map_err(|err| {
                     BasicErrorWith404::bad_request_with_code(
                         err,
@@ -235,3 +249,160 @@ fn view_request(
     );
     result.map(|r| r.with_gas_used(Some(output.gas_used)))
 }
+
+/// Executes a batch of view functions against the same state snapshot
+fn view_batch_request(
+    context: Arc<Context>,
+    requests: Vec<ViewRequest>,
+    ledger_version: Query<Option<U64>>,
+) -> BasicResultWith404<Vec<ViewFunctionResult>> {
+    // Retrieve the current state of the chain
+    let (ledger_info, requested_version) = context
+        .get_latest_ledger_info_and_verify_lookup_version(ledger_version.map(|inner| inner.0))?;
+
+    let max_batch_size = context.node_config.api.max_view_function_batch_size;
+    if requests.len() > max_batch_size {
+        return Err(BasicErrorWith404::bad_request_with_code(
+            format!(
+                "Requested too many view functions: {}, while limit is {}",
+                requests.len(),
+                max_batch_size
+            ),
+            AptosErrorCode::InvalidInput,
+            &ledger_info,
+        ));
+    }
+
+    let state_view = context
+        .state_view_at_version(requested_version)
+        .map_err(|err| {
+            BasicErrorWith404::bad_request_with_code(
+                err,
+                AptosErrorCode::InternalError,
+                &ledger_info,
+            )
+        })?;
+
+    // Execute each view function, while enforcing the aggregate gas budget
+    let mut remaining_gas = context.node_config.api.max_gas_view_function_batch;
+    let mut total_gas_used: u64 = 0;
+    let results = requests
+        .into_iter()
+        .map(|request| {
+            let result = execute_batch_view_function(&context, &state_view, request, remaining_gas);
+            let gas_used = result.gas_used.0;
+            remaining_gas = remaining_gas.saturating_sub(gas_used);
+            total_gas_used = total_gas_used.saturating_add(gas_used);
+            result
+        })
+        .collect::<Vec<_>>();
+
+    BasicResponse::try_from_json((results, &ledger_info, BasicResponseStatus::Ok))
+        .map(|r| r.with_gas_used(Some(total_gas_used)))
+}
+
+/// Executes a single view function in a batch. Any errors are returned in the result.
+fn execute_batch_view_function(
+    context: &Context,
+    state_view: &DbStateView,
+    request: ViewRequest,
+    remaining_gas: u64,
+) -> ViewFunctionResult {
+    if remaining_gas == 0 {
+        return ViewFunctionResult::failure(
+            "The aggregate gas budget for the batch has been exhausted".to_string(),
+            None,
+            0,
+        );
+    }
+
+    let view_function = match state_view
+        .as_converter(context.db.clone(), context.indexer_reader.clone())
+        .convert_view_function(request)
+    {
+        Ok(view_function) => view_function,
+        Err(error) => return ViewFunctionResult::failure(error.to_string(), None, 0),
+    };
+
+    // Reject the function if it's not allowed by the filter.
+    if !is_view_function_allowed(context, &view_function) {
+        return ViewFunctionResult::failure(
+            format!(
+                "Function {}::{} is not allowed",
+                view_function.module, view_function.function
+            ),
+            None,
+            0,
+        );
+    }
+
+    let max_gas = std::cmp::min(context.node_config.api.max_gas_view_function, remaining_gas);
+    let output = AptosVM::execute_view_function(
+        state_view,
+        view_function.module.clone(),
+        view_function.function.clone(),
+        view_function.ty_args.clone(),
+        view_function.args.clone(),
+        max_gas,
+    );
+    context.view_function_stats().increment(
+        FunctionStats::function_to_key(&view_function.module, &view_function.function),
+        output.gas_used,
+    );
+
+    let values = match output.values {
+        Ok(values) => values,
+        Err(error) => {
+            let (error, vm_error_code) = convert_view_function_error(&error, state_view, context);
+            return ViewFunctionResult::failure(
+                error,
+                vm_error_code.map(|code| code as u64),
+                output.gas_used,
+            );
+        },
+    };
+
+    match convert_return_values(context, state_view, &view_function, values).and_then(
+        |move_values| {
+            move_values
+                .into_iter()
+                .map(|move_value| serde_json::to_value(move_value).map_err(anyhow::Error::from))
+                .collect::<anyhow::Result<Vec<_>>>()
+        },
+    ) {
+        Ok(values) => ViewFunctionResult::success(values, output.gas_used),
+        Err(error) => ViewFunctionResult::failure(error.to_string(), None, output.gas_used),
+    }
+}
+
+/// Returns true iff the view function is allowed by the view filter
+fn is_view_function_allowed(context: &Context, view_function: &ViewFunction) -> bool {
+    context.node_config.api.view_filter.allows(
+        view_function.module.address(),
+        view_function.module.name().as_str(),
+        view_function.function.as_str(),
+    )
+}
+
+/// Converts the BCS encoded return values of a view function into Move values
+fn convert_return_values(
+    context: &Context,
+    state_view: &impl StateView,
+    view_function: &ViewFunction,
+    values: Vec<Vec<u8>>,
+) -> anyhow::Result<Vec<MoveValue>> {
+    let converter = state_view.as_converter(context.db.clone(), context.indexer_reader.clone());
+    let return_types = converter
+        .function_return_types(view_function)
+        .and_then(|tys| {
+            tys.iter()
+                .map(TypeTag::try_from)
+                .collect::<anyhow::Result<Vec<_>>>()
+        })?;
+
+    values
+        .into_iter()
+        .zip(return_types)
+        .map(|(v, ty)| converter.try_into_move_value(&ty, &v))
+        .collect()
+}
@@ -116,24 +116,36 @@ impl<'a, S: StateView> MoveConverter<'a, S> {
         address: Address,
         tag: &StructTag,
     ) -> Result<Option<Bytes>> {
+        let (key, is_group_member) = self.find_resource_state_key(address, tag)?;
+        match state_view.get_state_value_bytes(&key)? {
+            Some(bytes) if is_group_member => self.find_resource_group_member(tag, &bytes),
+            bytes => Ok(bytes),
+        }
+    }
+
+    /// Returns the state key under which the given resource is stored, and whether
+    /// the resource is a member of a resource group (i.e., the key is for the group).
+    pub fn find_resource_state_key(
+        &self,
+        address: Address,
+        tag: &StructTag,
+    ) -> Result<(StateKey, bool)> {
         Ok(match self.inner.view_resource_group_member(tag) {
-            Some(group_tag) => {
-                let key = StateKey::resource_group(&address.into(), &group_tag);
-                match state_view.get_state_value_bytes(&key)? {
-                    Some(group_bytes) => {
-                        let group: BTreeMap<StructTag, Bytes> = bcs::from_bytes(&group_bytes)?;
-                        group.get(tag).cloned()
-                    },
-                    None => None,
-                }
-            },
-            None => {
-                let key = StateKey::resource(&address.into(), tag)?;
-                state_view.get_state_value_bytes(&key)?
-            },
+            Some(group_tag) => (StateKey::resource_group(&address.into(), &group_tag), true),
+            None => (StateKey::resource(&address.into(), tag)?, false),
         })
     }
 
+    /// Returns the bytes of the given resource from the bytes of its resource group
+    pub fn find_resource_group_member(
+        &self,
+        tag: &StructTag,
+        group_bytes: &[u8],
+    ) -> Result<Option<Bytes>> {
+        let group: BTreeMap<StructTag, Bytes> = bcs::from_bytes(group_bytes)?;
+        Ok(group.get(tag).cloned())
+    }
+
     pub fn try_into_resources_from_resource_group(
         &self,
         bytes: &[u8],
@@ -37,20 +37,22 @@ pub use move_types::{
     verify_field_identifier, verify_function_identifier, verify_module_identifier, EntryFunctionId,
     HexEncodedBytes, MoveAbility, MoveFunction, MoveFunctionGenericTypeParam,
     MoveFunctionVisibility, MoveModule, MoveModuleBytecode, MoveModuleId, MoveResource,
-    MoveScriptBytecode, MoveStruct, MoveStructField, MoveStructTag, MoveType, MoveValue,
-    ResourceGroup, MAX_RECURSIVE_TYPES_ALLOWED, U128, U256, U64,
+    MoveResourceChange, MoveScriptBytecode, MoveStruct, MoveStructField, MoveStructTag, MoveType,
+    MoveValue, ResourceGroup, MAX_RECURSIVE_TYPES_ALLOWED, U128, U256, U64,
 };
 use serde::{Deserialize, Deserializer};
 pub use state::RawStateValueRequest;
 use std::str::FromStr;
 pub use table::{RawTableItemRequest, TableItemRequest};
 pub use transaction::{
-    AbstractSignature, AccountSignature, BlockMetadataTransaction, DeleteModule, DeleteResource,
-    DeleteTableItem, DirectWriteSet, Ed25519Signature, EncodeSubmissionRequest,
-    EntryFunctionPayload, Event, FeePayerSignature, GasEstimation, GasEstimationBcs,
-    GenesisPayload, GenesisTransaction, MultiAgentSignature, MultiEd25519Signature,
-    MultiKeySignature, MultisigPayload, MultisigTransactionPayload, NoAccountSignature,
-    PendingTransaction, PublicKey, ScriptPayload, ScriptWriteSet, Signature, SingleKeySignature,
+    AbstractSignature, AccountSignature, BalanceOverride, BlockMetadataTransaction, DeleteModule,
+    DeleteResource, DeleteTableItem, DirectWriteSet, Ed25519Signature, EncodeSubmissionRequest,
+    EntryFunctionPayload, Event, ExecutionTrace, ExecutionTraceAbort, ExecutionTraceCall,
+    ExecutionTraceEvent, FeePayerSignature, GasEstimation, GasEstimationBcs, GenesisPayload,
+    GenesisTransaction, MultiAgentSignature, MultiEd25519Signature, MultiKeySignature,
+    MultisigPayload, MultisigTransactionPayload, NoAccountSignature, PendingTransaction, PublicKey,
+    ResourceOverride, ScriptPayload, ScriptWriteSet, Signature,
+    SimulateTransactionWithOverridesRequest, SingleKeySignature, StateOverrides,
     SubmitTransactionRequest, Transaction, TransactionData, TransactionId, TransactionInfo,
     TransactionOnChainData, TransactionPayload, TransactionSignature, TransactionSigningMessage,
     TransactionSummary, TransactionsBatchSingleSubmissionFailure,
@@ -58,7 +60,7 @@ pub use transaction::{
     UserTransactionRequest, VersionedEvent, WriteModule, WriteResource, WriteSet, WriteSetChange,
     WriteSetPayload, WriteTableItem,
 };
-pub use view::{ViewFunction, ViewRequest};
+pub use view::{ViewFunction, ViewFunctionResult, ViewRequest};
 pub use wrappers::{EventGuid, IdentifierWrapper, StateKeyWrapper};
 
 pub fn deserialize_from_string<'de, D, T>(deserializer: D) -> Result<T, D::Error>
@@ -42,6 +42,15 @@ pub struct MoveResource {
     pub data: MoveStructValue,
 }
 
+/// A change to a Move resource at a specific ledger version
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct MoveResourceChange {
+    /// The ledger version of the transaction that changed the resource
+    pub version: U64,
+    /// The resource after the change (or empty, if the resource was deleted)
+    pub resource: Option<MoveResource>,
+}
+
 impl TryFrom<AnnotatedMoveStruct> for MoveResource {
     type Error = anyhow::Error;
 
@@ -327,6 +327,7 @@ impl
             request: (txn, payload).into(),
             events,
             timestamp: timestamp.into(),
+            trace: None,
         })
     }
 }
@@ -411,6 +412,11 @@ pub struct UserTransaction {
     /// Events generated by the transaction
     pub events: Vec<Event>,
     pub timestamp: U64,
+    /// The execution trace of the transaction. Only present for simulated
+    /// transactions, when the trace was requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[oai(skip_serializing_if_is_none)]
+    pub trace: Option<ExecutionTrace>,
 }
 
 /// A state checkpoint transaction
@@ -458,6 +464,159 @@ impl VerifyInput for SubmitTransactionRequest {
     }
 }
 
+/// A request to simulate a transaction on top of overridden state
+///
+/// The transaction must have a zero-padded signature (as with simulate transaction).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct SimulateTransactionWithOverridesRequest {
+    pub transaction: SubmitTransactionRequest,
+    #[serde(default)]
+    #[oai(default)]
+    pub state_overrides: StateOverrides,
+}
+
+impl VerifyInput for SimulateTransactionWithOverridesRequest {
+    fn verify(&self) -> anyhow::Result<()> {
+        self.transaction.verify()?;
+        self.state_overrides.verify()
+    }
+}
+
+/// State overrides that are applied on top of the on-chain state before simulation
+///
+/// The overrides only affect the simulation, and are never persisted.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct StateOverrides {
+    /// Resources to replace (or delete) before simulation
+    #[serde(default)]
+    #[oai(default)]
+    pub resources: Vec<ResourceOverride>,
+    /// BCS encoded module bytecode to inject (or replace) before simulation.
+    /// Each module is published at the address in its module id.
+    #[serde(default)]
+    #[oai(default)]
+    pub modules: Vec<HexEncodedBytes>,
+    /// APT balances to override before simulation. Each balance is set in the primary
+    /// fungible store, and any balance in the legacy coin store is cleared.
+    #[serde(default)]
+    #[oai(default)]
+    pub balances: Vec<BalanceOverride>,
+    /// The on-chain timestamp (in microseconds) to use for simulation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp_usecs: Option<U64>,
+}
+
+impl StateOverrides {
+    /// Returns true iff there are no state overrides
+    pub fn is_empty(&self) -> bool {
+        self.resources.is_empty()
+            && self.modules.is_empty()
+            && self.balances.is_empty()
+            && self.timestamp_usecs.is_none()
+    }
+}
+
+impl VerifyInput for StateOverrides {
+    fn verify(&self) -> anyhow::Result<()> {
+        for resource in &self.resources {
+            resource.resource_type.verify(0)?;
+        }
+        Ok(())
+    }
+}
+
+/// A resource to replace (or delete) before simulation
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct ResourceOverride {
+    pub address: Address,
+    /// The type of the resource, e.g., `0x1::account::Account`
+    pub resource_type: MoveStructTag,
+    /// The JSON encoded resource data (in the same format returned by the
+    /// account resource API). If not provided, the resource is deleted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+}
+
+/// An APT balance to override before simulation
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct BalanceOverride {
+    pub address: Address,
+    /// The new APT balance (in octas)
+    pub amount: U64,
+}
+
+/// The execution trace of a simulated transaction
+///
+/// The trace is derived from the call graph recorded by the gas profiler. Argument
+/// values are rendered in a compact, human-readable format, and may be truncated.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct ExecutionTrace {
+    /// The call tree, starting at the entry function (or script)
+    pub root: ExecutionTraceCall,
+    /// The events emitted during execution, in emission order
+    pub events: Vec<ExecutionTraceEvent>,
+    /// The location of the abort, if the execution failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[oai(skip_serializing_if_is_none)]
+    pub abort: Option<ExecutionTraceAbort>,
+}
+
+/// A single function call in an execution trace
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct ExecutionTraceCall {
+    /// The fully qualified function name, e.g., `0x1::coin::transfer`
+    pub function: String,
+    pub type_arguments: Vec<String>,
+    /// The rendered arguments. Not available for the entry function (or script).
+    pub arguments: Vec<String>,
+    /// The rendered return values. Only available for native functions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[oai(skip_serializing_if_is_none)]
+    pub return_values: Option<Vec<String>>,
+    pub is_native: bool,
+    /// Whether the function returned (as opposed to aborting or running out of gas)
+    pub returned: bool,
+    /// The execution gas used by the call (including nested calls), in internal gas units
+    pub execution_gas: U64,
+    /// The calls made by this function, in execution order
+    pub calls: Vec<ExecutionTraceCall>,
+}
+
+/// An event emitted during the execution of a simulated transaction
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct ExecutionTraceEvent {
+    pub event_type: String,
+    /// The function that emitted the event
+    pub emitted_by: String,
+    /// The rendered event data
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[oai(skip_serializing_if_is_none)]
+    pub data: Option<String>,
+}
+
+/// The location at which a simulated transaction was aborted
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct ExecutionTraceAbort {
+    /// The module (or script) in which the abort occurred
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[oai(skip_serializing_if_is_none)]
+    pub location: Option<String>,
+    /// The innermost function that did not return
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[oai(skip_serializing_if_is_none)]
+    pub function: Option<String>,
+    /// The abort code (only for Move aborts)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[oai(skip_serializing_if_is_none)]
+    pub code: Option<U64>,
+    /// The code offset of the failing instruction (only for execution failures)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[oai(skip_serializing_if_is_none)]
+    pub code_offset: Option<u16>,
+    /// A human-readable description of the failure
+    pub reason: String,
+}
+
 /// Batch transaction submission result
 ///
 /// Tells which transactions failed
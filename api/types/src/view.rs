@@ -1,7 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{EntryFunctionId, MoveType};
+use crate::{EntryFunctionId, MoveType, U64};
 use aptos_types::serde_helper::vec_bytes;
 use move_core_types::{
     identifier::Identifier,
@@ -20,6 +20,44 @@ pub struct ViewRequest {
     pub arguments: Vec<serde_json::Value>,
 }
 
+/// The result of a single view function in a batch view request
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct ViewFunctionResult {
+    /// Return values of the function (if the function executed successfully)
+    pub values: Option<Vec<serde_json::Value>>,
+    /// A message describing the error (if the function failed)
+    pub error: Option<String>,
+    /// A code providing VM error details (if the function failed in the VM)
+    pub vm_error_code: Option<u64>,
+    /// The gas used by the function
+    pub gas_used: U64,
+}
+
+impl ViewFunctionResult {
+    pub fn success(values: Vec<serde_json::Value>, gas_used: u64) -> Self {
+        Self {
+            values: Some(values),
+            error: None,
+            vm_error_code: None,
+            gas_used: gas_used.into(),
+        }
+    }
+
+    pub fn failure(error: String, vm_error_code: Option<u64>, gas_used: u64) -> Self {
+        Self {
+            values: None,
+            error: Some(error),
+            vm_error_code,
+            gas_used: gas_used.into(),
+        }
+    }
+
+    /// Returns true iff the function executed successfully
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
 #[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
 pub struct ViewFunction {
     pub module: ModuleId,
@@ -121,6 +121,27 @@ impl AptosDebugger {
         version: Version,
         txn: SignedTransaction,
         auxiliary_info: AuxiliaryInfo,
+    ) -> anyhow::Result<(VMStatus, VMOutput, TransactionGasLog)> {
+        self.execute_transaction_at_version_with_profiler(version, txn, auxiliary_info, false)
+    }
+
+    /// Executes the transaction with the gas profiler, and records the values of function
+    /// arguments and return values, so that an execution trace can be built from the gas log.
+    pub fn execute_transaction_at_version_with_value_tracing(
+        &self,
+        version: Version,
+        txn: SignedTransaction,
+        auxiliary_info: AuxiliaryInfo,
+    ) -> anyhow::Result<(VMStatus, VMOutput, TransactionGasLog)> {
+        self.execute_transaction_at_version_with_profiler(version, txn, auxiliary_info, true)
+    }
+
+    fn execute_transaction_at_version_with_profiler(
+        &self,
+        version: Version,
+        txn: SignedTransaction,
+        auxiliary_info: AuxiliaryInfo,
+        trace_values: bool,
     ) -> anyhow::Result<(VMStatus, VMOutput, TransactionGasLog)> {
         let state_view = DebuggerStateView::new(self.debugger.clone(), version);
         let log_context = AdapterLogSchema::new(state_view.id(), 0);
@@ -162,7 +183,11 @@ impl AptosDebugger {
                         unimplemented!("not supported yet")
                     },
                 };
-                gas_profiler
+                if trace_values {
+                    gas_profiler.with_value_tracing()
+                } else {
+                    gas_profiler
+                }
             },
             &auxiliary_info,
         )?;
@@ -0,0 +1,548 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    log::TransactionGasLog,
+    render::Render,
+    report::{ensure_dirs_exist, render_table},
+};
+use anyhow::{bail, Result};
+use aptos_gas_algebra::InternalGas;
+use handlebars::Handlebars;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter},
+    fs,
+    path::Path,
+};
+
+const TEMPLATE: &str = include_str!("../templates/diff.html");
+
+/// The name of the file the summary of a gas profile is saved to, next to its html report.
+pub const GAS_PROFILE_SUMMARY_FILE: &str = "summary.json";
+
+/// The number of hits and the total cost of an item of a gas profile.
+///
+/// Execution & IO costs are in internal gas units, while storage fees are in octas.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct GasProfileEntry {
+    pub name: String,
+    pub hits: usize,
+    pub cost: u64,
+}
+
+/// A serializable summary of a [`TransactionGasLog`], with all costs aggregated per function,
+/// instruction and storage item.
+///
+/// Unlike the gas log itself, the summary can be saved to disk and compared against the
+/// summary of a later run of the same transaction.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GasProfileSummary {
+    pub entry_point: String,
+    pub gas_scaling_factor: u64,
+
+    /// Total execution & IO cost, in internal gas units.
+    pub execution_and_io: u64,
+    pub intrinsic: u64,
+    pub keyless: u64,
+    pub transaction_write: u64,
+    pub dependencies: Vec<GasProfileEntry>,
+    pub ops: Vec<GasProfileEntry>,
+    pub methods: Vec<GasProfileEntry>,
+    pub methods_self: Vec<GasProfileEntry>,
+    pub storage_reads: Vec<GasProfileEntry>,
+    pub storage_writes: Vec<GasProfileEntry>,
+    pub event_writes: Vec<GasProfileEntry>,
+
+    /// Total storage fee, in octas.
+    pub storage_fee: u64,
+    pub storage_refund: u64,
+    pub txn_storage_fee: u64,
+    pub storage_fee_writes: Vec<GasProfileEntry>,
+    pub storage_refund_writes: Vec<GasProfileEntry>,
+    pub storage_fee_events: Vec<GasProfileEntry>,
+}
+
+impl TransactionGasLog {
+    /// Summarizes the gas log so it can be saved and compared against other runs.
+    pub fn to_summary(&self) -> GasProfileSummary {
+        let convert = |entries: Vec<(String, usize, InternalGas)>| {
+            entries
+                .into_iter()
+                .map(|(name, hits, cost)| GasProfileEntry {
+                    name,
+                    hits,
+                    cost: cost.into(),
+                })
+                .collect()
+        };
+        let aggregated = self.exec_io.aggregate_gas_events();
+
+        GasProfileSummary {
+            entry_point: self.entry_point().to_string(),
+            gas_scaling_factor: self.exec_io.gas_scaling_factor.into(),
+
+            execution_and_io: self.exec_io.total.into(),
+            intrinsic: self.exec_io.intrinsic_cost.into(),
+            keyless: self.exec_io.keyless_cost.into(),
+            transaction_write: aggregated.transaction_write.into(),
+            dependencies: self
+                .exec_io
+                .dependencies
+                .iter()
+                .map(|dep| GasProfileEntry {
+                    name: dep.render(),
+                    hits: 1,
+                    cost: dep.cost.into(),
+                })
+                .collect(),
+            ops: convert(aggregated.ops),
+            methods: convert(aggregated.methods),
+            methods_self: convert(aggregated.methods_self),
+            storage_reads: convert(aggregated.storage_reads),
+            storage_writes: convert(aggregated.storage_writes),
+            event_writes: convert(aggregated.event_writes),
+
+            storage_fee: self.storage.total.into(),
+            storage_refund: self.storage.total_refund.into(),
+            txn_storage_fee: self.storage.txn_storage.into(),
+            storage_fee_writes: self
+                .storage
+                .write_set_storage
+                .iter()
+                .map(|write| GasProfileEntry {
+                    name: format!("{}", Render(&write.key)),
+                    hits: 1,
+                    cost: write.cost.into(),
+                })
+                .collect(),
+            storage_refund_writes: self
+                .storage
+                .write_set_storage
+                .iter()
+                .filter(|write| !write.refund.is_zero())
+                .map(|write| GasProfileEntry {
+                    name: format!("{}", Render(&write.key)),
+                    hits: 1,
+                    cost: write.refund.into(),
+                })
+                .collect(),
+            storage_fee_events: aggregate_by_name(
+                self.storage
+                    .events
+                    .iter()
+                    .map(|event| (event.ty.to_canonical_string(), event.cost.into())),
+            ),
+        }
+    }
+}
+
+/// Aggregates the costs of items with the same name (e.g., events of the same type).
+fn aggregate_by_name(items: impl IntoIterator<Item = (String, u64)>) -> Vec<GasProfileEntry> {
+    let mut entries: BTreeMap<String, GasProfileEntry> = BTreeMap::new();
+    for (name, cost) in items {
+        let entry = entries
+            .entry(name.clone())
+            .or_insert_with(|| GasProfileEntry {
+                name,
+                hits: 0,
+                cost: 0,
+            });
+        entry.hits += 1;
+        entry.cost += cost;
+    }
+    entries.into_values().collect()
+}
+
+impl GasProfileSummary {
+    /// Loads the summary saved along with the html report in the given directory.
+    pub fn load_from_report_dir(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().join(GAS_PROFILE_SUMMARY_FILE);
+        if !path.exists() {
+            bail!(
+                "No gas profile summary found at {}. Was the report generated by an older version?",
+                path.display()
+            );
+        }
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+}
+
+/// The change of an item of a gas profile between two runs.
+///
+/// Items missing from one of the runs are reported with zero hits and cost for that run.
+#[derive(Debug, Clone, Serialize, Eq, PartialEq)]
+pub struct GasProfileEntryDiff {
+    pub name: String,
+    pub base_hits: usize,
+    pub new_hits: usize,
+    pub base_cost: u64,
+    pub new_cost: u64,
+    pub delta: i128,
+}
+
+impl GasProfileEntryDiff {
+    fn new(name: String, base: Option<&GasProfileEntry>, new: Option<&GasProfileEntry>) -> Self {
+        let (base_hits, base_cost) = base.map_or((0, 0), |entry| (entry.hits, entry.cost));
+        let (new_hits, new_cost) = new.map_or((0, 0), |entry| (entry.hits, entry.cost));
+        Self {
+            name,
+            base_hits,
+            new_hits,
+            base_cost,
+            new_cost,
+            delta: new_cost as i128 - base_cost as i128,
+        }
+    }
+
+    fn total(name: &str, base: u64, new: u64) -> Self {
+        Self::new(
+            name.to_string(),
+            Some(&GasProfileEntry {
+                name: name.to_string(),
+                hits: 1,
+                cost: base,
+            }),
+            Some(&GasProfileEntry {
+                name: name.to_string(),
+                hits: 1,
+                cost: new,
+            }),
+        )
+    }
+
+    fn is_unchanged(&self) -> bool {
+        self.delta == 0 && self.base_hits == self.new_hits
+    }
+}
+
+/// Compares the entries of two runs by name. Entries are sorted by the absolute change in
+/// cost, from high to low.
+fn diff_entries(base: &[GasProfileEntry], new: &[GasProfileEntry]) -> Vec<GasProfileEntryDiff> {
+    let mut entries: BTreeMap<&str, (Option<&GasProfileEntry>, Option<&GasProfileEntry>)> =
+        BTreeMap::new();
+    for entry in base {
+        entries.entry(entry.name.as_str()).or_default().0 = Some(entry);
+    }
+    for entry in new {
+        entries.entry(entry.name.as_str()).or_default().1 = Some(entry);
+    }
+
+    let mut diffs = entries
+        .into_iter()
+        .map(|(name, (base, new))| GasProfileEntryDiff::new(name.to_string(), base, new))
+        .collect::<Vec<_>>();
+    diffs.sort_by(|lhs, rhs| rhs.delta.abs().cmp(&lhs.delta.abs()));
+    diffs
+}
+
+/// The unit a section of a gas profile diff is measured in.
+#[derive(Debug, Clone, Copy, Serialize, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum GasProfileUnit {
+    /// Internal gas units, displayed in external gas units.
+    Gas,
+    /// Octas, displayed in APT.
+    Octas,
+}
+
+/// A section of a gas profile diff, e.g., the costs per function.
+#[derive(Debug, Clone, Serialize)]
+pub struct GasProfileDiffSection {
+    pub title: String,
+    pub unit: GasProfileUnit,
+    pub entries: Vec<GasProfileEntryDiff>,
+}
+
+/// A side-by-side comparison of the gas profiles of two runs of the same transaction, e.g.,
+/// before and after a code change, or under two different gas schedules.
+#[derive(Debug, Clone, Serialize)]
+pub struct GasProfileDiff {
+    pub base_entry_point: String,
+    pub new_entry_point: String,
+    pub gas_scaling_factor: u64,
+    pub totals: GasProfileDiffSection,
+    pub sections: Vec<GasProfileDiffSection>,
+}
+
+impl GasProfileDiff {
+    /// Compares the gas profile of a new run against the one of a base run.
+    pub fn new(base: &GasProfileSummary, new: &GasProfileSummary) -> Result<Self> {
+        if base.gas_scaling_factor != new.gas_scaling_factor {
+            bail!(
+                "Cannot compare gas profiles with different gas scaling factors ({} and {})",
+                base.gas_scaling_factor,
+                new.gas_scaling_factor
+            );
+        }
+
+        let gas_section = |title: &str, entries: Vec<GasProfileEntryDiff>| GasProfileDiffSection {
+            title: title.to_string(),
+            unit: GasProfileUnit::Gas,
+            entries,
+        };
+        let fee_section = |title: &str, entries: Vec<GasProfileEntryDiff>| GasProfileDiffSection {
+            title: title.to_string(),
+            unit: GasProfileUnit::Octas,
+            entries,
+        };
+
+        let totals = vec![
+            GasProfileEntryDiff::total(
+                "Execution & IO",
+                base.execution_and_io,
+                new.execution_and_io,
+            ),
+            GasProfileEntryDiff::total("Intrinsic", base.intrinsic, new.intrinsic),
+            GasProfileEntryDiff::total("Keyless", base.keyless, new.keyless),
+            GasProfileEntryDiff::total(
+                "Transaction write",
+                base.transaction_write,
+                new.transaction_write,
+            ),
+        ];
+        let storage_totals = vec![
+            GasProfileEntryDiff::total("Storage fee", base.storage_fee, new.storage_fee),
+            GasProfileEntryDiff::total("Storage refund", base.storage_refund, new.storage_refund),
+            GasProfileEntryDiff::total(
+                "Transaction storage fee",
+                base.txn_storage_fee,
+                new.txn_storage_fee,
+            ),
+        ];
+
+        Ok(Self {
+            base_entry_point: base.entry_point.clone(),
+            new_entry_point: new.entry_point.clone(),
+            gas_scaling_factor: base.gas_scaling_factor,
+            totals: gas_section("Totals", totals),
+            sections: vec![
+                fee_section("Storage Totals", storage_totals),
+                gas_section(
+                    "Dependencies",
+                    diff_entries(&base.dependencies, &new.dependencies),
+                ),
+                gas_section("Instructions", diff_entries(&base.ops, &new.ops)),
+                gas_section(
+                    "Functions (inclusive)",
+                    diff_entries(&base.methods, &new.methods),
+                ),
+                gas_section(
+                    "Functions (self)",
+                    diff_entries(&base.methods_self, &new.methods_self),
+                ),
+                gas_section(
+                    "Storage Reads",
+                    diff_entries(&base.storage_reads, &new.storage_reads),
+                ),
+                gas_section(
+                    "Storage Writes (IO)",
+                    diff_entries(&base.storage_writes, &new.storage_writes),
+                ),
+                gas_section(
+                    "Event Writes (IO)",
+                    diff_entries(&base.event_writes, &new.event_writes),
+                ),
+                fee_section(
+                    "Storage Fees for State Changes",
+                    diff_entries(&base.storage_fee_writes, &new.storage_fee_writes),
+                ),
+                fee_section(
+                    "Storage Refunds for State Changes",
+                    diff_entries(&base.storage_refund_writes, &new.storage_refund_writes),
+                ),
+                fee_section(
+                    "Storage Fees for Events",
+                    diff_entries(&base.storage_fee_events, &new.storage_fee_events),
+                ),
+            ],
+        })
+    }
+
+    /// Returns true iff the costs of all items are the same in both runs.
+    pub fn is_unchanged(&self) -> bool {
+        std::iter::once(&self.totals)
+            .chain(&self.sections)
+            .all(|section| section.entries.iter().all(|entry| entry.is_unchanged()))
+    }
+
+    fn format_cost(&self, unit: GasProfileUnit, cost: i128) -> String {
+        let scaling_factor = match unit {
+            GasProfileUnit::Gas => self.gas_scaling_factor as f64,
+            GasProfileUnit::Octas => 1_0000_0000f64,
+        };
+        let scaled = format!("{:.8}", cost as f64 / scaling_factor);
+        crate::misc::strip_trailing_zeros_and_decimal_point(&scaled).to_string()
+    }
+
+    fn format_delta(&self, unit: GasProfileUnit, entry: &GasProfileEntryDiff) -> String {
+        let delta = self.format_cost(unit, entry.delta);
+        let delta = if entry.delta > 0 {
+            format!("+{}", delta)
+        } else {
+            delta
+        };
+        if entry.base_cost == 0 {
+            delta
+        } else {
+            format!(
+                "{} ({:+.2}%)",
+                delta,
+                entry.delta as f64 / entry.base_cost as f64 * 100.0
+            )
+        }
+    }
+
+    fn render_section(&self, section: &GasProfileDiffSection) -> Value {
+        let unit = match section.unit {
+            GasProfileUnit::Gas => "Gas Units",
+            GasProfileUnit::Octas => "APT",
+        };
+        json!({
+            "title": section.title,
+            "unit": unit,
+            "entries": section
+                .entries
+                .iter()
+                .map(|entry| {
+                    let class = match entry.delta.signum() {
+                        1 => "increase",
+                        -1 => "decrease",
+                        _ => "",
+                    };
+                    json!({
+                        "name": entry.name,
+                        "base-hits": entry.base_hits,
+                        "new-hits": entry.new_hits,
+                        "base-cost": self.format_cost(section.unit, entry.base_cost as i128),
+                        "new-cost": self.format_cost(section.unit, entry.new_cost as i128),
+                        "delta": self.format_delta(section.unit, entry),
+                        "class": class,
+                    })
+                })
+                .collect::<Vec<_>>(),
+        })
+    }
+
+    /// Generates an html report showing both runs side by side, with increases and decreases
+    /// highlighted.
+    pub fn generate_html_report(&self, path: impl AsRef<Path>, header: String) -> Result<()> {
+        let data = json!({
+            "title": header,
+            "base-entry-point": self.base_entry_point,
+            "new-entry-point": self.new_entry_point,
+            "totals": self.render_section(&self.totals),
+            "sections": self
+                .sections
+                .iter()
+                .map(|section| self.render_section(section))
+                .collect::<Vec<_>>(),
+        });
+
+        let mut handlebars = Handlebars::new();
+        handlebars.register_template_string("diff", TEMPLATE)?;
+        let html = handlebars.render("diff", &data)?;
+
+        let path_root = path.as_ref();
+        ensure_dirs_exist(path_root)?;
+        fs::write(path_root.join("index.html"), html)?;
+
+        Ok(())
+    }
+}
+
+impl Display for GasProfileDiff {
+    /// Renders the changed items of all sections as plain text tables.
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        if self.base_entry_point != self.new_entry_point {
+            writeln!(
+                f,
+                "Warning: comparing different entry points ({} and {})",
+                self.base_entry_point, self.new_entry_point
+            )?;
+        }
+        if self.is_unchanged() {
+            return writeln!(f, "No gas changes");
+        }
+
+        for section in std::iter::once(&self.totals).chain(&self.sections) {
+            let changed = section
+                .entries
+                .iter()
+                .filter(|entry| !entry.is_unchanged())
+                .collect::<Vec<_>>();
+            if changed.is_empty() {
+                continue;
+            }
+
+            writeln!(f, "\n{}:", section.title)?;
+            let mut table = vec![[
+                "  Name".to_string(),
+                "Base".to_string(),
+                "New".to_string(),
+                "Delta".to_string(),
+            ]];
+            for entry in changed {
+                table.push([
+                    format!("  {}", entry.name),
+                    self.format_cost(section.unit, entry.base_cost as i128),
+                    self.format_cost(section.unit, entry.new_cost as i128),
+                    self.format_delta(section.unit, entry),
+                ]);
+            }
+            render_table(f, &table, 4)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, hits: usize, cost: u64) -> GasProfileEntry {
+        GasProfileEntry {
+            name: name.to_string(),
+            hits,
+            cost,
+        }
+    }
+
+    #[test]
+    fn test_diff_entries() {
+        let base = vec![
+            entry("add", 2, 10),
+            entry("call", 1, 100),
+            entry("ld_u64", 3, 6),
+        ];
+        let new = vec![
+            entry("add", 2, 10),
+            entry("call", 2, 150),
+            entry("mul", 1, 70),
+        ];
+
+        let diffs = diff_entries(&base, &new);
+        let summary = diffs
+            .iter()
+            .map(|diff| {
+                (
+                    diff.name.as_str(),
+                    diff.base_cost,
+                    diff.new_cost,
+                    diff.delta,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(summary, vec![
+            ("mul", 0, 70, 70),
+            ("call", 100, 150, 50),
+            ("ld_u64", 6, 0, -6),
+            ("add", 10, 10, 0),
+        ]);
+        assert!(diffs[3].is_unchanged());
+        assert_eq!((diffs[1].base_hits, diffs[1].new_hits), (1, 2));
+    }
+}
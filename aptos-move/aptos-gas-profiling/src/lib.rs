@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod aggregate;
+mod diff;
 mod erased;
 mod flamegraph;
 mod log;
@@ -9,6 +10,12 @@ mod misc;
 mod profiler;
 mod render;
 mod report;
+mod trace;
 
+pub use diff::{
+    GasProfileDiff, GasProfileDiffSection, GasProfileEntry, GasProfileEntryDiff, GasProfileSummary,
+    GasProfileUnit, GAS_PROFILE_SUMMARY_FILE,
+};
 pub use log::{FrameName, TransactionGasLog};
 pub use profiler::GasProfiler;
+pub use trace::{AbortTrace, CallTrace, EventTrace, ExecutionTrace};
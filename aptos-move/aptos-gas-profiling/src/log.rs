@@ -31,6 +31,11 @@ pub enum ExecutionGasEvent {
         fn_name: Identifier,
         ty_args: Vec<TypeTag>,
         cost: InternalGas,
+        /// The rendered arguments. Only recorded if value tracing is enabled.
+        args: Vec<String>,
+        /// The rendered return values, if the native function returned. The values
+        /// themselves are only recorded if value tracing is enabled.
+        ret_vals: Option<Vec<String>>,
     },
     LoadResource {
         addr: AccountAddress,
@@ -62,6 +67,10 @@ pub struct CallFrame {
     pub events: Vec<ExecutionGasEvent>,
     /// Accumulates gas charged by native functions. For frames of non-native functions, kept as 0.
     pub native_gas: InternalGas,
+    /// The rendered arguments. Only recorded if value tracing is enabled.
+    pub args: Vec<String>,
+    /// Whether the function returned (as opposed to aborting or running out of gas).
+    pub returned: bool,
 }
 
 /// The type of an operation performed on a storage item.
@@ -198,6 +207,8 @@ impl CallFrame {
             },
             events: vec![],
             native_gas: 0.into(),
+            args: vec![],
+            returned: false,
         }
     }
 
@@ -206,6 +217,8 @@ impl CallFrame {
             name: FrameName::Script,
             events: vec![],
             native_gas: 0.into(),
+            args: vec![],
+            returned: false,
         }
     }
 }
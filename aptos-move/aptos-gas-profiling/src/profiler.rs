@@ -1,9 +1,13 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::log::{
-    CallFrame, Dependency, EventStorage, EventTransient, ExecutionAndIOCosts, ExecutionGasEvent,
-    FrameName, StorageFees, TransactionGasLog, WriteOpType, WriteStorage, WriteTransient,
+use crate::{
+    log::{
+        CallFrame, Dependency, EventStorage, EventTransient, ExecutionAndIOCosts,
+        ExecutionGasEvent, FrameName, StorageFees, TransactionGasLog, WriteOpType, WriteStorage,
+        WriteTransient,
+    },
+    trace::render_value,
 };
 use aptos_gas_algebra::{Fee, FeePerGasUnit, InternalGas, NumArgs, NumBytes, NumTypeNodes};
 use aptos_gas_meter::{AptosGasMeter, GasAlgebra};
@@ -43,6 +47,9 @@ pub struct GasProfiler<G> {
     events_transient: Vec<EventTransient>,
     write_set_transient: Vec<WriteTransient>,
     storage_fees: Option<StorageFees>,
+
+    // Whether to record the arguments and return values of function calls
+    trace_values: bool,
 }
 
 // TODO: consider switching to a library like https://docs.rs/delegate/latest/delegate/.
@@ -100,6 +107,8 @@ impl<G> GasProfiler<G> {
             events_transient: vec![],
             write_set_transient: vec![],
             storage_fees: None,
+
+            trace_values: false,
         }
     }
 
@@ -120,8 +129,17 @@ impl<G> GasProfiler<G> {
             events_transient: vec![],
             write_set_transient: vec![],
             storage_fees: None,
+
+            trace_values: false,
         }
     }
+
+    /// Enables the recording of function arguments and return values, which are
+    /// needed to build an execution trace. This makes profiling more expensive.
+    pub fn with_value_tracing(mut self) -> Self {
+        self.trace_values = true;
+        self
+    }
 }
 
 impl<G> GasProfiler<G>
@@ -144,6 +162,15 @@ where
         self.record_gas_event(ExecutionGasEvent::Loc(offset))
     }
 
+    /// Renders the given values, if value tracing is enabled
+    fn render_values(
+        &self,
+        vals: impl ExactSizeIterator<Item = impl ValueView>,
+    ) -> Option<Vec<String>> {
+        self.trace_values
+            .then(|| vals.map(|val| render_value(&val)).collect())
+    }
+
     /// Delegate the charging call to the base gas meter and measure variation in balance.
     fn delegate_charge<F, R>(&mut self, charge: F) -> (InternalGas, R)
     where
@@ -393,6 +420,9 @@ where
         // Later when it realizes the function is native, it will transform the original frame
         // into a native-specific event that does not contain recursive structures.
         let frame = self.frames.pop().expect("frame must exist");
+        let rendered_ret_vals = ret_vals
+            .clone()
+            .map(|ret_vals| self.render_values(ret_vals).unwrap_or_default());
 
         // Add native gas accumulated per frame.
         let (mut cost, res) =
@@ -419,6 +449,8 @@ where
             fn_name: name,
             ty_args,
             cost,
+            args: frame.args,
+            ret_vals: rendered_ret_vals,
         });
 
         res
@@ -465,10 +497,15 @@ where
         //       This is a bit hacky and can lead to weird behaviors if the profiler is used
         //       over multiple transactions, but again, guarding against that case is a broader
         //       problem we can deal with in the future.
-        if matches!(instr, SimpleInstruction::Ret) && self.frames.len() > 1 {
-            let cur_frame = self.frames.pop().expect("frame must exist");
-            let last_frame = self.frames.last_mut().expect("frame must exist");
-            last_frame.events.push(ExecutionGasEvent::Call(cur_frame));
+        if matches!(instr, SimpleInstruction::Ret) {
+            if self.frames.len() > 1 {
+                let mut cur_frame = self.frames.pop().expect("frame must exist");
+                cur_frame.returned = true;
+                let last_frame = self.frames.last_mut().expect("frame must exist");
+                last_frame.events.push(ExecutionGasEvent::Call(cur_frame));
+            } else {
+                self.frames.last_mut().expect("frame must exist").returned = true;
+            }
         }
 
         res
@@ -481,15 +518,18 @@ where
         args: impl ExactSizeIterator<Item = impl ValueView> + Clone,
         num_locals: NumArgs,
     ) -> PartialVMResult<()> {
+        let rendered_args = self.render_values(args.clone());
         let (cost, res) =
             self.delegate_charge(|base| base.charge_call(module_id, func_name, args, num_locals));
 
         self.record_bytecode(Opcodes::CALL, cost);
-        self.frames.push(CallFrame::new_function(
+        let mut frame = CallFrame::new_function(
             module_id.clone(),
             Identifier::new(func_name).unwrap(),
             vec![],
-        ));
+        );
+        frame.args = rendered_args.unwrap_or_default();
+        self.frames.push(frame);
 
         res
     }
@@ -506,17 +546,20 @@ where
             .clone()
             .map(|ty| ty.to_type_tag())
             .collect::<Vec<_>>();
+        let rendered_args = self.render_values(args.clone());
 
         let (cost, res) = self.delegate_charge(|base| {
             base.charge_call_generic(module_id, func_name, ty_args, args, num_locals)
         });
 
         self.record_bytecode(Opcodes::CALL_GENERIC, cost);
-        self.frames.push(CallFrame::new_function(
+        let mut frame = CallFrame::new_function(
             module_id.clone(),
             Identifier::new(func_name).unwrap(),
             ty_tags,
-        ));
+        );
+        frame.args = rendered_args.unwrap_or_default();
+        self.frames.push(frame);
 
         res
     }
@@ -0,0 +1,439 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::log::{CallFrame, ExecutionGasEvent, FrameName, TransactionGasLog};
+use aptos_gas_algebra::InternalGas;
+use aptos_types::transaction::ExecutionStatus;
+use move_binary_format::errors::PartialVMResult;
+use move_core_types::{
+    account_address::AccountAddress, language_storage::TypeTag, u256::U256,
+    vm_status::AbortLocation,
+};
+use move_vm_types::{
+    delayed_values::delayed_field_id::DelayedFieldID,
+    views::{ValueView, ValueVisitor},
+};
+use serde::{Deserialize, Serialize};
+
+/// The maximum length of a rendered value. Longer values are truncated.
+const MAX_RENDERED_VALUE_LENGTH: usize = 512;
+
+/// The native functions used to emit (module and handle) events
+const EVENT_MODULE_NAME: &str = "event";
+const EVENT_NATIVE_FUNCTIONS: [&str; 2] = ["write_module_event_to_store", "write_to_event_store"];
+
+/// A structured trace of a transaction execution, derived from the call graph recorded by
+/// the gas profiler. Argument and return values are only available if the profiler was
+/// created with value tracing enabled.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExecutionTrace {
+    /// The call tree, starting at the entry function (or script)
+    pub root: CallTrace,
+    /// The events emitted during execution, in emission order
+    pub events: Vec<EventTrace>,
+    /// The location of the abort, if the execution failed
+    pub abort: Option<AbortTrace>,
+}
+
+/// A single function call in the execution trace
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallTrace {
+    /// The fully qualified function name (or `<script>`)
+    pub function: String,
+    pub type_args: Vec<String>,
+    /// The rendered arguments. Not available for the entry function (or script).
+    pub args: Vec<String>,
+    /// The rendered return values. Only available for native functions.
+    pub return_values: Option<Vec<String>>,
+    pub is_native: bool,
+    /// Whether the function returned (as opposed to aborting or running out of gas)
+    pub returned: bool,
+    /// The execution gas used by the call (including nested calls), in internal gas units
+    pub execution_gas: u64,
+    /// The calls made by this function, in execution order
+    pub calls: Vec<CallTrace>,
+}
+
+/// An event emitted during execution
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventTrace {
+    pub event_type: String,
+    /// The function that emitted the event
+    pub emitted_by: String,
+    /// The rendered event data
+    pub data: Option<String>,
+}
+
+/// The location at which the execution was aborted
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AbortTrace {
+    /// The module (or script) in which the abort occurred
+    pub location: Option<String>,
+    /// The innermost function that did not return
+    pub function: Option<String>,
+    /// The abort code (only for Move aborts)
+    pub code: Option<u64>,
+    /// The code offset of the failing instruction (only for execution failures)
+    pub code_offset: Option<u16>,
+    /// A human-readable description of the failure
+    pub reason: String,
+}
+
+impl ExecutionTrace {
+    /// Builds the execution trace from the gas log and the execution status of the transaction
+    pub fn new(gas_log: &TransactionGasLog, status: &ExecutionStatus) -> Self {
+        let call_graph = &gas_log.exec_io.call_graph;
+
+        let mut events = vec![];
+        collect_events(call_graph, &mut events);
+
+        let abort = match status {
+            ExecutionStatus::Success => None,
+            status => Some(AbortTrace::new(call_graph, status)),
+        };
+
+        Self {
+            root: CallTrace::from_frame(call_graph),
+            events,
+            abort,
+        }
+    }
+}
+
+impl CallTrace {
+    fn from_frame(frame: &CallFrame) -> Self {
+        let type_args = match &frame.name {
+            FrameName::Script => vec![],
+            FrameName::Function { ty_args, .. } => render_type_args(ty_args),
+        };
+
+        let mut execution_gas = frame.native_gas;
+        let mut calls = vec![];
+        for event in &frame.events {
+            match event {
+                ExecutionGasEvent::Loc(_) => (),
+                ExecutionGasEvent::Bytecode { cost, .. }
+                | ExecutionGasEvent::LoadResource { cost, .. }
+                | ExecutionGasEvent::CreateTy { cost } => execution_gas += *cost,
+                ExecutionGasEvent::Call(inner_frame) => {
+                    let call = Self::from_frame(inner_frame);
+                    execution_gas += InternalGas::new(call.execution_gas);
+                    calls.push(call);
+                },
+                ExecutionGasEvent::CallNative {
+                    module_id,
+                    fn_name,
+                    ty_args,
+                    cost,
+                    args,
+                    ret_vals,
+                } => {
+                    execution_gas += *cost;
+                    calls.push(Self {
+                        function: format!("{}::{}", module_id.short_str_lossless(), fn_name),
+                        type_args: render_type_args(ty_args),
+                        args: args.clone(),
+                        return_values: ret_vals.clone(),
+                        is_native: true,
+                        returned: ret_vals.is_some(),
+                        execution_gas: (*cost).into(),
+                        calls: vec![],
+                    });
+                },
+            }
+        }
+
+        Self {
+            function: render_function_name(&frame.name),
+            type_args,
+            args: frame.args.clone(),
+            return_values: None,
+            is_native: false,
+            returned: frame.returned,
+            execution_gas: execution_gas.into(),
+            calls,
+        }
+    }
+}
+
+impl AbortTrace {
+    fn new(call_graph: &CallFrame, status: &ExecutionStatus) -> Self {
+        let function =
+            innermost_unreturned_frame(call_graph).map(|frame| render_function_name(&frame.name));
+        let render_location = |location: &AbortLocation| match location {
+            AbortLocation::Module(module_id) => module_id.short_str_lossless(),
+            AbortLocation::Script => "<script>".to_string(),
+        };
+
+        match status {
+            ExecutionStatus::MoveAbort {
+                location,
+                code,
+                info,
+            } => Self {
+                location: Some(render_location(location)),
+                function,
+                code: Some(*code),
+                code_offset: None,
+                reason: match info {
+                    Some(info) => format!("{}: {}", info.reason_name, info.description),
+                    None => format!("Move abort with code {}", code),
+                },
+            },
+            ExecutionStatus::ExecutionFailure {
+                location,
+                code_offset,
+                ..
+            } => Self {
+                location: Some(render_location(location)),
+                function,
+                code: None,
+                code_offset: Some(*code_offset),
+                reason: "Execution failure".to_string(),
+            },
+            ExecutionStatus::OutOfGas => Self {
+                location: None,
+                function,
+                code: None,
+                code_offset: None,
+                reason: "Out of gas".to_string(),
+            },
+            ExecutionStatus::MiscellaneousError(status_code) => Self {
+                location: None,
+                function,
+                code: None,
+                code_offset: None,
+                reason: match status_code {
+                    Some(status_code) => format!("Miscellaneous error: {:?}", status_code),
+                    None => "Miscellaneous error".to_string(),
+                },
+            },
+            ExecutionStatus::Success => unreachable!("Successful executions do not abort"),
+        }
+    }
+}
+
+/// Returns the innermost frame on the path of frames that did not return
+fn innermost_unreturned_frame(frame: &CallFrame) -> Option<&CallFrame> {
+    if frame.returned {
+        return None;
+    }
+
+    // Only the last call of a frame can be unreturned
+    let last_call = frame.events.iter().rev().find_map(|event| match event {
+        ExecutionGasEvent::Call(inner_frame) => Some(inner_frame),
+        _ => None,
+    });
+    match last_call.and_then(innermost_unreturned_frame) {
+        Some(inner_frame) => Some(inner_frame),
+        None => Some(frame),
+    }
+}
+
+/// Collects the events emitted in the given frame (and all nested frames)
+fn collect_events(frame: &CallFrame, events: &mut Vec<EventTrace>) {
+    for event in &frame.events {
+        match event {
+            ExecutionGasEvent::Call(inner_frame) => collect_events(inner_frame, events),
+            ExecutionGasEvent::CallNative {
+                module_id,
+                fn_name,
+                ty_args,
+                args,
+                ..
+            } if module_id.address() == &AccountAddress::ONE
+                && module_id.name().as_str() == EVENT_MODULE_NAME
+                && EVENT_NATIVE_FUNCTIONS.contains(&fn_name.as_str()) =>
+            {
+                events.push(EventTrace {
+                    event_type: ty_args
+                        .first()
+                        .map(|ty| ty.to_canonical_string())
+                        .unwrap_or_default(),
+                    emitted_by: render_function_name(&frame.name),
+                    data: args.last().cloned(),
+                })
+            },
+            _ => (),
+        }
+    }
+}
+
+/// Renders the fully qualified name of the function (without type arguments)
+fn render_function_name(name: &FrameName) -> String {
+    match name {
+        FrameName::Script => "<script>".to_string(),
+        FrameName::Function {
+            module_id, name, ..
+        } => format!("{}::{}", module_id.short_str_lossless(), name),
+    }
+}
+
+fn render_type_args(ty_args: &[TypeTag]) -> Vec<String> {
+    ty_args.iter().map(|ty| ty.to_canonical_string()).collect()
+}
+
+/// Renders a value in a compact, human-readable format (e.g., `{0x1, [1, 2]}`)
+pub(crate) fn render_value(val: &impl ValueView) -> String {
+    let mut renderer = ValueRenderer::default();
+    let mut output = match val.visit(&mut renderer) {
+        Ok(()) => renderer.finish(),
+        Err(_) => return "<unknown>".to_string(),
+    };
+
+    if output.len() > MAX_RENDERED_VALUE_LENGTH {
+        let mut end = MAX_RENDERED_VALUE_LENGTH;
+        while !output.is_char_boundary(end) {
+            end -= 1;
+        }
+        output.truncate(end);
+        output.push_str("..");
+    }
+    output
+}
+
+/// A value visitor that renders the visited value into a string. The visitor only
+/// reports the depth of each value, so containers are closed once a value at the
+/// same (or a lower) depth is visited.
+#[derive(Default)]
+struct ValueRenderer {
+    output: String,
+    // The open containers: (depth, closing delimiter, number of elements visited so far)
+    containers: Vec<(u64, &'static str, usize)>,
+}
+
+impl ValueRenderer {
+    fn close_containers(&mut self, depth: u64) {
+        while let Some((container_depth, closing_delimiter, _)) = self.containers.last() {
+            if *container_depth < depth {
+                break;
+            }
+            self.output.push_str(closing_delimiter);
+            self.containers.pop();
+        }
+    }
+
+    fn begin_value(&mut self, depth: u64) {
+        self.close_containers(depth);
+        if let Some((_, closing_delimiter, num_elements)) = self.containers.last_mut() {
+            // References only have a single element, so no separator is needed
+            if *num_elements > 0 && !closing_delimiter.is_empty() {
+                self.output.push_str(", ");
+            }
+            *num_elements += 1;
+        }
+    }
+
+    fn render_primitive(&mut self, depth: u64, val: impl ToString) -> PartialVMResult<()> {
+        self.begin_value(depth);
+        self.output.push_str(&val.to_string());
+        Ok(())
+    }
+
+    fn open_container(
+        &mut self,
+        depth: u64,
+        opening_delimiter: &str,
+        closing_delimiter: &'static str,
+    ) -> PartialVMResult<bool> {
+        self.begin_value(depth);
+        self.output.push_str(opening_delimiter);
+        self.containers.push((depth, closing_delimiter, 0));
+        Ok(true)
+    }
+
+    fn finish(mut self) -> String {
+        self.close_containers(0);
+        self.output
+    }
+}
+
+impl ValueVisitor for ValueRenderer {
+    fn visit_delayed(&mut self, depth: u64, _id: DelayedFieldID) -> PartialVMResult<()> {
+        self.render_primitive(depth, "<delayed>")
+    }
+
+    fn visit_u8(&mut self, depth: u64, val: u8) -> PartialVMResult<()> {
+        self.render_primitive(depth, val)
+    }
+
+    fn visit_u16(&mut self, depth: u64, val: u16) -> PartialVMResult<()> {
+        self.render_primitive(depth, val)
+    }
+
+    fn visit_u32(&mut self, depth: u64, val: u32) -> PartialVMResult<()> {
+        self.render_primitive(depth, val)
+    }
+
+    fn visit_u64(&mut self, depth: u64, val: u64) -> PartialVMResult<()> {
+        self.render_primitive(depth, val)
+    }
+
+    fn visit_u128(&mut self, depth: u64, val: u128) -> PartialVMResult<()> {
+        self.render_primitive(depth, val)
+    }
+
+    fn visit_u256(&mut self, depth: u64, val: U256) -> PartialVMResult<()> {
+        self.render_primitive(depth, val)
+    }
+
+    fn visit_bool(&mut self, depth: u64, val: bool) -> PartialVMResult<()> {
+        self.render_primitive(depth, val)
+    }
+
+    fn visit_address(&mut self, depth: u64, val: AccountAddress) -> PartialVMResult<()> {
+        self.render_primitive(depth, val.to_hex_literal())
+    }
+
+    fn visit_struct(&mut self, depth: u64, _len: usize) -> PartialVMResult<bool> {
+        self.open_container(depth, "{", "}")
+    }
+
+    fn visit_closure(&mut self, depth: u64, _len: usize) -> PartialVMResult<bool> {
+        self.open_container(depth, "closure(", ")")
+    }
+
+    fn visit_vec(&mut self, depth: u64, _len: usize) -> PartialVMResult<bool> {
+        self.open_container(depth, "[", "]")
+    }
+
+    fn visit_ref(&mut self, depth: u64, _is_global: bool) -> PartialVMResult<bool> {
+        self.open_container(depth, "&", "")
+    }
+
+    fn visit_vec_u8(&mut self, depth: u64, vals: &[u8]) -> PartialVMResult<()> {
+        self.render_primitive(depth, format!("0x{}", hex::encode(vals)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_vm_types::values::{Struct, Value};
+
+    #[test]
+    fn test_render_value() {
+        assert_eq!(render_value(&Value::u64(7)), "7");
+        assert_eq!(render_value(&Value::bool(true)), "true");
+        assert_eq!(
+            render_value(&Value::address(AccountAddress::ONE)),
+            AccountAddress::ONE.to_hex_literal()
+        );
+        assert_eq!(render_value(&Value::vector_u8(vec![0xCA, 0xFE])), "0xcafe");
+        assert_eq!(
+            render_value(&Value::struct_(Struct::pack(vec![
+                Value::u64(1),
+                Value::struct_(Struct::pack(vec![Value::u8(2), Value::u8(3)])),
+                Value::bool(false),
+            ]))),
+            "{1, {2, 3}, false}"
+        );
+    }
+
+    #[test]
+    fn test_render_value_truncation() {
+        let rendered = render_value(&Value::vector_u8(vec![0; MAX_RENDERED_VALUE_LENGTH]));
+        assert_eq!(rendered.len(), MAX_RENDERED_VALUE_LENGTH + 2);
+        assert!(rendered.ends_with(".."));
+    }
+}
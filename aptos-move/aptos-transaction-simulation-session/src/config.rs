@@ -3,6 +3,7 @@
 
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use url::Url;
 
 // TODO: Config versioning?
@@ -29,6 +30,11 @@ pub struct Config {
 
     /// The number of operations the session has performed.
     pub ops: u64,
+
+    /// The named checkpoints of the session, and the number of operations
+    /// the session had performed when each checkpoint was created.
+    #[serde(default)]
+    pub checkpoints: BTreeMap<String, u64>,
 }
 
 impl Config {
@@ -37,6 +43,7 @@ impl Config {
         Self {
             base: BaseState::Empty,
             ops: 0,
+            checkpoints: BTreeMap::new(),
         }
     }
 
@@ -49,6 +56,7 @@ impl Config {
                 api_key,
             },
             ops: 0,
+            checkpoints: BTreeMap::new(),
         }
     }
 
@@ -11,8 +11,11 @@ use std::{
 
 // TODO: Store state values in human readable format
 
-/// Saves the state delta to a file.
-pub fn save_delta(delta_path: &Path, delta: &HashMap<StateKey, Option<StateValue>>) -> Result<()> {
+/// Encodes the state delta in a human readable format, where state keys are rendered as
+/// strings and state values are hex-encoded BCS bytes.
+pub fn encode_delta(
+    delta: &HashMap<StateKey, Option<StateValue>>,
+) -> Result<BTreeMap<String, Option<String>>> {
     // Use BTreeMap to ensure deterministic ordering
     let mut delta_str = BTreeMap::new();
 
@@ -25,17 +28,13 @@ pub fn save_delta(delta_path: &Path, delta: &HashMap<StateKey, Option<StateValue
         delta_str.insert(key_str, val_str_opt);
     }
 
-    let json = serde_json::to_string_pretty(&delta_str)?;
-    std::fs::write(delta_path, json)?;
-
-    Ok(())
+    Ok(delta_str)
 }
 
-/// Loads the state delta from a file.
-pub fn load_delta(delta_path: &Path) -> Result<HashMap<StateKey, Option<StateValue>>> {
-    let json = std::fs::read_to_string(delta_path)?;
-    let delta_str: HashMap<HumanReadable<StateKey>, Option<String>> = serde_json::from_str(&json)?;
-
+/// Decodes a state delta previously encoded with [`encode_delta`].
+pub fn decode_delta(
+    delta_str: HashMap<HumanReadable<StateKey>, Option<String>>,
+) -> Result<HashMap<StateKey, Option<StateValue>>> {
     let mut delta = HashMap::new();
 
     for (k, v) in delta_str {
@@ -50,6 +49,22 @@ pub fn load_delta(delta_path: &Path) -> Result<HashMap<StateKey, Option<StateVal
     Ok(delta)
 }
 
+/// Saves the state delta to a file.
+pub fn save_delta(delta_path: &Path, delta: &HashMap<StateKey, Option<StateValue>>) -> Result<()> {
+    let json = serde_json::to_string_pretty(&encode_delta(delta)?)?;
+    std::fs::write(delta_path, json)?;
+
+    Ok(())
+}
+
+/// Loads the state delta from a file.
+pub fn load_delta(delta_path: &Path) -> Result<HashMap<StateKey, Option<StateValue>>> {
+    let json = std::fs::read_to_string(delta_path)?;
+    let delta_str: HashMap<HumanReadable<StateKey>, Option<String>> = serde_json::from_str(&json)?;
+
+    decode_delta(delta_str)
+}
+
 #[test]
 fn test_delta_roundtrip() -> Result<()> {
     use aptos_transaction_simulation::{
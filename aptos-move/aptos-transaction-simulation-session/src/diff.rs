@@ -0,0 +1,382 @@
+// Copyright (c) Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::txn_output::annotate_state_value;
+use anyhow::Result;
+use aptos_resource_viewer::AptosValueAnnotator;
+use aptos_transaction_simulation::SimulationStateStore;
+use aptos_types::{
+    access_path::Path as AccessPath,
+    account_config::ObjectCoreResource,
+    state_store::state_key::{inner::StateKeyInner, StateKey},
+};
+use move_core_types::{
+    account_address::AccountAddress, language_storage::StructTag, move_resource::MoveStructType,
+};
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    str::FromStr,
+};
+
+/// A point in the history of a session, which can be compared against another point.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionPoint {
+    /// The base state the session was created from (e.g., genesis or a remote network state).
+    Base,
+    /// A named checkpoint of the session.
+    Checkpoint(String),
+    /// The state right after the given operation (numbered from 0, as in the output directories).
+    Operation(u64),
+    /// The current state of the session.
+    Current,
+}
+
+impl FromStr for SessionPoint {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "base" => Self::Base,
+            "current" => Self::Current,
+            name => match name.parse() {
+                Ok(op) => Self::Operation(op),
+                Err(_) => Self::Checkpoint(name.to_string()),
+            },
+        })
+    }
+}
+
+impl fmt::Display for SessionPoint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Base => write!(f, "base"),
+            Self::Checkpoint(name) => write!(f, "checkpoint {}", name),
+            Self::Operation(op) => write!(f, "operation {}", op),
+            Self::Current => write!(f, "current"),
+        }
+    }
+}
+
+/// How a state item changed between two points of a session.
+#[derive(Debug, Clone, Copy, Serialize, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Created,
+    Modified,
+    Deleted,
+}
+
+impl ChangeKind {
+    fn new<T>(before: &Option<T>, after: &Option<T>) -> Option<Self> {
+        match (before, after) {
+            (None, None) => None,
+            (None, Some(_)) => Some(Self::Created),
+            (Some(_), Some(_)) => Some(Self::Modified),
+            (Some(_), None) => Some(Self::Deleted),
+        }
+    }
+
+    fn symbol(&self) -> char {
+        match self {
+            Self::Created => '+',
+            Self::Modified => '~',
+            Self::Deleted => '-',
+        }
+    }
+}
+
+/// A change to a resource (or a member of a resource group), decoded for inspection.
+#[derive(Debug, Serialize)]
+pub struct ResourceChange {
+    pub account: AccountAddress,
+    pub resource_type: String,
+    pub change: ChangeKind,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+}
+
+/// A change to a table item. Keys and values are hex encoded, as the table types are unknown.
+#[derive(Debug, Serialize)]
+pub struct TableItemChange {
+    pub handle: AccountAddress,
+    pub key: String,
+    pub change: ChangeKind,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+/// A module that was published, upgraded or deleted.
+#[derive(Debug, Serialize)]
+pub struct ModuleChange {
+    pub module: String,
+    pub change: ChangeKind,
+}
+
+/// A change to the APT balance (coin store and primary fungible store) of an account.
+#[derive(Debug, Serialize)]
+pub struct BalanceChange {
+    pub account: AccountAddress,
+    pub before: u64,
+    pub after: u64,
+    pub delta: i128,
+}
+
+/// An event emitted by a session operation, decoded as saved in the operation's output directory.
+#[derive(Debug, Serialize)]
+pub struct EmittedEvent {
+    pub operation: u64,
+    pub event: serde_json::Value,
+}
+
+impl EmittedEvent {
+    /// Returns the type of the event, if it can be found in the decoded event.
+    fn type_tag(&self) -> Option<&str> {
+        self.event
+            .as_object()?
+            .values()
+            .next()?
+            .get("type_tag")?
+            .as_str()
+    }
+}
+
+/// The state changes between two points of a session, and the events emitted in between.
+#[derive(Debug, Serialize)]
+pub struct SessionDiff {
+    pub from: String,
+    pub to: String,
+    pub resources: Vec<ResourceChange>,
+    pub table_items: Vec<TableItemChange>,
+    pub modules: Vec<ModuleChange>,
+    pub balances: Vec<BalanceChange>,
+    pub events: Vec<EmittedEvent>,
+}
+
+impl SessionDiff {
+    /// Compares the given state keys between two states, along with the events emitted between
+    /// them.
+    ///
+    /// Resources are decoded using the state they belong to, so changes to modules between the
+    /// two points are taken into account. Balance changes are reported for all accounts with
+    /// changed resources, including the owners of changed objects (e.g., fungible stores).
+    pub(crate) fn new(
+        from: &SessionPoint,
+        to: &SessionPoint,
+        before: &impl SimulationStateStore,
+        after: &impl SimulationStateStore,
+        state_keys: impl IntoIterator<Item = StateKey>,
+        events: Vec<EmittedEvent>,
+    ) -> Result<Self> {
+        let before_annotator = AptosValueAnnotator::new(before);
+        let after_annotator = AptosValueAnnotator::new(after);
+
+        let mut resources = vec![];
+        let mut table_items = vec![];
+        let mut modules = vec![];
+        let mut accounts = BTreeSet::new();
+
+        for state_key in state_keys {
+            let before_bytes = before.get_state_value_bytes(&state_key)?;
+            let after_bytes = after.get_state_value_bytes(&state_key)?;
+            let change = match ChangeKind::new(&before_bytes, &after_bytes) {
+                Some(change) if before_bytes != after_bytes => change,
+                _ => continue,
+            };
+
+            match state_key.inner() {
+                StateKeyInner::AccessPath(access_path) => match access_path.get_path() {
+                    AccessPath::Resource(struct_tag) => {
+                        accounts.insert(access_path.address);
+                        resources.push(ResourceChange {
+                            account: access_path.address,
+                            resource_type: struct_tag.to_canonical_string(),
+                            change,
+                            before: before_bytes
+                                .map(|data| {
+                                    annotate_state_value(&before_annotator, &state_key, &data)
+                                })
+                                .transpose()?,
+                            after: after_bytes
+                                .map(|data| {
+                                    annotate_state_value(&after_annotator, &state_key, &data)
+                                })
+                                .transpose()?,
+                        });
+                    },
+                    AccessPath::ResourceGroup(_) => {
+                        let decode_group =
+                            |data: Option<&[u8]>| -> Result<BTreeMap<StructTag, Vec<u8>>> {
+                                Ok(match data {
+                                    Some(data) => bcs::from_bytes(data)?,
+                                    None => BTreeMap::new(),
+                                })
+                            };
+                        let before_group = decode_group(before_bytes.as_deref())?;
+                        let after_group = decode_group(after_bytes.as_deref())?;
+
+                        // Balances are tracked per owner for objects, as fungible stores live
+                        // at object addresses
+                        accounts.insert(access_path.address);
+                        for group in [&before_group, &after_group] {
+                            if let Some(object_core) = group.get(&ObjectCoreResource::struct_tag())
+                            {
+                                let object_core: ObjectCoreResource = bcs::from_bytes(object_core)?;
+                                accounts.insert(object_core.owner);
+                            }
+                        }
+
+                        let tags: BTreeSet<&StructTag> =
+                            before_group.keys().chain(after_group.keys()).collect();
+                        for tag in tags {
+                            let before_member = before_group.get(tag);
+                            let after_member = after_group.get(tag);
+                            let change = match ChangeKind::new(&before_member, &after_member) {
+                                Some(change) if before_member != after_member => change,
+                                _ => continue,
+                            };
+                            resources.push(ResourceChange {
+                                account: access_path.address,
+                                resource_type: tag.to_canonical_string(),
+                                change,
+                                before: before_member
+                                    .map(|data| before_annotator.view_resource(tag, data))
+                                    .transpose()?
+                                    .map(|resource| serde_json::json!(resource)),
+                                after: after_member
+                                    .map(|data| after_annotator.view_resource(tag, data))
+                                    .transpose()?
+                                    .map(|resource| serde_json::json!(resource)),
+                            });
+                        }
+                    },
+                    AccessPath::Code(module_id) => modules.push(ModuleChange {
+                        module: module_id.short_str_lossless(),
+                        change,
+                    }),
+                },
+                StateKeyInner::TableItem { handle, key } => table_items.push(TableItemChange {
+                    handle: handle.0,
+                    key: hex::encode(key),
+                    change,
+                    before: before_bytes.map(hex::encode),
+                    after: after_bytes.map(hex::encode),
+                }),
+                StateKeyInner::Raw(_) => (),
+            }
+        }
+
+        let mut balances = vec![];
+        for account in accounts {
+            let before_balance = before.get_apt_balance(account)?;
+            let after_balance = after.get_apt_balance(account)?;
+            if before_balance != after_balance {
+                balances.push(BalanceChange {
+                    account,
+                    before: before_balance,
+                    after: after_balance,
+                    delta: after_balance as i128 - before_balance as i128,
+                });
+            }
+        }
+
+        resources.sort_by(|a, b| (a.account, &a.resource_type).cmp(&(b.account, &b.resource_type)));
+        table_items.sort_by(|a, b| (a.handle, &a.key).cmp(&(b.handle, &b.key)));
+        modules.sort_by(|a, b| a.module.cmp(&b.module));
+
+        Ok(Self {
+            from: from.to_string(),
+            to: to.to_string(),
+            resources,
+            table_items,
+            modules,
+            balances,
+            events,
+        })
+    }
+
+    /// Returns true iff there are no changes between the two points.
+    pub fn is_empty(&self) -> bool {
+        self.resources.is_empty()
+            && self.table_items.is_empty()
+            && self.modules.is_empty()
+            && self.balances.is_empty()
+            && self.events.is_empty()
+    }
+}
+
+impl fmt::Display for SessionDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Diff from {} to {}", self.from, self.to)?;
+        if self.is_empty() {
+            return writeln!(f, "No changes");
+        }
+
+        if !self.balances.is_empty() {
+            writeln!(f, "\nAPT balances (octas):")?;
+            for balance in &self.balances {
+                writeln!(
+                    f,
+                    "  {}: {} -> {} ({:+})",
+                    balance.account.to_standard_string(),
+                    balance.before,
+                    balance.after,
+                    balance.delta
+                )?;
+            }
+        }
+
+        if !self.modules.is_empty() {
+            writeln!(f, "\nModules:")?;
+            for module in &self.modules {
+                writeln!(f, "  {} {}", module.change.symbol(), module.module)?;
+            }
+        }
+
+        if !self.resources.is_empty() {
+            writeln!(f, "\nResources:")?;
+            for resource in &self.resources {
+                writeln!(
+                    f,
+                    "  {} {} at {}",
+                    resource.change.symbol(),
+                    resource.resource_type,
+                    resource.account.to_standard_string()
+                )?;
+                for (label, value) in [("before", &resource.before), ("after", &resource.after)] {
+                    if let Some(value) = value {
+                        writeln!(f, "      {}: {}", label, value)?;
+                    }
+                }
+            }
+        }
+
+        if !self.table_items.is_empty() {
+            writeln!(f, "\nTable items:")?;
+            for item in &self.table_items {
+                writeln!(
+                    f,
+                    "  {} table {} key 0x{}",
+                    item.change.symbol(),
+                    item.handle.to_standard_string(),
+                    item.key
+                )?;
+            }
+        }
+
+        if !self.events.is_empty() {
+            writeln!(f, "\nEvents:")?;
+            for event in &self.events {
+                writeln!(
+                    f,
+                    "  [{}] {}",
+                    event.operation,
+                    event.type_tag().unwrap_or("<unknown>")
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -3,8 +3,15 @@
 
 mod config;
 mod delta;
+mod diff;
+mod remote_cache;
 mod session;
 mod state_store;
 mod txn_output;
 
+pub use diff::{
+    BalanceChange, ChangeKind, EmittedEvent, ModuleChange, ResourceChange, SessionDiff,
+    SessionPoint, TableItemChange,
+};
+pub use remote_cache::RemoteStateCacheStats;
 pub use session::Session;
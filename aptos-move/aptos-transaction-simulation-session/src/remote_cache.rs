@@ -0,0 +1,220 @@
+// Copyright (c) Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    delta::{decode_delta, encode_delta},
+    state_store::HumanReadable,
+};
+use anyhow::Result;
+use aptos_types::{
+    state_store::{
+        state_key::StateKey, state_slot::StateSlot, state_storage_usage::StateStorageUsage,
+        state_value::StateValue, StateViewId, StateViewResult, TStateView,
+    },
+    transaction::Version,
+};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, AtomicU64, Ordering},
+};
+
+/// Statistics on the lookups served by a remote state cache.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, Eq, PartialEq)]
+pub struct RemoteStateCacheStats {
+    /// The number of lookups served from the cache.
+    pub hits: u64,
+    /// The number of lookups that had to be fetched from the remote network.
+    pub misses: u64,
+}
+
+impl RemoteStateCacheStats {
+    /// Returns the fraction of lookups served from the cache (or 0 if there were no lookups).
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+/// The on-disk format of the remote state cache.
+///
+/// Entries use the same human readable encoding as the session delta, so the cache file
+/// can be inspected, and shared between sessions forked from the same network version.
+#[derive(Serialize, Deserialize)]
+struct RemoteStateCacheFile<E> {
+    network_version: Version,
+    stats: RemoteStateCacheStats,
+    entries: E,
+}
+
+/// A state view that caches all state values fetched from a remote network at a pinned
+/// version, and persists them to disk so they can be reused across session loads.
+///
+/// As the network version is fixed, cached values never become stale.
+pub struct RemoteStateCache<V> {
+    remote: V,
+    network_version: Version,
+    cache_path: PathBuf,
+    entries: RwLock<HashMap<StateKey, Option<StateValue>>>,
+    has_new_entries: AtomicBool,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl<V> RemoteStateCache<V> {
+    /// Creates a cache on top of the remote state view, loading any previously cached
+    /// entries from the given file. Fails if the file was created for a different version.
+    pub fn load_or_new(remote: V, network_version: Version, cache_path: &Path) -> Result<Self> {
+        let (entries, stats) = if cache_path.exists() {
+            let json = std::fs::read_to_string(cache_path)?;
+            let cache_file: RemoteStateCacheFile<HashMap<HumanReadable<StateKey>, Option<String>>> =
+                serde_json::from_str(&json)?;
+            if cache_file.network_version != network_version {
+                anyhow::bail!(
+                    "Remote state cache at {} was created for network version {}, but the session uses version {}",
+                    cache_path.display(),
+                    cache_file.network_version,
+                    network_version
+                );
+            }
+            (decode_delta(cache_file.entries)?, cache_file.stats)
+        } else {
+            (HashMap::new(), RemoteStateCacheStats::default())
+        };
+
+        Ok(Self {
+            remote,
+            network_version,
+            cache_path: cache_path.to_path_buf(),
+            entries: RwLock::new(entries),
+            has_new_entries: AtomicBool::new(false),
+            hits: AtomicU64::new(stats.hits),
+            misses: AtomicU64::new(stats.misses),
+        })
+    }
+
+    /// Returns the (cumulative) statistics of the cache.
+    pub fn stats(&self) -> RemoteStateCacheStats {
+        RemoteStateCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Saves the cached entries and statistics to the cache file, if any new entries
+    /// were fetched since the cache was loaded (or last saved).
+    pub fn save(&self) -> Result<()> {
+        if !self.has_new_entries.swap(false, Ordering::Relaxed) {
+            return Ok(());
+        }
+        if let Err(err) = self.save_to_file(&self.cache_path) {
+            self.has_new_entries.store(true, Ordering::Relaxed);
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Saves the cached entries and statistics to the given file.
+    pub fn save_to_file(&self, path: &Path) -> Result<()> {
+        let cache_file: RemoteStateCacheFile<BTreeMap<String, Option<String>>> =
+            RemoteStateCacheFile {
+                network_version: self.network_version,
+                stats: self.stats(),
+                entries: encode_delta(&self.entries.read())?,
+            };
+        std::fs::write(path, serde_json::to_string_pretty(&cache_file)?)?;
+        Ok(())
+    }
+}
+
+impl<V> TStateView for RemoteStateCache<V>
+where
+    V: TStateView<Key = StateKey>,
+{
+    type Key = StateKey;
+
+    fn id(&self) -> StateViewId {
+        self.remote.id()
+    }
+
+    fn get_state_slot(&self, state_key: &Self::Key) -> StateViewResult<StateSlot> {
+        let value_opt = self.get_state_value(state_key)?.map(|value| (0, value));
+        Ok(StateSlot::from_db_get(value_opt))
+    }
+
+    fn get_state_value(&self, state_key: &Self::Key) -> StateViewResult<Option<StateValue>> {
+        if let Some(value) = self.entries.read().get(state_key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(value.clone());
+        }
+
+        let value = self.remote.get_state_value(state_key)?;
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        self.entries
+            .write()
+            .insert(state_key.clone(), value.clone());
+        self.has_new_entries.store(true, Ordering::Relaxed);
+        Ok(value)
+    }
+
+    fn get_usage(&self) -> StateViewResult<StateStorageUsage> {
+        self.remote.get_usage()
+    }
+
+    fn next_version(&self) -> Version {
+        self.remote.next_version()
+    }
+}
+
+#[test]
+fn test_remote_state_cache_roundtrip() -> Result<()> {
+    use aptos_transaction_simulation::{InMemoryStateStore, SimulationStateStore};
+    use move_core_types::{account_address::AccountAddress, language_storage::StructTag};
+
+    let temp_dir = tempfile::tempdir()?;
+    let cache_path = temp_dir.path().join("remote_state_cache.json");
+
+    let remote = InMemoryStateStore::new();
+    let account_type: StructTag = "0x1::account::Account".parse()?;
+    let existing_key = StateKey::resource(&AccountAddress::ONE, &account_type)?;
+    let missing_key = StateKey::resource(&AccountAddress::TWO, &account_type)?;
+    remote.set_state_value(
+        existing_key.clone(),
+        StateValue::new_legacy(vec![1, 2, 3].into()),
+    )?;
+
+    // The first lookups are misses, and the second lookups are hits
+    let cache = RemoteStateCache::load_or_new(&remote, 10, &cache_path)?;
+    for _ in 0..2 {
+        assert!(cache.get_state_value(&existing_key)?.is_some());
+        assert!(cache.get_state_value(&missing_key)?.is_none());
+    }
+    assert_eq!(cache.stats(), RemoteStateCacheStats { hits: 2, misses: 2 });
+    cache.save()?;
+
+    // The entries are served from the cache file, even if the remote state changes
+    let remote = InMemoryStateStore::new();
+    let cache = RemoteStateCache::load_or_new(&remote, 10, &cache_path)?;
+    assert!(cache.get_state_value(&existing_key)?.is_some());
+    assert_eq!(cache.stats(), RemoteStateCacheStats { hits: 3, misses: 2 });
+
+    // The cache file is only written if new entries were fetched
+    std::fs::remove_file(&cache_path)?;
+    cache.save()?;
+    assert!(!cache_path.exists());
+    let new_key = StateKey::resource(&AccountAddress::THREE, &account_type)?;
+    assert!(cache.get_state_value(&new_key)?.is_none());
+    cache.save()?;
+    assert!(cache_path.exists());
+
+    // The cache file cannot be used for a different network version
+    assert!(RemoteStateCache::load_or_new(&remote, 11, &cache_path).is_err());
+
+    Ok(())
+}
@@ -4,9 +4,15 @@
 use crate::{
     config::{BaseState, Config},
     delta::{load_delta, save_delta},
-    txn_output::{save_events, save_write_set},
+    diff::{EmittedEvent, SessionDiff, SessionPoint},
+    remote_cache::{RemoteStateCache, RemoteStateCacheStats},
+    txn_output::{
+        load_state_changes, save_events, save_state_changes, save_write_set, StateChanges,
+    },
 };
 use anyhow::Result;
+use aptos_block_executor::txn_provider::default::DefaultTxnProvider;
+use aptos_crypto::HashValue;
 use aptos_resource_viewer::AptosValueAnnotator;
 use aptos_rest_client::{AptosBaseUrl, Client};
 use aptos_transaction_simulation::{
@@ -14,31 +20,91 @@ use aptos_transaction_simulation::{
 };
 use aptos_types::{
     account_address::AccountAddress,
+    account_config::reserved_vm_address,
+    block_metadata::BlockMetadata,
+    contract_event::ContractEvent,
     fee_statement::FeeStatement,
+    on_chain_config::{ConfigurationResource, CurrentTimeMicroseconds},
+    state_store::{state_key::StateKey, state_value::StateValue, TStateView},
     transaction::{
-        AuxiliaryInfo, PersistedAuxiliaryInfo, SignedTransaction, TransactionExecutable,
-        TransactionOutput, TransactionPayload, TransactionPayloadInner, TransactionStatus,
+        authenticator::{AccountAuthenticator, TransactionAuthenticator},
+        signature_verified_transaction::into_signature_verified_block,
+        AuxiliaryInfo, PersistedAuxiliaryInfo, SignedTransaction, Transaction,
+        TransactionExecutable, TransactionOutput, TransactionPayload, TransactionPayloadInner,
+        TransactionStatus,
     },
     vm_status::VMStatus,
+    write_set::{WriteOp, WriteSet, WriteSetMut},
 };
 use aptos_validator_interface::{DebuggerStateView, RestDebuggerInterface};
-use aptos_vm::{data_cache::AsMoveResolver, AptosVM};
+use aptos_vm::{
+    aptos_vm::AptosVMBlockExecutor,
+    data_cache::AsMoveResolver,
+    move_vm_ext::{MoveVmExt, SessionId},
+    AptosSimulationVM, AptosVM, VMBlockExecutor,
+};
 use aptos_vm_environment::environment::AptosEnvironment;
 use aptos_vm_logging::log_schema::AdapterLogSchema;
-use aptos_vm_types::{module_and_script_storage::AsAptosCodeStorage, resolver::StateStorageView};
+use aptos_vm_types::{
+    module_and_script_storage::AsAptosCodeStorage, module_write_set::ModuleWriteSet,
+    resolver::StateStorageView, storage::change_set_configs::ChangeSetConfigs,
+};
 use move_core_types::{
     identifier::Identifier,
-    language_storage::{ModuleId, TypeTag},
+    language_storage::{ModuleId, StructTag, TypeTag},
+    value::{serialize_values, MoveValue},
 };
+use move_vm_runtime::module_traversal::{TraversalContext, TraversalStorage};
+use move_vm_types::gas::UnmeteredGasMeter;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     path::{Path, PathBuf},
     sync::Arc,
 };
 use url::Url;
 
-type SessionStateStore = DeltaStateStore<EitherStateView<EmptyStateView, DebuggerStateView>>;
+type SessionRemoteStateView = Arc<RemoteStateCache<DebuggerStateView>>;
+type SessionBaseStateView = EitherStateView<EmptyStateView, SessionRemoteStateView>;
+
+/// The name of the file (in the session directory) that caches the remote state
+const REMOTE_STATE_CACHE_FILE: &str = "remote_state_cache.json";
+type SessionStateStore = DeltaStateStore<SessionBaseStateView>;
+
+/// Creates the directory for a new session. Fails if the directory already exists and is not empty.
+fn create_session_dir(session_path: &Path) -> Result<()> {
+    std::fs::create_dir_all(session_path)?;
+
+    if session_path.read_dir()?.next().is_some() {
+        anyhow::bail!(
+            "Cannot initialize new session at {} -- directory is not empty.",
+            session_path.display()
+        );
+    }
+
+    Ok(())
+}
+
+/// Checks that a checkpoint name is non-empty and only contains alphanumeric characters,
+/// dashes and underscores, so that it can be used as a directory name. The names `base` and
+/// `current` are reserved to refer to the base and current session states, and numbers are
+/// reserved to refer to operations.
+fn validate_checkpoint_name(name: &str) -> Result<()> {
+    if name == "base" || name == "current" || name.parse::<u64>().is_ok() {
+        anyhow::bail!("Checkpoint name {:?} is reserved", name);
+    }
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        anyhow::bail!(
+            "Invalid checkpoint name: {:?}. Checkpoint names may only contain alphanumeric characters, dashes and underscores.",
+            name
+        );
+    }
+    Ok(())
+}
 
 /// Formats a module ID for display by adjusting the address for better readability.
 fn format_module_id(module_id: &ModuleId) -> String {
@@ -77,6 +143,7 @@ enum Summary {
         after: u64,
     },
     ExecuteTransaction {
+        impersonated: bool,
         status: TransactionStatus,
         gas_used: u64,
         fee_statement: Option<FeeStatement>,
@@ -85,6 +152,31 @@ enum Summary {
         result: ViewResult,
         gas_used: u64,
     },
+    Checkpoint {
+        name: String,
+    },
+    Rewind {
+        checkpoint: String,
+    },
+    ViewResource {
+        account: AccountAddress,
+        resource_type: String,
+        resource: Option<serde_json::Value>,
+    },
+    SetTime {
+        before: u64,
+        after: u64,
+    },
+    BlockPrologue {
+        timestamp: u64,
+        status: TransactionStatus,
+        epoch_before: u64,
+        epoch_after: u64,
+    },
+    Reconfigure {
+        epoch_before: u64,
+        epoch_after: u64,
+    },
 }
 
 /// A session for simulating transactions, with data being persisted to a directory, allowing the session
@@ -95,6 +187,7 @@ pub struct Session {
     config: Config,
     path: PathBuf,
     state_store: SessionStateStore,
+    remote_cache: Option<SessionRemoteStateView>,
 }
 
 impl Session {
@@ -110,14 +203,7 @@ impl Session {
     pub fn init(session_path: impl AsRef<Path>) -> Result<Self> {
         let session_path = session_path.as_ref().to_path_buf();
 
-        std::fs::create_dir_all(&session_path)?;
-
-        if session_path.read_dir()?.next().is_some() {
-            anyhow::bail!(
-                "Cannot initialize new session at {} -- directory is not empty.",
-                session_path.display()
-            );
-        }
+        create_session_dir(&session_path)?;
 
         // Write config with empty base state
         let config = Config::new();
@@ -137,11 +223,12 @@ impl Session {
             config,
             path: session_path,
             state_store,
+            remote_cache: None,
         })
     }
 
     /// Initializes a new session by forking from a remote network state. Data will be fetched
-    /// from the remote network on-demand.
+    /// from the remote network on-demand, and cached in the session directory.
     ///
     /// It is strongly recommended that the caller provides an API key to avoid rate limiting.
     pub fn init_with_remote_state(
@@ -152,14 +239,7 @@ impl Session {
     ) -> Result<Self> {
         let session_path = session_path.as_ref().to_path_buf();
 
-        std::fs::create_dir_all(&session_path)?;
-
-        if session_path.read_dir()?.next().is_some() {
-            anyhow::bail!(
-                "Cannot initialize new session at {} -- directory is not empty.",
-                session_path.display()
-            );
-        }
+        create_session_dir(&session_path)?;
 
         let config = Config::with_remote(node_url.clone(), network_version, api_key.clone());
         let config_path = session_path.join("config.json");
@@ -168,22 +248,14 @@ impl Session {
         let delta_path = session_path.join("delta.json");
         save_delta(&delta_path, &HashMap::new())?;
 
-        let mut builder = Client::builder(AptosBaseUrl::Custom(node_url));
-        if let Some(api_key) = api_key {
-            builder = builder.api_key(&api_key)?;
-        }
-        let client = builder.build();
-
-        let state_store =
-            DeltaStateStore::new_with_base(EitherStateView::Right(DebuggerStateView::new(
-                Arc::new(RestDebuggerInterface::new(client)),
-                network_version,
-            )));
+        let (base, remote_cache) = Self::create_base_state_view(&config.base, &session_path)?;
+        let state_store = DeltaStateStore::new_with_base(base);
 
         Ok(Self {
             config,
             path: session_path,
             state_store,
+            remote_cache,
         })
     }
 
@@ -192,8 +264,26 @@ impl Session {
         let session_path = session_path.as_ref().to_path_buf();
         let config = Config::load_from_file(&session_path.join("config.json"))?;
 
-        let base = match &config.base {
-            BaseState::Empty => EitherStateView::Left(EmptyStateView),
+        let (base, remote_cache) = Self::create_base_state_view(&config.base, &session_path)?;
+        let delta = load_delta(&session_path.join("delta.json"))?;
+        let state_store = DeltaStateStore::new_with_base_and_delta(base, delta);
+
+        Ok(Self {
+            config,
+            path: session_path,
+            state_store,
+            remote_cache,
+        })
+    }
+
+    /// Creates the base state view described by the session configuration. For remote base
+    /// states, the fetched state values are cached in the session directory.
+    fn create_base_state_view(
+        base: &BaseState,
+        session_path: &Path,
+    ) -> Result<(SessionBaseStateView, Option<SessionRemoteStateView>)> {
+        let base = match base {
+            BaseState::Empty => (EitherStateView::Left(EmptyStateView), None),
             BaseState::Remote {
                 node_url,
                 network_version,
@@ -209,17 +299,287 @@ impl Session {
                     Arc::new(RestDebuggerInterface::new(client)),
                     *network_version,
                 );
-                EitherStateView::Right(debugger)
+                let remote_cache = Arc::new(RemoteStateCache::load_or_new(
+                    debugger,
+                    *network_version,
+                    &session_path.join(REMOTE_STATE_CACHE_FILE),
+                )?);
+                (
+                    EitherStateView::Right(remote_cache.clone()),
+                    Some(remote_cache),
+                )
             },
         };
+        Ok(base)
+    }
 
-        let delta = load_delta(&session_path.join("delta.json"))?;
+    /// Returns the statistics of the remote state cache, or `None` if the
+    /// session does not have a remote base state.
+    pub fn remote_cache_stats(&self) -> Option<RemoteStateCacheStats> {
+        self.remote_cache
+            .as_ref()
+            .map(|remote_cache| remote_cache.stats())
+    }
+
+    /// Persists the remote state cache (if any), so that the fetched
+    /// state values can be reused when the session is loaded again.
+    fn save_remote_cache(&self) -> Result<()> {
+        if let Some(remote_cache) = &self.remote_cache {
+            remote_cache.save()?;
+        }
+        Ok(())
+    }
+
+    /// Returns the path of the delta file stored for the given checkpoint.
+    fn checkpoint_delta_path(&self, name: &str) -> Result<PathBuf> {
+        if !self.config.checkpoints.contains_key(name) {
+            anyhow::bail!("Checkpoint {:?} does not exist in the session", name);
+        }
+        Ok(self.path.join("checkpoints").join(name).join("delta.json"))
+    }
+
+    /// Saves the current session state as a named checkpoint.
+    ///
+    /// The session can later be rewound to the checkpoint (see [`Session::rewind`]), or a new
+    /// session can be forked from it (see [`Session::fork`]).
+    pub fn checkpoint(&mut self, name: &str) -> Result<()> {
+        validate_checkpoint_name(name)?;
+        if self.config.checkpoints.contains_key(name) {
+            anyhow::bail!("Checkpoint {:?} already exists in the session", name);
+        }
+
+        let checkpoint_path = self.path.join("checkpoints").join(name);
+        std::fs::create_dir_all(&checkpoint_path)?;
+        save_delta(
+            &checkpoint_path.join("delta.json"),
+            &self.state_store.delta(),
+        )?;
+        self.config
+            .checkpoints
+            .insert(name.to_string(), self.config.ops);
+
+        let summary = Summary::Checkpoint {
+            name: name.to_string(),
+        };
+        let summary_path = self
+            .path
+            .join(format!("[{}] checkpoint {}", self.config.ops, name))
+            .join("summary.json");
+        std::fs::create_dir_all(summary_path.parent().unwrap())?;
+        std::fs::write(summary_path, serde_json::to_string_pretty(&summary)?)?;
+
+        self.config.ops += 1;
+        self.config.save_to_file(&self.path.join("config.json"))?;
+
+        Ok(())
+    }
+
+    /// Restores the session state to a previously saved checkpoint.
+    ///
+    /// This counts as a session operation, so the outputs of the operations performed after
+    /// the checkpoint are kept for inspection. All checkpoints remain available.
+    pub fn rewind(&mut self, checkpoint: &str) -> Result<()> {
+        let delta = load_delta(&self.checkpoint_delta_path(checkpoint)?)?;
+        let delta_before = self.state_store.delta();
+        self.state_store.set_delta(delta);
+
+        let summary = Summary::Rewind {
+            checkpoint: checkpoint.to_string(),
+        };
+        let output_path = self
+            .path
+            .join(format!("[{}] rewind {}", self.config.ops, checkpoint));
+        std::fs::create_dir_all(&output_path)?;
+        std::fs::write(
+            output_path.join("summary.json"),
+            serde_json::to_string_pretty(&summary)?,
+        )?;
+        self.save_operation_state_changes(&output_path, &delta_before)?;
+
+        self.config.ops += 1;
+        self.config.save_to_file(&self.path.join("config.json"))?;
+        save_delta(&self.path.join("delta.json"), &self.state_store.delta())?;
+
+        Ok(())
+    }
+
+    /// Saves the state changes made by the current operation (i.e., compared to the session
+    /// delta from before the operation) to the output directory of the operation, so that they
+    /// can be combined into diffs between points of the session (see [`Session::diff`]).
+    fn save_operation_state_changes(
+        &self,
+        output_path: &Path,
+        delta_before: &HashMap<StateKey, Option<StateValue>>,
+    ) -> Result<()> {
+        let delta_after = self.state_store.delta();
+        let base = self.state_store.base();
+
+        let mut changes = StateChanges::default();
+        for state_key in delta_before.keys().chain(delta_after.keys()) {
+            if changes.after.contains_key(state_key) {
+                continue;
+            }
+            let before = match delta_before.get(state_key) {
+                Some(value) => value.clone(),
+                None => base.get_state_value(state_key)?,
+            };
+            let after = match delta_after.get(state_key) {
+                Some(value) => value.clone(),
+                None => base.get_state_value(state_key)?,
+            };
+            if before != after {
+                changes.before.insert(state_key.clone(), before);
+                changes.after.insert(state_key.clone(), after);
+            }
+        }
+
+        save_state_changes(&output_path.join("state_changes.json"), &changes)
+    }
+
+    /// Returns the output directories of the session operations, by operation number.
+    fn operation_paths(&self) -> Result<BTreeMap<u64, PathBuf>> {
+        let mut paths = BTreeMap::new();
+        for entry in std::fs::read_dir(&self.path)? {
+            let entry = entry?;
+            let file_name = entry.file_name();
+            let op = file_name
+                .to_str()
+                .and_then(|name| name.strip_prefix('['))
+                .and_then(|name| name.split_once("] "))
+                .and_then(|(op, _)| op.parse().ok());
+            if let Some(op) = op {
+                paths.insert(op, entry.path());
+            }
+        }
+        Ok(paths)
+    }
+
+    /// Returns the number of operations performed before the given point of the session.
+    fn operations_before(&self, point: &SessionPoint) -> Result<u64> {
+        Ok(match point {
+            SessionPoint::Base => 0,
+            SessionPoint::Checkpoint(name) => {
+                *self.config.checkpoints.get(name).ok_or_else(|| {
+                    anyhow::anyhow!("Checkpoint {:?} does not exist in the session", name)
+                })?
+            },
+            SessionPoint::Operation(op) => {
+                if *op >= self.config.ops {
+                    anyhow::bail!("Operation {} does not exist in the session", op);
+                }
+                op + 1
+            },
+            SessionPoint::Current => self.config.ops,
+        })
+    }
+
+    /// Compares the session state between two points (the base state, a checkpoint, an operation
+    /// or the current state), decoding changed resources for inspection.
+    ///
+    /// The diff is built from the state changes and events saved by the operations in between,
+    /// so the first point must not come after the second one. If a state changing operation in
+    /// between did not save its state changes (e.g., it was performed by an older version), the
+    /// diff cannot be computed. This does not count as a session operation.
+    pub fn diff(&self, from: &SessionPoint, to: &SessionPoint) -> Result<SessionDiff> {
+        let start = self.operations_before(from)?;
+        let end = self.operations_before(to)?;
+        if start > end {
+            anyhow::bail!(
+                "Cannot compare {} to {}, as it comes later in the session",
+                from,
+                to
+            );
+        }
+
+        // Combine the changes of the operations, keeping the first value before and the last
+        // value after each change
+        let mut before = HashMap::new();
+        let mut after = HashMap::new();
+        let mut events = vec![];
+        for (op, output_path) in self.operation_paths()?.range(start..end) {
+            let state_changes_path = output_path.join("state_changes.json");
+            if state_changes_path.exists() {
+                let changes = load_state_changes(&state_changes_path)?;
+                for (state_key, value) in changes.before {
+                    before.entry(state_key).or_insert(value);
+                }
+                after.extend(changes.after);
+            } else if may_change_state(output_path) {
+                // The operation was likely performed before state changes were saved (e.g., by
+                // an older version), so the diff would be incomplete
+                anyhow::bail!(
+                    "Cannot compare {} to {}, as operation {:?} did not save its state changes",
+                    from,
+                    to,
+                    output_path.file_name().unwrap_or_default()
+                );
+            }
+
+            let events_path = output_path.join("events.json");
+            if events_path.exists() {
+                let op_events: Vec<serde_json::Value> =
+                    serde_json::from_str(&std::fs::read_to_string(events_path)?)?;
+                events.extend(op_events.into_iter().map(|event| EmittedEvent {
+                    operation: *op,
+                    event,
+                }));
+            }
+        }
+
+        // Decode the changes on top of the current state, so that unchanged modules and
+        // resources (e.g., object owners) can be resolved
+        let state_keys: Vec<StateKey> = after.keys().cloned().collect();
+        let mut before_delta = self.state_store.delta();
+        before_delta.extend(before);
+        let mut after_delta = self.state_store.delta();
+        after_delta.extend(after);
+
+        let before =
+            DeltaStateStore::new_with_base_and_delta(self.state_store.base(), before_delta);
+        let after = DeltaStateStore::new_with_base_and_delta(self.state_store.base(), after_delta);
+        let diff = SessionDiff::new(from, to, &before, &after, state_keys, events)?;
+        self.save_remote_cache()?;
+
+        Ok(diff)
+    }
+
+    /// Forks the session into a new session directory.
+    ///
+    /// The new session shares the same base state (e.g., the same remote network and version),
+    /// and starts from either the given checkpoint or the current session state. The remote
+    /// state cache is copied to the new session, but checkpoints are not. This session is
+    /// left unchanged.
+    pub fn fork(
+        &self,
+        new_session_path: impl AsRef<Path>,
+        checkpoint: Option<&str>,
+    ) -> Result<Self> {
+        let new_session_path = new_session_path.as_ref().to_path_buf();
+        let delta = match checkpoint {
+            Some(checkpoint) => load_delta(&self.checkpoint_delta_path(checkpoint)?)?,
+            None => self.state_store.delta(),
+        };
+
+        create_session_dir(&new_session_path)?;
+
+        let config = Config {
+            base: self.config.base.clone(),
+            ..Config::new()
+        };
+        config.save_to_file(&new_session_path.join("config.json"))?;
+        save_delta(&new_session_path.join("delta.json"), &delta)?;
+        if let Some(remote_cache) = &self.remote_cache {
+            remote_cache.save_to_file(&new_session_path.join(REMOTE_STATE_CACHE_FILE))?;
+        }
+
+        let (base, remote_cache) = Self::create_base_state_view(&config.base, &new_session_path)?;
         let state_store = DeltaStateStore::new_with_base_and_delta(base, delta);
 
         Ok(Self {
             config,
-            path: session_path,
+            path: new_session_path,
             state_store,
+            remote_cache,
         })
     }
 
@@ -231,6 +591,7 @@ impl Session {
     /// This can be useful for testing -- for example, to fund an account before using it to
     /// send its first transaction.
     pub fn fund_account(&mut self, account: AccountAddress, amount: u64) -> Result<()> {
+        let delta_before = self.state_store.delta();
         let (before, after) = self.state_store.fund_apt_fungible_store(account, amount)?;
 
         let summary = Summary::FundFungible {
@@ -239,51 +600,292 @@ impl Session {
             before,
             after,
         };
-        let summary_path = self
+        let output_path = self
             .path
-            .join(format!("[{}] fund (fungible)", self.config.ops))
-            .join("summary.json");
-        std::fs::create_dir_all(summary_path.parent().unwrap())?;
-        std::fs::write(summary_path, serde_json::to_string_pretty(&summary)?)?;
+            .join(format!("[{}] fund (fungible)", self.config.ops));
+        std::fs::create_dir_all(&output_path)?;
+        std::fs::write(
+            output_path.join("summary.json"),
+            serde_json::to_string_pretty(&summary)?,
+        )?;
+        self.save_operation_state_changes(&output_path, &delta_before)?;
 
         self.config.ops += 1;
 
         self.config.save_to_file(&self.path.join("config.json"))?;
         save_delta(&self.path.join("delta.json"), &self.state_store.delta())?;
+        self.save_remote_cache()?;
+
+        Ok(())
+    }
+
+    /// Returns the current on-chain time, in microseconds.
+    fn now_microseconds(&self) -> Result<u64> {
+        Ok(self
+            .state_store
+            .get_on_chain_config::<CurrentTimeMicroseconds>()?
+            .microseconds)
+    }
+
+    /// Returns the current epoch.
+    fn epoch(&self) -> Result<u64> {
+        Ok(self
+            .state_store
+            .get_on_chain_config::<ConfigurationResource>()?
+            .epoch())
+    }
+
+    /// Saves the outputs of a system operation (i.e., one that is not a user transaction) to a
+    /// dedicated directory for inspection, and persists the session state.
+    fn save_system_operation(
+        &mut self,
+        name: &str,
+        summary: &Summary,
+        events: &[ContractEvent],
+        write_set: &WriteSet,
+        delta_before: &HashMap<StateKey, Option<StateValue>>,
+    ) -> Result<()> {
+        let output_path = self.path.join(format!("[{}] {}", self.config.ops, name));
+        std::fs::create_dir_all(&output_path)?;
+
+        let summary_path = output_path.join("summary.json");
+        std::fs::write(summary_path, serde_json::to_string_pretty(summary)?)?;
+        save_events(&output_path.join("events.json"), &self.state_store, events)?;
+        save_write_set(
+            &self.state_store,
+            &output_path.join("write_set.json"),
+            write_set,
+        )?;
+        self.save_operation_state_changes(&output_path, delta_before)?;
+
+        self.config.ops += 1;
+        self.config.save_to_file(&self.path.join("config.json"))?;
+        save_delta(&self.path.join("delta.json"), &self.state_store.delta())?;
+        self.save_remote_cache()?;
 
         Ok(())
     }
 
+    /// Sets the on-chain time (`0x1::timestamp`) to the given timestamp, in microseconds.
+    ///
+    /// The time cannot go backwards. This counts as a session operation but is not a real
+    /// transaction, as it modifies the storage state directly. No block is produced, so
+    /// epoch changes only happen on the next block prologue (see
+    /// [`Session::execute_block_prologue`]).
+    pub fn set_time(&mut self, timestamp_usecs: u64) -> Result<()> {
+        let before = self.now_microseconds()?;
+        if timestamp_usecs < before {
+            anyhow::bail!(
+                "Cannot set the time to {} as it is before the current time {}",
+                timestamp_usecs,
+                before
+            );
+        }
+
+        // Keep the metadata of the existing state value, as a transaction updating the time would
+        let state_key = StateKey::on_chain_config::<CurrentTimeMicroseconds>()?;
+        let state_value = self
+            .state_store
+            .get_state_value(&state_key)?
+            .ok_or_else(|| anyhow::anyhow!("The on-chain time does not exist"))?;
+        let data = bcs::to_bytes(&CurrentTimeMicroseconds {
+            microseconds: timestamp_usecs,
+        })?;
+        let write_set = WriteSetMut::new(vec![(
+            state_key,
+            WriteOp::modification(data.into(), state_value.into_metadata()),
+        )])
+        .freeze()?;
+
+        let delta_before = self.state_store.delta();
+        self.state_store.apply_write_set(&write_set)?;
+
+        let summary = Summary::SetTime {
+            before,
+            after: timestamp_usecs,
+        };
+        self.save_system_operation("set time", &summary, &[], &write_set, &delta_before)
+    }
+
+    /// Advances the on-chain time by the given duration, in microseconds.
+    ///
+    /// See [`Session::set_time`].
+    pub fn advance_time(&mut self, duration_usecs: u64) -> Result<()> {
+        let now = self.now_microseconds()?;
+        let timestamp_usecs = now
+            .checked_add(duration_usecs)
+            .ok_or_else(|| anyhow::anyhow!("Timestamp overflow"))?;
+        self.set_time(timestamp_usecs)
+    }
+
+    /// Executes a block prologue for a NIL block (i.e., proposed by the VM) at the current
+    /// on-chain time, and updates the session state.
+    ///
+    /// As on a real network, this starts a new epoch if the epoch interval has passed since the
+    /// last reconfiguration. Combined with [`Session::set_time`], this can be used to move the
+    /// session into the future.
+    pub fn execute_block_prologue(&mut self) -> Result<TransactionStatus> {
+        let timestamp = self.now_microseconds()?;
+        let epoch_before = self.epoch()?;
+
+        let block_metadata = BlockMetadata::new(
+            HashValue::zero(),
+            epoch_before,
+            0,
+            reserved_vm_address(),
+            vec![],
+            vec![],
+            timestamp,
+        );
+        let txn_provider =
+            DefaultTxnProvider::new_without_info(into_signature_verified_block(vec![
+                Transaction::BlockMetadata(block_metadata),
+            ]));
+        let outputs =
+            AptosVMBlockExecutor::new().execute_block_no_limit(&txn_provider, &self.state_store)?;
+        let output = outputs
+            .into_iter()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Block prologue produced no output"))?;
+        let delta_before = self.state_store.delta();
+        self.state_store.apply_write_set(output.write_set())?;
+
+        let summary = Summary::BlockPrologue {
+            timestamp,
+            status: output.status().clone(),
+            epoch_before,
+            epoch_after: self.epoch()?,
+        };
+        self.save_system_operation(
+            "block prologue",
+            &summary,
+            output.events(),
+            output.write_set(),
+            &delta_before,
+        )?;
+
+        Ok(output.status().clone())
+    }
+
+    /// Forces a reconfiguration, starting a new epoch, and updates the session state.
+    ///
+    /// This runs `0x1::aptos_governance::force_end_epoch` as the framework account, which is
+    /// what a governance proposal would do. Note that there can be at most one reconfiguration
+    /// per on-chain timestamp, so the time may need to be advanced first (see
+    /// [`Session::advance_time`]). Fails without changing the session state if no new epoch
+    /// was started.
+    pub fn reconfigure(&mut self) -> Result<()> {
+        let epoch_before = self.epoch()?;
+
+        let (write_set, events) = {
+            let env = AptosEnvironment::new(&self.state_store);
+            let resolver = self.state_store.as_move_resolver();
+            let vm = MoveVmExt::new(&env);
+
+            let module_storage = self.state_store.as_aptos_code_storage(&env);
+            let mut session = vm.new_session(&resolver, SessionId::void(), None);
+
+            let traversal_storage = TraversalStorage::new();
+            let mut traversal_context = TraversalContext::new(&traversal_storage);
+
+            session
+                .execute_function_bypass_visibility(
+                    &ModuleId::new(AccountAddress::ONE, Identifier::new("aptos_governance")?),
+                    &Identifier::new("force_end_epoch")?,
+                    vec![],
+                    serialize_values(&vec![MoveValue::Signer(AccountAddress::ONE)]),
+                    &mut UnmeteredGasMeter,
+                    &mut traversal_context,
+                    &module_storage,
+                )
+                .map_err(|err| {
+                    anyhow::anyhow!(
+                        "Failed to force a reconfiguration: {}",
+                        err.into_vm_status()
+                    )
+                })?;
+
+            let change_set = session
+                .finish(
+                    &ChangeSetConfigs::unlimited_at_gas_feature_version(env.gas_feature_version()),
+                    &module_storage,
+                )
+                .map_err(|err| anyhow::anyhow!("Failed to finish the session: {}", err))?;
+            change_set
+                .try_combine_into_storage_change_set(ModuleWriteSet::empty())
+                .map_err(|err| anyhow::anyhow!("Failed to convert the change set: {:?}", err))?
+                .into_inner()
+        };
+        let delta_before = self.state_store.delta();
+        self.state_store.apply_write_set(&write_set)?;
+
+        let epoch_after = self.epoch()?;
+        if epoch_after == epoch_before {
+            self.state_store.set_delta(delta_before);
+            anyhow::bail!(
+                "Failed to force a reconfiguration: the epoch is still {}, as there was already \
+                 a reconfiguration at the current on-chain time (advance the time first)",
+                epoch_before
+            );
+        }
+
+        let summary = Summary::Reconfigure {
+            epoch_before,
+            epoch_after,
+        };
+        self.save_system_operation("reconfigure", &summary, &events, &write_set, &delta_before)
+    }
+
     /// Executes a transaction and updates the session state.
     ///
+    /// If `impersonate` is set, the authenticators of all signers (sender, secondary signers
+    /// and fee payer) are dropped and the transaction is executed without verifying them, so
+    /// it can be sent on behalf of accounts whose keys are not held (including multisig and
+    /// keyless accounts). All other prologue checks still apply. This is only possible in a
+    /// simulation and the resulting transaction could never be committed on a real network.
+    ///
     /// After execution, selected parts of the transaction output get saved to a dedicated directory for inspection:
     /// - Write set changes
     /// - Emitted events
+    /// - State changes (in a reversible format, used for diffs)
     pub fn execute_transaction(
         &mut self,
         txn: SignedTransaction,
+        impersonate: bool,
     ) -> Result<(VMStatus, TransactionOutput)> {
-        let env = AptosEnvironment::new(&self.state_store);
-        let vm = AptosVM::new(&env, &self.state_store);
-        let log_context = AdapterLogSchema::new(self.state_store.id(), 0);
-
-        let resolver = self.state_store.as_move_resolver();
-        let code_storage = self.state_store.as_aptos_code_storage(&env);
-
-        let (vm_status, vm_output) = vm.execute_user_transaction(
-            &resolver,
-            &code_storage,
-            &txn,
-            &log_context,
-            &AuxiliaryInfo::new(
-                PersistedAuxiliaryInfo::V1 {
-                    transaction_index: 0,
-                },
-                None,
-            ),
-        );
-        let txn_output = vm_output.try_materialize_into_transaction_output(&resolver)?;
+        let (txn, vm_status, txn_output) = if impersonate {
+            let txn = strip_authenticators(txn);
+            let (vm_status, txn_output) =
+                AptosSimulationVM::create_vm_and_simulate_signed_transaction(
+                    &txn,
+                    &self.state_store,
+                );
+            (txn, vm_status, txn_output)
+        } else {
+            let env = AptosEnvironment::new(&self.state_store);
+            let vm = AptosVM::new(&env, &self.state_store);
+            let log_context = AdapterLogSchema::new(self.state_store.id(), 0);
+
+            let resolver = self.state_store.as_move_resolver();
+            let code_storage = self.state_store.as_aptos_code_storage(&env);
+
+            let (vm_status, vm_output) = vm.execute_user_transaction(
+                &resolver,
+                &code_storage,
+                &txn,
+                &log_context,
+                &AuxiliaryInfo::new(
+                    PersistedAuxiliaryInfo::V1 {
+                        transaction_index: 0,
+                    },
+                    None,
+                ),
+            );
+            let txn_output = vm_output.try_materialize_into_transaction_output(&resolver)?;
+            (txn, vm_status, txn_output)
+        };
 
+        let delta_before = self.state_store.delta();
         self.state_store.apply_write_set(txn_output.write_set())?;
 
         fn name_from_executable(executable: &TransactionExecutable) -> String {
@@ -319,12 +921,16 @@ impl Session {
             TransactionPayload::ModuleBundle(_) => unreachable!(),
         };
 
-        let output_path = self
-            .path
-            .join(format!("[{}] execute {}", self.config.ops, name));
+        let output_path = self.path.join(format!(
+            "[{}] execute{} {}",
+            self.config.ops,
+            if impersonate { " (impersonated)" } else { "" },
+            name
+        ));
         std::fs::create_dir_all(&output_path)?;
 
         let summary = Summary::ExecuteTransaction {
+            impersonated: impersonate,
             status: txn_output.status().clone(),
             gas_used: txn_output.gas_used(),
             fee_statement: txn_output.try_extract_fee_statement()?,
@@ -338,14 +944,30 @@ impl Session {
 
         let write_set_path = output_path.join("write_set.json");
         save_write_set(&self.state_store, &write_set_path, txn_output.write_set())?;
+        self.save_operation_state_changes(&output_path, &delta_before)?;
 
         self.config.ops += 1;
         self.config.save_to_file(&self.path.join("config.json"))?;
         save_delta(&self.path.join("delta.json"), &self.state_store.delta())?;
+        self.save_remote_cache()?;
 
         Ok((vm_status, txn_output))
     }
 
+    /// Simulates a transaction on top of the session state, without updating the session state.
+    ///
+    /// As with simulations on a fullnode, the transaction must not have a valid signature.
+    /// This does not count as a session operation, so no outputs are saved.
+    pub fn simulate_transaction(
+        &self,
+        txn: &SignedTransaction,
+    ) -> Result<(VMStatus, TransactionOutput)> {
+        if txn.verify_signature().is_ok() {
+            anyhow::bail!("Simulated transactions must not have a valid signature");
+        }
+        Ok(AptosSimulationVM::create_vm_and_simulate_signed_transaction(txn, &self.state_store))
+    }
+
     /// Executes a view function and returns the output values.
     pub fn execute_view_function(
         &mut self,
@@ -410,11 +1032,111 @@ impl Session {
 
         self.config.ops += 1;
         self.config.save_to_file(&self.path.join("config.json"))?;
+        self.save_remote_cache()?;
 
         res
     }
 
-    // TODO: view resource
+    /// Reads a resource (or resource group member) from the session state, and returns it
+    /// in the same JSON format as the REST API. Returns `None` if the resource does not exist.
+    pub fn view_resource(
+        &mut self,
+        account: AccountAddress,
+        resource_type: &StructTag,
+    ) -> Result<Option<serde_json::Value>> {
+        let annotator = AptosValueAnnotator::new(&self.state_store);
+
+        let resource_bytes = match annotator.view_resource_group_member(resource_type) {
+            Some(group_tag) => {
+                let group_key = StateKey::resource_group(&account, &group_tag);
+                match self.state_store.get_state_value_bytes(&group_key)? {
+                    Some(group_bytes) => {
+                        let mut group: BTreeMap<StructTag, Vec<u8>> =
+                            bcs::from_bytes(&group_bytes)?;
+                        group.remove(resource_type)
+                    },
+                    None => None,
+                }
+            },
+            None => {
+                let resource_key = StateKey::resource(&account, resource_type)?;
+                self.state_store
+                    .get_state_value_bytes(&resource_key)?
+                    .map(|bytes| bytes.to_vec())
+            },
+        };
+
+        let resource = match resource_bytes {
+            Some(bytes) => {
+                let annotated = annotator.view_resource(resource_type, &bytes)?;
+                let resource = aptos_api_types::MoveResource::try_from(annotated)?;
+                Some(serde_json::to_value(resource.data)?)
+            },
+            None => None,
+        };
+
+        let summary = Summary::ViewResource {
+            account,
+            resource_type: resource_type.to_canonical_string(),
+            resource: resource.clone(),
+        };
+        let summary_path = self
+            .path
+            .join(format!(
+                "[{}] view resource {}::{}",
+                self.config.ops,
+                format_module_id(&resource_type.module_id()),
+                resource_type.name
+            ))
+            .join("summary.json");
+        std::fs::create_dir_all(summary_path.parent().unwrap())?;
+        std::fs::write(summary_path, serde_json::to_string_pretty(&summary)?)?;
+
+        self.config.ops += 1;
+        self.config.save_to_file(&self.path.join("config.json"))?;
+        self.save_remote_cache()?;
+
+        Ok(resource)
+    }
+}
+
+/// Replaces the authenticators of all signers of the transaction with empty ones, keeping the
+/// signer addresses, so the transaction can be simulated without the signers' keys.
+fn strip_authenticators(txn: SignedTransaction) -> SignedTransaction {
+    let authenticator = txn.authenticator_ref();
+    let secondary_signer_addresses = authenticator.secondary_signer_addresses();
+    let secondary_signers =
+        vec![AccountAuthenticator::NoAccountAuthenticator; secondary_signer_addresses.len()];
+    let authenticator = match authenticator.fee_payer_address() {
+        Some(fee_payer_address) => TransactionAuthenticator::fee_payer(
+            AccountAuthenticator::NoAccountAuthenticator,
+            secondary_signer_addresses,
+            secondary_signers,
+            fee_payer_address,
+            AccountAuthenticator::NoAccountAuthenticator,
+        ),
+        None if secondary_signer_addresses.is_empty() => {
+            TransactionAuthenticator::single_sender(AccountAuthenticator::NoAccountAuthenticator)
+        },
+        None => TransactionAuthenticator::multi_agent(
+            AccountAuthenticator::NoAccountAuthenticator,
+            secondary_signer_addresses,
+            secondary_signers,
+        ),
+    };
+    SignedTransaction::new_signed_transaction(txn.into_raw_transaction(), authenticator)
+}
+
+/// Returns true iff the operation with the given output directory may have changed the session
+/// state, i.e., unless its summary shows that it only viewed the state or saved a checkpoint.
+fn may_change_state(output_path: &Path) -> bool {
+    let summary = std::fs::read_to_string(output_path.join("summary.json"))
+        .ok()
+        .and_then(|summary| serde_json::from_str(&summary).ok());
+    !matches!(
+        summary,
+        Some(Summary::View { .. } | Summary::ViewResource { .. } | Summary::Checkpoint { .. })
+    )
 }
 
 #[test]
@@ -455,3 +1177,212 @@ async fn test_init_then_load_session_remote() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_view_resource_local() -> Result<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let session_path = temp_dir.path();
+
+    let mut session = Session::init(session_path)?;
+
+    // The framework account exists in the genesis state
+    let account_type: StructTag = "0x1::account::Account".parse()?;
+    let resource = session.view_resource(AccountAddress::ONE, &account_type)?;
+    assert!(resource.is_some_and(|resource| resource.get("sequence_number").is_some()));
+
+    // Resources that do not exist are reported as missing
+    let resource =
+        session.view_resource(AccountAddress::from_hex_literal("0xabcd")?, &account_type)?;
+    assert!(resource.is_none());
+
+    Ok(())
+}
+
+#[test]
+fn test_checkpoint_rewind_and_fork() -> Result<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let session_path = temp_dir.path().join("session");
+    let account = AccountAddress::from_hex_literal("0xabcd")?;
+
+    let mut session = Session::init(&session_path)?;
+    session.checkpoint("genesis")?;
+    session.fund_account(account, 100)?;
+    assert_eq!(session.state_store.get_apt_balance(account)?, 100);
+
+    // Checkpoint names must be unique and valid
+    assert!(session.checkpoint("genesis").is_err());
+    assert!(session.checkpoint("../genesis").is_err());
+
+    // Fork from the current state and from the checkpoint
+    let forked = session.fork(temp_dir.path().join("fork_current"), None)?;
+    assert_eq!(forked.state_store.get_apt_balance(account)?, 100);
+    let forked = session.fork(temp_dir.path().join("fork_genesis"), Some("genesis"))?;
+    assert_eq!(forked.state_store.get_apt_balance(account)?, 0);
+    assert_eq!(forked.config.base, session.config.base);
+
+    // Rewind to the checkpoint, and verify it persists across loads
+    session.rewind("genesis")?;
+    assert_eq!(session.state_store.get_apt_balance(account)?, 0);
+    let session_loaded = Session::load(&session_path)?;
+    assert_eq!(session.config, session_loaded.config);
+    assert_eq!(
+        session.state_store.delta(),
+        session_loaded.state_store.delta()
+    );
+    assert!(session.rewind("unknown").is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_execute_impersonated_transaction() -> Result<()> {
+    use aptos_crypto::{ed25519::Ed25519PrivateKey, PrivateKey, SigningKey, Uniform};
+    use aptos_types::transaction::{EntryFunction, ExecutionStatus, RawTransaction};
+    use move_core_types::ident_str;
+
+    let temp_dir = tempfile::tempdir()?;
+    let sender = AccountAddress::from_hex_literal("0xabcd")?;
+    let receiver = AccountAddress::from_hex_literal("0xbeef")?;
+
+    let mut session = Session::init(temp_dir.path())?;
+    session.fund_account(sender, 1_000_000_000)?;
+
+    // Sign the transfer with a key that does not match the sender's authentication key
+    let raw_txn = RawTransaction::new(
+        sender,
+        0,
+        TransactionPayload::EntryFunction(EntryFunction::new(
+            ModuleId::new(AccountAddress::ONE, ident_str!("aptos_account").to_owned()),
+            ident_str!("transfer").to_owned(),
+            vec![],
+            vec![bcs::to_bytes(&receiver)?, bcs::to_bytes(&100u64)?],
+        )),
+        100_000,
+        100,
+        u64::MAX,
+        session.state_store.get_chain_id()?,
+    );
+    let private_key = Ed25519PrivateKey::generate_for_testing();
+    let signature = private_key.sign(&raw_txn)?;
+    let txn = SignedTransaction::new(raw_txn, private_key.public_key(), signature);
+
+    // Without impersonation, the prologue rejects the mismatching authentication key
+    let (_, txn_output) = session.execute_transaction(txn.clone(), false)?;
+    assert!(matches!(txn_output.status(), TransactionStatus::Discard(_)));
+    assert_eq!(session.state_store.get_apt_balance(receiver)?, 0);
+
+    // With impersonation, the transaction is executed on behalf of the sender
+    let (_, txn_output) = session.execute_transaction(txn, true)?;
+    assert_eq!(
+        txn_output.status(),
+        &TransactionStatus::Keep(ExecutionStatus::Success)
+    );
+    assert_eq!(session.state_store.get_apt_balance(receiver)?, 100);
+
+    Ok(())
+}
+
+#[test]
+fn test_diff() -> Result<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let account = AccountAddress::from_hex_literal("0xabcd")?;
+
+    let mut session = Session::init(temp_dir.path())?;
+    session.checkpoint("start")?;
+    session.fund_account(account, 100)?;
+
+    session.set_time(1_000_000)?;
+    session.execute_block_prologue()?;
+
+    // Funding creates the primary fungible store, and the balance change is attributed to its owner
+    let diff = session.diff(
+        &SessionPoint::Checkpoint("start".to_string()),
+        &SessionPoint::Operation(1),
+    )?;
+    assert_eq!(diff.balances.len(), 1);
+    assert_eq!(diff.balances[0].account, account);
+    assert_eq!(diff.balances[0].delta, 100);
+    assert!(diff.resources.iter().any(|resource| resource.resource_type
+        == "0x1::fungible_asset::FungibleStore"
+        && resource.change == crate::ChangeKind::Created));
+    assert!(diff.events.is_empty());
+
+    // The changes of several operations are combined, including the emitted events
+    let diff = session.diff(&SessionPoint::Operation(1), &SessionPoint::Current)?;
+    assert!(diff.balances.is_empty());
+    assert!(diff.resources.iter().any(|resource| resource.resource_type
+        == "0x1::timestamp::CurrentTimeMicroseconds"
+        && resource.change == crate::ChangeKind::Modified));
+    assert!(!diff.events.is_empty());
+    assert!(diff.events.iter().all(|event| event.operation == 3));
+
+    // Comparing a point with itself yields no changes, and points must be in order
+    assert!(session
+        .diff(&SessionPoint::Current, &SessionPoint::Current)?
+        .is_empty());
+    assert!(session
+        .diff(&SessionPoint::Current, &SessionPoint::Base)
+        .is_err());
+
+    // The names of the base and current states, and numbers, cannot be used for checkpoints
+    assert!(session.checkpoint("current").is_err());
+    assert!(session.checkpoint("1").is_err());
+
+    // View operations do not save state changes, and are skipped
+    session.view_resource(account, &"0x1::account::Account".parse()?)?;
+    assert!(session
+        .diff(&SessionPoint::Operation(3), &SessionPoint::Current)?
+        .is_empty());
+
+    // State changing operations without saved state changes (e.g., performed by an older
+    // version) cannot be compared
+    std::fs::remove_file(
+        temp_dir
+            .path()
+            .join("[1] fund (fungible)")
+            .join("state_changes.json"),
+    )?;
+    let error = session
+        .diff(&SessionPoint::Base, &SessionPoint::Current)
+        .unwrap_err();
+    assert!(error.to_string().contains("[1] fund (fungible)"));
+
+    Ok(())
+}
+
+#[test]
+fn test_time_and_reconfiguration() -> Result<()> {
+    use aptos_types::transaction::ExecutionStatus;
+
+    let temp_dir = tempfile::tempdir()?;
+
+    let mut session = Session::init(temp_dir.path())?;
+    let epoch = session.epoch()?;
+
+    // The time can be set and advanced, but not moved backwards
+    session.set_time(1_000_000)?;
+    session.advance_time(1_000_000)?;
+    assert_eq!(session.now_microseconds()?, 2_000_000);
+    assert!(session.set_time(1_000_000).is_err());
+
+    // A NIL block can be produced at the current time
+    let status = session.execute_block_prologue()?;
+    assert_eq!(status, TransactionStatus::Keep(ExecutionStatus::Success));
+    assert_eq!(session.now_microseconds()?, 2_000_000);
+
+    session.reconfigure()?;
+    assert_eq!(session.epoch()?, epoch + 1);
+
+    // A second reconfiguration at the same time does not start a new epoch, so it fails
+    let ops = session.config.ops;
+    assert!(session.reconfigure().is_err());
+    assert_eq!(session.epoch()?, epoch + 1);
+    assert_eq!(session.config.ops, ops);
+
+    // Once the time is advanced, a new epoch can be started again
+    session.advance_time(1)?;
+    session.reconfigure()?;
+    assert_eq!(session.epoch()?, epoch + 2);
+
+    Ok(())
+}
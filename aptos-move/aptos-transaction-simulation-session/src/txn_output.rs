@@ -1,18 +1,61 @@
 // Copyright (c) Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::state_store::HumanReadable;
+use crate::{
+    delta::{decode_delta, encode_delta},
+    state_store::HumanReadable,
+};
 use anyhow::{bail, Result};
 use aptos_resource_viewer::AptosValueAnnotator;
 use aptos_types::{
     access_path::Path as AccessPath,
     contract_event::ContractEvent,
-    state_store::{state_key::inner::StateKeyInner, StateView},
+    state_store::{
+        state_key::{inner::StateKeyInner, StateKey},
+        state_value::StateValue,
+        StateView,
+    },
     write_set::{PersistedWriteOp, WriteSet},
 };
 use move_core_types::language_storage::{StructTag, TypeTag};
+use serde::{Deserialize, Serialize};
 use serde_json::json;
-use std::{collections::BTreeMap, path::Path};
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::Path,
+};
+
+/// Decodes a state value into a human-readable JSON value.
+///
+/// Resources (including all members of resource groups) are annotated with field names and
+/// structure, while all other state values are hex encoded.
+pub fn annotate_state_value(
+    annotator: &AptosValueAnnotator<'_, impl StateView>,
+    state_key: &StateKey,
+    data: &[u8],
+) -> Result<serde_json::Value> {
+    let val = match state_key.inner() {
+        StateKeyInner::AccessPath(access_path) => match access_path.get_path() {
+            AccessPath::Resource(struct_tag) => {
+                json!(annotator.view_resource(&struct_tag, data)?)
+            },
+            AccessPath::ResourceGroup(_struct_tag) => {
+                let group: BTreeMap<StructTag, Vec<u8>> = bcs::from_bytes(data)?;
+
+                let mut group_pretty = BTreeMap::new();
+
+                for (k, v) in group {
+                    group_pretty.insert(k.to_canonical_string(), annotator.view_resource(&k, &v)?);
+                }
+
+                json!(group_pretty)
+            },
+            _ => json!(hex::encode(data)),
+        },
+        _ => json!(hex::encode(data)),
+    };
+    Ok(val)
+}
 
 /// Writes a write set to a file in a human-readable format.
 ///
@@ -33,30 +76,7 @@ pub fn save_write_set(
     for (k, v) in write_set.write_op_iter() {
         let key = HumanReadable(k).to_string();
 
-        let encode_data = |data: &[u8]| -> Result<serde_json::Value> {
-            let val = match k.inner() {
-                StateKeyInner::AccessPath(access_path) => match access_path.get_path() {
-                    AccessPath::Resource(struct_tag) => {
-                        json!(annotator.view_resource(&struct_tag, data)?)
-                    },
-                    AccessPath::ResourceGroup(_struct_tag) => {
-                        let group: BTreeMap<StructTag, Vec<u8>> = bcs::from_bytes(data)?;
-
-                        let mut group_pretty = BTreeMap::new();
-
-                        for (k, v) in group {
-                            group_pretty
-                                .insert(k.to_canonical_string(), annotator.view_resource(&k, &v)?);
-                        }
-
-                        json!(group_pretty)
-                    },
-                    _ => json!(hex::encode(&data)),
-                },
-                _ => json!(hex::encode(&data)),
-            };
-            Ok(val)
-        };
+        let encode_data = |data: &[u8]| annotate_state_value(&annotator, k, data);
 
         let val = match v.to_persistable() {
             PersistedWriteOp::Creation(data) => {
@@ -159,3 +179,43 @@ pub fn save_events(
 
     Ok(())
 }
+
+/// The values of the state items changed by a session operation, before and after the operation.
+#[derive(Debug, Default)]
+pub struct StateChanges {
+    pub before: HashMap<StateKey, Option<StateValue>>,
+    pub after: HashMap<StateKey, Option<StateValue>>,
+}
+
+/// The file format of [`StateChanges`], with the state values encoded as in the session delta.
+#[derive(Serialize, Deserialize)]
+struct EncodedStateChanges<M> {
+    before: M,
+    after: M,
+}
+
+/// Saves the state changes of a session operation to a file.
+///
+/// Unlike [`save_write_set`], this format is reversible (state values are hex-encoded BCS
+/// bytes, as in the session delta), so the changes of several operations can be combined.
+pub fn save_state_changes(state_changes_path: &Path, changes: &StateChanges) -> Result<()> {
+    let encoded = EncodedStateChanges {
+        before: encode_delta(&changes.before)?,
+        after: encode_delta(&changes.after)?,
+    };
+    std::fs::write(state_changes_path, serde_json::to_string_pretty(&encoded)?)?;
+
+    Ok(())
+}
+
+/// Loads the state changes of a session operation previously saved with [`save_state_changes`].
+pub fn load_state_changes(state_changes_path: &Path) -> Result<StateChanges> {
+    let json = std::fs::read_to_string(state_changes_path)?;
+    let encoded: EncodedStateChanges<HashMap<HumanReadable<StateKey>, Option<String>>> =
+        serde_json::from_str(&json)?;
+
+    Ok(StateChanges {
+        before: decode_delta(encoded.before)?,
+        after: decode_delta(encoded.after)?,
+    })
+}
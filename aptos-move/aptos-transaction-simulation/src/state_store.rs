@@ -5,7 +5,8 @@ use crate::{genesis::GENESIS_CHANGE_SET_HEAD, Account, AccountData};
 use anyhow::{anyhow, bail, Result};
 use aptos_types::{
     account_config::{
-        primary_apt_store, CoinStoreResource, FungibleStoreResource, ObjectGroupResource,
+        primary_apt_store, CoinStoreResource, ConcurrentFungibleBalanceResource,
+        FungibleStoreResource, ObjectGroupResource,
     },
     chain_id::ChainId,
     on_chain_config::{FeatureFlag, Features, OnChainConfig},
@@ -288,6 +289,42 @@ pub trait SimulationStateStore: TStateView<Key = StateKey> {
 
     /// Adds APT to an account's fungible store.
     fn fund_apt_fungible_store(&self, address: AccountAddress, amount: u64) -> Result<(u64, u64)> {
+        self.update_apt_fungible_store_balance(address, |balance| {
+            balance
+                .checked_add(amount)
+                .ok_or_else(|| anyhow!("failed to fund account -- APT balance overflow"))
+        })
+    }
+
+    /// Sets the APT balance of an account, and returns the balance before and after.
+    /// The balance is held in the fungible store, so any balance in the legacy coin store
+    /// is cleared (and included in the balance before).
+    fn set_apt_balance(&self, address: AccountAddress, amount: u64) -> Result<(u64, u64)> {
+        let legacy_balance = match self.get_resource::<CoinStoreResource<AptosCoinType>>(address)? {
+            Some(mut coin_store) if coin_store.coin() > 0 => {
+                let legacy_balance = coin_store.coin();
+                coin_store.set_coin(0);
+                self.set_resource(address, &coin_store)?;
+                legacy_balance
+            },
+            _ => 0,
+        };
+
+        let (before, after) = self.update_apt_fungible_store_balance(address, |_| Ok(amount))?;
+        Ok((legacy_balance + before, after))
+    }
+
+    /// Updates the APT balance of an account's fungible store using the given function, and
+    /// returns the balance before and after. Any concurrent balance in the store is removed
+    /// (and used as the balance before), so that the store balance is used.
+    fn update_apt_fungible_store_balance<F>(
+        &self,
+        address: AccountAddress,
+        update: F,
+    ) -> Result<(u64, u64)>
+    where
+        F: FnOnce(u64) -> Result<u64>,
+    {
         let primary_store_object_address = primary_apt_store(address);
 
         let mut resource_group = self
@@ -298,11 +335,13 @@ pub trait SimulationStateStore: TStateView<Key = StateKey> {
             Some(blob) => bcs::from_bytes(blob)?,
             None => FungibleStoreResource::new(AccountAddress::TEN, 0, false),
         };
+        let before = match resource_group.remove(&ConcurrentFungibleBalanceResource::struct_tag()) {
+            Some(blob) => bcs::from_bytes::<ConcurrentFungibleBalanceResource>(&blob)?.balance(),
+            None => fungible_store.balance,
+        };
 
-        let before = fungible_store.balance;
-        fungible_store.balance += amount;
-        let after = fungible_store.balance;
-
+        let after = update(before)?;
+        fungible_store.balance = after;
         resource_group.insert(
             FungibleStoreResource::struct_tag(),
             bcs::to_bytes(&fungible_store)?,
@@ -541,6 +580,16 @@ impl<V> DeltaStateStore<V> {
     pub fn delta(&self) -> HashMap<StateKey, Option<StateValue>> {
         self.states.read().clone()
     }
+
+    /// Returns the base state view that the state changes are stacked on top of.
+    pub fn base(&self) -> &V {
+        &self.base
+    }
+
+    /// Replaces all state changes stacked on top of the base state view with the given delta.
+    pub fn set_delta(&self, delta: HashMap<StateKey, Option<StateValue>>) {
+        *self.states.write() = delta;
+    }
 }
 
 impl<V> Clone for DeltaStateStore<V>
@@ -746,4 +795,42 @@ mod tests {
             assert_eq!(map, store1.to_btree_map());
         }
     }
+
+    #[test]
+    fn test_set_apt_balance_clears_legacy_coin_store() {
+        let state_store = InMemoryStateStore::new();
+        let account_data = AccountData::with_account(Account::new(), 1_000, 0, false, false);
+        state_store.add_account_data(&account_data).unwrap();
+        let address = *account_data.address();
+
+        // The legacy balance is moved out of the coin store, and the new balance is set
+        assert_eq!(
+            state_store.set_apt_balance(address, 500).unwrap(),
+            (1_000, 500)
+        );
+        assert_eq!(state_store.get_apt_balance_legacy(address).unwrap(), 0);
+        assert_eq!(
+            state_store.get_apt_balance_fungible_store(address).unwrap(),
+            500
+        );
+        assert_eq!(state_store.get_apt_balance(address).unwrap(), 500);
+    }
+
+    #[test]
+    fn test_fund_apt_fungible_store_with_concurrent_balance() {
+        let state_store = InMemoryStateStore::new();
+        let account_data = AccountData::with_account(Account::new(), 1_000, 0, true, true);
+        state_store.add_account_data(&account_data).unwrap();
+        let address = *account_data.address();
+
+        // The concurrent balance is funded (and moved into the fungible store)
+        assert_eq!(
+            state_store.fund_apt_fungible_store(address, 500).unwrap(),
+            (1_000, 1_500)
+        );
+        assert_eq!(
+            state_store.get_apt_balance_fungible_store(address).unwrap(),
+            1_500
+        );
+    }
 }
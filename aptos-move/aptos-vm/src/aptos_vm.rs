@@ -3236,6 +3236,70 @@ impl AptosSimulationVM {
             .expect("Materializing aggregator V1 deltas should never fail");
         (vm_status, txn_output)
     }
+
+    /// Simulates a signed transaction on a newly created VM instance, using a modified version
+    /// of the production gas meter (e.g., a gas profiler that records the call graph). The
+    /// modified gas meter is returned alongside the output, unless the transaction could not
+    /// be executed at all (in which case, the output is discarded).
+    /// *Precondition:* the transaction must **not** have a valid signature.
+    pub fn create_vm_and_simulate_signed_transaction_with_modified_gas_meter<G, F>(
+        transaction: &SignedTransaction,
+        state_view: &impl StateView,
+        modify_gas_meter: F,
+    ) -> (VMStatus, TransactionOutput, Option<G>)
+    where
+        F: FnOnce(ProdGasMeter<'static, NoopBlockSynchronizationKillSwitch>) -> G,
+        G: AptosGasMeter,
+    {
+        assert_err!(
+            transaction.verify_signature(),
+            "Simulated transaction should not have a valid signature"
+        );
+
+        let env = AptosEnvironment::new(state_view);
+        let mut vm = AptosVM::new(&env, state_view);
+        vm.is_simulation = true;
+
+        let log_context = AdapterLogSchema::new(state_view.id(), 0);
+        let original_view = state_view.as_move_resolver();
+        let patched_view = Self::patch_randomness_seed(&original_view);
+        let resolver = vm.as_move_resolver(&patched_view);
+        let code_storage = state_view.as_aptos_code_storage(&env);
+
+        let (vm_status, vm_output, gas_meter) = match vm
+            .execute_user_transaction_with_custom_gas_meter(
+                &resolver,
+                &code_storage,
+                transaction,
+                &log_context,
+                |gas_feature_version,
+                 vm_gas_params,
+                 storage_gas_params,
+                 is_approved_gov_script,
+                 meter_balance,
+                 _maybe_block_synchronization_kill_switch| {
+                    modify_gas_meter(make_prod_gas_meter(
+                        gas_feature_version,
+                        vm_gas_params,
+                        storage_gas_params,
+                        is_approved_gov_script,
+                        meter_balance,
+                        &NoopBlockSynchronizationKillSwitch {},
+                    ))
+                },
+                &AuxiliaryInfo::new_empty(),
+            ) {
+            Ok((vm_status, vm_output, gas_meter)) => (vm_status, vm_output, Some(gas_meter)),
+            Err(vm_status) => {
+                let vm_output = discarded_output(vm_status.status_code());
+                (vm_status, vm_output, None)
+            },
+        };
+        let txn_output = vm_output
+            .try_materialize_into_transaction_output(&resolver)
+            .expect("Materializing aggregator V1 deltas should never fail");
+        (vm_status, txn_output, gas_meter)
+    }
 }
 
 fn create_account_if_does_not_exist(
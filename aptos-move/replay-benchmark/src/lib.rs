@@ -5,7 +5,7 @@ pub mod commands;
 mod diff;
 mod execution;
 mod generator;
-mod overrides;
+pub mod overrides;
 mod runner;
 mod state_view;
 mod workload;
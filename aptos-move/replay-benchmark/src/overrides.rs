@@ -88,10 +88,23 @@ impl OverrideConfig {
         })
     }
 
-    pub(crate) fn get_state_override(
-        &self,
-        state_view: &impl StateView,
-    ) -> HashMap<StateKey, StateValue> {
+    /// Creates a configuration that only overrides the given packages, which were built using the
+    /// specified build options.
+    pub fn with_built_packages(packages: Vec<BuiltPackage>, build_options: BuildOptions) -> Self {
+        Self {
+            additional_enabled_features: vec![],
+            additional_disabled_features: vec![],
+            gas_feature_version: None,
+            package_override: PackageOverride {
+                packages,
+                build_options,
+            },
+        }
+    }
+
+    /// Returns the state values to override on top of the given state view. Panics if the
+    /// state view cannot be read, or if an overridden package is inconsistent.
+    pub fn get_state_override(&self, state_view: &impl StateView) -> HashMap<StateKey, StateValue> {
         let mut state_override = HashMap::new();
 
         // Enable/disable features.
@@ -160,7 +173,13 @@ impl OverrideConfig {
                                 package_address, err
                             )
                         })
-                        .expect("Package registry for override must always exist"),
+                        .unwrap_or_else(|| {
+                            // New packages may be published at addresses without any packages.
+                            let package_registry = PackageRegistry { packages: vec![] };
+                            let bytes = bcs::to_bytes(&package_registry)
+                                .expect("Package registry should serialize");
+                            StateValue::new_legacy(bytes.into())
+                        }),
                 };
 
             let metadata = package.extract_metadata().unwrap_or_else(|err| {
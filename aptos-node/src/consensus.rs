@@ -4,7 +4,7 @@
 use crate::{network::ApplicationNetworkInterfaces, services};
 use aptos_admin_service::AdminService;
 use aptos_channels::aptos_channel::Receiver;
-use aptos_config::config::NodeConfig;
+use aptos_config::config::{NodeConfig, ReloadableTransactionFilters};
 use aptos_consensus::{
     consensus_observer::{
         network::{
@@ -45,6 +45,7 @@ pub fn create_consensus_runtime(
     consensus_to_mempool_sender: Sender<QuorumStoreRequest>,
     vtxn_pool: VTxnPoolState,
     consensus_publisher: Option<Arc<ConsensusPublisher>>,
+    transaction_filters: ReloadableTransactionFilters,
     admin_service: &mut AdminService,
 ) -> Option<Runtime> {
     consensus_network_interfaces.map(|consensus_network_interfaces| {
@@ -57,6 +58,7 @@ pub fn create_consensus_runtime(
             consensus_to_mempool_sender.clone(),
             vtxn_pool,
             consensus_publisher.clone(),
+            transaction_filters,
         );
         admin_service.set_consensus_dbs(consensus_db, quorum_store_db);
 
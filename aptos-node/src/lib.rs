@@ -22,7 +22,9 @@ use anyhow::{anyhow, Context};
 use aptos_admin_service::AdminService;
 use aptos_api::bootstrap as bootstrap_api;
 use aptos_build_info::build_information;
-use aptos_config::config::{merge_node_config, NodeConfig, PersistableConfig};
+use aptos_config::config::{
+    merge_node_config, NodeConfig, PersistableConfig, ReloadableTransactionFilters,
+};
 use aptos_framework::ReleaseBundle;
 use aptos_genesis::builder::GenesisConfiguration;
 use aptos_logger::{prelude::*, telemetry_log_writer::TelemetryLog, Level, LoggerFilterUpdater};
@@ -732,6 +734,7 @@ pub fn setup_environment_and_start_node(
         consensus_reconfig_subscription,
         dkg_subscriptions,
         jwk_consensus_subscriptions,
+        api_commit_subscription,
     ) = state_sync::create_event_subscription_service(&node_config, &db_rw);
 
     // Set up the networks and gather the application network handles
@@ -793,11 +796,16 @@ pub fn setup_environment_and_start_node(
         update_receiver,
         api_port_tx,
         indexer_grpc_port_tx,
+        api_commit_subscription,
     )?;
 
     // Set mempool client sender in order to enable the Mempool API in the admin service
     admin_service.set_mempool_client_sender(mempool_client_sender);
 
+    // Create the runtime reloadable transaction filters (and enable updates via the admin service)
+    let transaction_filters = ReloadableTransactionFilters::new(&node_config.transaction_filters);
+    admin_service.set_transaction_filters(transaction_filters.clone());
+
     // Create mempool and get the consensus to mempool sender
     let (mempool_runtime, consensus_to_mempool_sender) =
         services::start_mempool_runtime_and_get_consensus_sender(
@@ -808,6 +816,7 @@ pub fn setup_environment_and_start_node(
             mempool_listener,
             mempool_client_receiver,
             peers_and_metadata,
+            transaction_filters.clone(),
         );
 
     // Create the DKG runtime and get the VTxn pool
@@ -848,6 +857,7 @@ pub fn setup_environment_and_start_node(
         consensus_to_mempool_sender.clone(),
         vtxn_pool,
         consensus_publisher.clone(),
+        transaction_filters,
         &mut admin_service,
     );
 
@@ -4,7 +4,7 @@
 use crate::{bootstrap_api, indexer, mpsc::Receiver, network::ApplicationNetworkInterfaces};
 use aptos_admin_service::AdminService;
 use aptos_build_info::build_information;
-use aptos_config::config::NodeConfig;
+use aptos_config::config::{NodeConfig, ReloadableTransactionFilters};
 use aptos_consensus::{
     consensus_observer::publisher::consensus_publisher::ConsensusPublisher,
     network_interface::ConsensusMsg, persistent_liveness_storage::StorageWriteProxy,
@@ -13,7 +13,9 @@ use aptos_consensus::{
 use aptos_consensus_notifications::ConsensusNotifier;
 use aptos_data_client::client::AptosDataClient;
 use aptos_db_indexer::{db_indexer::InternalIndexerDB, indexer_reader::IndexerReaders};
-use aptos_event_notifications::{DbBackedOnChainConfig, ReconfigNotificationListener};
+use aptos_event_notifications::{
+    CommitNotificationListener, DbBackedOnChainConfig, ReconfigNotificationListener,
+};
 use aptos_indexer_grpc_fullnode::runtime::bootstrap as bootstrap_indexer_grpc;
 use aptos_indexer_grpc_table_info::runtime::{
     bootstrap as bootstrap_indexer_table_info, bootstrap_internal_indexer_db,
@@ -56,6 +58,7 @@ pub fn bootstrap_api_and_indexer(
     update_receiver: Option<WatchReceiver<(Instant, Version)>>,
     api_port_tx: Option<oneshot::Sender<u16>>,
     indexer_grpc_port_tx: Option<oneshot::Sender<u16>>,
+    api_commit_subscription: Option<CommitNotificationListener>,
 ) -> anyhow::Result<(
     Receiver<MempoolClientRequest>,
     Option<Runtime>,
@@ -105,6 +108,7 @@ pub fn bootstrap_api_and_indexer(
             mempool_client_sender.clone(),
             indexer_reader.clone(),
             api_port_tx,
+            api_commit_subscription,
         )?)
     } else {
         None
@@ -149,6 +153,7 @@ pub fn start_consensus_runtime(
     consensus_to_mempool_sender: Sender<QuorumStoreRequest>,
     vtxn_pool: VTxnPoolState,
     consensus_publisher: Option<Arc<ConsensusPublisher>>,
+    transaction_filters: ReloadableTransactionFilters,
 ) -> (Runtime, Arc<StorageWriteProxy>, Arc<QuorumStoreDB>) {
     let instant = Instant::now();
 
@@ -165,6 +170,7 @@ pub fn start_consensus_runtime(
         reconfig_subscription,
         vtxn_pool,
         consensus_publisher,
+        transaction_filters,
     );
     debug!("Consensus started in {} ms", instant.elapsed().as_millis());
 
@@ -180,6 +186,7 @@ pub fn start_mempool_runtime_and_get_consensus_sender(
     mempool_listener: MempoolNotificationListener,
     mempool_client_receiver: Receiver<MempoolClientRequest>,
     peers_and_metadata: Arc<PeersAndMetadata>,
+    transaction_filters: ReloadableTransactionFilters,
 ) -> (Runtime, Sender<QuorumStoreRequest>) {
     // Create a communication channel between consensus and mempool
     let (consensus_to_mempool_sender, consensus_to_mempool_receiver) =
@@ -197,6 +204,7 @@ pub fn start_mempool_runtime_and_get_consensus_sender(
         mempool_listener,
         mempool_reconfig_subscription,
         peers_and_metadata,
+        transaction_filters.mempool_filter,
     );
     debug!("Mempool started in {} ms", instant.elapsed().as_millis());
 
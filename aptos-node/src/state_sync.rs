@@ -10,8 +10,8 @@ use aptos_data_streaming_service::{
     streaming_service::DataStreamingService,
 };
 use aptos_event_notifications::{
-    DbBackedOnChainConfig, EventNotificationListener, EventSubscriptionService,
-    ReconfigNotificationListener,
+    CommitNotificationListener, DbBackedOnChainConfig, EventNotificationListener,
+    EventSubscriptionService, ReconfigNotificationListener,
 };
 use aptos_executor::chunk_executor::ChunkExecutor;
 use aptos_infallible::RwLock;
@@ -39,6 +39,7 @@ use tokio::runtime::Runtime;
 
 /// Creates the event subscription service and two reconfiguration
 /// notification listeners (for mempool and consensus, respectively).
+/// Also creates a commit notification listener for the API (if streaming is enabled).
 pub fn create_event_subscription_service(
     node_config: &NodeConfig,
     db_rw: &DbReaderWriter,
@@ -55,6 +56,7 @@ pub fn create_event_subscription_service(
         ReconfigNotificationListener<DbBackedOnChainConfig>,
         EventNotificationListener,
     )>, // (reconfig_events, jwk_updated_events) for JWK consensus
+    Option<CommitNotificationListener>, // Commit notifications for the API (e.g., streaming)
 ) {
     // Create the event subscription service
     let mut event_subscription_service =
@@ -114,6 +116,17 @@ pub fn create_event_subscription_service(
         None
     };
 
+    // Create a commit subscription for the API (if streaming is enabled)
+    let api_commit_subscription = if node_config.api.enabled && node_config.api.stream_enabled {
+        Some(
+            event_subscription_service
+                .subscribe_to_commits()
+                .expect("The API must subscribe to commits"),
+        )
+    } else {
+        None
+    };
+
     (
         event_subscription_service,
         mempool_reconfig_subscription,
@@ -121,6 +134,7 @@ pub fn create_event_subscription_service(
         consensus_reconfig_subscription,
         dkg_subscriptions,
         jwk_consensus_subscriptions,
+        api_commit_subscription,
     )
 }
 
@@ -51,8 +51,13 @@ pub struct ApiConfig {
     /// Enables transaction simulation
     #[serde(default = "default_enabled")]
     pub transaction_simulation_enabled: bool,
+    /// Enables execution traces for transaction simulation (i.e., `trace=true`)
+    #[serde(default = "default_disabled")]
+    pub transaction_simulation_trace_enabled: bool,
     /// Maximum number of transactions that can be sent with the Batch submit API
     pub max_submit_transaction_batch_size: usize,
+    /// Maximum number of transactions that can be sent with the bundle simulation API
+    pub max_simulate_transaction_bundle_size: usize,
     /// Maximum page size for transaction paginated APIs
     pub max_transactions_page_size: u16,
     /// Maximum page size for block transaction APIs
@@ -63,10 +68,18 @@ pub struct ApiConfig {
     pub max_account_resources_page_size: u16,
     /// Maximum page size for module paginated APIs
     pub max_account_modules_page_size: u16,
+    /// Maximum page size for resource history paginated APIs
+    pub max_resource_history_page_size: u16,
+    /// Maximum number of versions to scan in a single resource history request
+    pub max_resource_history_scan_size: u64,
     /// Maximum gas unit limit for view functions
     ///
     /// This limits the execution length of a view function to the given gas used.
     pub max_gas_view_function: u64,
+    /// Maximum number of view functions in a single batch view request
+    pub max_view_function_batch_size: usize,
+    /// Maximum total gas unit limit for all view functions in a single batch view request
+    pub max_gas_view_function_batch: u64,
     /// Optional: Maximum number of worker threads for the API.
     ///
     /// If not set, `runtime_worker_multiplier` will multiply times the number of CPU cores on the machine
@@ -89,16 +102,27 @@ pub struct ApiConfig {
     pub wait_by_hash_poll_interval_ms: u64,
     /// The number of active wait_by_hash requests that can be active at any given time.
     pub wait_by_hash_max_active_connections: usize,
+    /// Enables the streaming endpoints (e.g., for committed transactions and events)
+    pub stream_enabled: bool,
+    /// The number of active streams that can be active at any given time.
+    pub stream_max_active_connections: usize,
+    /// The interval at which streams poll the storage for new transactions
+    /// (this is only used if commit notifications are unavailable).
+    pub stream_poll_interval_ms: u64,
 }
 
 const DEFAULT_ADDRESS: &str = "127.0.0.1";
 const DEFAULT_PORT: u16 = 8080;
 const DEFAULT_REQUEST_CONTENT_LENGTH_LIMIT: u64 = 8 * 1024 * 1024; // 8 MB
 pub const DEFAULT_MAX_SUBMIT_TRANSACTION_BATCH_SIZE: usize = 10;
+const DEFAULT_MAX_SIMULATE_TRANSACTION_BUNDLE_SIZE: usize = 10;
 pub const DEFAULT_MAX_PAGE_SIZE: u16 = 100;
 const DEFAULT_MAX_ACCOUNT_RESOURCES_PAGE_SIZE: u16 = 9999;
 const DEFAULT_MAX_ACCOUNT_MODULES_PAGE_SIZE: u16 = 9999;
+const DEFAULT_MAX_RESOURCE_HISTORY_SCAN_SIZE: u64 = 10_000;
 const DEFAULT_MAX_VIEW_GAS: u64 = 2_000_000; // We keep this value the same as the max number of gas allowed for one single transaction defined in aptos-gas.
+const DEFAULT_MAX_VIEW_FUNCTION_BATCH_SIZE: usize = 50;
+const DEFAULT_MAX_VIEW_FUNCTION_BATCH_GAS: u64 = 5 * DEFAULT_MAX_VIEW_GAS;
 
 fn default_enabled() -> bool {
     true
@@ -125,13 +149,19 @@ impl Default for ApiConfig {
             encode_submission_enabled: default_enabled(),
             transaction_submission_enabled: default_enabled(),
             transaction_simulation_enabled: default_enabled(),
+            transaction_simulation_trace_enabled: default_disabled(),
             max_submit_transaction_batch_size: DEFAULT_MAX_SUBMIT_TRANSACTION_BATCH_SIZE,
+            max_simulate_transaction_bundle_size: DEFAULT_MAX_SIMULATE_TRANSACTION_BUNDLE_SIZE,
             max_block_transactions_page_size: *MAX_RECEIVING_BLOCK_TXNS as u16,
             max_transactions_page_size: DEFAULT_MAX_PAGE_SIZE,
             max_events_page_size: DEFAULT_MAX_PAGE_SIZE,
             max_account_resources_page_size: DEFAULT_MAX_ACCOUNT_RESOURCES_PAGE_SIZE,
             max_account_modules_page_size: DEFAULT_MAX_ACCOUNT_MODULES_PAGE_SIZE,
+            max_resource_history_page_size: DEFAULT_MAX_PAGE_SIZE,
+            max_resource_history_scan_size: DEFAULT_MAX_RESOURCE_HISTORY_SCAN_SIZE,
             max_gas_view_function: DEFAULT_MAX_VIEW_GAS,
+            max_view_function_batch_size: DEFAULT_MAX_VIEW_FUNCTION_BATCH_SIZE,
+            max_gas_view_function_batch: DEFAULT_MAX_VIEW_FUNCTION_BATCH_GAS,
             max_runtime_workers: None,
             runtime_worker_multiplier: 2,
             gas_estimation: GasEstimationConfig::default(),
@@ -141,6 +171,9 @@ impl Default for ApiConfig {
             wait_by_hash_timeout_ms: 1_000,
             wait_by_hash_poll_interval_ms: 20,
             wait_by_hash_max_active_connections: 100,
+            stream_enabled: default_disabled(),
+            stream_max_active_connections: 100,
+            stream_poll_interval_ms: 1_000,
         }
     }
 }
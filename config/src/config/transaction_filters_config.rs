@@ -5,9 +5,11 @@ use aptos_transaction_filters::{
     batch_transaction_filter::BatchTransactionFilter,
     block_transaction_filter::BlockTransactionFilter, transaction_filter::TransactionFilter,
 };
+use arc_swap::ArcSwap;
 use serde::{Deserialize, Serialize};
+use std::{path::PathBuf, sync::Arc};
 
-#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct TransactionFiltersConfig {
     pub api_filter: TransactionFilterConfig, // Filter for the API (e.g., txn simulation)
@@ -15,6 +17,78 @@ pub struct TransactionFiltersConfig {
     pub execution_filter: BlockTransactionFilterConfig, // Filter for execution (e.g., block execution)
     pub mempool_filter: TransactionFilterConfig,        // Filter for mempool (e.g., txn submission)
     pub quorum_store_filter: BatchTransactionFilterConfig, // Filter for quorum store (e.g., batch voting)
+    pub reload_file_path: Option<PathBuf>, // File to watch for runtime filter updates (if any)
+    pub reload_file_poll_interval_ms: u64, // Interval at which to poll the reload file for changes
+}
+
+impl Default for TransactionFiltersConfig {
+    fn default() -> Self {
+        Self {
+            api_filter: TransactionFilterConfig::default(),
+            consensus_filter: BlockTransactionFilterConfig::default(),
+            execution_filter: BlockTransactionFilterConfig::default(),
+            mempool_filter: TransactionFilterConfig::default(),
+            quorum_store_filter: BatchTransactionFilterConfig::default(),
+            reload_file_path: None, // Don't watch a reload file by default
+            reload_file_poll_interval_ms: 5_000, // 5 seconds
+        }
+    }
+}
+
+/// The transaction filters that can be updated at runtime (e.g., via the
+/// admin service), without restarting the node. Clones share the same filters.
+#[derive(Clone, Debug)]
+pub struct ReloadableTransactionFilters {
+    pub consensus_filter: ReloadableFilterConfig<BlockTransactionFilterConfig>,
+    pub mempool_filter: ReloadableFilterConfig<TransactionFilterConfig>,
+    pub quorum_store_filter: ReloadableFilterConfig<BatchTransactionFilterConfig>,
+}
+
+impl ReloadableTransactionFilters {
+    pub fn new(transaction_filters_config: &TransactionFiltersConfig) -> Self {
+        Self {
+            consensus_filter: ReloadableFilterConfig::new(
+                transaction_filters_config.consensus_filter.clone(),
+            ),
+            mempool_filter: ReloadableFilterConfig::new(
+                transaction_filters_config.mempool_filter.clone(),
+            ),
+            quorum_store_filter: ReloadableFilterConfig::new(
+                transaction_filters_config.quorum_store_filter.clone(),
+            ),
+        }
+    }
+}
+
+/// A filter config that can be updated at runtime. Clones share the same
+/// underlying config, so updates are immediately visible to all holders.
+#[derive(Clone, Debug)]
+pub struct ReloadableFilterConfig<T> {
+    filter_config: Arc<ArcSwap<T>>,
+}
+
+impl<T> ReloadableFilterConfig<T> {
+    pub fn new(filter_config: T) -> Self {
+        Self {
+            filter_config: Arc::new(ArcSwap::from_pointee(filter_config)),
+        }
+    }
+
+    /// Returns the current filter config
+    pub fn load(&self) -> Arc<T> {
+        self.filter_config.load_full()
+    }
+
+    /// Replaces the current filter config, and returns the previous config
+    pub fn store(&self, filter_config: T) -> Arc<T> {
+        self.filter_config.swap(Arc::new(filter_config))
+    }
+}
+
+impl<T> From<T> for ReloadableFilterConfig<T> {
+    fn from(filter_config: T) -> Self {
+        Self::new(filter_config)
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
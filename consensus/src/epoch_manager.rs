@@ -60,7 +60,7 @@ use aptos_bounded_executor::BoundedExecutor;
 use aptos_channels::{aptos_channel, message_queues::QueueStyle};
 use aptos_config::config::{
     BatchTransactionFilterConfig, BlockTransactionFilterConfig, ConsensusConfig,
-    DagConsensusConfig, NodeConfig,
+    DagConsensusConfig, NodeConfig, ReloadableFilterConfig, ReloadableTransactionFilters,
 };
 use aptos_consensus_types::{
     block_retrieval::BlockRetrievalRequest,
@@ -177,8 +177,8 @@ pub struct EpochManager<P: OnChainConfigProvider> {
     pending_blocks: Arc<Mutex<PendingBlocks>>,
     key_storage: PersistentSafetyStorage,
 
-    consensus_txn_filter_config: BlockTransactionFilterConfig,
-    quorum_store_txn_filter_config: BatchTransactionFilterConfig,
+    consensus_txn_filter_config: ReloadableFilterConfig<BlockTransactionFilterConfig>,
+    quorum_store_txn_filter_config: ReloadableFilterConfig<BatchTransactionFilterConfig>,
 }
 
 impl<P: OnChainConfigProvider> EpochManager<P> {
@@ -199,6 +199,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
         vtxn_pool: VTxnPoolState,
         rand_storage: Arc<dyn RandStorage<AugmentedData>>,
         consensus_publisher: Option<Arc<ConsensusPublisher>>,
+        transaction_filters: ReloadableTransactionFilters,
     ) -> Self {
         let author = node_config.validator_network.as_ref().unwrap().peer_id();
         let config = node_config.consensus.clone();
@@ -206,9 +207,8 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
         let sr_config = &node_config.consensus.safety_rules;
         let safety_rules_manager = SafetyRulesManager::new(sr_config);
         let key_storage = safety_rules_manager::storage(sr_config);
-        let consensus_txn_filter_config = node_config.transaction_filters.consensus_filter.clone();
-        let quorum_store_txn_filter_config =
-            node_config.transaction_filters.quorum_store_filter.clone();
+        let consensus_txn_filter_config = transaction_filters.consensus_filter;
+        let quorum_store_txn_filter_config = transaction_filters.quorum_store_filter;
 
         Self {
             author,
@@ -14,7 +14,7 @@ use crate::{
     },
 };
 use anyhow::ensure;
-use aptos_config::config::BatchTransactionFilterConfig;
+use aptos_config::config::{BatchTransactionFilterConfig, ReloadableFilterConfig};
 use aptos_consensus_types::payload::TDataInfo;
 use aptos_logger::prelude::*;
 use aptos_short_hex_str::AsShortHexStr;
@@ -43,7 +43,7 @@ pub struct BatchCoordinator {
     max_total_txns: u64,
     max_total_bytes: u64,
     batch_expiry_gap_when_init_usecs: u64,
-    transaction_filter_config: BatchTransactionFilterConfig,
+    transaction_filter_config: ReloadableFilterConfig<BatchTransactionFilterConfig>,
 }
 
 impl BatchCoordinator {
@@ -58,7 +58,7 @@ impl BatchCoordinator {
         max_total_txns: u64,
         max_total_bytes: u64,
         batch_expiry_gap_when_init_usecs: u64,
-        transaction_filter_config: BatchTransactionFilterConfig,
+        transaction_filter_config: ReloadableFilterConfig<BatchTransactionFilterConfig>,
     ) -> Self {
         Self {
             my_peer_id,
@@ -163,26 +163,26 @@ impl BatchCoordinator {
 
         // Filter the transactions in the batches. If any transaction is rejected,
         // the message will be dropped, and all batches will be rejected.
-        if self.transaction_filter_config.is_enabled() {
-            let transaction_filter = &self.transaction_filter_config.batch_transaction_filter();
+        let transaction_filter_config = self.transaction_filter_config.load();
+        if transaction_filter_config.is_enabled() {
+            let transaction_filter = transaction_filter_config.batch_transaction_filter();
             for batch in batches.iter() {
-                for transaction in batch.txns() {
-                    if !transaction_filter.allows_transaction(
+                let denied_transactions = transaction_filter.get_denied_batch_transactions(
+                    batch.batch_info().batch_id(),
+                    batch.author(),
+                    batch.digest(),
+                    batch.txns(),
+                );
+                if let Some(transaction) = denied_transactions.first() {
+                    error!(
+                        "Transaction {}, in batch {}, from {}, was rejected by the filter. Dropping {} batches!",
+                        transaction.committed_hash(),
                         batch.batch_info().batch_id(),
-                        batch.author(),
-                        batch.digest(),
-                        transaction,
-                    ) {
-                        error!(
-                            "Transaction {}, in batch {}, from {}, was rejected by the filter. Dropping {} batches!",
-                            transaction.committed_hash(),
-                            batch.batch_info().batch_id(),
-                            author.short_str().as_str(),
-                            batches.len()
-                        );
-                        counters::RECEIVED_BATCH_REJECTED_BY_FILTER.inc();
-                        return;
-                    }
+                        author.short_str().as_str(),
+                        batches.len()
+                    );
+                    counters::RECEIVED_BATCH_REJECTED_BY_FILTER.inc();
+                    return;
                 }
             }
         }
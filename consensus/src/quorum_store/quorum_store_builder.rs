@@ -28,7 +28,9 @@ use crate::{
     round_manager::VerifiedEvent,
 };
 use aptos_channels::{aptos_channel, message_queues::QueueStyle};
-use aptos_config::config::{BatchTransactionFilterConfig, QuorumStoreConfig};
+use aptos_config::config::{
+    BatchTransactionFilterConfig, QuorumStoreConfig, ReloadableFilterConfig,
+};
 use aptos_consensus_types::{
     common::Author, proof_of_store::ProofCache, request_response::GetPayloadCommand,
 };
@@ -125,7 +127,7 @@ pub struct InnerBuilder {
     author: Author,
     num_validators: u64,
     config: QuorumStoreConfig,
-    transaction_filter_config: BatchTransactionFilterConfig,
+    transaction_filter_config: ReloadableFilterConfig<BatchTransactionFilterConfig>,
     consensus_to_quorum_store_receiver: Receiver<GetPayloadCommand>,
     quorum_store_to_mempool_sender: Sender<QuorumStoreRequest>,
     mempool_txn_pull_timeout_ms: u64,
@@ -161,7 +163,7 @@ impl InnerBuilder {
         author: Author,
         num_validators: u64,
         config: QuorumStoreConfig,
-        transaction_filter_config: BatchTransactionFilterConfig,
+        transaction_filter_config: ReloadableFilterConfig<BatchTransactionFilterConfig>,
         consensus_to_quorum_store_receiver: Receiver<GetPayloadCommand>,
         quorum_store_to_mempool_sender: Sender<QuorumStoreRequest>,
         mempool_txn_pull_timeout_ms: u64,
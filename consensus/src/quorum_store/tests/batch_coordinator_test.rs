@@ -141,7 +141,7 @@ fn create_batch_coordinator(
         10_000,
         10_000,
         10_000,
-        transaction_filter_config,
+        transaction_filter_config.into(),
     )
 }
 
@@ -35,7 +35,7 @@ use crate::{
 };
 use anyhow::{bail, ensure, Context};
 use aptos_channels::aptos_channel;
-use aptos_config::config::{BlockTransactionFilterConfig, ConsensusConfig};
+use aptos_config::config::{BlockTransactionFilterConfig, ConsensusConfig, ReloadableFilterConfig};
 use aptos_consensus_types::{
     block::Block,
     block_data::BlockType,
@@ -274,7 +274,7 @@ pub struct RoundManager {
     onchain_config: OnChainConsensusConfig,
     vtxn_config: ValidatorTxnConfig,
     buffered_proposal_tx: aptos_channel::Sender<Author, VerifiedEvent>,
-    block_txn_filter_config: BlockTransactionFilterConfig,
+    block_txn_filter_config: ReloadableFilterConfig<BlockTransactionFilterConfig>,
     local_config: ConsensusConfig,
     randomness_config: OnChainRandomnessConfig,
     jwk_consensus_config: OnChainJWKConsensusConfig,
@@ -306,7 +306,7 @@ impl RoundManager {
         storage: Arc<dyn PersistentLivenessStorage>,
         onchain_config: OnChainConsensusConfig,
         buffered_proposal_tx: aptos_channel::Sender<Author, VerifiedEvent>,
-        block_txn_filter_config: BlockTransactionFilterConfig,
+        block_txn_filter_config: ReloadableFilterConfig<BlockTransactionFilterConfig>,
         local_config: ConsensusConfig,
         randomness_config: OnChainRandomnessConfig,
         jwk_consensus_config: OnChainJWKConsensusConfig,
@@ -1165,7 +1165,7 @@ impl RoundManager {
         // (e.g., due to filtering) drop the message and do not vote for the block.
         if let Err(error) = self
             .block_store
-            .check_denied_inline_transactions(&proposal, &self.block_txn_filter_config)
+            .check_denied_inline_transactions(&proposal, &self.block_txn_filter_config.load())
         {
             counters::REJECTED_PROPOSAL_DENY_TXN_COUNT.inc();
             bail!(
@@ -218,7 +218,7 @@ fn create_node_for_fuzzing() -> RoundManager {
         storage,
         OnChainConsensusConfig::default(),
         round_manager_tx,
-        BlockTransactionFilterConfig::default(),
+        BlockTransactionFilterConfig::default().into(),
         ConsensusConfig::default(),
         OnChainRandomnessConfig::default_enabled(),
         OnChainJWKConsensusConfig::default_enabled(),
@@ -473,7 +473,7 @@ impl NodeSetup {
             storage.clone(),
             onchain_consensus_config.clone(),
             round_manager_tx,
-            block_txn_filter_config.clone(),
+            block_txn_filter_config.clone().into(),
             local_config,
             onchain_randomness_config.clone(),
             onchain_jwk_consensus_config.clone(),
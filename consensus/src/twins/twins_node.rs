@@ -18,7 +18,7 @@ use crate::{
 use aptos_bounded_executor::BoundedExecutor;
 use aptos_channels::{self, aptos_channel, message_queues::QueueStyle};
 use aptos_config::{
-    config::{NodeConfig, WaypointConfig},
+    config::{NodeConfig, ReloadableTransactionFilters, WaypointConfig},
     generator::{self, ValidatorSwarm},
     network_id::{NetworkId, PeerNetworkId},
 };
@@ -164,6 +164,7 @@ impl SMRNode {
             vtxn_pool,
             Arc::new(InMemRandDb::new()),
             None,
+            ReloadableTransactionFilters::new(&config.transaction_filters),
         );
         let (network_task, network_receiver) =
             NetworkTask::new(network_service_events, self_receiver);
@@ -1,7 +1,10 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_config::config::{AdminServiceConfig, AuthenticationConfig, NodeConfig};
+use aptos_config::config::{
+    AdminServiceConfig, AuthenticationConfig, NodeConfig, ReloadableTransactionFilters,
+    TransactionFiltersConfig,
+};
 use aptos_consensus::{
     persistent_liveness_storage::StorageWriteProxy, quorum_store::quorum_store_db::QuorumStoreDB,
 };
@@ -23,6 +26,7 @@ use std::{
     convert::Infallible,
     net::{SocketAddr, ToSocketAddrs},
     sync::Arc,
+    time::Duration,
 };
 use tokio::runtime::Runtime;
 
@@ -30,6 +34,10 @@ mod consensus;
 #[cfg(unix)]
 mod malloc;
 mod mempool;
+mod transaction_filters;
+
+#[cfg(test)]
+mod tests;
 
 #[derive(Default)]
 pub struct Context {
@@ -39,6 +47,7 @@ pub struct Context {
     consensus_db: RwLock<Option<Arc<StorageWriteProxy>>>,
     quorum_store_db: RwLock<Option<Arc<QuorumStoreDB>>>,
     mempool_client_sender: RwLock<Option<MempoolClientSender>>,
+    transaction_filters: RwLock<Option<ReloadableTransactionFilters>>,
 }
 
 impl Context {
@@ -58,11 +67,16 @@ impl Context {
     fn set_mempool_client_sender(&self, mempool_client_sender: MempoolClientSender) {
         *self.mempool_client_sender.write() = Some(mempool_client_sender);
     }
+
+    fn set_transaction_filters(&self, transaction_filters: ReloadableTransactionFilters) {
+        *self.transaction_filters.write() = Some(transaction_filters);
+    }
 }
 
 pub struct AdminService {
     runtime: Runtime,
     context: Arc<Context>,
+    transaction_filters_config: TransactionFiltersConfig,
 }
 
 impl AdminService {
@@ -97,6 +111,7 @@ impl AdminService {
                 config,
                 ..Default::default()
             }),
+            transaction_filters_config: node_config.transaction_filters.clone(),
         };
         admin_service.start(address, enabled);
 
@@ -121,6 +136,23 @@ impl AdminService {
             .set_mempool_client_sender(mempool_client_sender)
     }
 
+    /// Sets the runtime reloadable transaction filters. This enables the transaction
+    /// filter endpoints, and starts watching the reload file (if one is configured).
+    pub fn set_transaction_filters(&self, transaction_filters: ReloadableTransactionFilters) {
+        self.context
+            .set_transaction_filters(transaction_filters.clone());
+
+        if let Some(reload_file_path) = &self.transaction_filters_config.reload_file_path {
+            let poll_interval =
+                Duration::from_millis(self.transaction_filters_config.reload_file_poll_interval_ms);
+            self.runtime.spawn(transaction_filters::watch_reload_file(
+                reload_file_path.clone(),
+                poll_interval,
+                transaction_filters,
+            ));
+        }
+    }
+
     fn start(&self, address: SocketAddr, enabled: bool) {
         let context = self.context.clone();
         self.runtime.spawn(async move {
@@ -245,6 +277,30 @@ impl AdminService {
                     ))
                 }
             },
+            (hyper::Method::PUT, path)
+                if path.starts_with(transaction_filters::TRANSACTION_FILTERS_PATH_PREFIX)
+                    && context.config.authentication_configs.is_empty() =>
+            {
+                // Filter updates are only allowed if the admin service requires authentication
+                Ok(reply_with_status(
+                    StatusCode::FORBIDDEN,
+                    transaction_filters::UPDATES_REQUIRE_AUTHENTICATION_MESSAGE,
+                ))
+            },
+            (hyper::Method::GET | hyper::Method::PUT, path)
+                if path.starts_with(transaction_filters::TRANSACTION_FILTERS_PATH_PREFIX) =>
+            {
+                let transaction_filters = context.transaction_filters.read().clone();
+                if let Some(transaction_filters) = transaction_filters {
+                    transaction_filters::handle_transaction_filter_request(req, transaction_filters)
+                        .await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Transaction filters are not available.",
+                    ))
+                }
+            },
             _ => Ok(reply_with_status(StatusCode::NOT_FOUND, "Not found.")),
         }
     }
@@ -0,0 +1,315 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::server::{
+    transaction_filters::{
+        apply_reload_file, watch_reload_file, UPDATES_REQUIRE_AUTHENTICATION_MESSAGE,
+    },
+    AdminService, Context,
+};
+use aptos_config::config::{
+    AdminServiceConfig, AuthenticationConfig, BlockTransactionFilterConfig,
+    ReloadableTransactionFilters, TransactionFilterConfig, TransactionFiltersConfig,
+};
+use aptos_temppath::TempPath;
+use aptos_transaction_filters::{
+    block_transaction_filter::BlockTransactionFilter, transaction_filter::TransactionFilter,
+};
+use aptos_types::account_address::AccountAddress;
+use hyper::{body, Body, Method, Request, Response, StatusCode};
+use std::{sync::Arc, time::Duration};
+
+// Useful test constants
+const MEMPOOL_FILTER_PATH: &str = "/transaction_filters/mempool";
+const PASSCODE: &str = "test_passcode";
+
+#[tokio::test]
+async fn test_get_transaction_filter() {
+    // Create the transaction filters with an enabled mempool filter
+    let mempool_filter = create_mempool_filter(AccountAddress::ONE);
+    let transaction_filters = create_transaction_filters(mempool_filter.clone());
+
+    // Get the mempool filter and verify the response
+    let context = create_context(vec![], transaction_filters);
+    let (status, response_body) =
+        send_request(context.clone(), Method::GET, MEMPOOL_FILTER_PATH, "").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        serde_yaml::from_str::<TransactionFilterConfig>(&response_body).unwrap(),
+        mempool_filter
+    );
+
+    // Get an unknown filter and verify that it is not found
+    let (status, _) = send_request(context, Method::GET, "/transaction_filters/unknown", "").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_update_transaction_filter() {
+    // Create the transaction filters and a context that requires authentication
+    let transaction_filters =
+        create_transaction_filters(create_mempool_filter(AccountAddress::ONE));
+    let context = create_context(
+        vec![AuthenticationConfig::PasscodeSha256(sha256::digest(
+            PASSCODE,
+        ))],
+        transaction_filters.clone(),
+    );
+    let update_path = format!("{}?passcode={}", MEMPOOL_FILTER_PATH, PASSCODE);
+
+    // Update the mempool filter and verify that the update is applied
+    let new_mempool_filter = create_mempool_filter(AccountAddress::TWO);
+    let new_mempool_filter_yaml = serde_yaml::to_string(&new_mempool_filter).unwrap();
+    let (status, _) = send_request(
+        context.clone(),
+        Method::PUT,
+        &update_path,
+        &new_mempool_filter_yaml,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        *transaction_filters.mempool_filter.load(),
+        new_mempool_filter
+    );
+
+    // Send an invalid filter update and verify that it is rejected
+    let (status, _) = send_request(
+        context.clone(),
+        Method::PUT,
+        &update_path,
+        "invalid_field: true",
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(
+        *transaction_filters.mempool_filter.load(),
+        new_mempool_filter
+    );
+
+    // Send an unauthenticated filter update and verify that it is rejected
+    let (status, _) = send_request(
+        context,
+        Method::PUT,
+        MEMPOOL_FILTER_PATH,
+        &serde_yaml::to_string(&create_mempool_filter(AccountAddress::ONE)).unwrap(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NETWORK_AUTHENTICATION_REQUIRED);
+    assert_eq!(
+        *transaction_filters.mempool_filter.load(),
+        new_mempool_filter
+    );
+}
+
+#[tokio::test]
+async fn test_update_transaction_filter_without_authentication() {
+    // Create the transaction filters and a context without authentication
+    let mempool_filter = create_mempool_filter(AccountAddress::ONE);
+    let transaction_filters = create_transaction_filters(mempool_filter.clone());
+    let context = create_context(vec![], transaction_filters.clone());
+
+    // Attempt to update the mempool filter and verify that the update is forbidden
+    let new_mempool_filter_yaml =
+        serde_yaml::to_string(&create_mempool_filter(AccountAddress::TWO)).unwrap();
+    let (status, response_body) = send_request(
+        context.clone(),
+        Method::PUT,
+        MEMPOOL_FILTER_PATH,
+        &new_mempool_filter_yaml,
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(response_body, UPDATES_REQUIRE_AUTHENTICATION_MESSAGE);
+    assert_eq!(*transaction_filters.mempool_filter.load(), mempool_filter);
+
+    // Verify that the filter can still be read
+    let (status, _) = send_request(context, Method::GET, MEMPOOL_FILTER_PATH, "").await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_apply_reload_file() {
+    // Create the transaction filters and a reload file
+    let transaction_filters =
+        create_transaction_filters(create_mempool_filter(AccountAddress::ONE));
+    let reload_file = TempPath::new();
+    reload_file.create_as_file().unwrap();
+
+    // Write a reload file that only updates the mempool filter
+    let new_mempool_filter = create_mempool_filter(AccountAddress::TWO);
+    write_mempool_reload_file(&reload_file, &new_mempool_filter);
+
+    // Apply the reload file and verify that only the mempool filter is updated
+    apply_reload_file(reload_file.path(), &transaction_filters)
+        .await
+        .unwrap();
+    assert_eq!(
+        *transaction_filters.mempool_filter.load(),
+        new_mempool_filter
+    );
+    assert_eq!(
+        *transaction_filters.consensus_filter.load(),
+        BlockTransactionFilterConfig::default()
+    );
+
+    // Write a reload file with a valid consensus filter and an invalid mempool filter
+    let new_consensus_filter = BlockTransactionFilterConfig::new(
+        true,
+        BlockTransactionFilter::empty().add_all_filter(false),
+    );
+    write_reload_file(&reload_file, vec![
+        (
+            "consensus_filter",
+            serde_yaml::to_value(&new_consensus_filter).unwrap(),
+        ),
+        (
+            "mempool_filter",
+            serde_yaml::from_str("invalid_field: true").unwrap(),
+        ),
+    ]);
+
+    // Verify that applying the reload file fails, and that no filters are updated
+    assert!(apply_reload_file(reload_file.path(), &transaction_filters)
+        .await
+        .is_err());
+    assert_eq!(
+        *transaction_filters.mempool_filter.load(),
+        new_mempool_filter
+    );
+    assert_eq!(
+        *transaction_filters.consensus_filter.load(),
+        BlockTransactionFilterConfig::default()
+    );
+}
+
+#[tokio::test]
+async fn test_watch_reload_file() {
+    // Create the transaction filters and a reload file path (the file doesn't exist yet)
+    let transaction_filters =
+        create_transaction_filters(create_mempool_filter(AccountAddress::ONE));
+    let reload_file = TempPath::new();
+
+    // Start watching the reload file
+    let watcher = tokio::spawn(watch_reload_file(
+        reload_file.path().to_path_buf(),
+        Duration::from_millis(10),
+        transaction_filters.clone(),
+    ));
+
+    // Write the reload file and verify that the update is eventually applied
+    let new_mempool_filter = create_mempool_filter(AccountAddress::TWO);
+    write_mempool_reload_file(&reload_file, &new_mempool_filter);
+    wait_for_mempool_filter(&transaction_filters, &new_mempool_filter).await;
+
+    // Write an invalid reload file and verify that the watcher keeps the active filter
+    write_reload_file(&reload_file, vec![(
+        "invalid_filter",
+        serde_yaml::Value::Bool(true),
+    )]);
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(
+        *transaction_filters.mempool_filter.load(),
+        new_mempool_filter
+    );
+
+    // Write another valid reload file and verify that the update is eventually applied
+    let new_mempool_filter = create_mempool_filter(AccountAddress::THREE);
+    write_mempool_reload_file(&reload_file, &new_mempool_filter);
+    wait_for_mempool_filter(&transaction_filters, &new_mempool_filter).await;
+
+    watcher.abort();
+}
+
+/// Creates a context for the admin service with the given authentication configs and filters
+fn create_context(
+    authentication_configs: Vec<AuthenticationConfig>,
+    transaction_filters: ReloadableTransactionFilters,
+) -> Arc<Context> {
+    let context = Context {
+        config: AdminServiceConfig {
+            enabled: Some(true),
+            authentication_configs,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    context.set_transaction_filters(transaction_filters);
+    Arc::new(context)
+}
+
+/// Creates an enabled mempool filter that denies transactions from the given sender
+fn create_mempool_filter(sender: AccountAddress) -> TransactionFilterConfig {
+    let transaction_filter = TransactionFilter::empty()
+        .add_sender_filter(false, sender)
+        .add_all_filter(true);
+    TransactionFilterConfig::new(true, transaction_filter)
+}
+
+/// Creates the reloadable transaction filters with the given mempool filter
+fn create_transaction_filters(
+    mempool_filter: TransactionFilterConfig,
+) -> ReloadableTransactionFilters {
+    let transaction_filters_config = TransactionFiltersConfig {
+        mempool_filter,
+        ..Default::default()
+    };
+    ReloadableTransactionFilters::new(&transaction_filters_config)
+}
+
+/// Sends a request to the admin service, and returns the response status and body
+async fn send_request(
+    context: Arc<Context>,
+    method: Method,
+    path: &str,
+    request_body: &str,
+) -> (StatusCode, String) {
+    let request = Request::builder()
+        .uri(path)
+        .method(method)
+        .body(Body::from(request_body.to_string()))
+        .unwrap();
+    let mut response: Response<Body> = AdminService::serve_requests(context, request, true)
+        .await
+        .unwrap();
+    let response_body = body::to_bytes(response.body_mut()).await.unwrap();
+    (
+        response.status(),
+        String::from_utf8(response_body.to_vec()).unwrap(),
+    )
+}
+
+/// Waits (with a timeout) until the active mempool filter matches the expected filter
+async fn wait_for_mempool_filter(
+    transaction_filters: &ReloadableTransactionFilters,
+    expected_mempool_filter: &TransactionFilterConfig,
+) {
+    for _ in 0..500 {
+        if *transaction_filters.mempool_filter.load() == *expected_mempool_filter {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("Timed out waiting for the mempool filter to be updated!");
+}
+
+/// Writes a reload file that only updates the mempool filter
+fn write_mempool_reload_file(reload_file: &TempPath, mempool_filter: &TransactionFilterConfig) {
+    write_reload_file(reload_file, vec![(
+        "mempool_filter",
+        serde_yaml::to_value(mempool_filter).unwrap(),
+    )]);
+}
+
+/// Writes a reload file containing the given filter updates (keyed by filter name)
+fn write_reload_file(reload_file: &TempPath, filter_updates: Vec<(&str, serde_yaml::Value)>) {
+    let filter_updates: serde_yaml::Mapping = filter_updates
+        .into_iter()
+        .map(|(filter_name, filter)| (serde_yaml::Value::String(filter_name.into()), filter))
+        .collect();
+    std::fs::write(
+        reload_file.path(),
+        serde_yaml::to_string(&filter_updates).unwrap(),
+    )
+    .unwrap();
+}
@@ -0,0 +1,243 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Error;
+use aptos_config::config::{
+    BatchTransactionFilterConfig, BlockTransactionFilterConfig, ReloadableFilterConfig,
+    ReloadableTransactionFilters, TransactionFilterConfig,
+};
+use aptos_logger::{error, info};
+use aptos_system_utils::utils::{reply_with, reply_with_status};
+use http::header::{HeaderValue, CONTENT_TYPE};
+use hyper::{Body, Request, Response, StatusCode};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    fmt::Debug,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+// Useful constants for the transaction filter endpoints and names
+pub const TRANSACTION_FILTERS_PATH_PREFIX: &str = "/transaction_filters/";
+const CONSENSUS_FILTER_NAME: &str = "consensus";
+const MEMPOOL_FILTER_NAME: &str = "mempool";
+const QUORUM_STORE_FILTER_NAME: &str = "quorum_store";
+
+// The error message returned when filter updates are attempted without authentication
+pub const UPDATES_REQUIRE_AUTHENTICATION_MESSAGE: &str =
+    "Transaction filter updates require authentication to be configured for the AdminService.";
+
+/// The set of filter updates contained in a reload file. Any filters
+/// that are not specified in the file are left unchanged.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+struct TransactionFilterUpdates {
+    consensus_filter: Option<BlockTransactionFilterConfig>,
+    mempool_filter: Option<TransactionFilterConfig>,
+    quorum_store_filter: Option<BatchTransactionFilterConfig>,
+}
+
+/// Handles a request to get (GET) or replace (PUT) an active transaction filter.
+/// The filter is identified by the last path segment, e.g., `/transaction_filters/mempool`.
+/// Note: the caller must only forward PUT requests if authentication is configured.
+pub async fn handle_transaction_filter_request(
+    req: Request<Body>,
+    transaction_filters: ReloadableTransactionFilters,
+) -> hyper::Result<Response<Body>> {
+    let filter_name = req
+        .uri()
+        .path()
+        .trim_start_matches(TRANSACTION_FILTERS_PATH_PREFIX)
+        .to_string();
+    let is_update = req.method() == hyper::Method::PUT;
+
+    match filter_name.as_str() {
+        CONSENSUS_FILTER_NAME => {
+            let filter_config = &transaction_filters.consensus_filter;
+            if is_update {
+                handle_update_filter_request(req, CONSENSUS_FILTER_NAME, filter_config).await
+            } else {
+                handle_get_filter_request(CONSENSUS_FILTER_NAME, filter_config)
+            }
+        },
+        MEMPOOL_FILTER_NAME => {
+            let filter_config = &transaction_filters.mempool_filter;
+            if is_update {
+                handle_update_filter_request(req, MEMPOOL_FILTER_NAME, filter_config).await
+            } else {
+                handle_get_filter_request(MEMPOOL_FILTER_NAME, filter_config)
+            }
+        },
+        QUORUM_STORE_FILTER_NAME => {
+            let filter_config = &transaction_filters.quorum_store_filter;
+            if is_update {
+                handle_update_filter_request(req, QUORUM_STORE_FILTER_NAME, filter_config).await
+            } else {
+                handle_get_filter_request(QUORUM_STORE_FILTER_NAME, filter_config)
+            }
+        },
+        _ => Ok(reply_with_status(
+            StatusCode::NOT_FOUND,
+            format!("Unknown transaction filter: {filter_name}"),
+        )),
+    }
+}
+
+/// Handles a request to get the currently active filter (serialized as YAML)
+fn handle_get_filter_request<T: Serialize>(
+    filter_name: &str,
+    filter_config: &ReloadableFilterConfig<T>,
+) -> hyper::Result<Response<Body>> {
+    match serde_yaml::to_string(filter_config.load().as_ref()) {
+        Ok(filter_yaml) => {
+            let headers: Vec<(_, HeaderValue)> =
+                vec![(CONTENT_TYPE, HeaderValue::from_static("application/yaml"))];
+            Ok(reply_with(headers, filter_yaml))
+        },
+        Err(error) => {
+            info!("Failed to serialize the {filter_name} transaction filter: {error:?}");
+            Ok(reply_with_status(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error.to_string(),
+            ))
+        },
+    }
+}
+
+/// Handles a request to replace the currently active filter. The request
+/// body must contain the new filter (serialized as YAML). If the new filter
+/// is invalid, the request is rejected and the active filter is unchanged.
+async fn handle_update_filter_request<T: Debug + DeserializeOwned>(
+    req: Request<Body>,
+    filter_name: &str,
+    filter_config: &ReloadableFilterConfig<T>,
+) -> hyper::Result<Response<Body>> {
+    // Read the request body
+    let body = match hyper::body::to_bytes(req.into_body()).await {
+        Ok(body) => body,
+        Err(error) => {
+            return Ok(reply_with_status(
+                StatusCode::BAD_REQUEST,
+                format!("Failed to read the request body: {error}"),
+            ))
+        },
+    };
+
+    // Parse and validate the new filter
+    let new_filter_config: T = match serde_yaml::from_slice(&body) {
+        Ok(new_filter_config) => new_filter_config,
+        Err(error) => {
+            info!("Rejected an invalid {filter_name} transaction filter update: {error:?}");
+            return Ok(reply_with_status(
+                StatusCode::BAD_REQUEST,
+                format!("Invalid {filter_name} transaction filter: {error}"),
+            ));
+        },
+    };
+
+    // Update the active filter
+    update_filter(
+        filter_name,
+        filter_config,
+        new_filter_config,
+        "admin service",
+    );
+    Ok(reply_with_status(
+        StatusCode::OK,
+        format!("Updated the {filter_name} transaction filter."),
+    ))
+}
+
+/// Replaces the active filter and logs an audit line for the change
+fn update_filter<T: Debug>(
+    filter_name: &str,
+    filter_config: &ReloadableFilterConfig<T>,
+    new_filter_config: T,
+    update_source: &str,
+) {
+    let new_filter_log = format!("{:?}", new_filter_config);
+    let old_filter_config = filter_config.store(new_filter_config);
+    info!(
+        "Transaction filter updated! Filter: {}, source: {}, old filter: {:?}, new filter: {}",
+        filter_name, update_source, old_filter_config, new_filter_log
+    );
+}
+
+/// Periodically polls the reload file for changes (i.e., using the file modification
+/// time), and applies any filter updates contained in the file. If the file is
+/// invalid, the error is logged and the active filters are left unchanged.
+pub async fn watch_reload_file(
+    reload_file_path: PathBuf,
+    poll_interval: Duration,
+    transaction_filters: ReloadableTransactionFilters,
+) {
+    info!(
+        "Watching the transaction filter reload file: {:?}",
+        reload_file_path
+    );
+
+    let mut last_modified_time: Option<SystemTime> = None;
+    let mut poll_interval = tokio::time::interval(poll_interval);
+    loop {
+        poll_interval.tick().await;
+
+        // Check if the file has been modified since the last poll
+        let modified_time = match tokio::fs::metadata(&reload_file_path)
+            .await
+            .and_then(|metadata| metadata.modified())
+        {
+            Ok(modified_time) => modified_time,
+            Err(_) => continue, // The file may not exist (yet)
+        };
+        if last_modified_time == Some(modified_time) {
+            continue;
+        }
+        last_modified_time = Some(modified_time);
+
+        // Apply the filter updates in the file
+        if let Err(error) = apply_reload_file(&reload_file_path, &transaction_filters).await {
+            error!(
+                "Failed to apply the transaction filter reload file: {:?}. Error: {:?}",
+                reload_file_path, error
+            );
+        }
+    }
+}
+
+/// Reads and validates the filter updates in the reload file, and applies them.
+/// If any filter is invalid, no filters are updated.
+pub async fn apply_reload_file(
+    reload_file_path: &Path,
+    transaction_filters: &ReloadableTransactionFilters,
+) -> Result<(), Error> {
+    let file_contents = tokio::fs::read_to_string(reload_file_path).await?;
+    let filter_updates: TransactionFilterUpdates = serde_yaml::from_str(&file_contents)?;
+
+    let update_source = format!("reload file {:?}", reload_file_path);
+    if let Some(consensus_filter) = filter_updates.consensus_filter {
+        update_filter(
+            CONSENSUS_FILTER_NAME,
+            &transaction_filters.consensus_filter,
+            consensus_filter,
+            &update_source,
+        );
+    }
+    if let Some(mempool_filter) = filter_updates.mempool_filter {
+        update_filter(
+            MEMPOOL_FILTER_NAME,
+            &transaction_filters.mempool_filter,
+            mempool_filter,
+            &update_source,
+        );
+    }
+    if let Some(quorum_store_filter) = filter_updates.quorum_store_filter {
+        update_filter(
+            QUORUM_STORE_FILTER_NAME,
+            &transaction_filters.quorum_store_filter,
+            quorum_store_filter,
+            &update_source,
+        );
+    }
+
+    Ok(())
+}
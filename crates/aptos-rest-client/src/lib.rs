@@ -26,7 +26,7 @@ use aptos_api_types::{
     mime_types::{BCS, BCS_SIGNED_TRANSACTION, BCS_VIEW_FUNCTION, JSON},
     AptosError, AptosErrorCode, BcsBlock, Block, GasEstimation, HexEncodedBytes, IndexResponse,
     MoveModuleId, TransactionData, TransactionOnChainData, TransactionsBatchSubmissionResult,
-    UserTransaction, VersionedEvent, ViewFunction, ViewRequest,
+    UserTransaction, VersionedEvent, ViewFunction, ViewFunctionResult, ViewRequest,
 };
 use aptos_crypto::HashValue;
 use aptos_logger::{debug, info, sample, sample::SampleRate};
@@ -436,6 +436,30 @@ impl Client {
         self.json(response).await
     }
 
+    /// Executes a batch of view functions against the same ledger version. The
+    /// result of each view function is returned in order (including any errors).
+    pub async fn view_batch(
+        &self,
+        requests: &[ViewRequest],
+        version: Option<u64>,
+    ) -> AptosResult<Response<Vec<ViewFunctionResult>>> {
+        let request = serde_json::to_string(requests)?;
+        let mut url = self.build_path("view/batch")?;
+        if let Some(version) = version {
+            url.set_query(Some(format!("ledger_version={}", version).as_str()));
+        }
+
+        let response = self
+            .inner
+            .post(url)
+            .header(CONTENT_TYPE, JSON)
+            .body(request)
+            .send()
+            .await?;
+
+        self.json(response).await
+    }
+
     pub async fn view_bcs<T: DeserializeOwned>(
         &self,
         request: &ViewFunction,
@@ -570,6 +594,44 @@ impl Client {
         Ok(response.and_then(|bytes| bcs::from_bytes(&bytes))?)
     }
 
+    pub async fn simulate_bundle(
+        &self,
+        txns: &[SignedTransaction],
+    ) -> AptosResult<Response<Vec<UserTransaction>>> {
+        let txn_payload = bcs::to_bytes(&txns.to_vec())?;
+        let url = self.build_path("transactions/simulate_bundle")?;
+
+        let response = self
+            .inner
+            .post(url)
+            .header(CONTENT_TYPE, BCS_SIGNED_TRANSACTION)
+            .body(txn_payload)
+            .send()
+            .await?;
+
+        self.json(response).await
+    }
+
+    pub async fn simulate_bundle_bcs(
+        &self,
+        txns: &[SignedTransaction],
+    ) -> AptosResult<Response<Vec<TransactionOnChainData>>> {
+        let txn_payload = bcs::to_bytes(&txns.to_vec())?;
+        let url = self.build_path("transactions/simulate_bundle")?;
+
+        let response = self
+            .inner
+            .post(url)
+            .header(CONTENT_TYPE, BCS_SIGNED_TRANSACTION)
+            .header(ACCEPT, BCS)
+            .body(txn_payload)
+            .send()
+            .await?;
+
+        let response = self.check_and_parse_bcs_response(response).await?;
+        Ok(response.and_then(|bytes| bcs::from_bytes(&bytes))?)
+    }
+
     pub async fn submit(
         &self,
         txn: &SignedTransaction,
@@ -1,7 +1,10 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::transaction_filter::TransactionMatcher;
+use crate::{
+    transaction_filter::TransactionMatcher,
+    transaction_quota::{QuotaUsage, TransactionQuotaRule},
+};
 use aptos_crypto::HashValue;
 use aptos_types::{quorum_store::BatchId, transaction::SignedTransaction, PeerId};
 #[cfg(any(test, feature = "fuzzing"))]
@@ -14,19 +17,28 @@ use serde::{Deserialize, Serialize};
 /// Rules are applied in the order they are defined, and the first
 /// matching rule determines the outcome for the transaction.
 /// If no rules match, the transaction is allowed by default.
+///
+/// Transactions that are allowed by the rules are then checked against
+/// the quota rules (if any). Quotas are tracked per batch (in batch order),
+/// and a transaction is denied if it would exceed any quota in the batch.
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct BatchTransactionFilter {
     batch_transaction_rules: Vec<BatchTransactionRule>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    quota_rules: Vec<TransactionQuotaRule>,
 }
 
 impl BatchTransactionFilter {
     pub fn new(batch_transaction_rules: Vec<BatchTransactionRule>) -> Self {
         Self {
             batch_transaction_rules,
+            quota_rules: Vec::new(),
         }
     }
 
-    /// Returns true iff the filter allows the transaction in the batch
+    /// Returns true iff the filter allows the transaction in the batch.
+    /// Note: this does not check the quota rules, as quotas are tracked
+    /// across all transactions in the batch (see `filter_batch_transactions`).
     pub fn allows_transaction(
         &self,
         batch_id: BatchId,
@@ -59,9 +71,31 @@ impl BatchTransactionFilter {
 
     /// Returns an empty batch transaction filter with no rules
     pub fn empty() -> Self {
-        Self {
-            batch_transaction_rules: Vec::new(),
-        }
+        Self::new(Vec::new())
+    }
+
+    /// Identifies the transactions in the given batch that are denied by the filter.
+    /// Note: this returns the inverse of `filter_batch_transactions`.
+    pub fn get_denied_batch_transactions<'a>(
+        &self,
+        batch_id: BatchId,
+        batch_author: PeerId,
+        batch_digest: &HashValue,
+        transactions: &'a [SignedTransaction],
+    ) -> Vec<&'a SignedTransaction> {
+        let mut quota_usage = QuotaUsage::default();
+        transactions
+            .iter()
+            .filter(|txn| {
+                !self.allows_batch_transaction(
+                    batch_id,
+                    batch_author,
+                    batch_digest,
+                    txn,
+                    &mut quota_usage,
+                )
+            })
+            .collect()
     }
 
     /// Filters the transactions in the given batch and returns only those that are allowed
@@ -72,15 +106,38 @@ impl BatchTransactionFilter {
         batch_digest: HashValue,
         transactions: Vec<SignedTransaction>,
     ) -> Vec<SignedTransaction> {
+        let mut quota_usage = QuotaUsage::default();
         transactions
             .into_iter()
-            .filter(|txn| self.allows_transaction(batch_id, batch_author, &batch_digest, txn))
+            .filter(|txn| {
+                self.allows_batch_transaction(
+                    batch_id,
+                    batch_author,
+                    &batch_digest,
+                    txn,
+                    &mut quota_usage,
+                )
+            })
             .collect()
     }
 
-    /// Returns true iff the filter is empty (i.e., has no rules)
+    /// Returns true iff the filter allows the transaction in the batch, and the
+    /// transaction fits within the batch quotas (given the current quota usage).
+    fn allows_batch_transaction(
+        &self,
+        batch_id: BatchId,
+        batch_author: PeerId,
+        batch_digest: &HashValue,
+        signed_transaction: &SignedTransaction,
+        quota_usage: &mut QuotaUsage,
+    ) -> bool {
+        self.allows_transaction(batch_id, batch_author, batch_digest, signed_transaction)
+            && quota_usage.try_consume(&self.quota_rules, signed_transaction)
+    }
+
+    /// Returns true iff the filter is empty (i.e., has no rules or quota rules)
     pub fn is_empty(&self) -> bool {
-        self.batch_transaction_rules.is_empty()
+        self.batch_transaction_rules.is_empty() && self.quota_rules.is_empty()
     }
 
     /// Adds a filter that matches all batch transactions
@@ -104,6 +161,12 @@ impl BatchTransactionFilter {
 
         self
     }
+
+    /// Adds a quota rule to the filter
+    pub fn add_quota_rule(mut self, quota_rule: TransactionQuotaRule) -> Self {
+        self.quota_rules.push(quota_rule);
+        self
+    }
 }
 
 // These are useful test-only methods for creating and testing filters
@@ -1,7 +1,10 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::transaction_filter::TransactionMatcher;
+use crate::{
+    transaction_filter::TransactionMatcher,
+    transaction_quota::{QuotaUsage, TransactionQuotaRule},
+};
 use aptos_crypto::HashValue;
 use aptos_types::transaction::SignedTransaction;
 use move_core_types::account_address::AccountAddress;
@@ -13,19 +16,28 @@ use serde::{Deserialize, Serialize};
 /// Rules are applied in the order they are defined, and the first
 /// matching rule determines the outcome for the transaction.
 /// If no rules match, the transaction is allowed by default.
+///
+/// Transactions that are allowed by the rules are then checked against
+/// the quota rules (if any). Quotas are tracked per block (in block order),
+/// and a transaction is denied if it would exceed any quota in the block.
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct BlockTransactionFilter {
     block_transaction_rules: Vec<BlockTransactionRule>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    quota_rules: Vec<TransactionQuotaRule>,
 }
 
 impl BlockTransactionFilter {
     pub fn new(block_transaction_rules: Vec<BlockTransactionRule>) -> Self {
         Self {
             block_transaction_rules,
+            quota_rules: Vec::new(),
         }
     }
 
-    /// Returns true iff the filter allows the transaction in the block
+    /// Returns true iff the filter allows the transaction in the block.
+    /// Note: this does not check the quota rules, as quotas are tracked
+    /// across all transactions in the block (see `filter_block_transactions`).
     pub fn allows_transaction(
         &self,
         block_id: HashValue,
@@ -60,9 +72,7 @@ impl BlockTransactionFilter {
 
     /// Returns an empty block transaction filter with no rules
     pub fn empty() -> Self {
-        Self {
-            block_transaction_rules: Vec::new(),
-        }
+        Self::new(Vec::new())
     }
 
     /// Identifies the transactions in the given block that are denied by the filter.
@@ -75,15 +85,17 @@ impl BlockTransactionFilter {
         block_timestamp_usecs: u64,
         transactions: Vec<SignedTransaction>,
     ) -> Vec<SignedTransaction> {
+        let mut quota_usage = QuotaUsage::default();
         transactions
             .into_iter()
             .filter(|txn| {
-                !self.allows_transaction(
+                !self.allows_block_transaction(
                     block_id,
                     block_author,
                     block_epoch,
                     block_timestamp_usecs,
                     txn,
+                    &mut quota_usage,
                 )
             })
             .collect()
@@ -98,23 +110,45 @@ impl BlockTransactionFilter {
         block_timestamp_usecs: u64,
         transactions: Vec<SignedTransaction>,
     ) -> Vec<SignedTransaction> {
+        let mut quota_usage = QuotaUsage::default();
         transactions
             .into_iter()
             .filter(|txn| {
-                self.allows_transaction(
+                self.allows_block_transaction(
                     block_id,
                     block_author,
                     block_epoch,
                     block_timestamp_usecs,
                     txn,
+                    &mut quota_usage,
                 )
             })
             .collect()
     }
 
-    /// Returns true iff the filter is empty (i.e., has no rules)
+    /// Returns true iff the filter allows the transaction in the block, and the
+    /// transaction fits within the block quotas (given the current quota usage).
+    fn allows_block_transaction(
+        &self,
+        block_id: HashValue,
+        block_author: Option<AccountAddress>,
+        block_epoch: u64,
+        block_timestamp_usecs: u64,
+        signed_transaction: &SignedTransaction,
+        quota_usage: &mut QuotaUsage,
+    ) -> bool {
+        self.allows_transaction(
+            block_id,
+            block_author,
+            block_epoch,
+            block_timestamp_usecs,
+            signed_transaction,
+        ) && quota_usage.try_consume(&self.quota_rules, signed_transaction)
+    }
+
+    /// Returns true iff the filter is empty (i.e., has no rules or quota rules)
     pub fn is_empty(&self) -> bool {
-        self.block_transaction_rules.is_empty()
+        self.block_transaction_rules.is_empty() && self.quota_rules.is_empty()
     }
 
     /// Adds a filter that matches all block transactions
@@ -138,6 +172,12 @@ impl BlockTransactionFilter {
 
         self
     }
+
+    /// Adds a quota rule to the filter
+    pub fn add_quota_rule(mut self, quota_rule: TransactionQuotaRule) -> Self {
+        self.quota_rules.push(quota_rule);
+        self
+    }
 }
 
 // These are useful test-only methods for creating and testing filters
@@ -0,0 +1,22 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_metrics_core::{register_int_counter_vec, IntCounterVec};
+use once_cell::sync::Lazy;
+
+/// Counter for the number of transactions matched by each shadow rule
+pub static SHADOW_RULE_MATCHES: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_transaction_filter_shadow_rule_matches",
+        "Number of transactions matched by each shadow rule (evaluated, but not enforced)",
+        &["filter", "rule_index", "rule_type"]
+    )
+    .unwrap()
+});
+
+/// Increments the shadow rule match counter for the given filter and rule
+pub fn increment_shadow_rule_match_counter(filter_label: &str, rule_index: usize, rule_type: &str) {
+    SHADOW_RULE_MATCHES
+        .with_label_values(&[filter_label, &rule_index.to_string(), rule_type])
+        .inc();
+}
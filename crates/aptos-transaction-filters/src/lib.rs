@@ -5,7 +5,9 @@
 
 pub mod batch_transaction_filter;
 pub mod block_transaction_filter;
+mod counters;
 pub mod transaction_filter;
+pub mod transaction_quota;
 
 #[cfg(test)]
 mod tests;
@@ -7,4 +7,5 @@ mod block_transaction_filter;
 mod block_transaction_filter_config;
 mod transaction_filter;
 mod transaction_filter_config;
+mod transaction_quota;
 mod utils;
@@ -2,9 +2,17 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    counters::SHADOW_RULE_MATCHES,
     tests::utils,
     transaction_filter::{TransactionFilter, TransactionMatcher},
 };
+use aptos_types::transaction::{EntryFunction, SignedTransaction, TransactionPayload};
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::{ModuleId, TypeTag},
+};
+use std::str::FromStr;
 
 #[test]
 fn test_account_address_filter_simple() {
@@ -93,6 +101,57 @@ fn test_account_address_filter_script_argument() {
     }
 }
 
+#[test]
+fn test_argument_address_filter() {
+    // Create entry function transactions with address arguments (at index 1)
+    let addresses: Vec<AccountAddress> = (0..3).map(|_| AccountAddress::random()).collect();
+    let transactions: Vec<_> = addresses
+        .iter()
+        .map(|address| {
+            let arguments = vec![
+                bcs::to_bytes(&100u64).unwrap(),
+                bcs::to_bytes(address).unwrap(),
+            ];
+            create_entry_function_transaction(vec![], arguments)
+        })
+        .collect();
+
+    // Create a filter that denies transactions with the first address as argument 1
+    let filter = TransactionFilter::empty()
+        .add_argument_address_filter(false, 1, addresses[0])
+        .add_all_filter(true);
+
+    // Verify that the filter denies only the first transaction
+    let filtered_transactions = filter.filter_transactions(transactions.clone());
+    assert_eq!(filtered_transactions, transactions[1..].to_vec());
+
+    // Create a filter that denies transactions with the first address as argument 0
+    let filter = TransactionFilter::empty()
+        .add_argument_address_filter(false, 0, addresses[0])
+        .add_argument_address_filter(false, 5, addresses[0])
+        .add_all_filter(true);
+
+    // Verify that the filter allows all transactions (the argument indices don't match)
+    let filtered_transactions = filter.filter_transactions(transactions.clone());
+    assert_eq!(filtered_transactions, transactions);
+
+    for use_new_txn_payload_format in [false, true] {
+        // Create a filter that only allows script transactions with a specific address argument (at index 2)
+        let transactions = utils::create_script_transactions(use_new_txn_payload_format);
+        let filter = TransactionFilter::empty()
+            .add_argument_address_filter(
+                true,
+                2,
+                utils::get_script_argument_address(&transactions[0]),
+            )
+            .add_all_filter(false);
+
+        // Verify that the filter allows only the first transaction
+        let filtered_transactions = filter.filter_transactions(transactions.clone());
+        assert_eq!(filtered_transactions, vec![transactions[0].clone()]);
+    }
+}
+
 #[test]
 fn test_account_address_filter_transaction_authenticator() {
     // Create a filter that only allows transactions from specific account addresses.
@@ -193,6 +252,62 @@ fn test_entry_function_filter() {
     }
 }
 
+#[test]
+fn test_expiration_timestamp_filter() {
+    // Create transactions with increasing expiration timestamps
+    let transactions = create_transactions_with_parameters(|i| (0, 0, i * 100));
+
+    // Create a filter that denies transactions expiring after 500 secs or before 200 secs
+    let filter = TransactionFilter::empty()
+        .add_expiration_timestamp_greater_than_filter(false, 500)
+        .add_expiration_timestamp_less_than_filter(false, 200)
+        .add_all_filter(true);
+
+    // Verify that only the transactions expiring between 200 and 500 secs are allowed
+    let filtered_transactions = filter.filter_transactions(transactions.clone());
+    assert_eq!(filtered_transactions, transactions[2..6].to_vec());
+}
+
+#[test]
+fn test_gas_unit_price_filter() {
+    // Create transactions with increasing gas unit prices
+    let transactions = create_transactions_with_parameters(|i| (0, i * 100, 0));
+
+    // Create a filter that denies transactions with a gas unit price below 300
+    let filter = TransactionFilter::empty()
+        .add_gas_unit_price_less_than_filter(false, 300)
+        .add_all_filter(true);
+
+    // Verify that only the transactions with a gas unit price of at least 300 are allowed
+    let filtered_transactions = filter.filter_transactions(transactions.clone());
+    assert_eq!(filtered_transactions, transactions[3..].to_vec());
+
+    // Create a filter that only allows transactions with a gas unit price above 700
+    let filter = TransactionFilter::empty()
+        .add_gas_unit_price_greater_than_filter(true, 700)
+        .add_all_filter(false);
+
+    // Verify that only the transactions with a gas unit price above 700 are allowed
+    let filtered_transactions = filter.filter_transactions(transactions.clone());
+    assert_eq!(filtered_transactions, transactions[8..].to_vec());
+}
+
+#[test]
+fn test_max_gas_amount_filter() {
+    // Create transactions with increasing max gas amounts
+    let transactions = create_transactions_with_parameters(|i| (i * 1000, 0, 0));
+
+    // Create a filter that denies transactions with a max gas amount above 4000 or below 1000
+    let filter = TransactionFilter::empty()
+        .add_max_gas_amount_greater_than_filter(false, 4000)
+        .add_max_gas_amount_less_than_filter(false, 1000)
+        .add_all_filter(true);
+
+    // Verify that only the transactions with a max gas amount between 1000 and 4000 are allowed
+    let filtered_transactions = filter.filter_transactions(transactions.clone());
+    assert_eq!(filtered_transactions, transactions[1..5].to_vec());
+}
+
 #[test]
 fn test_module_address_filter() {
     for use_new_txn_payload_format in [false, true] {
@@ -270,6 +385,23 @@ fn test_multiple_matchers_filter() {
     }
 }
 
+#[test]
+fn test_payload_size_filter() {
+    // Create entry function transactions with increasing argument sizes
+    let transactions: Vec<_> = (0..5)
+        .map(|i| create_entry_function_transaction(vec![], vec![vec![0; i * 1000]]))
+        .collect();
+
+    // Create a filter that denies transactions with a payload size greater than 2500 bytes
+    let filter = TransactionFilter::empty()
+        .add_payload_size_greater_than_filter(false, 2500)
+        .add_all_filter(true);
+
+    // Verify that only the transactions with small payloads are allowed
+    let filtered_transactions = filter.filter_transactions(transactions.clone());
+    assert_eq!(filtered_transactions, transactions[0..3].to_vec());
+}
+
 #[test]
 fn test_public_key_filter() {
     for use_new_txn_payload_format in [false, true] {
@@ -323,6 +455,62 @@ fn test_sender_filter() {
     }
 }
 
+#[test]
+fn test_shadow_filter() {
+    for use_new_txn_payload_format in [false, true] {
+        // Create a filter that only contains shadow rules (denying txn 0 and txn 1 senders)
+        let transactions = utils::create_entry_function_transactions(use_new_txn_payload_format);
+        let filter = TransactionFilter::empty()
+            .add_multiple_matchers_shadow_filter(false, vec![TransactionMatcher::Sender(
+                transactions[0].sender(),
+            )])
+            .add_multiple_matchers_shadow_filter(false, vec![TransactionMatcher::Sender(
+                transactions[1].sender(),
+            )]);
+
+        // Verify that the filter is not empty, but that all transactions are still allowed
+        assert!(!filter.is_empty());
+        let filtered_transactions = filter.filter_transactions(transactions.clone());
+        assert_eq!(filtered_transactions, transactions);
+
+        // Verify that the shadow rules match the expected transactions
+        assert_eq!(
+            filter.get_matching_shadow_rule_index(&transactions[0]),
+            Some(0)
+        );
+        assert_eq!(
+            filter.get_matching_shadow_rule_index(&transactions[1]),
+            Some(1)
+        );
+        for transaction in transactions[2..].iter() {
+            assert_eq!(filter.get_matching_shadow_rule_index(transaction), None);
+        }
+
+        // Record the shadow rule matches, and verify that they are counted for the filter
+        let filter_label = format!("test_shadow_filter_{}", use_new_txn_payload_format);
+        for transaction in transactions.iter() {
+            filter.record_shadow_rule_match(&filter_label, transaction);
+        }
+        for rule_index in 0..2 {
+            let num_matches = SHADOW_RULE_MATCHES
+                .with_label_values(&[&filter_label, &rule_index.to_string(), "deny"])
+                .get();
+            assert_eq!(num_matches, 1);
+        }
+
+        // Add an enforced rule that denies txn 2, and verify the shadow rules are still ignored
+        let filter = filter
+            .add_sender_filter(false, transactions[2].sender())
+            .add_all_filter(true);
+        let filtered_transactions = filter.filter_transactions(transactions.clone());
+        let mut expected_transactions = transactions[0..2].to_vec();
+        expected_transactions.extend_from_slice(&transactions[3..]);
+        assert_eq!(filtered_transactions, expected_transactions);
+        assert_eq!(filter.get_matching_rule_index(&transactions[0]), Some(1));
+        assert_eq!(filter.get_matching_rule_index(&transactions[2]), Some(0));
+    }
+}
+
 #[test]
 fn test_transaction_id_filter() {
     for use_new_txn_payload_format in [false, true] {
@@ -346,3 +534,102 @@ fn test_transaction_id_filter() {
         assert_eq!(filtered_transactions, transactions[1..].to_vec());
     }
 }
+
+#[test]
+fn test_type_argument_filter() {
+    // Create entry function transactions with different type arguments
+    let coin_type_arguments = ["0x1::aptos_coin::AptosCoin", "0x2::coin::Coin", "u64"];
+    let transactions: Vec<_> = coin_type_arguments
+        .iter()
+        .map(|type_argument| {
+            let type_arguments = vec![TypeTag::Bool, TypeTag::from_str(type_argument).unwrap()];
+            create_entry_function_transaction(type_arguments, vec![])
+        })
+        .collect();
+
+    // Create a filter that denies transactions with AptosCoin as type argument 1
+    let filter = TransactionFilter::empty()
+        .add_type_argument_filter(
+            false,
+            1,
+            TypeTag::from_str("0x1::aptos_coin::AptosCoin").unwrap(),
+        )
+        .add_all_filter(true);
+
+    // Verify that the filter denies only the first transaction
+    let filtered_transactions = filter.filter_transactions(transactions.clone());
+    assert_eq!(filtered_transactions, transactions[1..].to_vec());
+
+    // Create a filter that only allows transactions with u64 as type argument 1 (using the long address format)
+    let filter = TransactionFilter::empty()
+        .add_type_argument_filter(true, 1, TypeTag::U64)
+        .add_type_argument_filter(
+            true,
+            1,
+            TypeTag::from_str(
+                "0x0000000000000000000000000000000000000000000000000000000000000002::coin::Coin",
+            )
+            .unwrap(),
+        )
+        .add_all_filter(false);
+
+    // Verify that the filter allows only the last two transactions
+    let filtered_transactions = filter.filter_transactions(transactions.clone());
+    assert_eq!(filtered_transactions, transactions[1..].to_vec());
+
+    // Create a filter with a missing type argument index
+    let filter = TransactionFilter::empty()
+        .add_type_argument_filter(false, 2, TypeTag::Bool)
+        .add_all_filter(true);
+
+    // Verify that the filter allows all transactions
+    let filtered_transactions = filter.filter_transactions(transactions.clone());
+    assert_eq!(filtered_transactions, transactions);
+
+    // Verify that a filter with an invalid type argument fails to deserialize
+    let filter_string = r#"
+        transaction_rules:
+            - Deny:
+                - TypeArgument:
+                    - 1
+                    - "invalid type!"
+    "#;
+    let error = serde_yaml::from_str::<TransactionFilter>(filter_string).unwrap_err();
+    assert!(error.to_string().contains("invalid type argument"));
+}
+
+/// Creates and returns an entry function transaction with the given type arguments and arguments
+fn create_entry_function_transaction(
+    type_arguments: Vec<TypeTag>,
+    arguments: Vec<Vec<u8>>,
+) -> SignedTransaction {
+    let entry_function = EntryFunction::new(
+        ModuleId::new(AccountAddress::ONE, Identifier::new("test").unwrap()),
+        Identifier::new("run").unwrap(),
+        type_arguments,
+        arguments,
+    );
+    utils::create_signed_transaction(TransactionPayload::EntryFunction(entry_function), false)
+}
+
+/// Creates and returns a list of transactions using the given parameter generator,
+/// which returns the max gas amount, gas unit price and expiration for each index.
+fn create_transactions_with_parameters(
+    parameter_generator: impl Fn(u64) -> (u64, u64, u64),
+) -> Vec<SignedTransaction> {
+    (0..10)
+        .map(|i| {
+            let (max_gas_amount, gas_unit_price, expiration_timestamp_secs) =
+                parameter_generator(i);
+            let transaction_payload = TransactionPayload::EntryFunction(
+                utils::create_entry_function(str::parse("0x1::entry::add").unwrap()),
+            );
+            utils::create_signed_transaction_with_parameters(
+                transaction_payload,
+                max_gas_amount,
+                gas_unit_price,
+                expiration_timestamp_secs,
+            )
+        })
+        .collect()
+}
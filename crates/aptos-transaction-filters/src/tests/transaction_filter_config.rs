@@ -1,7 +1,12 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{tests::utils, transaction_filter::TransactionFilter};
+use crate::{
+    tests::utils,
+    transaction_filter::{TransactionFilter, TransactionMatcher},
+};
+use move_core_types::{account_address::AccountAddress, language_storage::TypeTag};
+use std::str::FromStr;
 
 #[test]
 fn test_transaction_filter_config_allow() {
@@ -118,3 +123,51 @@ fn test_transaction_filter_config_multiple_matchers() {
         assert_eq!(filtered_transactions, transactions[4..].to_vec());
     }
 }
+
+#[test]
+fn test_transaction_filter_config_payload_matchers() {
+    // Create a filter that denies transactions based on payload and gas criteria
+    let transaction_filter_string = r#"
+        transaction_rules:
+            - Deny:
+                - TypeArgument:
+                    - 0
+                    - "0x1::aptos_coin::AptosCoin"
+                - ArgumentAddress:
+                    - 1
+                    - "0000000000000000000000000000000000000000000000000000000000000001"
+            - Deny:
+                - PayloadSizeGreaterThan: 10000
+            - Deny:
+                - GasUnitPriceLessThan: 100
+                - MaxGasAmountGreaterThan: 2000000
+            - Deny:
+                - ExpirationTimestampGreaterThan: 1000
+                - ExpirationTimestampLessThan: 2000
+            - Allow:
+                - All
+    "#;
+    let transaction_filter =
+        serde_yaml::from_str::<TransactionFilter>(transaction_filter_string).unwrap();
+
+    // Verify that the filter was parsed correctly
+    let expected_transaction_filter = TransactionFilter::empty()
+        .add_multiple_matchers_filter(false, vec![
+            TransactionMatcher::TypeArgument(
+                0,
+                TypeTag::from_str("0x1::aptos_coin::AptosCoin").unwrap(),
+            ),
+            TransactionMatcher::ArgumentAddress(1, AccountAddress::ONE),
+        ])
+        .add_payload_size_greater_than_filter(false, 10000)
+        .add_multiple_matchers_filter(false, vec![
+            TransactionMatcher::GasUnitPriceLessThan(100),
+            TransactionMatcher::MaxGasAmountGreaterThan(2000000),
+        ])
+        .add_multiple_matchers_filter(false, vec![
+            TransactionMatcher::ExpirationTimestampGreaterThan(1000),
+            TransactionMatcher::ExpirationTimestampLessThan(2000),
+        ])
+        .add_all_filter(true);
+    assert_eq!(transaction_filter, expected_transaction_filter);
+}
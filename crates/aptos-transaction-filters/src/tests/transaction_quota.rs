@@ -0,0 +1,314 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    batch_transaction_filter::BatchTransactionFilter,
+    block_transaction_filter::BlockTransactionFilter,
+    tests::utils,
+    transaction_filter::{TransactionFilter, TransactionMatcher},
+    transaction_quota::{QuotaKey, QuotaLimit, TransactionQuotaRule},
+};
+use aptos_types::transaction::SignedTransaction;
+use std::time::{Duration, Instant};
+
+#[test]
+fn test_batch_quota_filter() {
+    // Create a filter that allows at most 3 transactions for each module address in a batch
+    let quota_rule = TransactionQuotaRule::new(
+        QuotaKey::ModuleAddress,
+        QuotaLimit::MaxTransactions(3),
+        vec![],
+    );
+    let filter = BatchTransactionFilter::empty().add_quota_rule(quota_rule);
+
+    // Create a batch with 5 transactions for module 0x1, and 2 transactions for module 0x2
+    let mut transactions = vec![];
+    for _ in 0..5 {
+        transactions.push(utils::create_entry_function_transaction(
+            str::parse("0x1::entry::add").unwrap(),
+            false,
+        ));
+    }
+    for _ in 0..2 {
+        transactions.push(utils::create_entry_function_transaction(
+            str::parse("0x2::entry::sub").unwrap(),
+            true,
+        ));
+    }
+
+    // Verify that only the first 3 transactions for module 0x1 are allowed
+    let (batch_id, batch_author, batch_digest) = utils::get_random_batch_info();
+    let filtered_transactions = filter.filter_batch_transactions(
+        batch_id,
+        batch_author,
+        batch_digest,
+        transactions.clone(),
+    );
+    let mut expected_transactions = transactions[0..3].to_vec();
+    expected_transactions.extend_from_slice(&transactions[5..]);
+    assert_eq!(filtered_transactions, expected_transactions);
+
+    // Verify that the denied transactions are the inverse of the filtered transactions
+    let denied_transactions =
+        filter.get_denied_batch_transactions(batch_id, batch_author, &batch_digest, &transactions);
+    assert_eq!(
+        denied_transactions,
+        transactions[3..5].iter().collect::<Vec<_>>()
+    );
+
+    // Verify that the quota is reset for each batch
+    let filtered_transactions = filter.filter_batch_transactions(
+        batch_id,
+        batch_author,
+        batch_digest,
+        transactions.clone(),
+    );
+    assert_eq!(filtered_transactions, expected_transactions);
+}
+
+#[test]
+fn test_block_quota_filter_gas_units() {
+    // Create a filter that allows at most 1000 gas units for each entry function in a block
+    let quota_rule = TransactionQuotaRule::new(
+        QuotaKey::EntryFunction,
+        QuotaLimit::MaxGasUnits(1000),
+        vec![],
+    );
+    let filter = BlockTransactionFilter::empty().add_quota_rule(quota_rule);
+
+    // Create several transactions for two entry functions (in the same module)
+    let transactions = vec![
+        utils::create_entry_function_transaction_with_max_gas(
+            str::parse("0x1::entry::add").unwrap(),
+            600,
+        ),
+        utils::create_entry_function_transaction_with_max_gas(
+            str::parse("0x1::entry::sub").unwrap(),
+            900,
+        ),
+        utils::create_entry_function_transaction_with_max_gas(
+            str::parse("0x1::entry::add").unwrap(),
+            500,
+        ),
+        utils::create_entry_function_transaction_with_max_gas(
+            str::parse("0x1::entry::add").unwrap(),
+            400,
+        ),
+        utils::create_entry_function_transaction_with_max_gas(
+            str::parse("0x1::entry::sub").unwrap(),
+            200,
+        ),
+    ];
+
+    // Verify that transactions exceeding the gas quota are denied (txn 2 and txn 4)
+    let (block_id, block_author, block_epoch, block_timestamp) = utils::get_random_block_info();
+    let denied_transactions = filter.get_denied_block_transactions(
+        block_id,
+        Some(block_author),
+        block_epoch,
+        block_timestamp,
+        transactions.clone(),
+    );
+    assert_eq!(denied_transactions, vec![
+        transactions[2].clone(),
+        transactions[4].clone()
+    ]);
+
+    // Verify that the remaining transactions are allowed
+    let filtered_transactions = filter.filter_block_transactions(
+        block_id,
+        Some(block_author),
+        block_epoch,
+        block_timestamp,
+        transactions.clone(),
+    );
+    assert_eq!(filtered_transactions, vec![
+        transactions[0].clone(),
+        transactions[1].clone(),
+        transactions[3].clone()
+    ]);
+}
+
+#[test]
+fn test_block_quota_filter_config() {
+    // Create a block filter that denies a sender, and limits the transactions for module 0x1
+    let transactions = utils::create_entry_function_transactions(false);
+    let block_transaction_filter_string = format!(
+        r#"
+        block_transaction_rules:
+            - Deny:
+                - Transaction:
+                    Sender: "{}"
+        quota_rules:
+            - quota_key: All
+              quota_limit:
+                MaxTransactions: 5
+        "#,
+        transactions[0].sender().to_standard_string(),
+    );
+    let filter =
+        serde_yaml::from_str::<BlockTransactionFilter>(&block_transaction_filter_string).unwrap();
+
+    // Verify that the denied transaction does not consume the quota
+    let (block_id, block_author, block_epoch, block_timestamp) = utils::get_random_block_info();
+    let filtered_transactions = filter.filter_block_transactions(
+        block_id,
+        Some(block_author),
+        block_epoch,
+        block_timestamp,
+        transactions.clone(),
+    );
+    assert_eq!(filtered_transactions, transactions[1..6].to_vec());
+}
+
+#[test]
+fn test_transaction_quota_filter_config() {
+    // Create a filter that limits the transactions for each sender calling module 0x1
+    let transaction_filter_string = r#"
+        transaction_rules:
+            - Allow:
+                - All
+        quota_rules:
+            - quota_key: Sender
+              quota_limit:
+                MaxTransactions: 1
+              transaction_matchers:
+                - ModuleAddress: "0000000000000000000000000000000000000000000000000000000000000001"
+        quota_window_secs: 30
+    "#;
+    let filter = serde_yaml::from_str::<TransactionFilter>(transaction_filter_string).unwrap();
+
+    // Verify that the filter was parsed correctly
+    let quota_rule =
+        TransactionQuotaRule::new(QuotaKey::Sender, QuotaLimit::MaxTransactions(1), vec![
+            TransactionMatcher::ModuleAddress(str::parse("0x1").unwrap()),
+        ]);
+    let expected_filter = TransactionFilter::empty()
+        .add_all_filter(true)
+        .add_quota_rule(quota_rule)
+        .with_quota_window_secs(30);
+    assert_eq!(filter, expected_filter);
+
+    // Verify that only a single transaction calling module 0x1 is allowed for the sender
+    let transaction =
+        utils::create_entry_function_transaction(str::parse("0x1::entry::add").unwrap(), false);
+    let time_now = Instant::now();
+    assert!(allow_and_consume(&filter, &transaction, time_now));
+    assert!(!allow_and_consume(&filter, &transaction, time_now));
+
+    // Verify that transactions that don't match the quota rule are not limited
+    let transaction =
+        utils::create_entry_function_transaction(str::parse("0x2::entry::add").unwrap(), false);
+    for _ in 0..10 {
+        assert!(allow_and_consume(&filter, &transaction, time_now));
+    }
+}
+
+#[test]
+fn test_transaction_quota_filter_config_zero_window() {
+    // Verify that a quota window of zero seconds is rejected
+    let transaction_filter_string = r#"
+        transaction_rules: []
+        quota_rules:
+            - quota_key: Sender
+              quota_limit:
+                MaxTransactions: 1
+        quota_window_secs: 0
+    "#;
+    let error = serde_yaml::from_str::<TransactionFilter>(transaction_filter_string).unwrap_err();
+    assert!(error
+        .to_string()
+        .contains("quota_window_secs must be greater than zero"));
+}
+
+#[test]
+fn test_transaction_quota_filter_check_does_not_consume() {
+    // Create a filter that allows at most 1 transaction for each sender
+    let quota_rule =
+        TransactionQuotaRule::new(QuotaKey::Sender, QuotaLimit::MaxTransactions(1), vec![]);
+    let filter = TransactionFilter::empty().add_quota_rule(quota_rule);
+
+    // Verify that checking the transaction (e.g., for simulation, or before
+    // validation) never consumes the quota of the sender.
+    let transaction =
+        utils::create_entry_function_transaction(str::parse("0x1::entry::add").unwrap(), false);
+    let time_now = Instant::now();
+    for _ in 0..10 {
+        assert!(filter.allows_transaction_at_time(&transaction, time_now));
+    }
+
+    // Verify that the quota is only exhausted once the transaction is consumed
+    assert!(filter.try_consume_quota_at_time(&transaction, time_now));
+    assert!(!filter.allows_transaction_at_time(&transaction, time_now));
+    assert!(!filter.try_consume_quota_at_time(&transaction, time_now));
+}
+
+#[test]
+fn test_transaction_quota_filter_window() {
+    for use_new_txn_payload_format in [false, true] {
+        // Create a filter that allows at most 2 transactions for each sender in each window
+        let quota_rule =
+            TransactionQuotaRule::new(QuotaKey::Sender, QuotaLimit::MaxTransactions(2), vec![]);
+        let filter = TransactionFilter::empty()
+            .add_quota_rule(quota_rule)
+            .with_quota_window_secs(10);
+
+        // Verify that the first two transactions from the sender are allowed
+        let transactions = utils::create_entry_function_transactions(use_new_txn_payload_format);
+        let time_now = Instant::now();
+        assert!(allow_and_consume(&filter, &transactions[0], time_now));
+        assert!(allow_and_consume(
+            &filter,
+            &transactions[0],
+            time_now + Duration::from_secs(1)
+        ));
+
+        // Verify that the next transaction from the sender is denied
+        assert!(!allow_and_consume(
+            &filter,
+            &transactions[0],
+            time_now + Duration::from_secs(2)
+        ));
+
+        // Verify that transactions from a different sender are still allowed
+        assert!(allow_and_consume(
+            &filter,
+            &transactions[1],
+            time_now + Duration::from_secs(2)
+        ));
+
+        // Verify that the quota is reset in the next window
+        assert!(allow_and_consume(
+            &filter,
+            &transactions[0],
+            time_now + Duration::from_secs(10)
+        ));
+    }
+}
+
+#[test]
+fn test_transaction_quota_filter_with_rules() {
+    for use_new_txn_payload_format in [false, true] {
+        // Create a filter that denies txn 0, and allows at most 3 transactions overall
+        let transactions = utils::create_entry_function_transactions(use_new_txn_payload_format);
+        let quota_rule =
+            TransactionQuotaRule::new(QuotaKey::All, QuotaLimit::MaxTransactions(3), vec![]);
+        let filter = TransactionFilter::empty()
+            .add_sender_filter(false, transactions[0].sender())
+            .add_quota_rule(quota_rule);
+
+        // Verify that txn 0 is denied, and only the next 3 transactions are allowed
+        let filtered_transactions = filter.filter_transactions(transactions.clone());
+        assert_eq!(filtered_transactions, transactions[1..4].to_vec());
+    }
+}
+
+/// Checks the transaction against the filter, and consumes the quota if it is allowed
+fn allow_and_consume(
+    filter: &TransactionFilter,
+    transaction: &SignedTransaction,
+    time_now: Instant,
+) -> bool {
+    filter.allows_transaction_at_time(transaction, time_now)
+        && filter.try_consume_quota_at_time(transaction, time_now)
+}
@@ -63,6 +63,16 @@ pub fn create_entry_function_transaction(
     create_signed_transaction(transaction_payload, false)
 }
 
+/// Creates and returns a signed entry function transaction with the given max gas amount
+pub fn create_entry_function_transaction_with_max_gas(
+    function: MemberId,
+    max_gas_amount: u64,
+) -> SignedTransaction {
+    let entry_function = create_entry_function(function);
+    let transaction_payload = TransactionPayload::EntryFunction(entry_function);
+    create_signed_transaction_with_parameters(transaction_payload, max_gas_amount, 0, 0)
+}
+
 /// Creates and returns a list of signed entry function transactions
 pub fn create_entry_function_transactions(
     use_new_txn_payload_format: bool,
@@ -189,6 +199,31 @@ pub fn create_signed_transaction(
     }
 }
 
+/// Creates and returns a signed transaction with the given payload, gas parameters and expiration
+pub fn create_signed_transaction_with_parameters(
+    transaction_payload: TransactionPayload,
+    max_gas_amount: u64,
+    gas_unit_price: u64,
+    expiration_timestamp_secs: u64,
+) -> SignedTransaction {
+    let raw_transaction = RawTransaction::new(
+        AccountAddress::random(),
+        0,
+        transaction_payload,
+        max_gas_amount,
+        gas_unit_price,
+        expiration_timestamp_secs,
+        ChainId::new(10),
+    );
+
+    let private_key = Ed25519PrivateKey::generate(&mut thread_rng());
+    SignedTransaction::new(
+        raw_transaction.clone(),
+        private_key.public_key(),
+        private_key.sign(&raw_transaction).unwrap(),
+    )
+}
+
 /// Creates and returns a script transaction with the given payload
 pub fn create_script_transaction(use_new_txn_payload_format: bool) -> SignedTransaction {
     let script_arguments = vec![
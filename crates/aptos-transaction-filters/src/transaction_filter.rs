@@ -1,14 +1,33 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::{
+    counters,
+    transaction_quota::{QuotaWindowTracker, TransactionQuotaRule},
+};
 use aptos_crypto::{ed25519::Ed25519PublicKey, HashValue};
+use aptos_logger::{info, sample, sample::SampleRate};
 use aptos_types::transaction::{
     authenticator::{AccountAuthenticator, AnyPublicKey, TransactionAuthenticator},
     EntryFunction, MultisigTransactionPayload, Script, SignedTransaction, TransactionExecutableRef,
     TransactionExtraConfig, TransactionPayload, TransactionPayloadInner,
 };
-use move_core_types::{account_address::AccountAddress, transaction_argument::TransactionArgument};
-use serde::{Deserialize, Serialize};
+use move_core_types::{
+    account_address::AccountAddress, language_storage::TypeTag,
+    transaction_argument::TransactionArgument,
+};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::time::{Duration, Instant};
+
+/// The default duration (in seconds) of each quota window
+const DEFAULT_QUOTA_WINDOW_SECS: u64 = 60;
+
+/// The interval (in seconds) at which shadow rule matches are logged
+const SHADOW_MATCH_LOG_INTERVAL_SECS: u64 = 10;
+
+// Useful labels for identifying the filter that recorded a shadow rule match
+pub const API_FILTER_LABEL: &str = "api_filter";
+pub const MEMPOOL_FILTER_LABEL: &str = "mempool_filter";
 
 /// A transaction filter that applies a set of rules to determine
 /// if a transaction should be allowed or denied.
@@ -16,57 +35,202 @@ use serde::{Deserialize, Serialize};
 /// Rules are applied in the order they are defined, and the first
 /// matching rule determines the outcome for the transaction.
 /// If no rules match, the transaction is allowed by default.
+///
+/// Transactions that are allowed by the rules are then checked against
+/// the quota rules (if any). Quotas are tracked over fixed time windows,
+/// and a transaction is denied if it would exceed any quota in the
+/// current window. Checking a transaction never consumes quota; callers
+/// consume it explicitly (see `try_consume_quota`) once the transaction
+/// has been fully validated.
+///
+/// Shadow rules (if any) are evaluated against every transaction checked
+/// by the filter (see `record_shadow_rule_match`), and matches are counted
+/// (per filter) and logged. However, shadow rules are never enforced, i.e.,
+/// they do not affect the outcome of the filter. This is useful for estimating
+/// the impact of new rules before deployment. Note: shadow rules are only
+/// supported by the API and mempool filters, and not by the block and batch
+/// filters (i.e., the consensus, execution and quorum store filters).
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct TransactionFilter {
     transaction_rules: Vec<TransactionRule>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    shadow_rules: Vec<TransactionRule>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    quota_rules: Vec<TransactionQuotaRule>,
+    #[serde(
+        default = "default_quota_window_secs",
+        deserialize_with = "deserialize_quota_window_secs"
+    )]
+    quota_window_secs: u64,
+    #[serde(skip)]
+    quota_tracker: QuotaWindowTracker,
 }
 
 impl TransactionFilter {
     pub fn new(transaction_rules: Vec<TransactionRule>) -> Self {
-        Self { transaction_rules }
+        Self {
+            transaction_rules,
+            shadow_rules: Vec::new(),
+            quota_rules: Vec::new(),
+            quota_window_secs: DEFAULT_QUOTA_WINDOW_SECS,
+            quota_tracker: QuotaWindowTracker::default(),
+        }
     }
 
     /// Returns true iff the filter allows the transaction
     pub fn allows_transaction(&self, signed_transaction: &SignedTransaction) -> bool {
+        self.allows_transaction_at_time(signed_transaction, Instant::now())
+    }
+
+    /// Returns true iff the filter allows the transaction at the given time.
+    ///
+    /// Note: this checks that the transaction fits within the quotas, but does
+    /// not count it against them. Callers that accept the transaction must call
+    /// `try_consume_quota` once it has passed all other validation (e.g., the
+    /// signature and VM checks), so that invalid transactions never consume
+    /// the quota of their (claimed) sender.
+    pub fn allows_transaction_at_time(
+        &self,
+        signed_transaction: &SignedTransaction,
+        time_now: Instant,
+    ) -> bool {
         // If the filter is empty, allow the transaction by default
         if self.is_empty() {
             return true;
         }
 
         // Check if any rule matches the transaction
-        for transaction_rule in &self.transaction_rules {
-            if transaction_rule.matches(signed_transaction) {
-                return match transaction_rule {
-                    TransactionRule::Allow(_) => true,
-                    TransactionRule::Deny(_) => false,
-                };
+        if let Some(rule_index) = self.get_matching_rule_index(signed_transaction) {
+            if let TransactionRule::Deny(_) = self.transaction_rules[rule_index] {
+                return false;
             }
         }
 
-        true // No rules match (allow the transaction by default)
+        // Check the transaction against the quotas (if any)
+        self.fits_quota_at_time(signed_transaction, time_now)
+    }
+
+    /// Returns true iff the transaction fits within the quotas, without
+    /// counting it against them (or evaluating any of the rules).
+    pub fn fits_quota(&self, signed_transaction: &SignedTransaction) -> bool {
+        self.fits_quota_at_time(signed_transaction, Instant::now())
+    }
+
+    /// Returns true iff the transaction fits within the quotas at the given time
+    /// (see `fits_quota`)
+    pub fn fits_quota_at_time(
+        &self,
+        signed_transaction: &SignedTransaction,
+        time_now: Instant,
+    ) -> bool {
+        self.quota_rules.is_empty()
+            || self.quota_tracker.fits(
+                &self.quota_rules,
+                Duration::from_secs(self.quota_window_secs),
+                time_now,
+                signed_transaction,
+            )
+    }
+
+    /// Counts the transaction against the quotas, and returns true iff the
+    /// transaction fits within them. If it does not, the quota usage is left
+    /// unchanged. This should only be called for valid transactions that are
+    /// allowed by the filter (see `allows_transaction`).
+    pub fn try_consume_quota(&self, signed_transaction: &SignedTransaction) -> bool {
+        self.try_consume_quota_at_time(signed_transaction, Instant::now())
+    }
+
+    /// Counts the transaction against the quotas at the given time (see `try_consume_quota`)
+    pub fn try_consume_quota_at_time(
+        &self,
+        signed_transaction: &SignedTransaction,
+        time_now: Instant,
+    ) -> bool {
+        self.quota_rules.is_empty()
+            || self.quota_tracker.try_consume(
+                &self.quota_rules,
+                Duration::from_secs(self.quota_window_secs),
+                time_now,
+                signed_transaction,
+            )
     }
 
     /// Returns an empty transaction filter with no rules
     pub fn empty() -> Self {
-        Self {
-            transaction_rules: Vec::new(),
-        }
+        Self::new(Vec::new())
     }
 
-    /// Filters the given transactions and returns only those that are allowed
+    /// Filters the given transactions and returns only those that are allowed.
+    /// The allowed transactions are counted against the quotas (in order).
     pub fn filter_transactions(
         &self,
         transactions: Vec<SignedTransaction>,
     ) -> Vec<SignedTransaction> {
         transactions
             .into_iter()
-            .filter(|txn| self.allows_transaction(txn))
+            .filter(|txn| self.allows_transaction(txn) && self.try_consume_quota(txn))
             .collect()
     }
 
-    /// Returns true iff the filter is empty (i.e., has no rules)
+    /// Returns the index of the first rule that matches the transaction (if any)
+    pub fn get_matching_rule_index(&self, signed_transaction: &SignedTransaction) -> Option<usize> {
+        get_matching_rule_index(&self.transaction_rules, signed_transaction)
+    }
+
+    /// Returns the index of the first shadow rule that matches the transaction (if any)
+    pub fn get_matching_shadow_rule_index(
+        &self,
+        signed_transaction: &SignedTransaction,
+    ) -> Option<usize> {
+        get_matching_rule_index(&self.shadow_rules, signed_transaction)
+    }
+
+    /// Returns the rules of the filter
+    pub fn transaction_rules(&self) -> &[TransactionRule] {
+        &self.transaction_rules
+    }
+
+    /// Returns the shadow rules of the filter
+    pub fn shadow_rules(&self) -> &[TransactionRule] {
+        &self.shadow_rules
+    }
+
+    /// Returns true iff the filter is empty (i.e., has no rules, shadow rules or quota rules)
     pub fn is_empty(&self) -> bool {
         self.transaction_rules.is_empty()
+            && self.shadow_rules.is_empty()
+            && self.quota_rules.is_empty()
+    }
+
+    /// Evaluates the shadow rules against the transaction, and records the first
+    /// matching shadow rule (if any) in the metrics and logs. The filter label
+    /// identifies the filter that evaluated the transaction (e.g., "mempool_filter").
+    ///
+    /// Note: shadow rules are never enforced, so this does not affect whether
+    /// the transaction is allowed (see `allows_transaction`).
+    pub fn record_shadow_rule_match(
+        &self,
+        filter_label: &str,
+        signed_transaction: &SignedTransaction,
+    ) {
+        if self.shadow_rules.is_empty() {
+            return;
+        }
+
+        if let Some(rule_index) = self.get_matching_shadow_rule_index(signed_transaction) {
+            let rule_type = self.shadow_rules[rule_index].get_label();
+            counters::increment_shadow_rule_match_counter(filter_label, rule_index, rule_type);
+            sample!(
+                SampleRate::Duration(Duration::from_secs(SHADOW_MATCH_LOG_INTERVAL_SECS)),
+                info!(
+                    "Shadow transaction filter rule matched! Filter: {}, rule index: {}, rule type: {}, transaction hash: {}",
+                    filter_label,
+                    rule_index,
+                    rule_type,
+                    signed_transaction.committed_hash()
+                )
+            );
+        }
     }
 
     /// Adds an all matcher to the filter (matching all transactions)
@@ -90,6 +254,64 @@ impl TransactionFilter {
 
         self
     }
+
+    /// Adds a shadow rule containing multiple matchers. Shadow rules are
+    /// evaluated and counted, but never enforced.
+    pub fn add_multiple_matchers_shadow_filter(
+        mut self,
+        allow: bool,
+        transaction_matchers: Vec<TransactionMatcher>,
+    ) -> Self {
+        let transaction_rule = if allow {
+            TransactionRule::Allow(transaction_matchers)
+        } else {
+            TransactionRule::Deny(transaction_matchers)
+        };
+        self.shadow_rules.push(transaction_rule);
+
+        self
+    }
+
+    /// Adds a quota rule to the filter
+    pub fn add_quota_rule(mut self, quota_rule: TransactionQuotaRule) -> Self {
+        self.quota_rules.push(quota_rule);
+        self
+    }
+
+    /// Sets the duration (in seconds) of each quota window
+    pub fn with_quota_window_secs(mut self, quota_window_secs: u64) -> Self {
+        self.quota_window_secs = quota_window_secs;
+        self
+    }
+}
+
+/// Returns the default duration (in seconds) of each quota window
+fn default_quota_window_secs() -> u64 {
+    DEFAULT_QUOTA_WINDOW_SECS
+}
+
+/// Deserializes the duration (in seconds) of each quota window, which must be non-zero
+fn deserialize_quota_window_secs<'de, D>(deserializer: D) -> Result<u64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let quota_window_secs = u64::deserialize(deserializer)?;
+    if quota_window_secs == 0 {
+        return Err(serde::de::Error::custom(
+            "quota_window_secs must be greater than zero",
+        ));
+    }
+    Ok(quota_window_secs)
+}
+
+/// Returns the index of the first rule that matches the transaction (if any)
+fn get_matching_rule_index(
+    transaction_rules: &[TransactionRule],
+    signed_transaction: &SignedTransaction,
+) -> Option<usize> {
+    transaction_rules
+        .iter()
+        .position(|transaction_rule| transaction_rule.matches(signed_transaction))
 }
 
 // These are useful test-only methods for creating and testing filters
@@ -101,6 +323,17 @@ impl TransactionFilter {
         self.add_multiple_matchers_filter(allow, vec![transaction_matcher])
     }
 
+    /// Adds an argument address matcher to the filter
+    pub fn add_argument_address_filter(
+        self,
+        allow: bool,
+        argument_index: usize,
+        address: AccountAddress,
+    ) -> Self {
+        let transaction_matcher = TransactionMatcher::ArgumentAddress(argument_index, address);
+        self.add_multiple_matchers_filter(allow, vec![transaction_matcher])
+    }
+
     /// Adds an entry function matcher to the filter
     pub fn add_entry_function_filter(
         self,
@@ -113,12 +346,64 @@ impl TransactionFilter {
         self.add_multiple_matchers_filter(allow, vec![transaction_matcher])
     }
 
+    /// Adds an expiration timestamp greater than matcher to the filter
+    pub fn add_expiration_timestamp_greater_than_filter(
+        self,
+        allow: bool,
+        expiration_timestamp_secs: u64,
+    ) -> Self {
+        let transaction_matcher =
+            TransactionMatcher::ExpirationTimestampGreaterThan(expiration_timestamp_secs);
+        self.add_multiple_matchers_filter(allow, vec![transaction_matcher])
+    }
+
+    /// Adds an expiration timestamp less than matcher to the filter
+    pub fn add_expiration_timestamp_less_than_filter(
+        self,
+        allow: bool,
+        expiration_timestamp_secs: u64,
+    ) -> Self {
+        let transaction_matcher =
+            TransactionMatcher::ExpirationTimestampLessThan(expiration_timestamp_secs);
+        self.add_multiple_matchers_filter(allow, vec![transaction_matcher])
+    }
+
+    /// Adds a gas unit price greater than matcher to the filter
+    pub fn add_gas_unit_price_greater_than_filter(self, allow: bool, gas_unit_price: u64) -> Self {
+        let transaction_matcher = TransactionMatcher::GasUnitPriceGreaterThan(gas_unit_price);
+        self.add_multiple_matchers_filter(allow, vec![transaction_matcher])
+    }
+
+    /// Adds a gas unit price less than matcher to the filter
+    pub fn add_gas_unit_price_less_than_filter(self, allow: bool, gas_unit_price: u64) -> Self {
+        let transaction_matcher = TransactionMatcher::GasUnitPriceLessThan(gas_unit_price);
+        self.add_multiple_matchers_filter(allow, vec![transaction_matcher])
+    }
+
+    /// Adds a max gas amount greater than matcher to the filter
+    pub fn add_max_gas_amount_greater_than_filter(self, allow: bool, max_gas_amount: u64) -> Self {
+        let transaction_matcher = TransactionMatcher::MaxGasAmountGreaterThan(max_gas_amount);
+        self.add_multiple_matchers_filter(allow, vec![transaction_matcher])
+    }
+
+    /// Adds a max gas amount less than matcher to the filter
+    pub fn add_max_gas_amount_less_than_filter(self, allow: bool, max_gas_amount: u64) -> Self {
+        let transaction_matcher = TransactionMatcher::MaxGasAmountLessThan(max_gas_amount);
+        self.add_multiple_matchers_filter(allow, vec![transaction_matcher])
+    }
+
     /// Adds a module address matcher to the filter
     pub fn add_module_address_filter(self, allow: bool, address: AccountAddress) -> Self {
         let transaction_matcher = TransactionMatcher::ModuleAddress(address);
         self.add_multiple_matchers_filter(allow, vec![transaction_matcher])
     }
 
+    /// Adds a payload size greater than matcher to the filter
+    pub fn add_payload_size_greater_than_filter(self, allow: bool, payload_size: u64) -> Self {
+        let transaction_matcher = TransactionMatcher::PayloadSizeGreaterThan(payload_size);
+        self.add_multiple_matchers_filter(allow, vec![transaction_matcher])
+    }
+
     /// Adds a public key matcher to the filter
     pub fn add_public_key_filter(self, allow: bool, public_key: AnyPublicKey) -> Self {
         let transaction_matcher = TransactionMatcher::PublicKey(public_key);
@@ -136,6 +421,18 @@ impl TransactionFilter {
         let transaction_matcher = TransactionMatcher::TransactionId(txn_id);
         self.add_multiple_matchers_filter(allow, vec![transaction_matcher])
     }
+
+    /// Adds a type argument matcher to the filter
+    pub fn add_type_argument_filter(
+        self,
+        allow: bool,
+        type_argument_index: usize,
+        type_argument: TypeTag,
+    ) -> Self {
+        let transaction_matcher =
+            TransactionMatcher::TypeArgument(type_argument_index, type_argument);
+        self.add_multiple_matchers_filter(allow, vec![transaction_matcher])
+    }
 }
 
 /// A transaction rule that defines whether to allow or deny transactions
@@ -147,6 +444,14 @@ pub enum TransactionRule {
 }
 
 impl TransactionRule {
+    /// Returns the label of the rule (e.g., for metrics and logging)
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            TransactionRule::Allow(_) => "allow",
+            TransactionRule::Deny(_) => "deny",
+        }
+    }
+
     /// Returns true iff the rule matches the given transaction. This
     /// requires that all matchers in the rule match the transaction.
     fn matches(&self, signed_transaction: &SignedTransaction) -> bool {
@@ -163,13 +468,22 @@ impl TransactionRule {
 /// A matcher that defines the criteria for matching transactions
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub enum TransactionMatcher {
-    All,                                           // Matches any transaction
-    TransactionId(HashValue),                      // Matches a specific transaction by its ID
-    Sender(AccountAddress), // Matches any transaction sent by a specific account address
+    All,                                                             // Matches any transaction
+    TransactionId(HashValue), // Matches a specific transaction by its ID
+    Sender(AccountAddress),   // Matches any transaction sent by a specific account address
     ModuleAddress(AccountAddress), // Matches any transaction that calls a module at a specific address
     EntryFunction(AccountAddress, String, String), // Matches any transaction that calls a specific entry function in a module
     AccountAddress(AccountAddress), // Matches any transaction that involves a specific account address
     PublicKey(AnyPublicKey),        // Matches any transaction that involves a specific public key
+    TypeArgument(usize, #[serde(with = "type_tag_string")] TypeTag), // Matches any transaction with a specific type argument (e.g., "0x1::aptos_coin::AptosCoin") at the given index
+    ArgumentAddress(usize, AccountAddress), // Matches any transaction with a specific address argument at the given index
+    PayloadSizeGreaterThan(u64), // Matches any transaction with a payload size (in bytes) greater than the specified value
+    MaxGasAmountGreaterThan(u64), // Matches any transaction with a max gas amount greater than the specified value
+    MaxGasAmountLessThan(u64), // Matches any transaction with a max gas amount less than the specified value
+    GasUnitPriceGreaterThan(u64), // Matches any transaction with a gas unit price greater than the specified value
+    GasUnitPriceLessThan(u64), // Matches any transaction with a gas unit price less than the specified value
+    ExpirationTimestampGreaterThan(u64), // Matches any transaction with an expiration timestamp (in secs) greater than the specified value
+    ExpirationTimestampLessThan(u64), // Matches any transaction with an expiration timestamp (in secs) less than the specified value
 }
 
 impl TransactionMatcher {
@@ -197,6 +511,33 @@ impl TransactionMatcher {
             TransactionMatcher::PublicKey(public_key) => {
                 matches_transaction_authenticator_public_key(signed_transaction, public_key)
             },
+            TransactionMatcher::TypeArgument(type_argument_index, type_argument) => {
+                matches_type_argument(signed_transaction, *type_argument_index, type_argument)
+            },
+            TransactionMatcher::ArgumentAddress(argument_index, address) => {
+                matches_argument_address(signed_transaction, *argument_index, address)
+            },
+            TransactionMatcher::PayloadSizeGreaterThan(payload_size) => {
+                matches_payload_size_greater_than(signed_transaction, *payload_size)
+            },
+            TransactionMatcher::MaxGasAmountGreaterThan(max_gas_amount) => {
+                signed_transaction.max_gas_amount() > *max_gas_amount
+            },
+            TransactionMatcher::MaxGasAmountLessThan(max_gas_amount) => {
+                signed_transaction.max_gas_amount() < *max_gas_amount
+            },
+            TransactionMatcher::GasUnitPriceGreaterThan(gas_unit_price) => {
+                signed_transaction.gas_unit_price() > *gas_unit_price
+            },
+            TransactionMatcher::GasUnitPriceLessThan(gas_unit_price) => {
+                signed_transaction.gas_unit_price() < *gas_unit_price
+            },
+            TransactionMatcher::ExpirationTimestampGreaterThan(expiration_timestamp_secs) => {
+                signed_transaction.expiration_timestamp_secs() > *expiration_timestamp_secs
+            },
+            TransactionMatcher::ExpirationTimestampLessThan(expiration_timestamp_secs) => {
+                signed_transaction.expiration_timestamp_secs() < *expiration_timestamp_secs
+            },
         }
     }
 }
@@ -226,6 +567,48 @@ fn compare_entry_function(
         && entry_function.function().to_string() == *function_name
 }
 
+/// Returns the entry function called by the transaction (if any)
+pub(crate) fn get_entry_function(signed_transaction: &SignedTransaction) -> Option<&EntryFunction> {
+    // Match all variants explicitly to ensure future enum changes are caught during compilation
+    match signed_transaction.payload() {
+        TransactionPayload::Script(_) | TransactionPayload::ModuleBundle(_) => None,
+        TransactionPayload::Multisig(multisig) => {
+            multisig
+                .transaction_payload
+                .as_ref()
+                .map(|payload| match payload {
+                    MultisigTransactionPayload::EntryFunction(entry_function) => entry_function,
+                })
+        },
+        TransactionPayload::EntryFunction(entry_function) => Some(entry_function),
+        TransactionPayload::Payload(TransactionPayloadInner::V1 { executable, .. }) => {
+            match executable.as_ref() {
+                TransactionExecutableRef::Script(_) | TransactionExecutableRef::Empty => None,
+                TransactionExecutableRef::EntryFunction(entry_function) => Some(entry_function),
+            }
+        },
+    }
+}
+
+/// Returns the script executed by the transaction (if any)
+fn get_script(signed_transaction: &SignedTransaction) -> Option<&Script> {
+    // Match all variants explicitly to ensure future enum changes are caught during compilation
+    match signed_transaction.payload() {
+        TransactionPayload::EntryFunction(_)
+        | TransactionPayload::Multisig(_)
+        | TransactionPayload::ModuleBundle(_) => None,
+        TransactionPayload::Script(script) => Some(script),
+        TransactionPayload::Payload(TransactionPayloadInner::V1 { executable, .. }) => {
+            match executable.as_ref() {
+                TransactionExecutableRef::EntryFunction(_) | TransactionExecutableRef::Empty => {
+                    None
+                },
+                TransactionExecutableRef::Script(script) => Some(script),
+            }
+        },
+    }
+}
+
 /// Returns true iff the entry function's module address matches the given account address
 fn compare_entry_function_module_address(
     entry_function: &EntryFunction,
@@ -245,6 +628,32 @@ fn compare_script_argument_address(script: &Script, address: &AccountAddress) ->
     })
 }
 
+/// Returns true iff the transaction's argument at the given index is the given account address
+fn matches_argument_address(
+    signed_transaction: &SignedTransaction,
+    argument_index: usize,
+    address: &AccountAddress,
+) -> bool {
+    if let Some(entry_function) = get_entry_function(signed_transaction) {
+        // Entry function arguments are BCS encoded
+        return entry_function
+            .args()
+            .get(argument_index)
+            .and_then(|argument| bcs::from_bytes::<AccountAddress>(argument).ok())
+            .map(|argument_address| argument_address == *address)
+            .unwrap_or(false);
+    }
+
+    if let Some(script) = get_script(signed_transaction) {
+        return matches!(
+            script.args().get(argument_index),
+            Some(TransactionArgument::Address(argument_address)) if argument_address == address
+        );
+    }
+
+    false // The transaction has no arguments
+}
+
 /// Returns true iff the account authenticator contains the given account address
 fn matches_account_authenticator_address(
     account_authenticator: &AccountAuthenticator,
@@ -424,6 +833,16 @@ fn matches_script_argument_address(
     }
 }
 
+/// Returns true iff the transaction's payload size (in bytes) is greater than the given size
+fn matches_payload_size_greater_than(
+    signed_transaction: &SignedTransaction,
+    payload_size: u64,
+) -> bool {
+    bcs::serialized_size(signed_transaction.payload())
+        .map(|serialized_size| serialized_size as u64 > payload_size)
+        .unwrap_or(false)
+}
+
 /// Returns true iff the transaction's sender matches the given account address
 fn matches_sender_address(signed_transaction: &SignedTransaction, sender: &AccountAddress) -> bool {
     signed_transaction.sender() == *sender
@@ -513,6 +932,50 @@ fn matches_transaction_authenticator_public_key(
     }
 }
 
+/// Returns true iff the transaction's type argument at the given index matches the
+/// given type argument.
+fn matches_type_argument(
+    signed_transaction: &SignedTransaction,
+    type_argument_index: usize,
+    type_argument: &TypeTag,
+) -> bool {
+    let type_arguments = if let Some(entry_function) = get_entry_function(signed_transaction) {
+        entry_function.ty_args()
+    } else if let Some(script) = get_script(signed_transaction) {
+        script.ty_args()
+    } else {
+        return false; // The transaction has no type arguments
+    };
+
+    type_arguments.get(type_argument_index) == Some(type_argument)
+}
+
+/// Serializes and deserializes type tags using their string representation
+/// (e.g., "0x1::aptos_coin::AptosCoin"). Invalid type strings are rejected
+/// when the filter is deserialized.
+mod type_tag_string {
+    use move_core_types::language_storage::TypeTag;
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::str::FromStr;
+
+    pub fn serialize<S>(type_tag: &TypeTag, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&type_tag.to_canonical_string())
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<TypeTag, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let type_tag = String::deserialize(deserializer)?;
+        TypeTag::from_str(&type_tag).map_err(|error| {
+            serde::de::Error::custom(format!("invalid type argument {:?}: {}", type_tag, error))
+        })
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -0,0 +1,264 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::transaction_filter::{get_entry_function, TransactionMatcher};
+use aptos_infallible::Mutex;
+use aptos_types::transaction::SignedTransaction;
+use move_core_types::account_address::AccountAddress;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// A quota rule that limits the number of transactions (or gas units) accepted
+/// for each distinct quota key (e.g., for each sender) within a quota window.
+///
+/// Only transactions that match all of the rule's transaction matchers count
+/// towards (and are limited by) the quota. If there are no matchers, the quota
+/// applies to all transactions.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct TransactionQuotaRule {
+    quota_key: QuotaKey,     // The key used to group transactions (e.g., by sender)
+    quota_limit: QuotaLimit, // The limit to enforce for each group
+    #[serde(default)]
+    transaction_matchers: Vec<TransactionMatcher>, // The transactions the quota applies to
+}
+
+impl TransactionQuotaRule {
+    pub fn new(
+        quota_key: QuotaKey,
+        quota_limit: QuotaLimit,
+        transaction_matchers: Vec<TransactionMatcher>,
+    ) -> Self {
+        Self {
+            quota_key,
+            quota_limit,
+            transaction_matchers,
+        }
+    }
+
+    /// Returns the quota key value and the cost of the given transaction
+    /// (for this rule), or None if the rule does not apply to the transaction.
+    fn get_key_value_and_cost(
+        &self,
+        signed_transaction: &SignedTransaction,
+    ) -> Option<(QuotaKeyValue, u64)> {
+        // Verify that all matchers match the transaction
+        if !self
+            .transaction_matchers
+            .iter()
+            .all(|matcher| matcher.matches(signed_transaction))
+        {
+            return None;
+        }
+
+        // Calculate the key value and cost
+        let key_value = self.quota_key.get_key_value(signed_transaction)?;
+        let cost = self.quota_limit.get_transaction_cost(signed_transaction);
+        Some((key_value, cost))
+    }
+}
+
+/// The key used to group transactions when enforcing a quota
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum QuotaKey {
+    All,           // All transactions share a single quota
+    Sender,        // Each transaction sender has its own quota
+    ModuleAddress, // Each entry function module address has its own quota
+    EntryFunction, // Each entry function has its own quota
+}
+
+impl QuotaKey {
+    /// Returns the key value for the given transaction, or None if the
+    /// transaction does not have the key (e.g., a script has no entry function).
+    fn get_key_value(&self, signed_transaction: &SignedTransaction) -> Option<QuotaKeyValue> {
+        match self {
+            QuotaKey::All => Some(QuotaKeyValue::All),
+            QuotaKey::Sender => Some(QuotaKeyValue::Sender(signed_transaction.sender())),
+            QuotaKey::ModuleAddress => {
+                get_entry_function(signed_transaction).map(|entry_function| {
+                    QuotaKeyValue::ModuleAddress(*entry_function.module().address())
+                })
+            },
+            QuotaKey::EntryFunction => {
+                get_entry_function(signed_transaction).map(|entry_function| {
+                    QuotaKeyValue::EntryFunction(
+                        *entry_function.module().address(),
+                        entry_function.module().name().to_string(),
+                        entry_function.function().to_string(),
+                    )
+                })
+            },
+        }
+    }
+}
+
+/// The value of a quota key for a specific transaction
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+enum QuotaKeyValue {
+    All,
+    Sender(AccountAddress),
+    ModuleAddress(AccountAddress),
+    EntryFunction(AccountAddress, String, String),
+}
+
+/// The limit enforced by a quota rule (for each quota key value)
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum QuotaLimit {
+    MaxTransactions(u64), // The maximum number of transactions
+    MaxGasUnits(u64),     // The maximum number of gas units (i.e., the sum of max gas amounts)
+}
+
+impl QuotaLimit {
+    /// Returns the maximum usage allowed by the limit
+    fn get_limit(&self) -> u64 {
+        match self {
+            QuotaLimit::MaxTransactions(max_transactions) => *max_transactions,
+            QuotaLimit::MaxGasUnits(max_gas_units) => *max_gas_units,
+        }
+    }
+
+    /// Returns the amount of quota consumed by the given transaction
+    fn get_transaction_cost(&self, signed_transaction: &SignedTransaction) -> u64 {
+        match self {
+            QuotaLimit::MaxTransactions(_) => 1,
+            QuotaLimit::MaxGasUnits(_) => signed_transaction.max_gas_amount(),
+        }
+    }
+}
+
+/// Tracks the quota usage for a set of quota rules (e.g., within a single block)
+#[derive(Debug, Default)]
+pub(crate) struct QuotaUsage {
+    usage: HashMap<(usize, QuotaKeyValue), u64>, // The usage per (rule index, key value)
+}
+
+impl QuotaUsage {
+    /// Returns true iff the transaction fits within all the quotas. If it
+    /// does, the quota usage is updated to account for the transaction.
+    /// Otherwise, the quota usage is left unchanged.
+    pub(crate) fn try_consume(
+        &mut self,
+        quota_rules: &[TransactionQuotaRule],
+        signed_transaction: &SignedTransaction,
+    ) -> bool {
+        match self.get_usage_updates(quota_rules, signed_transaction) {
+            Some(usage_updates) => {
+                self.usage.extend(usage_updates);
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Returns true iff the transaction fits within all the quotas. The
+    /// quota usage is never updated.
+    pub(crate) fn fits(
+        &self,
+        quota_rules: &[TransactionQuotaRule],
+        signed_transaction: &SignedTransaction,
+    ) -> bool {
+        self.get_usage_updates(quota_rules, signed_transaction)
+            .is_some()
+    }
+
+    /// Returns the new usage for all rules that apply to the transaction,
+    /// or None if the transaction would exceed any of the quotas.
+    fn get_usage_updates(
+        &self,
+        quota_rules: &[TransactionQuotaRule],
+        signed_transaction: &SignedTransaction,
+    ) -> Option<Vec<((usize, QuotaKeyValue), u64)>> {
+        let mut usage_updates = vec![];
+        for (rule_index, quota_rule) in quota_rules.iter().enumerate() {
+            if let Some((key_value, cost)) = quota_rule.get_key_value_and_cost(signed_transaction) {
+                let usage_key = (rule_index, key_value);
+                let current_usage = self.usage.get(&usage_key).copied().unwrap_or(0);
+                let new_usage = current_usage.saturating_add(cost);
+                if new_usage > quota_rule.quota_limit.get_limit() {
+                    return None; // The quota would be exceeded
+                }
+                usage_updates.push((usage_key, new_usage));
+            }
+        }
+        Some(usage_updates)
+    }
+}
+
+/// Tracks the quota usage across fixed time windows. The usage
+/// is reset at the start of each window.
+///
+/// Note: the tracker holds runtime state only (it is never serialized),
+/// and clones of the tracker share the same underlying state.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct QuotaWindowTracker {
+    window_state: Arc<Mutex<Option<(Instant, QuotaUsage)>>>, // The window start time and usage
+}
+
+impl QuotaWindowTracker {
+    /// Returns true iff the transaction fits within all the quotas for the
+    /// window containing the given time. The quota usage is not updated.
+    pub(crate) fn fits(
+        &self,
+        quota_rules: &[TransactionQuotaRule],
+        quota_window: Duration,
+        time_now: Instant,
+        signed_transaction: &SignedTransaction,
+    ) -> bool {
+        self.with_window_usage(quota_window, time_now, |quota_usage| {
+            quota_usage.fits(quota_rules, signed_transaction)
+        })
+    }
+
+    /// Returns true iff the transaction fits within all the quotas for the
+    /// window containing the given time. If so, the quota usage is updated.
+    pub(crate) fn try_consume(
+        &self,
+        quota_rules: &[TransactionQuotaRule],
+        quota_window: Duration,
+        time_now: Instant,
+        signed_transaction: &SignedTransaction,
+    ) -> bool {
+        self.with_window_usage(quota_window, time_now, |quota_usage| {
+            quota_usage.try_consume(quota_rules, signed_transaction)
+        })
+    }
+
+    /// Calls the given function with the quota usage of the window containing
+    /// the given time. A new window is started if the current one has expired.
+    fn with_window_usage(
+        &self,
+        quota_window: Duration,
+        time_now: Instant,
+        function: impl FnOnce(&mut QuotaUsage) -> bool,
+    ) -> bool {
+        // Start a new window if there is no window, or the current window has expired
+        let mut window_state = self.window_state.lock();
+        let window_expired = match window_state.as_ref() {
+            Some((window_start, _)) => {
+                time_now.saturating_duration_since(*window_start) >= quota_window
+            },
+            None => true,
+        };
+        if window_expired {
+            *window_state = Some((time_now, QuotaUsage::default()));
+        }
+
+        let (_, quota_usage) = window_state
+            .as_mut()
+            .expect("The quota window should have been initialized!");
+        function(quota_usage)
+    }
+}
+
+// The tracker only holds runtime state, so it is ignored when comparing filters
+impl PartialEq for QuotaWindowTracker {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for QuotaWindowTracker {}
@@ -3,9 +3,11 @@
 
 use crate::common::types::{CliError, CliTypedResult};
 use aptos_crypto::HashValue;
-use aptos_gas_profiling::FrameName;
+use aptos_gas_profiling::{ExecutionTrace, FrameName};
 use aptos_move_debugger::aptos_debugger::AptosDebugger;
-use aptos_types::transaction::{AuxiliaryInfo, PersistedAuxiliaryInfo, SignedTransaction};
+use aptos_types::transaction::{
+    AuxiliaryInfo, ExecutionStatus, PersistedAuxiliaryInfo, SignedTransaction, TransactionStatus,
+};
 use aptos_vm::{data_cache::AsMoveResolver, AptosVM};
 use aptos_vm_environment::environment::AptosEnvironment;
 use aptos_vm_logging::log_schema::AdapterLogSchema;
@@ -15,6 +17,22 @@ use aptos_vm_types::{
 use move_core_types::vm_status::VMStatus;
 use std::{path::Path, time::Instant};
 
+/// The index within its block that a simulated transaction is executed at. A simulated transaction
+/// is not part of a block, so any index a user transaction could have (i.e., after the block
+/// metadata and validator transactions) works. A fixed index keeps the gas profiles and traces of
+/// a transaction consistent, as the index is observable (e.g., via the transaction context).
+const SIMULATED_TRANSACTION_INDEX: u32 = 2;
+
+/// Returns the auxiliary info to execute a simulated transaction with
+fn simulated_transaction_auxiliary_info() -> AuxiliaryInfo {
+    AuxiliaryInfo::new(
+        PersistedAuxiliaryInfo::V1 {
+            transaction_index: SIMULATED_TRANSACTION_INDEX,
+        },
+        None,
+    )
+}
+
 pub fn run_transaction_using_debugger(
     debugger: &AptosDebugger,
     version: u64,
@@ -134,12 +152,7 @@ pub fn profile_transaction_using_debugger(
         .execute_transaction_at_version_with_gas_profiler(
             version,
             transaction,
-            AuxiliaryInfo::new(
-                PersistedAuxiliaryInfo::V1 {
-                    transaction_index: 2,
-                },
-                None,
-            ),
+            simulated_transaction_auxiliary_info(),
         )
         .map_err(|err| {
             CliError::UnexpectedError(format!("failed to simulate txn with gas profiler: {}", err))
@@ -172,3 +185,42 @@ pub fn profile_transaction_using_debugger(
 
     Ok((vm_status, vm_output))
 }
+
+pub fn trace_transaction_using_debugger(
+    debugger: &AptosDebugger,
+    version: u64,
+    transaction: SignedTransaction,
+    hash: HashValue,
+) -> CliTypedResult<(VMStatus, VMOutput)> {
+    let (vm_status, vm_output, gas_log) = debugger
+        .execute_transaction_at_version_with_value_tracing(
+            version,
+            transaction,
+            simulated_transaction_auxiliary_info(),
+        )
+        .map_err(|err| {
+            CliError::UnexpectedError(format!("failed to simulate txn with tracing: {}", err))
+        })?;
+
+    // Build the execution trace from the gas log
+    let execution_status = match vm_output.status() {
+        TransactionStatus::Keep(execution_status) => execution_status.clone(),
+        TransactionStatus::Discard(status_code) => {
+            ExecutionStatus::MiscellaneousError(Some(*status_code))
+        },
+        TransactionStatus::Retry => ExecutionStatus::MiscellaneousError(None),
+    };
+    let trace = ExecutionTrace::new(&gas_log, &execution_status);
+
+    // Save the trace
+    let path = Path::new("execution-traces").join(format!("txn-{}.json", hash));
+    let trace_json = serde_json::to_string_pretty(&trace)
+        .map_err(|err| CliError::UnexpectedError(format!("failed to serialize trace: {}", err)))?;
+    std::fs::create_dir_all("execution-traces")
+        .and_then(|_| std::fs::write(&path, trace_json))
+        .map_err(|err| CliError::IO(path.display().to_string(), err))?;
+
+    println!("Execution trace saved to {}.", path.display());
+
+    Ok((vm_status, vm_output))
+}
@@ -331,6 +331,19 @@ impl TxnOptions {
             .await
     }
 
+    /// Simulates the transaction locally, and saves a structured execution trace
+    /// (i.e., the call tree, events and abort location) of the transaction.
+    pub async fn trace_locally(
+        &self,
+        payload: TransactionPayload,
+    ) -> CliTypedResult<TransactionSummary> {
+        println!();
+        println!("Simulating transaction locally with execution tracing...");
+
+        self.simulate_using_debugger(payload, local_simulation::trace_transaction_using_debugger)
+            .await
+    }
+
     /// Benchmarks the transaction payload locally.
     /// The transaction is executed multiple times, and the median value is calculated to improve
     /// the accuracy of the measurement results.
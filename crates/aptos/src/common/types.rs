@@ -49,9 +49,9 @@ use aptos_types::{
     account_config::AccountResource,
     chain_id::ChainId,
     transaction::{
-        authenticator::AuthenticationKey, EntryFunction, MultisigTransactionPayload,
-        ReplayProtector, Script, SignedTransaction, TransactionArgument, TransactionPayload,
-        TransactionStatus,
+        authenticator::{AccountAuthenticator, AuthenticationKey, TransactionAuthenticator},
+        EntryFunction, MultisigTransactionPayload, ReplayProtector, Script, SignedTransaction,
+        TransactionArgument, TransactionPayload, TransactionStatus,
     },
 };
 use aptos_vm_types::output::VMOutput;
@@ -1806,6 +1806,13 @@ pub struct TransactionOptions {
     #[clap(long)]
     pub(crate) session: Option<PathBuf>,
 
+    /// Execute the transaction in the session on behalf of this account, without its private key
+    ///
+    /// Authenticator verification is skipped (including for multisig and keyless accounts), but
+    /// all other prologue checks still apply. This is only available in simulation sessions.
+    #[clap(long, requires = "session", value_parser = crate::common::types::load_account_arg)]
+    pub(crate) impersonate: Option<AccountAddress>,
+
     /// Replay protection mechanism to use when generating the transaction.
     ///
     /// When "nonce" is chosen, the transaction will be an orderless transaction and contains a replay protection nonce.
@@ -2190,7 +2197,13 @@ impl TransactionOptions {
         const DEFAULT_GAS_UNIT_PRICE: u64 = 100;
         const DEFAULT_MAX_GAS: u64 = 2_000_000;
 
-        let (sender_key, sender_address) = self.get_key_and_address()?;
+        let (sender_key, sender_address) = match self.impersonate {
+            Some(sender_address) => (None, sender_address),
+            None => {
+                let (sender_key, sender_address) = self.get_key_and_address()?;
+                (Some(sender_key), sender_address)
+            },
+        };
 
         // TODO: Support orderless transactions
         let account = state_store.get_resource::<AccountResource>(sender_address)?;
@@ -2218,12 +2231,27 @@ impl TransactionOptions {
             .with_gas_unit_price(gas_unit_price)
             .with_max_gas_amount(max_gas)
             .with_transaction_expiration_time(self.gas_options.expiration_secs);
-        let sender_account = &mut LocalAccount::new(sender_address, sender_key, seq_num);
-        let transaction =
-            sender_account.sign_with_transaction_builder(transaction_factory.payload(payload));
+        let transaction_builder = transaction_factory.payload(payload);
+        let transaction = match sender_key {
+            Some(sender_key) => {
+                let sender_account = &mut LocalAccount::new(sender_address, sender_key, seq_num);
+                sender_account.sign_with_transaction_builder(transaction_builder)
+            },
+            // Impersonated transactions are executed without authenticators
+            None => SignedTransaction::new_signed_transaction(
+                transaction_builder
+                    .sender(sender_address)
+                    .sequence_number(seq_num)
+                    .build(),
+                TransactionAuthenticator::single_sender(
+                    AccountAuthenticator::NoAccountAuthenticator,
+                ),
+            ),
+        };
         let hash = transaction.committed_hash();
 
-        let (vm_status, txn_output) = sess.execute_transaction(transaction)?;
+        let (vm_status, txn_output) =
+            sess.execute_transaction(transaction, self.impersonate.is_some())?;
 
         let success = match txn_output.status() {
             TransactionStatus::Keep(exec_status) => Some(exec_status.is_success()),
@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    common::types::{CliCommand, CliError, CliResult, CliTypedResult, MovePackageOptions},
+    common::{
+        types::{CliCommand, CliError, CliResult, CliTypedResult, MovePackageOptions},
+        utils::write_to_file,
+    },
     move_tool::fix_bytecode_version,
 };
 use aptos_framework::extended_checks;
@@ -10,14 +13,19 @@ use async_trait::async_trait;
 use clap::{Parser, Subcommand};
 use legacy_move_compiler::compiled_unit::{CompiledUnit, NamedCompiledModule};
 use move_coverage::{
+    cobertura::output_cobertura,
     coverage_map::CoverageMap,
     format_csv_summary, format_human_summary,
+    lcov::output_lcov,
+    line_coverage::PackageLineCoverage,
     source_coverage::{ColorChoice, SourceCoverageBuilder, TextIndicator},
     summary::summarize_inst_cov,
 };
 use move_disassembler::disassembler::Disassembler;
 use move_model::metadata::{CompilerVersion, LanguageVersion};
 use move_package::{compilation::compiled_package::CompiledPackage, BuildConfig, CompilerConfig};
+use serde::Serialize;
+use std::path::PathBuf;
 
 /// Display a coverage summary for all modules in a package
 ///
@@ -166,6 +174,174 @@ impl CliCommand<()> for BytecodeCoverage {
     }
 }
 
+/// Output line and branch coverage of the package in the LCOV format
+#[derive(Debug, Parser)]
+pub struct LcovCoverage {
+    /// File to write the report to
+    ///
+    /// If not provided, the report is printed to stdout
+    #[clap(long, value_parser)]
+    pub output_file: Option<PathBuf>,
+    #[clap(flatten)]
+    pub move_options: MovePackageOptions,
+}
+
+#[async_trait]
+impl CliCommand<()> for LcovCoverage {
+    fn command_name(&self) -> &'static str {
+        "LcovCoverage"
+    }
+
+    async fn execute(self) -> CliTypedResult<()> {
+        let (coverage_map, package) = compile_coverage(self.move_options)?;
+        let coverage = compute_line_coverage(&coverage_map, &package)?;
+        let mut report = vec![];
+        output_lcov(
+            &coverage,
+            package.compiled_package_info.package_name.as_str(),
+            &mut report,
+        )
+        .map_err(|err| CliError::UnexpectedError(format!("Failed to get coverage {}", err)))?;
+        output_report(self.output_file, "LCOV report", &report)
+    }
+}
+
+/// Output line and branch coverage of the package in the Cobertura XML format
+#[derive(Debug, Parser)]
+pub struct CoberturaCoverage {
+    /// File to write the report to
+    ///
+    /// If not provided, the report is printed to stdout
+    #[clap(long, value_parser)]
+    pub output_file: Option<PathBuf>,
+    #[clap(flatten)]
+    pub move_options: MovePackageOptions,
+}
+
+#[async_trait]
+impl CliCommand<()> for CoberturaCoverage {
+    fn command_name(&self) -> &'static str {
+        "CoberturaCoverage"
+    }
+
+    async fn execute(self) -> CliTypedResult<()> {
+        let package_path = self.move_options.get_package_path()?;
+        let (coverage_map, package) = compile_coverage(self.move_options)?;
+        let coverage = compute_line_coverage(&coverage_map, &package)?;
+        let mut report = vec![];
+        output_cobertura(
+            &coverage,
+            package.compiled_package_info.package_name.as_str(),
+            &package_path,
+            &mut report,
+        )
+        .map_err(|err| CliError::UnexpectedError(format!("Failed to get coverage {}", err)))?;
+        output_report(self.output_file, "Cobertura report", &report)
+    }
+}
+
+/// List the conditional branches of the package and how often each side was taken
+///
+/// A side which was never taken usually points at an untested error path.
+#[derive(Debug, Parser)]
+pub struct BranchCoverage {
+    /// Only list branches of the given module
+    #[clap(long = "module")]
+    pub module_name: Option<String>,
+
+    /// Only list branches where one of the sides was never taken
+    #[clap(long)]
+    pub uncovered_only: bool,
+
+    #[clap(flatten)]
+    pub move_options: MovePackageOptions,
+}
+
+/// The coverage of a conditional branch
+#[derive(Debug, Serialize)]
+pub struct BranchSummary {
+    pub module: String,
+    pub function: String,
+    pub source_path: PathBuf,
+    pub line: u32,
+    pub column: u32,
+    /// How often the condition was true, or `None` if the branch was never executed
+    pub true_count: Option<u64>,
+    /// How often the condition was false, or `None` if the branch was never executed
+    pub false_count: Option<u64>,
+}
+
+#[async_trait]
+impl CliCommand<Vec<BranchSummary>> for BranchCoverage {
+    fn command_name(&self) -> &'static str {
+        "BranchCoverage"
+    }
+
+    async fn execute(self) -> CliTypedResult<Vec<BranchSummary>> {
+        let package_path = self.move_options.get_package_path()?;
+        let (coverage_map, package) = compile_coverage(self.move_options)?;
+        let coverage = compute_line_coverage(&coverage_map, &package)?;
+        let mut summaries = vec![];
+        for file in &coverage.files {
+            let source_path = file
+                .source_path
+                .strip_prefix(&package_path)
+                .unwrap_or(&file.source_path);
+            for module in &file.modules {
+                if let Some(module_name) = &self.module_name {
+                    if module.module_name.rsplit("::").next() != Some(module_name.as_str()) {
+                        continue;
+                    }
+                }
+                for function in &module.functions {
+                    for branch in &function.branches {
+                        if self.uncovered_only && branch.is_covered() {
+                            continue;
+                        }
+                        summaries.push(BranchSummary {
+                            module: module.module_name.clone(),
+                            function: function.name.clone(),
+                            source_path: source_path.to_path_buf(),
+                            line: branch.line,
+                            column: branch.column,
+                            true_count: branch.true_count,
+                            false_count: branch.false_count,
+                        });
+                    }
+                }
+            }
+        }
+        Ok(summaries)
+    }
+}
+
+fn compute_line_coverage(
+    coverage_map: &CoverageMap,
+    package: &CompiledPackage,
+) -> CliTypedResult<PackageLineCoverage> {
+    let root_modules: Vec<_> = package
+        .root_modules()
+        .map(|unit| match &unit.unit {
+            CompiledUnit::Module(NamedCompiledModule {
+                module, source_map, ..
+            }) => (module, source_map, unit.source_path.as_path()),
+            _ => unreachable!("Should all be modules"),
+        })
+        .collect();
+    PackageLineCoverage::new(coverage_map, root_modules)
+        .map_err(|err| CliError::UnexpectedError(format!("Failed to get coverage {}", err)))
+}
+
+fn output_report(output_file: Option<PathBuf>, name: &str, report: &[u8]) -> CliTypedResult<()> {
+    match output_file {
+        Some(output_file) => write_to_file(&output_file, name, report),
+        None => {
+            print!("{}", String::from_utf8_lossy(report));
+            Ok(())
+        },
+    }
+}
+
 fn compile_coverage(
     move_options: MovePackageOptions,
 ) -> CliTypedResult<(CoverageMap, CompiledPackage)> {
@@ -215,6 +391,9 @@ pub enum CoveragePackage {
     Summary(SummaryCoverage),
     Source(SourceCoverage),
     Bytecode(BytecodeCoverage),
+    Lcov(LcovCoverage),
+    Cobertura(CoberturaCoverage),
+    Branches(BranchCoverage),
 }
 
 impl CoveragePackage {
@@ -223,6 +402,9 @@ impl CoveragePackage {
             Self::Summary(tool) => tool.execute_serialized_success().await,
             Self::Source(tool) => tool.execute_serialized_success().await,
             Self::Bytecode(tool) => tool.execute_serialized_success().await,
+            Self::Lcov(tool) => tool.execute_serialized_success().await,
+            Self::Cobertura(tool) => tool.execute_serialized_success().await,
+            Self::Branches(tool) => tool.execute_serialized().await,
         }
     }
 }
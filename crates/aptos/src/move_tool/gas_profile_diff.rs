@@ -0,0 +1,133 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::types::{CliCommand, CliTypedResult};
+use aptos_gas_profiling::{GasProfileDiff, GasProfileSummary};
+use async_trait::async_trait;
+use clap::Parser;
+use std::path::PathBuf;
+
+/// Compare the gas profiles of two runs of the same transaction
+///
+/// The gas profiles are the report directories generated with `--profile-gas` (e.g., by
+/// `aptos move replay` or `aptos move test`), which can come from runs before and after a
+/// code change, or under two different gas schedules. The changes per function, instruction
+/// and storage item are printed, and a side-by-side html report is saved.
+#[derive(Debug, Parser)]
+pub struct GasProfileDiffCommand {
+    /// The report directory of the base run
+    #[clap(long, value_parser)]
+    base: PathBuf,
+
+    /// The report directory of the new run
+    #[clap(long, value_parser)]
+    new: PathBuf,
+
+    /// The directory to save the html report of the differences to
+    #[clap(long, value_parser, default_value = "gas-profiling/diff")]
+    output_dir: PathBuf,
+}
+
+#[async_trait]
+impl CliCommand<GasProfileDiff> for GasProfileDiffCommand {
+    fn command_name(&self) -> &'static str {
+        "GasProfileDiff"
+    }
+
+    async fn execute(self) -> CliTypedResult<GasProfileDiff> {
+        let base = GasProfileSummary::load_from_report_dir(&self.base)?;
+        let new = GasProfileSummary::load_from_report_dir(&self.new)?;
+
+        let diff = GasProfileDiff::new(&base, &new)?;
+        diff.generate_html_report(
+            &self.output_dir,
+            format!(
+                "Gas Report Diff - {} vs {}",
+                self.base.display(),
+                self.new.display()
+            ),
+        )?;
+
+        eprint!("{}", diff);
+        eprintln!("Gas report diff saved to {}.", self.output_dir.display());
+
+        Ok(diff)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::move_tool::test_gas_profiling::GasProfilingTestFactory;
+    use aptos_gas_schedule::{AptosGasParameters, InitialGasSchedule};
+    use move_core_types::{account_address::AccountAddress, ident_str, language_storage::ModuleId};
+    use move_unit_test::test_reporter::UnitTestFactory;
+    use move_vm_types::gas::{GasMeter, SimpleInstruction};
+    use std::path::Path;
+
+    /// Profiles a run of the given instructions, and saves its gas report to the given directory
+    fn generate_report(report_dir: &Path, instructions: &[SimpleInstruction]) {
+        let factory = GasProfilingTestFactory::new(AptosGasParameters::initial(), PathBuf::new());
+        let module_id = ModuleId::new(AccountAddress::ONE, ident_str!("m").to_owned());
+        let mut gas_meter = factory.new_gas_meter_for_test(&module_id, "f").unwrap();
+        for instruction in instructions {
+            gas_meter.charge_simple_instr(*instruction).unwrap();
+        }
+        gas_meter
+            .finish()
+            .generate_html_report(report_dir, "Gas Report".to_string())
+            .unwrap();
+    }
+
+    /// Returns the table row of the item with the given name in the rendered diff
+    fn find_row<'a>(output: &'a str, name: &str) -> Vec<&'a str> {
+        output
+            .lines()
+            .map(|line| line.split_whitespace().collect::<Vec<_>>())
+            .find(|row| row.first() == Some(&name))
+            .unwrap_or_else(|| panic!("No row for {} in the diff:\n{}", name, output))
+    }
+
+    #[tokio::test]
+    async fn test_gas_profile_diff() {
+        // Save the reports of two runs, which share only some of their instructions
+        let temp_dir = tempfile::tempdir().unwrap();
+        let base = temp_dir.path().join("base");
+        let new = temp_dir.path().join("new");
+        generate_report(&base, &[SimpleInstruction::Add, SimpleInstruction::LdU64]);
+        generate_report(&new, &[
+            SimpleInstruction::Add,
+            SimpleInstruction::Add,
+            SimpleInstruction::Mul,
+        ]);
+
+        // Compare the runs
+        let output_dir = temp_dir.path().join("diff");
+        let diff = GasProfileDiffCommand {
+            base,
+            new,
+            output_dir: output_dir.clone(),
+        }
+        .execute()
+        .await
+        .unwrap();
+        assert!(!diff.is_unchanged());
+
+        // Verify that the changed instructions are rendered, including the ones only present
+        // in one of the runs
+        let output = diff.to_string();
+        assert!(output.contains("\nInstructions:\n"));
+        let add = find_row(&output, "add");
+        assert_eq!(add[4], "(+100.00%)");
+        let ld_u64 = find_row(&output, "ld_u64");
+        assert_eq!((ld_u64[2], ld_u64[4]), ("0", "(-100.00%)"));
+        let mul = find_row(&output, "mul");
+        assert_eq!(mul[1], "0");
+        assert_eq!(mul.len(), 4);
+        assert!(mul[3].starts_with('+'));
+
+        // Verify that the html report shows the instructions of both runs
+        let html = std::fs::read_to_string(output_dir.join("index.html")).unwrap();
+        assert!(html.contains("ld_u64") && html.contains("mul"));
+    }
+}
@@ -26,6 +26,7 @@ use crate::{
         lint::LintPackage,
         manifest::{Dependency, ManifestNamedAddress, MovePackageManifest, PackageInfo},
         sim::Sim,
+        test_gas_profiling::GasProfilingTestFactory,
     },
     CliCommand, CliResult,
 };
@@ -41,7 +42,9 @@ use aptos_framework::{
     prover::ProverOptions,
     BuildOptions, BuiltPackage,
 };
-use aptos_gas_schedule::{MiscGasParameters, NativeGasParameters};
+use aptos_gas_schedule::{
+    AptosGasParameters, InitialGasSchedule, MiscGasParameters, NativeGasParameters,
+};
 use aptos_move_debugger::aptos_debugger::AptosDebugger;
 use aptos_rest_client::{
     aptos_api_types::{EntryFunctionId, HexEncodedBytes, IdentifierWrapper, MoveModuleId},
@@ -65,7 +68,10 @@ use move_cli::{self, base::test::UnitTestResult};
 use move_command_line_common::{address::NumericalAddress, env::MOVE_HOME};
 use move_core_types::{identifier::Identifier, language_storage::ModuleId, u256::U256};
 use move_model::metadata::{CompilerVersion, LanguageVersion};
-use move_package::{source_package::layout::SourcePackageLayout, BuildConfig, CompilerConfig};
+use move_package::{
+    compilation::package_layout::CompiledPackageLayout,
+    source_package::layout::SourcePackageLayout, BuildConfig, CompilerConfig,
+};
 use move_unit_test::UnitTestingConfig;
 pub use package_hooks::*;
 use rand::SeedableRng;
@@ -84,12 +90,16 @@ pub mod aptos_debug_natives;
 mod bytecode;
 pub mod coverage;
 mod fmt;
+mod gas_profile_diff;
 mod lint;
 mod manifest;
 pub mod package_hooks;
+mod replay_range;
 mod show;
 mod sim;
+mod sim_server;
 pub mod stored_package;
+mod test_gas_profiling;
 
 const HELLO_BLOCKCHAIN_EXAMPLE: &str = include_str!(
     "../../../../aptos-move/move-examples/hello_blockchain/sources/hello_blockchain.move"
@@ -121,6 +131,7 @@ pub enum MoveTool {
     #[clap(alias = "doc")]
     Document(DocumentPackage),
     Download(DownloadPackage),
+    GasProfileDiff(gas_profile_diff::GasProfileDiffCommand),
     Init(InitPackage),
     Lint(LintPackage),
     List(ListPackage),
@@ -136,6 +147,7 @@ pub enum MoveTool {
     VerifyPackage(VerifyPackage),
     View(ViewFunction),
     Replay(Replay),
+    ReplayRange(replay_range::ReplayRange),
     Fmt(Fmt),
     #[clap(subcommand)]
     Sim(Sim),
@@ -163,6 +175,7 @@ impl MoveTool {
             MoveTool::Decompile(tool) => tool.execute_serialized().await,
             MoveTool::Document(tool) => tool.execute_serialized().await,
             MoveTool::Download(tool) => tool.execute_serialized().await,
+            MoveTool::GasProfileDiff(tool) => tool.execute_serialized().await,
             MoveTool::Init(tool) => tool.execute_serialized_success().await,
             MoveTool::List(tool) => tool.execute_serialized().await,
             MoveTool::Prove(tool) => tool.execute_serialized().await,
@@ -175,6 +188,7 @@ impl MoveTool {
             MoveTool::VerifyPackage(tool) => tool.execute_serialized().await,
             MoveTool::View(tool) => tool.execute_serialized().await,
             MoveTool::Replay(tool) => tool.execute_serialized().await,
+            MoveTool::ReplayRange(tool) => tool.execute_serialized().await,
             MoveTool::Fmt(tool) => tool.execute_serialized().await,
             MoveTool::Lint(tool) => tool.execute_serialized().await,
             MoveTool::Sim(tool) => tool.execute().await,
@@ -543,6 +557,39 @@ pub struct TestPackage {
     /// Dump storage state on failure.
     #[clap(long = "dump")]
     pub dump_state: bool,
+
+    /// Profile the gas usage of every test under the Aptos gas schedule
+    ///
+    /// A gas report is saved per test in the `gas-profiling` directory of the build directory.
+    /// Tests are metered like transactions in this mode, so the instruction bound is replaced by
+    /// the gas limits of a transaction.
+    #[clap(long)]
+    pub profile_gas: bool,
+
+    /// Write a JUnit XML report of the test results to the given file
+    ///
+    /// Each module becomes a test suite, and the gas used and abort code of each test are
+    /// included as properties of the test case.
+    #[clap(long, value_parser)]
+    pub junit_report: Option<PathBuf>,
+
+    /// Write the test results to the given file as line-delimited JSON, one test per line
+    #[clap(long, value_parser)]
+    pub json_report: Option<PathBuf>,
+
+    /// Number of generated inputs to run each test with generated parameters with
+    ///
+    /// Parameters of a `#[fuzz]` test which are not assigned in its `#[test]` attribute have their
+    /// values generated, and failing inputs are shrunk to a minimal one.
+    #[clap(long, default_value_t = move_unit_test::fuzz::DEFAULT_FUZZ_RUNS)]
+    pub fuzz_runs: u64,
+
+    /// Seed for generating the inputs of tests with generated parameters
+    ///
+    /// If not given, a random seed is used. The seed is reported with failing inputs, and
+    /// passing it again reproduces them.
+    #[clap(long)]
+    pub fuzz_seed: Option<u64>,
 }
 
 pub(crate) fn fix_bytecode_version(
@@ -561,12 +608,12 @@ pub(crate) fn fix_bytecode_version(
 }
 
 #[async_trait]
-impl CliCommand<&'static str> for TestPackage {
+impl CliCommand<String> for TestPackage {
     fn command_name(&self) -> &'static str {
         "TestPackage"
     }
 
-    async fn execute(self) -> CliTypedResult<&'static str> {
+    async fn execute(self) -> CliTypedResult<String> {
         let known_attributes = extended_checks::get_all_attribute_names();
         let mut config = BuildConfig {
             dev_mode: self.move_options.dev,
@@ -596,38 +643,70 @@ impl CliCommand<&'static str> for TestPackage {
         };
 
         let path = self.move_options.get_package_path()?;
-        let result = move_cli::base::test::run_move_unit_tests(
-            path.as_path(),
-            config.clone(),
-            UnitTestingConfig {
-                filter: self.filter.clone(),
-                report_storage_on_error: self.dump_state,
-                ignore_compile_warnings: self.ignore_compile_warnings,
-                named_address_values: self
-                    .move_options
-                    .named_addresses
-                    .iter()
-                    .map(|(name, addr_wrap)| {
-                        (
-                            name.clone(),
-                            NumericalAddress::from_account_address(addr_wrap.account_address),
-                        )
-                    })
-                    .collect(),
-                ..UnitTestingConfig::default()
-            },
-            // TODO(Gas): we may want to switch to non-zero costs in the future
-            aptos_debug_natives::aptos_debug_natives(
-                NativeGasParameters::zeros(),
-                MiscGasParameters::zeros(),
-            ),
-            aptos_test_feature_flags_genesis(),
-            None,
-            None,
-            self.compute_coverage,
-            &mut std::io::stdout(),
-        )
-        .map_err(|err| CliError::UnexpectedError(format!("Failed to run tests: {:#}", err)))?;
+        let unit_test_config = UnitTestingConfig {
+            filter: self.filter.clone(),
+            report_storage_on_error: self.dump_state,
+            ignore_compile_warnings: self.ignore_compile_warnings,
+            named_address_values: self
+                .move_options
+                .named_addresses
+                .iter()
+                .map(|(name, addr_wrap)| {
+                    (
+                        name.clone(),
+                        NumericalAddress::from_account_address(addr_wrap.account_address),
+                    )
+                })
+                .collect(),
+            junit_report: self.junit_report.clone(),
+            json_report: self.json_report.clone(),
+            fuzz_runs: self.fuzz_runs,
+            fuzz_seed: self.fuzz_seed,
+            ..UnitTestingConfig::default()
+        };
+        let report_dir = self.profile_gas.then(|| {
+            self.move_options
+                .output_dir
+                .clone()
+                .unwrap_or_else(|| path.clone())
+                .join(CompiledPackageLayout::Root.path())
+                .join("gas-profiling")
+        });
+        let result = if let Some(report_dir) = &report_dir {
+            // Natives are charged with the real gas schedule, so their costs show up in the reports
+            let gas_params = AptosGasParameters::initial();
+            move_cli::base::test::run_move_unit_tests_with_factory(
+                path.as_path(),
+                config.clone(),
+                unit_test_config,
+                aptos_debug_natives::aptos_debug_natives(
+                    gas_params.natives.clone(),
+                    gas_params.vm.misc.clone(),
+                ),
+                aptos_test_feature_flags_genesis(),
+                self.compute_coverage,
+                &mut std::io::stdout(),
+                GasProfilingTestFactory::new(gas_params, report_dir.clone()),
+            )
+        } else {
+            move_cli::base::test::run_move_unit_tests(
+                path.as_path(),
+                config.clone(),
+                unit_test_config,
+                // TODO(Gas): we may want to switch to non-zero costs in the future
+                aptos_debug_natives::aptos_debug_natives(
+                    NativeGasParameters::zeros(),
+                    MiscGasParameters::zeros(),
+                ),
+                aptos_test_feature_flags_genesis(),
+                None,
+                None,
+                self.compute_coverage,
+                &mut std::io::stdout(),
+            )
+        };
+        let result = result
+            .map_err(|err| CliError::UnexpectedError(format!("Failed to run tests: {:#}", err)))?;
 
         // Print coverage summary if --coverage is set
         if self.compute_coverage {
@@ -644,9 +723,13 @@ impl CliCommand<&'static str> for TestPackage {
             println!("Please use `aptos move coverage -h` for more detailed source or bytecode test coverage of this package");
         }
 
-        match result {
-            UnitTestResult::Success => Ok("Success"),
-            UnitTestResult::Failure => Err(CliError::MoveTestError),
+        match (result, report_dir) {
+            (UnitTestResult::Success, None) => Ok("Success".to_string()),
+            (UnitTestResult::Success, Some(report_dir)) => Ok(format!(
+                "Success. Gas reports saved to {}",
+                report_dir.display()
+            )),
+            (UnitTestResult::Failure, _) => Err(CliError::MoveTestError),
         }
     }
 }
@@ -2167,6 +2250,13 @@ pub struct Simulate {
 
     #[clap(long)]
     local: bool,
+
+    /// If this option is set, save a structured execution trace of the transaction (i.e., the
+    /// call tree with arguments, the events emitted and the abort location) as JSON.
+    ///
+    /// The trace is recorded by simulating the transaction locally, so this implies `--local`.
+    #[clap(long)]
+    trace: bool,
 }
 
 #[async_trait]
@@ -2178,7 +2268,9 @@ impl CliCommand<TransactionSummary> for Simulate {
     async fn execute(self) -> CliTypedResult<TransactionSummary> {
         let payload = TransactionPayload::EntryFunction(self.entry_function_args.try_into()?);
 
-        if self.local {
+        if self.trace {
+            self.txn_options.trace_locally(payload).await
+        } else if self.local {
             self.txn_options.simulate_locally(payload).await
         } else {
             let mut rng = rand::rngs::StdRng::from_entropy();
@@ -0,0 +1,425 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    common::types::{CliCommand, CliError, CliTypedResult, MovePackageOptions},
+    move_tool::{IncludedArtifactsArgs, ReplayNetworkSelection},
+};
+use aptos_api_types::TransactionOnChainData;
+use aptos_crypto::{hash::CryptoHash, HashValue};
+use aptos_framework::BuiltPackage;
+use aptos_move_debugger::aptos_debugger::AptosDebugger;
+use aptos_replay_benchmark::overrides::OverrideConfig;
+use aptos_rest_client::Client;
+use aptos_transaction_simulation::DeltaStateStore;
+use aptos_types::{
+    proof::accumulator::InMemoryEventAccumulator,
+    state_store::{
+        state_key::StateKey, state_slot::StateSlot, state_storage_usage::StateStorageUsage,
+        state_value::StateValue, StateView, StateViewId, StateViewResult, TStateView,
+    },
+    transaction::{
+        AuxiliaryInfo, SignedTransaction, Transaction, TransactionInfo, TransactionOutput,
+        TransactionStatus, Version,
+    },
+    write_set::WriteSet,
+};
+use aptos_vm::{data_cache::AsMoveResolver, AptosVM};
+use aptos_vm_environment::environment::AptosEnvironment;
+use aptos_vm_logging::log_schema::AdapterLogSchema;
+use aptos_vm_types::module_and_script_storage::AsAptosCodeStorage;
+use async_trait::async_trait;
+use clap::Parser;
+use move_core_types::language_storage::ModuleId;
+use serde::Serialize;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Mutex,
+};
+
+/// Replay a range of committed transactions with a locally compiled package
+///
+/// The modules of the package replace the on-chain modules with the same ids (or are added, if
+/// they do not exist yet), and the package metadata is updated accordingly. Every user
+/// transaction in the range is then re-executed and compared against its committed output, and
+/// all transactions whose status, gas used, events or write set changed are reported.
+///
+/// Each transaction is replayed independently on top of its own committed pre-state, so the
+/// changes made by earlier transactions under the overridden code are not carried forward.
+#[derive(Debug, Parser)]
+pub struct ReplayRange {
+    /// The network to replay on.
+    ///
+    /// Possible values:
+    ///     mainnet, testnet, <REST_ENDPOINT_URL>
+    #[clap(long)]
+    network: ReplayNetworkSelection,
+
+    /// The first version of the range to replay.
+    #[clap(long)]
+    begin_version: Version,
+
+    /// The number of transactions in the range to replay.
+    #[clap(long, default_value_t = 100)]
+    limit: u64,
+
+    /// If set, only report the transactions touching the given module (e.g., `0x1::coin`), i.e.,
+    /// the transactions with the module in the read or write set of their committed or replayed
+    /// execution.
+    #[clap(long)]
+    module: Option<ModuleId>,
+
+    #[clap(flatten)]
+    move_options: MovePackageOptions,
+
+    #[clap(flatten)]
+    included_artifacts_args: IncludedArtifactsArgs,
+
+    /// Key to use for ratelimiting purposes with the node API. This value will be used
+    /// as `Authorization: Bearer <key>`
+    #[clap(long)]
+    node_api_key: Option<String>,
+}
+
+/// A transaction whose replayed output differs from its committed output.
+#[derive(Debug, Serialize)]
+pub struct ChangedTransaction {
+    pub version: Version,
+    pub hash: HashValue,
+    pub expected_status: String,
+    pub actual_status: String,
+    pub expected_gas_used: u64,
+    pub actual_gas_used: u64,
+    pub events_changed: bool,
+    pub write_set_changed: bool,
+}
+
+/// The result of replaying a range of transactions with local code overrides.
+#[derive(Debug, Serialize)]
+pub struct ReplayRangeSummary {
+    /// The number of transactions that were replayed.
+    pub replayed: u64,
+    /// The number of transactions that were skipped (non-user transactions, or transactions
+    /// not touching the module of the filter).
+    pub skipped: u64,
+    pub changed: Vec<ChangedTransaction>,
+}
+
+/// A state view that records the keys of all state items read through it.
+struct ReadRecordingStateView<S> {
+    state_view: S,
+    reads: Mutex<HashSet<StateKey>>,
+}
+
+impl<S: StateView> TStateView for ReadRecordingStateView<S> {
+    type Key = StateKey;
+
+    fn id(&self) -> StateViewId {
+        self.state_view.id()
+    }
+
+    fn get_state_slot(&self, state_key: &StateKey) -> StateViewResult<StateSlot> {
+        self.reads.lock().unwrap().insert(state_key.clone());
+        self.state_view.get_state_slot(state_key)
+    }
+
+    fn get_usage(&self) -> StateViewResult<StateStorageUsage> {
+        self.state_view.get_usage()
+    }
+
+    fn next_version(&self) -> Version {
+        self.state_view.next_version()
+    }
+}
+
+/// Returns true iff the module is in the given read or write set.
+fn touches_module(module: &ModuleId, reads: &HashSet<StateKey>, write_set: &WriteSet) -> bool {
+    let state_key = StateKey::module_id(module);
+    reads.contains(&state_key) || write_set.get_write_op(&state_key).is_some()
+}
+
+impl ReplayRange {
+    /// Fetches the committed transactions in the range, along with their outputs.
+    async fn get_committed_transactions(
+        client: &Client,
+        begin_version: Version,
+        limit: u64,
+    ) -> CliTypedResult<Vec<TransactionOnChainData>> {
+        let mut txns = Vec::with_capacity(limit as usize);
+        while (txns.len() as u64) < limit {
+            let remaining = (limit - txns.len() as u64).min(u16::MAX as u64) as u16;
+            let batch = client
+                .get_transactions_bcs(Some(begin_version + txns.len() as u64), Some(remaining))
+                .await?
+                .into_inner();
+            if batch.is_empty() {
+                break;
+            }
+            txns.extend(batch);
+        }
+        Ok(txns)
+    }
+
+    /// Executes the transaction on top of its committed pre-state, with the given state
+    /// overrides applied. Returns the output, and the keys of all state items read.
+    fn execute_with_overrides(
+        debugger: &AptosDebugger,
+        version: Version,
+        txn: &SignedTransaction,
+        state_override: HashMap<StateKey, StateValue>,
+    ) -> CliTypedResult<(TransactionOutput, HashSet<StateKey>)> {
+        let state_view = ReadRecordingStateView {
+            state_view: DeltaStateStore::new_with_base_and_state_values(
+                debugger.state_view_at_version(version),
+                state_override,
+            ),
+            reads: Mutex::new(HashSet::new()),
+        };
+
+        let env = AptosEnvironment::new(&state_view);
+        let vm = AptosVM::new(&env, &state_view);
+        let log_context = AdapterLogSchema::new(state_view.id(), 0);
+        let resolver = state_view.as_move_resolver();
+        let code_storage = state_view.as_aptos_code_storage(&env);
+
+        let (_vm_status, vm_output) = vm.execute_user_transaction(
+            &resolver,
+            &code_storage,
+            txn,
+            &log_context,
+            &AuxiliaryInfo::default(),
+        );
+        let output = vm_output
+            .try_materialize_into_transaction_output(&resolver)
+            .map_err(|err| {
+                CliError::UnexpectedError(format!(
+                    "Failed to materialize into transaction output: {}",
+                    err
+                ))
+            })?;
+        Ok((output, state_view.reads.into_inner().unwrap()))
+    }
+
+    /// Compares the replayed output against the committed transaction info.
+    fn compare(
+        version: Version,
+        hash: HashValue,
+        output: &TransactionOutput,
+        txn_info: &TransactionInfo,
+    ) -> Option<ChangedTransaction> {
+        let expected_status: TransactionStatus = txn_info.status().clone().into();
+
+        let event_hashes = output
+            .events()
+            .iter()
+            .map(CryptoHash::hash)
+            .collect::<Vec<_>>();
+        let events_changed = InMemoryEventAccumulator::from_leaves(&event_hashes).root_hash
+            != txn_info.event_root_hash();
+        let write_set_changed =
+            CryptoHash::hash(output.write_set()) != txn_info.state_change_hash();
+
+        if output.status() == &expected_status
+            && output.gas_used() == txn_info.gas_used()
+            && !events_changed
+            && !write_set_changed
+        {
+            return None;
+        }
+
+        Some(ChangedTransaction {
+            version,
+            hash,
+            expected_status: format!("{:?}", expected_status),
+            actual_status: format!("{:?}", output.status()),
+            expected_gas_used: txn_info.gas_used(),
+            actual_gas_used: output.gas_used(),
+            events_changed,
+            write_set_changed,
+        })
+    }
+}
+
+#[async_trait]
+impl CliCommand<ReplayRangeSummary> for ReplayRange {
+    fn command_name(&self) -> &'static str {
+        "ReplayRange"
+    }
+
+    async fn execute(self) -> CliTypedResult<ReplayRangeSummary> {
+        // Compile the package providing the code overrides
+        let build_options = self
+            .included_artifacts_args
+            .included_artifacts
+            .build_options(&self.move_options)?;
+        let package =
+            BuiltPackage::build(self.move_options.get_package_path()?, build_options.clone())?;
+        let override_config = OverrideConfig::with_built_packages(vec![package], build_options);
+
+        // Build the client
+        let mut client = Client::builder(self.network.to_base_url()?);
+        if let Some(api_key) = &self.node_api_key {
+            client = client.api_key(api_key)?;
+        }
+        let client = client.build();
+        let debugger = AptosDebugger::rest_client(client.clone())?;
+
+        let txns =
+            Self::get_committed_transactions(&client, self.begin_version, self.limit).await?;
+
+        let mut summary = ReplayRangeSummary {
+            replayed: 0,
+            skipped: 0,
+            changed: vec![],
+        };
+        for txn_data in txns {
+            let version = txn_data.version;
+            let txn = match txn_data.transaction {
+                Transaction::UserTransaction(txn) => txn,
+                _ => {
+                    summary.skipped += 1;
+                    continue;
+                },
+            };
+
+            eprintln!("Replaying transaction {}...", version);
+            let state_override =
+                override_config.get_state_override(&debugger.state_view_at_version(version));
+            let (output, reads) =
+                Self::execute_with_overrides(&debugger, version, &txn, state_override)?;
+
+            // The read set of the committed execution is not stored on chain, so it is obtained
+            // by replaying the transaction without overrides, if needed
+            if let Some(module) = &self.module {
+                let touched = touches_module(module, &reads, output.write_set())
+                    || touches_module(module, &HashSet::new(), &txn_data.changes)
+                    || {
+                        let (committed_output, committed_reads) =
+                            Self::execute_with_overrides(&debugger, version, &txn, HashMap::new())?;
+                        touches_module(module, &committed_reads, committed_output.write_set())
+                    };
+                if !touched {
+                    summary.skipped += 1;
+                    continue;
+                }
+            }
+
+            summary.replayed += 1;
+            if let Some(changed) =
+                Self::compare(version, txn.committed_hash(), &output, &txn_data.info)
+            {
+                summary.changed.push(changed);
+            }
+        }
+
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_types::{
+        transaction::{ExecutionStatus, TransactionAuxiliaryData},
+        write_set::WriteOp,
+    };
+    use move_core_types::{account_address::AccountAddress, ident_str};
+
+    /// Creates a successful transaction output with the given write set and gas used
+    fn create_output(write_set: WriteSet, gas_used: u64) -> TransactionOutput {
+        TransactionOutput::new(
+            write_set,
+            vec![],
+            gas_used,
+            TransactionStatus::Keep(ExecutionStatus::Success),
+            TransactionAuxiliaryData::default(),
+        )
+    }
+
+    /// Creates the committed info of a successful transaction with the given output
+    fn create_txn_info(output: &TransactionOutput) -> TransactionInfo {
+        TransactionInfo::new(
+            HashValue::zero(),
+            CryptoHash::hash(output.write_set()),
+            InMemoryEventAccumulator::from_leaves(&[]).root_hash,
+            None,
+            output.gas_used(),
+            ExecutionStatus::Success,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_compare() {
+        let committed_output = create_output(WriteSet::default(), 10);
+        let txn_info = create_txn_info(&committed_output);
+
+        // The same output is not reported
+        assert!(ReplayRange::compare(0, HashValue::zero(), &committed_output, &txn_info).is_none());
+
+        // A different gas usage is reported
+        let changed = ReplayRange::compare(
+            0,
+            HashValue::zero(),
+            &create_output(WriteSet::default(), 20),
+            &txn_info,
+        )
+        .unwrap();
+        assert_eq!(changed.expected_gas_used, 10);
+        assert_eq!(changed.actual_gas_used, 20);
+        assert!(!changed.events_changed);
+        assert!(!changed.write_set_changed);
+
+        // A different write set is reported
+        let state_key = StateKey::module(&AccountAddress::ONE, ident_str!("coin"));
+        let write_set = WriteSet::new(vec![(
+            state_key,
+            WriteOp::legacy_modification(vec![1].into()),
+        )])
+        .unwrap();
+        let changed = ReplayRange::compare(
+            0,
+            HashValue::zero(),
+            &create_output(write_set, 10),
+            &txn_info,
+        )
+        .unwrap();
+        assert_eq!(changed.expected_status, changed.actual_status);
+        assert!(changed.write_set_changed);
+    }
+
+    #[test]
+    fn test_touches_module() {
+        let coin = ModuleId::new(AccountAddress::ONE, ident_str!("coin").to_owned());
+        let account = ModuleId::new(AccountAddress::ONE, ident_str!("account").to_owned());
+        let coin_key = StateKey::module_id(&coin);
+
+        // Modules are matched in the read set
+        let reads = HashSet::from([coin_key.clone()]);
+        assert!(touches_module(&coin, &reads, &WriteSet::default()));
+        assert!(!touches_module(&account, &reads, &WriteSet::default()));
+
+        // Modules are matched in the write set (e.g., when they are published)
+        let write_set = WriteSet::new(vec![(
+            coin_key,
+            WriteOp::legacy_modification(vec![1].into()),
+        )])
+        .unwrap();
+        assert!(touches_module(&coin, &HashSet::new(), &write_set));
+        assert!(!touches_module(&account, &HashSet::new(), &write_set));
+
+        // Resources are not modules, even if they are defined in the module
+        let resource_key = StateKey::resource(
+            &AccountAddress::ONE,
+            &"0x1::coin::CoinInfo<0x1::aptos_coin::AptosCoin>"
+                .parse()
+                .unwrap(),
+        )
+        .unwrap();
+        assert!(!touches_module(
+            &coin,
+            &HashSet::from([resource_key]),
+            &WriteSet::default()
+        ));
+    }
+}
@@ -2,15 +2,36 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    common::types::{CliCommand, CliResult, CliTypedResult},
-    move_tool::ReplayNetworkSelection,
+    common::{
+        types::{
+            CliCommand, CliError, CliResult, CliTypedResult, TransactionOptions, TransactionSummary,
+        },
+        utils::write_to_file,
+    },
+    move_tool::{self, sim_server::run_session_server, ReplayNetworkSelection},
 };
 use aptos_rest_client::Client;
-use aptos_transaction_simulation_session::Session;
+use aptos_transaction_simulation_session::{Session, SessionDiff, SessionPoint};
+use aptos_types::transaction::TransactionStatus;
 use async_trait::async_trait;
 use clap::{Parser, Subcommand};
-use move_core_types::account_address::AccountAddress;
-use std::path::PathBuf;
+use move_core_types::{account_address::AccountAddress, language_storage::StructTag};
+use serde::Serialize;
+use std::{
+    net::{IpAddr, SocketAddr},
+    path::PathBuf,
+};
+
+/// Ensures that a session is specified for a command that can only operate on a session
+fn check_session(command_name: &str, txn_options: &TransactionOptions) -> CliTypedResult<()> {
+    if txn_options.session.is_none() {
+        return Err(CliError::CommandArgumentError(format!(
+            "`--session` must be specified for `aptos move sim {}`",
+            command_name
+        )));
+    }
+    Ok(())
+}
 
 /// Initializes a new simulation session
 #[derive(Debug, Parser)]
@@ -99,6 +120,442 @@ impl CliCommand<()> for Fund {
     }
 }
 
+/// Publishes a Move package in a simulation session
+#[derive(Parser)]
+pub struct Publish {
+    #[clap(flatten)]
+    publish_package: move_tool::PublishPackage,
+}
+
+#[async_trait]
+impl CliCommand<TransactionSummary> for Publish {
+    fn command_name(&self) -> &'static str {
+        "publish"
+    }
+
+    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+        check_session(self.command_name(), &self.publish_package.txn_options)?;
+        if self.publish_package.chunked_publish_option.chunked_publish {
+            return Err(CliError::CommandArgumentError(
+                "`--chunked-publish` is not supported in simulation sessions".to_string(),
+            ));
+        }
+
+        self.publish_package.execute().await
+    }
+}
+
+/// Runs a Move entry function in a simulation session
+#[derive(Parser)]
+pub struct Run {
+    #[clap(flatten)]
+    run_function: move_tool::RunFunction,
+}
+
+#[async_trait]
+impl CliCommand<TransactionSummary> for Run {
+    fn command_name(&self) -> &'static str {
+        "run"
+    }
+
+    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+        check_session(self.command_name(), &self.run_function.txn_options)?;
+        self.run_function.execute().await
+    }
+}
+
+/// Runs a Move script in a simulation session
+#[derive(Parser)]
+pub struct RunScript {
+    #[clap(flatten)]
+    run_script: move_tool::RunScript,
+}
+
+#[async_trait]
+impl CliCommand<TransactionSummary> for RunScript {
+    fn command_name(&self) -> &'static str {
+        "run-script"
+    }
+
+    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+        check_session(self.command_name(), &self.run_script.txn_options)?;
+        self.run_script.execute().await
+    }
+}
+
+/// Runs a view function against the state of a simulation session
+#[derive(Parser)]
+pub struct View {
+    #[clap(flatten)]
+    view_function: move_tool::ViewFunction,
+}
+
+#[async_trait]
+impl CliCommand<Vec<serde_json::Value>> for View {
+    fn command_name(&self) -> &'static str {
+        "view"
+    }
+
+    async fn execute(self) -> CliTypedResult<Vec<serde_json::Value>> {
+        check_session(self.command_name(), &self.view_function.txn_options)?;
+        self.view_function.execute().await
+    }
+}
+
+/// Reads a resource from the state of a simulation session
+#[derive(Debug, Parser)]
+pub struct ViewResource {
+    /// Path to a stored session
+    #[clap(long)]
+    session: PathBuf,
+
+    /// Account that stores the resource, can be an address or a CLI profile name
+    #[clap(long, value_parser = crate::common::types::load_account_arg)]
+    account: AccountAddress,
+
+    /// Type of the resource, e.g. `0x1::account::Account`
+    #[clap(long)]
+    resource_type: StructTag,
+}
+
+#[async_trait]
+impl CliCommand<Option<serde_json::Value>> for ViewResource {
+    fn command_name(&self) -> &'static str {
+        "view-resource"
+    }
+
+    async fn execute(self) -> CliTypedResult<Option<serde_json::Value>> {
+        let mut session = Session::load(&self.session)?;
+
+        Ok(session.view_resource(self.account, &self.resource_type)?)
+    }
+}
+
+/// Saves the current state of a session as a named checkpoint
+#[derive(Debug, Parser)]
+pub struct Checkpoint {
+    /// Path to a stored session
+    #[clap(long)]
+    session: PathBuf,
+
+    /// Name of the checkpoint, which may only contain alphanumeric characters, dashes and
+    /// underscores
+    #[clap(long)]
+    name: String,
+}
+
+#[async_trait]
+impl CliCommand<()> for Checkpoint {
+    fn command_name(&self) -> &'static str {
+        "checkpoint"
+    }
+
+    async fn execute(self) -> CliTypedResult<()> {
+        let mut session = Session::load(&self.session)?;
+
+        session.checkpoint(&self.name)?;
+
+        Ok(())
+    }
+}
+
+/// Rewinds a session to a previously saved checkpoint
+#[derive(Debug, Parser)]
+pub struct Rewind {
+    /// Path to a stored session
+    #[clap(long)]
+    session: PathBuf,
+
+    /// Name of the checkpoint to rewind to
+    #[clap(long)]
+    checkpoint: String,
+}
+
+#[async_trait]
+impl CliCommand<()> for Rewind {
+    fn command_name(&self) -> &'static str {
+        "rewind"
+    }
+
+    async fn execute(self) -> CliTypedResult<()> {
+        let mut session = Session::load(&self.session)?;
+
+        session.rewind(&self.checkpoint)?;
+
+        Ok(())
+    }
+}
+
+/// Forks a session into a new session that shares the same base state
+#[derive(Debug, Parser)]
+pub struct Fork {
+    /// Path to a stored session
+    #[clap(long)]
+    session: PathBuf,
+
+    /// Path to the directory where the new session data will be stored.
+    #[clap(long)]
+    path: PathBuf,
+
+    /// If specified, the new session starts from this checkpoint instead of
+    /// the current state of the session.
+    #[clap(long)]
+    checkpoint: Option<String>,
+}
+
+#[async_trait]
+impl CliCommand<()> for Fork {
+    fn command_name(&self) -> &'static str {
+        "fork"
+    }
+
+    async fn execute(self) -> CliTypedResult<()> {
+        let session = Session::load(&self.session)?;
+
+        session.fork(&self.path, self.checkpoint.as_deref())?;
+
+        Ok(())
+    }
+}
+
+/// Statistics on the remote state cache of a session
+#[derive(Debug, Serialize)]
+pub struct RemoteCacheStatsSummary {
+    /// The number of state lookups served from the cache
+    hits: u64,
+    /// The number of state lookups fetched from the remote network
+    misses: u64,
+    /// The fraction of state lookups served from the cache
+    hit_rate: f64,
+}
+
+/// Shows the statistics of the remote state cache of a session
+///
+/// Sessions forked from a remote network cache all fetched state in the session directory
+/// (`remote_state_cache.json`). The cache file can be copied to other sessions forked from
+/// the same network version to avoid fetching the state again.
+#[derive(Debug, Parser)]
+pub struct RemoteCacheStats {
+    /// Path to a stored session
+    #[clap(long)]
+    session: PathBuf,
+}
+
+#[async_trait]
+impl CliCommand<RemoteCacheStatsSummary> for RemoteCacheStats {
+    fn command_name(&self) -> &'static str {
+        "remote-cache-stats"
+    }
+
+    async fn execute(self) -> CliTypedResult<RemoteCacheStatsSummary> {
+        let session = Session::load(&self.session)?;
+
+        let stats = session.remote_cache_stats().ok_or_else(|| {
+            CliError::CommandArgumentError(
+                "The session was not forked from a remote network, so it has no remote state cache"
+                    .to_string(),
+            )
+        })?;
+        Ok(RemoteCacheStatsSummary {
+            hits: stats.hits,
+            misses: stats.misses,
+            hit_rate: stats.hit_rate(),
+        })
+    }
+}
+
+/// Sets the on-chain time of a session
+///
+/// The time cannot go backwards. No block is produced, so use `aptos move sim block-prologue`
+/// afterwards to trigger an epoch change if the epoch interval has passed.
+#[derive(Debug, Parser)]
+pub struct SetTime {
+    /// Path to a stored session
+    #[clap(long)]
+    session: PathBuf,
+
+    /// The new on-chain time, in microseconds since the Unix epoch
+    #[clap(long)]
+    timestamp_usecs: u64,
+}
+
+#[async_trait]
+impl CliCommand<()> for SetTime {
+    fn command_name(&self) -> &'static str {
+        "set-time"
+    }
+
+    async fn execute(self) -> CliTypedResult<()> {
+        let mut session = Session::load(&self.session)?;
+
+        session.set_time(self.timestamp_usecs)?;
+
+        Ok(())
+    }
+}
+
+/// Advances the on-chain time of a session
+///
+/// No block is produced, so use `aptos move sim block-prologue` afterwards to trigger an epoch
+/// change if the epoch interval has passed.
+#[derive(Debug, Parser)]
+pub struct AdvanceTime {
+    /// Path to a stored session
+    #[clap(long)]
+    session: PathBuf,
+
+    /// The number of seconds to advance the on-chain time by
+    #[clap(long)]
+    seconds: u64,
+}
+
+#[async_trait]
+impl CliCommand<()> for AdvanceTime {
+    fn command_name(&self) -> &'static str {
+        "advance-time"
+    }
+
+    async fn execute(self) -> CliTypedResult<()> {
+        let mut session = Session::load(&self.session)?;
+
+        let duration_usecs = self.seconds.checked_mul(1_000_000).ok_or_else(|| {
+            CliError::CommandArgumentError("The number of seconds is too large".to_string())
+        })?;
+        session.advance_time(duration_usecs)?;
+
+        Ok(())
+    }
+}
+
+/// Executes a block prologue at the current on-chain time of a session
+///
+/// The block is a NIL block proposed by the VM. As on a real network, a new epoch is started
+/// if the epoch interval has passed since the last reconfiguration.
+#[derive(Debug, Parser)]
+pub struct BlockPrologue {
+    /// Path to a stored session
+    #[clap(long)]
+    session: PathBuf,
+}
+
+#[async_trait]
+impl CliCommand<TransactionStatus> for BlockPrologue {
+    fn command_name(&self) -> &'static str {
+        "block-prologue"
+    }
+
+    async fn execute(self) -> CliTypedResult<TransactionStatus> {
+        let mut session = Session::load(&self.session)?;
+
+        Ok(session.execute_block_prologue()?)
+    }
+}
+
+/// Forces a reconfiguration in a session, starting a new epoch
+///
+/// There can be at most one reconfiguration per on-chain timestamp, so the time may need to be
+/// advanced first.
+#[derive(Debug, Parser)]
+pub struct Reconfigure {
+    /// Path to a stored session
+    #[clap(long)]
+    session: PathBuf,
+}
+
+#[async_trait]
+impl CliCommand<()> for Reconfigure {
+    fn command_name(&self) -> &'static str {
+        "reconfigure"
+    }
+
+    async fn execute(self) -> CliTypedResult<()> {
+        let mut session = Session::load(&self.session)?;
+
+        session.reconfigure()?;
+
+        Ok(())
+    }
+}
+
+/// Shows the state changes between two points of a session
+///
+/// Points can be `base` (the state the session was created from), `current`, the name of a
+/// checkpoint, or an operation number (the state right after that operation). The diff includes
+/// decoded resource changes, table item changes, published modules, APT balance changes per
+/// account, and the events emitted in between.
+#[derive(Debug, Parser)]
+pub struct Diff {
+    /// Path to a stored session
+    #[clap(long)]
+    session: PathBuf,
+
+    /// The point to compare from: `base`, `current`, a checkpoint name or an operation number
+    #[clap(long, default_value = "base")]
+    from: SessionPoint,
+
+    /// The point to compare to: `base`, `current`, a checkpoint name or an operation number
+    #[clap(long, default_value = "current")]
+    to: SessionPoint,
+
+    /// If specified, also writes a human-readable report of the diff to this file
+    #[clap(long)]
+    report_file: Option<PathBuf>,
+}
+
+#[async_trait]
+impl CliCommand<SessionDiff> for Diff {
+    fn command_name(&self) -> &'static str {
+        "diff"
+    }
+
+    async fn execute(self) -> CliTypedResult<SessionDiff> {
+        let session = Session::load(&self.session)?;
+
+        let diff = session.diff(&self.from, &self.to)?;
+        if let Some(report_file) = &self.report_file {
+            write_to_file(report_file, "diff report", diff.to_string().as_bytes())?;
+        }
+
+        Ok(diff)
+    }
+}
+
+/// Serves a session as a local REST API
+///
+/// The server implements the subset of the node REST API used by wallets and dApp
+/// frontends (accounts, resources, view functions, transaction submission, simulation and
+/// gas estimation), so they can be pointed at the session with `http://<bind>:<port>/v1`.
+/// Submitted transactions are executed and committed to the session.
+#[derive(Debug, Parser)]
+pub struct Serve {
+    /// Path to a stored session
+    #[clap(long)]
+    session: PathBuf,
+
+    /// The address to bind the server to
+    #[clap(long, default_value = "127.0.0.1")]
+    bind: IpAddr,
+
+    /// The port to serve the REST API on
+    #[clap(long, default_value_t = 8080)]
+    port: u16,
+}
+
+#[async_trait]
+impl CliCommand<()> for Serve {
+    fn command_name(&self) -> &'static str {
+        "serve"
+    }
+
+    async fn execute(self) -> CliTypedResult<()> {
+        let session = Session::load(&self.session)?;
+
+        run_session_server(session, SocketAddr::new(self.bind, self.port)).await?;
+
+        Ok(())
+    }
+}
+
 /// BETA: Commands for interacting with a local simulation session
 ///
 /// BETA: Subject to change
@@ -106,6 +563,21 @@ impl CliCommand<()> for Fund {
 pub enum Sim {
     Init(Init),
     Fund(Fund),
+    Publish(Publish),
+    Run(Run),
+    RunScript(RunScript),
+    View(View),
+    ViewResource(ViewResource),
+    Checkpoint(Checkpoint),
+    Rewind(Rewind),
+    Fork(Fork),
+    RemoteCacheStats(RemoteCacheStats),
+    SetTime(SetTime),
+    AdvanceTime(AdvanceTime),
+    BlockPrologue(BlockPrologue),
+    Reconfigure(Reconfigure),
+    Diff(Diff),
+    Serve(Serve),
 }
 
 impl Sim {
@@ -113,6 +585,21 @@ impl Sim {
         match self {
             Sim::Init(init) => init.execute_serialized_success().await,
             Sim::Fund(fund) => fund.execute_serialized_success().await,
+            Sim::Publish(publish) => publish.execute_serialized().await,
+            Sim::Run(run) => run.execute_serialized().await,
+            Sim::RunScript(run_script) => run_script.execute_serialized().await,
+            Sim::View(view) => view.execute_serialized().await,
+            Sim::ViewResource(view_resource) => view_resource.execute_serialized().await,
+            Sim::Checkpoint(checkpoint) => checkpoint.execute_serialized_success().await,
+            Sim::Rewind(rewind) => rewind.execute_serialized_success().await,
+            Sim::Fork(fork) => fork.execute_serialized_success().await,
+            Sim::RemoteCacheStats(stats) => stats.execute_serialized().await,
+            Sim::SetTime(set_time) => set_time.execute_serialized_success().await,
+            Sim::AdvanceTime(advance_time) => advance_time.execute_serialized_success().await,
+            Sim::BlockPrologue(block_prologue) => block_prologue.execute_serialized().await,
+            Sim::Reconfigure(reconfigure) => reconfigure.execute_serialized_success().await,
+            Sim::Diff(diff) => diff.execute_serialized().await,
+            Sim::Serve(serve) => serve.execute_serialized_success().await,
         }
     }
 }
@@ -0,0 +1,614 @@
+// Copyright (c) Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! A REST API server backed by a simulation session.
+//!
+//! The server implements the subset of the node REST API used by wallets and dApp frontends,
+//! so they can be pointed at a (possibly forked) session state:
+//! - `GET /v1`
+//! - `GET /v1/accounts/:address`
+//! - `GET /v1/accounts/:address/resource/:resource_type`
+//! - `POST /v1/view`
+//! - `POST /v1/transactions`
+//! - `GET /v1/transactions/by_hash/:hash` (and `wait_by_hash`)
+//! - `POST /v1/transactions/simulate`
+//! - `GET /v1/estimate_gas_price`
+//!
+//! Submitted transactions are executed immediately, and committed to the session (as regular
+//! session operations). Transactions are only kept in memory, so they can only be fetched by
+//! hash while the server is running.
+
+use anyhow::Context;
+use aptos_api_types::{
+    AccountData, Address, AptosError, AptosErrorCode, AsConverter, GasEstimation, HashValue,
+    IndexResponse, LedgerInfo, MoveValue, SubmitTransactionRequest, Transaction,
+    TransactionOnChainData, ViewRequest,
+};
+use aptos_config::config::RoleType;
+use aptos_crypto::hash::CryptoHash;
+use aptos_storage_interface::DbReader;
+use aptos_transaction_simulation::SimulationStateStore;
+use aptos_transaction_simulation_session::Session;
+use aptos_types::{
+    account_config::AccountResource,
+    on_chain_config::{ConfigurationResource, CurrentTimeMicroseconds, FeatureFlag},
+    transaction::{
+        ExecutionStatus, SignedTransaction, TransactionInfo, TransactionOutput, TransactionStatus,
+    },
+};
+use aptos_vm::AptosVM;
+use move_core_types::language_storage::{StructTag, TypeTag};
+use poem::{
+    get, handler,
+    http::StatusCode,
+    listener::TcpListener,
+    middleware::Cors,
+    post,
+    web::{Data, Path},
+    Body, Endpoint, EndpointExt, Request, Response, Route, Server,
+};
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+
+/// The content type used by clients to submit BCS encoded signed transactions
+const BCS_SIGNED_TRANSACTION: &str = "application/x.aptos.signed_transaction+bcs";
+
+/// The gas unit price returned by the gas estimation endpoint
+const DEFAULT_GAS_UNIT_PRICE: u64 = 100;
+
+/// The maximum amount of gas used to execute view functions
+const MAX_VIEW_FUNCTION_GAS: u64 = 2_000_000;
+
+/// A database reader that is only used to satisfy the API type converter. Sessions have
+/// no indexer, so table info lookups (which are optional for rendering) are unavailable.
+struct NoopDbReader;
+
+impl DbReader for NoopDbReader {
+    fn indexer_enabled(&self) -> bool {
+        false
+    }
+}
+
+/// The state shared by all request handlers
+struct SessionServerState {
+    session: Session,
+    /// The version assigned to the next committed transaction
+    next_version: u64,
+    /// The transactions committed while the server has been running
+    committed_transactions: HashMap<HashValue, Transaction>,
+}
+
+type SharedState = Arc<Mutex<SessionServerState>>;
+
+/// The result of a request handler, where errors are already rendered as responses
+type HandlerResult = Result<Response, Response>;
+
+/// Runs a REST API server for the given session until the server is stopped
+pub async fn run_session_server(session: Session, address: SocketAddr) -> anyhow::Result<()> {
+    let app = create_session_app(session);
+
+    eprintln!("Serving the simulation session at http://{}/v1", address);
+    Server::new(TcpListener::bind(address))
+        .name("simulation-session-server")
+        .run(app)
+        .await?;
+    Ok(())
+}
+
+/// Creates the REST API endpoints for the given session
+fn create_session_app(session: Session) -> impl Endpoint {
+    let next_version = session.state_store().next_version();
+    let state: SharedState = Arc::new(Mutex::new(SessionServerState {
+        session,
+        next_version,
+        committed_transactions: HashMap::new(),
+    }));
+
+    Route::new()
+        .at("/v1", get(index))
+        .at("/v1/accounts/:address", get(get_account))
+        .at(
+            "/v1/accounts/:address/resource/:resource_type",
+            get(get_account_resource),
+        )
+        .at("/v1/view", post(view))
+        .at("/v1/transactions", post(submit_transaction))
+        .at("/v1/transactions/simulate", post(simulate_transaction))
+        .at(
+            "/v1/transactions/by_hash/:hash",
+            get(get_transaction_by_hash),
+        )
+        .at(
+            "/v1/transactions/wait_by_hash/:hash",
+            get(get_transaction_by_hash),
+        )
+        .at("/v1/estimate_gas_price", get(estimate_gas_price))
+        .data(state)
+        .with(Cors::new())
+}
+
+/// Returns a JSON response with the given status
+fn json_response<T: Serialize>(status: StatusCode, value: &T) -> HandlerResult {
+    let body = serde_json::to_vec(value).map_err(|err| {
+        error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            AptosErrorCode::InternalError,
+            err,
+        )
+    })?;
+    Ok(Response::builder()
+        .status(status)
+        .content_type("application/json")
+        .body(body))
+}
+
+/// Returns an error response in the same format as the node REST API
+fn error_response(
+    status: StatusCode,
+    error_code: AptosErrorCode,
+    error: impl std::fmt::Display,
+) -> Response {
+    let error = AptosError::new_with_error_code(error, error_code);
+    Response::builder()
+        .status(status)
+        .content_type("application/json")
+        .body(serde_json::to_vec(&error).unwrap_or_default())
+}
+
+fn bad_request(error: impl std::fmt::Display) -> Response {
+    error_response(StatusCode::BAD_REQUEST, AptosErrorCode::InvalidInput, error)
+}
+
+fn internal_error(error: impl std::fmt::Display) -> Response {
+    error_response(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        AptosErrorCode::InternalError,
+        error,
+    )
+}
+
+/// Runs the given function on the shared state in a blocking task, as session
+/// operations may block on fetching remote state.
+async fn with_state<F>(state: &SharedState, f: F) -> Response
+where
+    F: FnOnce(&mut SessionServerState) -> HandlerResult + Send + 'static,
+{
+    let state = state.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        let mut state = state
+            .lock()
+            .map_err(|_| internal_error("The session state is poisoned"))?;
+        f(&mut state)
+    })
+    .await;
+    match result {
+        Ok(Ok(response)) | Ok(Err(response)) => response,
+        Err(err) => internal_error(err),
+    }
+}
+
+impl SessionServerState {
+    /// Returns the current ledger info of the session
+    fn ledger_info(&self) -> anyhow::Result<LedgerInfo> {
+        let state_store = self.session.state_store();
+        let epoch = state_store
+            .get_on_chain_config::<ConfigurationResource>()
+            .map(|config| config.epoch())
+            .unwrap_or_default();
+        let timestamp = state_store
+            .get_on_chain_config::<CurrentTimeMicroseconds>()
+            .map(|time| time.microseconds)
+            .unwrap_or_default();
+        Ok(LedgerInfo::new_ledger_info(
+            &state_store.get_chain_id()?,
+            epoch,
+            self.next_version.saturating_sub(1),
+            0,
+            0,
+            0,
+            timestamp,
+        ))
+    }
+
+    /// Parses a signed transaction from a JSON or BCS request body
+    fn parse_signed_transaction(
+        &self,
+        content_type: Option<&str>,
+        body: &[u8],
+    ) -> anyhow::Result<SignedTransaction> {
+        if content_type == Some(BCS_SIGNED_TRANSACTION) {
+            return bcs::from_bytes(body).context("Failed to deserialize the BCS transaction");
+        }
+
+        let request: SubmitTransactionRequest =
+            serde_json::from_slice(body).context("Failed to parse the JSON transaction")?;
+        let state_store = self.session.state_store();
+        state_store
+            .as_converter(Arc::new(NoopDbReader), None)
+            .try_into_signed_transaction_poem(request, state_store.get_chain_id()?)
+    }
+
+    /// Renders a transaction and its output in the same format as the node REST API
+    fn render_transaction(
+        &self,
+        txn: SignedTransaction,
+        output: &TransactionOutput,
+        status: ExecutionStatus,
+        version: u64,
+    ) -> anyhow::Result<Transaction> {
+        let txn = aptos_types::transaction::Transaction::UserTransaction(txn);
+        let zero_hash = aptos_crypto::HashValue::zero();
+        let info = TransactionInfo::new(
+            txn.hash(),
+            zero_hash,
+            zero_hash,
+            None,
+            output.gas_used(),
+            status,
+            None,
+        );
+        let data = TransactionOnChainData {
+            version,
+            transaction: txn,
+            info,
+            events: output.events().to_vec(),
+            accumulator_root_hash: zero_hash,
+            changes: output.write_set().clone(),
+        };
+
+        let timestamp = self.ledger_info()?.timestamp();
+        self.session
+            .state_store()
+            .as_converter(Arc::new(NoopDbReader), None)
+            .try_into_onchain_transaction(timestamp, data)
+    }
+}
+
+#[handler]
+async fn index(Data(state): Data<&SharedState>) -> Response {
+    with_state(state, |state| {
+        let ledger_info = state.ledger_info().map_err(internal_error)?;
+        json_response(
+            StatusCode::OK,
+            &IndexResponse::new(ledger_info, RoleType::FullNode, None),
+        )
+    })
+    .await
+}
+
+#[handler]
+async fn get_account(Path(address): Path<Address>, Data(state): Data<&SharedState>) -> Response {
+    with_state(state, move |state| {
+        let state_store = state.session.state_store();
+        let account = match state_store
+            .get_resource::<AccountResource>(address.into())
+            .map_err(internal_error)?
+        {
+            Some(account) => account,
+            None => {
+                let stateless_accounts_enabled = state_store
+                    .get_features()
+                    .map_err(internal_error)?
+                    .is_enabled(FeatureFlag::DEFAULT_ACCOUNT_RESOURCE);
+                if !stateless_accounts_enabled {
+                    return Err(error_response(
+                        StatusCode::NOT_FOUND,
+                        AptosErrorCode::AccountNotFound,
+                        format!("Account not found by Address({})", address),
+                    ));
+                }
+                AccountResource::new_stateless(address.into())
+            },
+        };
+        json_response(StatusCode::OK, &AccountData::from(account))
+    })
+    .await
+}
+
+#[handler]
+async fn get_account_resource(
+    Path((address, resource_type)): Path<(Address, String)>,
+    Data(state): Data<&SharedState>,
+) -> Response {
+    with_state(state, move |state| {
+        let tag: StructTag = resource_type.parse().map_err(bad_request)?;
+        let state_store = state.session.state_store();
+        let converter = state_store.as_converter(Arc::new(NoopDbReader), None);
+        let bytes = converter
+            .find_resource(state_store, address, &tag)
+            .map_err(internal_error)?
+            .ok_or_else(|| {
+                error_response(
+                    StatusCode::NOT_FOUND,
+                    AptosErrorCode::ResourceNotFound,
+                    format!(
+                        "Resource not found by Address({}), Struct tag({})",
+                        address, tag
+                    ),
+                )
+            })?;
+        let resource = converter
+            .try_into_resource(&tag, &bytes)
+            .map_err(internal_error)?;
+        json_response(StatusCode::OK, &resource)
+    })
+    .await
+}
+
+#[handler]
+async fn view(body: Body, Data(state): Data<&SharedState>) -> Response {
+    let body = match body.into_vec().await {
+        Ok(body) => body,
+        Err(err) => return bad_request(err),
+    };
+    with_state(state, move |state| {
+        let request: ViewRequest = serde_json::from_slice(&body).map_err(bad_request)?;
+        let state_store = state.session.state_store();
+        let converter = state_store.as_converter(Arc::new(NoopDbReader), None);
+        let view_function = converter
+            .convert_view_function(request)
+            .map_err(bad_request)?;
+
+        let output = AptosVM::execute_view_function(
+            state_store,
+            view_function.module.clone(),
+            view_function.function.clone(),
+            view_function.ty_args.clone(),
+            view_function.args.clone(),
+            MAX_VIEW_FUNCTION_GAS,
+        );
+        let values = output.values.map_err(bad_request)?;
+
+        let return_types = converter
+            .function_return_types(&view_function)
+            .and_then(|tys| {
+                tys.iter()
+                    .map(TypeTag::try_from)
+                    .collect::<anyhow::Result<Vec<_>>>()
+            })
+            .map_err(internal_error)?;
+        let move_values = values
+            .into_iter()
+            .zip(return_types)
+            .map(|(value, ty)| converter.try_into_move_value(&ty, &value))
+            .collect::<anyhow::Result<Vec<MoveValue>>>()
+            .map_err(internal_error)?;
+        json_response(StatusCode::OK, &move_values)
+    })
+    .await
+}
+
+#[handler]
+async fn submit_transaction(
+    req: &Request,
+    body: Body,
+    Data(state): Data<&SharedState>,
+) -> Response {
+    let content_type = req
+        .content_type()
+        .map(|content_type| content_type.to_string());
+    let body = match body.into_vec().await {
+        Ok(body) => body,
+        Err(err) => return bad_request(err),
+    };
+    with_state(state, move |state| {
+        let txn = state
+            .parse_signed_transaction(content_type.as_deref(), &body)
+            .map_err(bad_request)?;
+        txn.verify_signature()
+            .context("Invalid transaction signature")
+            .map_err(bad_request)?;
+
+        let state_store = state.session.state_store();
+        let pending_transaction = state_store
+            .as_converter(Arc::new(NoopDbReader), None)
+            .try_into_pending_transaction_poem(txn.clone())
+            .map_err(bad_request)?;
+
+        // Execute the transaction, and commit it to the session
+        let hash = txn.committed_hash();
+        let (vm_status, output) = state
+            .session
+            .execute_transaction(txn.clone(), false)
+            .map_err(internal_error)?;
+        let status = match output.status() {
+            TransactionStatus::Keep(status) => status.clone(),
+            TransactionStatus::Discard(_) | TransactionStatus::Retry => {
+                return Err(bad_request(format!(
+                    "Transaction was rejected with status: {}",
+                    vm_status
+                )));
+            },
+        };
+
+        let version = state.next_version;
+        let transaction = state
+            .render_transaction(txn, &output, status, version)
+            .map_err(internal_error)?;
+        state.next_version += 1;
+        state
+            .committed_transactions
+            .insert(hash.into(), transaction);
+
+        json_response(StatusCode::ACCEPTED, &pending_transaction)
+    })
+    .await
+}
+
+#[handler]
+async fn simulate_transaction(
+    req: &Request,
+    body: Body,
+    Data(state): Data<&SharedState>,
+) -> Response {
+    let content_type = req
+        .content_type()
+        .map(|content_type| content_type.to_string());
+    let body = match body.into_vec().await {
+        Ok(body) => body,
+        Err(err) => return bad_request(err),
+    };
+    with_state(state, move |state| {
+        let txn = state
+            .parse_signed_transaction(content_type.as_deref(), &body)
+            .map_err(bad_request)?;
+        let (_vm_status, output) = state
+            .session
+            .simulate_transaction(&txn)
+            .map_err(bad_request)?;
+
+        // Ensure that all known statuses return their values in the output (even if they aren't supposed to)
+        let status = ExecutionStatus::conmbine_vm_status_for_simulation(
+            output.auxiliary_data(),
+            output.status().clone(),
+        );
+        let transaction = state
+            .render_transaction(txn, &output, status, state.next_version)
+            .map_err(internal_error)?;
+        let user_transaction = match transaction {
+            Transaction::UserTransaction(user_transaction) => user_transaction,
+            _ => {
+                return Err(internal_error(
+                    "Simulation transaction resulted in a non-UserTransaction",
+                ))
+            },
+        };
+        json_response(StatusCode::OK, &vec![user_transaction])
+    })
+    .await
+}
+
+#[handler]
+async fn get_transaction_by_hash(
+    Path(hash): Path<String>,
+    Data(state): Data<&SharedState>,
+) -> Response {
+    with_state(state, move |state| {
+        let hash: HashValue = hash.parse().map_err(bad_request)?;
+        match state.committed_transactions.get(&hash) {
+            Some(transaction) => json_response(StatusCode::OK, transaction),
+            None => Err(error_response(
+                StatusCode::NOT_FOUND,
+                AptosErrorCode::TransactionNotFound,
+                format!("Transaction not found by Transaction hash({})", hash),
+            )),
+        }
+    })
+    .await
+}
+
+#[handler]
+async fn estimate_gas_price() -> Response {
+    let gas_estimation = GasEstimation {
+        deprioritized_gas_estimate: Some(DEFAULT_GAS_UNIT_PRICE),
+        gas_estimate: DEFAULT_GAS_UNIT_PRICE,
+        prioritized_gas_estimate: Some(DEFAULT_GAS_UNIT_PRICE),
+    };
+    match json_response(StatusCode::OK, &gas_estimation) {
+        Ok(response) | Err(response) => response,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_transaction_simulation::Account;
+    use aptos_types::transaction::{EntryFunction, TransactionPayload};
+    use move_core_types::{account_address::AccountAddress, ident_str, language_storage::ModuleId};
+    use poem::http::Method;
+    use serde_json::{json, Value};
+
+    #[tokio::test]
+    async fn test_submit_transaction_then_view() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut session = Session::init(temp_dir.path()).unwrap();
+        let sender = Account::new();
+        let receiver = AccountAddress::from_hex_literal("0xbeef").unwrap();
+        session
+            .fund_account(*sender.address(), 1_000_000_000)
+            .unwrap();
+
+        // Create a signed transfer from the funded sender
+        let txn = sender
+            .transaction()
+            .sequence_number(0)
+            .payload(TransactionPayload::EntryFunction(EntryFunction::new(
+                ModuleId::new(AccountAddress::ONE, ident_str!("aptos_account").to_owned()),
+                ident_str!("transfer").to_owned(),
+                vec![],
+                vec![
+                    bcs::to_bytes(&receiver).unwrap(),
+                    bcs::to_bytes(&100u64).unwrap(),
+                ],
+            )))
+            .max_gas_amount(100_000)
+            .gas_unit_price(100)
+            .chain_id(session.state_store().get_chain_id().unwrap())
+            .sign();
+        let app = create_session_app(session);
+
+        // Submit the transfer, and verify that it was committed successfully
+        let (status, pending_transaction) = send_request(
+            &app,
+            Method::POST,
+            "/v1/transactions",
+            BCS_SIGNED_TRANSACTION,
+            bcs::to_bytes(&txn).unwrap(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+        let (status, transaction) = send_request(
+            &app,
+            Method::GET,
+            &format!(
+                "/v1/transactions/by_hash/{}",
+                pending_transaction["hash"].as_str().unwrap()
+            ),
+            "application/json",
+            vec![],
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(transaction["success"], true);
+
+        // View the balance of the receiver, and verify that the transfer was applied
+        let view_request = json!({
+            "function": "0x1::coin::balance",
+            "type_arguments": ["0x1::aptos_coin::AptosCoin"],
+            "arguments": [receiver.to_hex_literal()],
+        });
+        let (status, values) = send_request(
+            &app,
+            Method::POST,
+            "/v1/view",
+            "application/json",
+            serde_json::to_vec(&view_request).unwrap(),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(values, json!(["100"]));
+    }
+
+    /// Sends a request to the server, and returns the response status and JSON body
+    async fn send_request(
+        app: &impl Endpoint,
+        method: Method,
+        uri: &str,
+        content_type: &str,
+        body: Vec<u8>,
+    ) -> (StatusCode, Value) {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri.parse().unwrap())
+            .content_type(content_type)
+            .body(body);
+        let response = app.get_response(request).await;
+        let status = response.status();
+        let body = response.into_body().into_vec().await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+}
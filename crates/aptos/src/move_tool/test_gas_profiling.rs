@@ -0,0 +1,111 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_gas_meter::{StandardGasAlgebra, StandardGasMeter};
+use aptos_gas_profiling::{FrameName, GasProfiler};
+use aptos_gas_schedule::{AptosGasParameters, LATEST_GAS_FEATURE_VERSION};
+use aptos_vm_types::{resolver::NoopBlockSynchronizationKillSwitch, storage::StorageGasParameters};
+use move_core_types::{effects::ChangeSet, identifier::Identifier, language_storage::ModuleId};
+use move_unit_test::test_reporter::{TestRunInfo, UnitTestFactory};
+use move_vm_runtime::native_extensions::NativeContextExtensions;
+use std::path::PathBuf;
+
+type TestGasMeter =
+    GasProfiler<StandardGasMeter<StandardGasAlgebra<'static, NoopBlockSynchronizationKillSwitch>>>;
+
+/// A unit test factory which meters tests with the Aptos gas schedule, and saves a gas report
+/// for every test.
+///
+/// Tests are metered like a transaction with the maximum gas amount, so tests exceeding the
+/// gas limits of a transaction time out.
+pub(crate) struct GasProfilingTestFactory {
+    gas_params: AptosGasParameters,
+    storage_gas_params: StorageGasParameters,
+    report_dir: PathBuf,
+}
+
+impl GasProfilingTestFactory {
+    pub fn new(gas_params: AptosGasParameters, report_dir: PathBuf) -> Self {
+        Self {
+            gas_params,
+            storage_gas_params: StorageGasParameters::latest(),
+            report_dir,
+        }
+    }
+
+    fn new_base_gas_meter(
+        &self,
+    ) -> StandardGasMeter<StandardGasAlgebra<'static, NoopBlockSynchronizationKillSwitch>> {
+        StandardGasMeter::new(StandardGasAlgebra::new(
+            LATEST_GAS_FEATURE_VERSION,
+            self.gas_params.vm.clone(),
+            self.storage_gas_params.clone(),
+            false,
+            self.gas_params.vm.txn.maximum_number_of_gas_units,
+            &NoopBlockSynchronizationKillSwitch {},
+        ))
+    }
+}
+
+impl UnitTestFactory for GasProfilingTestFactory {
+    type GasMeter = TestGasMeter;
+
+    fn new_gas_meter(&self) -> Self::GasMeter {
+        GasProfiler::new_script(self.new_base_gas_meter())
+    }
+
+    fn new_gas_meter_for_test(
+        &self,
+        module_id: &ModuleId,
+        function_name: &str,
+    ) -> anyhow::Result<Self::GasMeter> {
+        Ok(GasProfiler::new_function(
+            self.new_base_gas_meter(),
+            module_id.clone(),
+            Identifier::new(function_name)?,
+            vec![],
+        ))
+    }
+
+    fn finalize_test_run_info(
+        &self,
+        _change_set: &ChangeSet,
+        _extensions: &mut NativeContextExtensions,
+        gas_meter: Self::GasMeter,
+        mut test_run_info: TestRunInfo,
+    ) -> TestRunInfo {
+        let gas_log = gas_meter.finish();
+        test_run_info.gas_used = u64::from(gas_log.exec_io.total)
+            .div_ceil(u64::from(gas_log.exec_io.gas_scaling_factor));
+
+        let (raw_file_name, human_readable_name) = match gas_log.entry_point() {
+            FrameName::Function {
+                module_id, name, ..
+            } => (
+                format!(
+                    "{}-{}-{}",
+                    module_id.address().short_str_lossless(),
+                    module_id.name(),
+                    name
+                ),
+                format!("{}::{}", module_id.name(), name),
+            ),
+            FrameName::Script => (
+                test_run_info.function_ident.clone(),
+                test_run_info.function_ident.clone(),
+            ),
+        };
+
+        let path = self.report_dir.join(raw_file_name);
+        if let Err(err) =
+            gas_log.generate_html_report(&path, format!("Gas Report - {}", human_readable_name))
+        {
+            eprintln!(
+                "Failed to save the gas report of {}: {:#}",
+                human_readable_name, err
+            );
+        }
+
+        test_run_info
+    }
+}
@@ -850,7 +850,8 @@ impl CliTestFramework {
         &self,
         account_strs: BTreeMap<&str, &str>,
         filter: Option<&str>,
-    ) -> CliTypedResult<&'static str> {
+        profile_gas: bool,
+    ) -> CliTypedResult<String> {
         TestPackage {
             instruction_execution_bound: 100_000,
             move_options: self.move_options(account_strs),
@@ -858,6 +859,11 @@ impl CliTestFramework {
             ignore_compile_warnings: false,
             compute_coverage: false,
             dump_state: false,
+            profile_gas,
+            junit_report: None,
+            json_report: None,
+            fuzz_runs: move_unit_test::fuzz::DEFAULT_FUZZ_RUNS,
+            fuzz_seed: None,
         }
         .execute()
         .await
@@ -11,7 +11,7 @@ use crate::{
     },
     QuorumStoreRequest,
 };
-use aptos_config::config::{NodeConfig, NodeType};
+use aptos_config::config::{NodeConfig, NodeType, ReloadableFilterConfig, TransactionFilterConfig};
 use aptos_event_notifications::{DbBackedOnChainConfig, ReconfigNotificationListener};
 use aptos_infallible::{Mutex, RwLock};
 use aptos_logger::Level;
@@ -46,12 +46,12 @@ pub(crate) fn start_shared_mempool<TransactionValidator, ConfigProvider>(
     validator: Arc<RwLock<TransactionValidator>>,
     subscribers: Vec<UnboundedSender<SharedMempoolNotification>>,
     peers_and_metadata: Arc<PeersAndMetadata>,
+    transaction_filter_config: ReloadableFilterConfig<TransactionFilterConfig>,
 ) where
     TransactionValidator: TransactionValidation + 'static,
     ConfigProvider: OnChainConfigProvider,
 {
     let node_type = NodeType::extract_from_config(config);
-    let transaction_filter_config = config.transaction_filters.mempool_filter.clone();
     let smp: SharedMempool<NetworkClient<MempoolSyncMsg>, TransactionValidator> =
         SharedMempool::new(
             mempool.clone(),
@@ -99,6 +99,7 @@ pub fn bootstrap(
     mempool_listener: MempoolNotificationListener,
     mempool_reconfig_events: ReconfigNotificationListener<DbBackedOnChainConfig>,
     peers_and_metadata: Arc<PeersAndMetadata>,
+    transaction_filter_config: ReloadableFilterConfig<TransactionFilterConfig>,
 ) -> Runtime {
     let runtime = aptos_runtimes::spawn_named_runtime("shared-mem".into(), None);
     let mempool = Arc::new(Mutex::new(CoreMempool::new(config)));
@@ -120,6 +121,7 @@ pub fn bootstrap(
         vm_validator,
         vec![],
         peers_and_metadata,
+        transaction_filter_config,
     );
     runtime
 }
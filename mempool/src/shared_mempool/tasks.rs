@@ -29,6 +29,7 @@ use aptos_mempool_notifications::CommittedTransaction;
 use aptos_metrics_core::HistogramTimer;
 use aptos_network::application::interface::NetworkClientInterface;
 use aptos_storage_interface::state_store::state_view::db_state_view::LatestDbStateCheckpointView;
+use aptos_transaction_filters::transaction_filter::MEMPOOL_FILTER_LABEL;
 use aptos_types::{
     account_address::AccountAddress,
     mempool_status::{MempoolStatus, MempoolStatusCode},
@@ -318,8 +319,11 @@ where
 {
     // Filter out any disallowed transactions
     let mut statuses = vec![];
-    let transactions =
-        filter_transactions(&smp.transaction_filter_config, transactions, &mut statuses);
+    let transactions = filter_transactions(
+        &smp.transaction_filter_config.load(),
+        transactions,
+        &mut statuses,
+    );
 
     // If there are no transactions left after filtering, return early
     if transactions.is_empty() {
@@ -433,10 +437,9 @@ fn filter_transactions(
     let transactions = transactions
         .into_iter()
         .filter_map(|(transaction, account_sequence_number, priority)| {
-            if transaction_filter_config
-                .transaction_filter()
-                .allows_transaction(&transaction)
-            {
+            let transaction_filter = transaction_filter_config.transaction_filter();
+            transaction_filter.record_shadow_rule_match(MEMPOOL_FILTER_LABEL, &transaction);
+            if transaction_filter.allows_transaction(&transaction) {
                 Some((transaction, account_sequence_number, priority))
             } else {
                 info!(LogSchema::event_log(
@@ -504,6 +507,7 @@ fn validate_and_add_transactions<NetworkClient, TransactionValidator>(
     });
     vm_validation_timer.stop_and_record();
     {
+        let transaction_filter_config = smp.transaction_filter_config.load();
         let mut mempool = smp.mempool.lock();
         for (idx, (transaction, account_sequence_number, ready_time_at_sender, priority)) in
             transactions.into_iter().enumerate()
@@ -511,6 +515,25 @@ fn validate_and_add_transactions<NetworkClient, TransactionValidator>(
             if let Ok(validation_result) = &validation_results[idx] {
                 match validation_result.status() {
                     None => {
+                        // The transaction passed validation, so check that it still fits
+                        // within the filter quotas. Quotas are only consumed by valid
+                        // transactions, so that invalid transactions (e.g., with forged
+                        // senders) cannot exhaust the quota of other accounts.
+                        if transaction_filter_config.is_enabled()
+                            && !transaction_filter_config
+                                .transaction_filter()
+                                .fits_quota(&transaction)
+                        {
+                            statuses.push((
+                                transaction,
+                                (
+                                    MempoolStatus::new(MempoolStatusCode::RejectedByFilter),
+                                    None,
+                                ),
+                            ));
+                            continue;
+                        }
+
                         let ranking_score = validation_result.score();
                         let mempool_status = mempool.add_txn(
                             transaction.clone(),
@@ -521,6 +544,15 @@ fn validate_and_add_transactions<NetworkClient, TransactionValidator>(
                             ready_time_at_sender,
                             priority.clone(),
                         );
+
+                        // Only transactions accepted by mempool (e.g., not duplicates) consume quota
+                        if mempool_status.code == MempoolStatusCode::Accepted
+                            && transaction_filter_config.is_enabled()
+                        {
+                            let _ = transaction_filter_config
+                                .transaction_filter()
+                                .try_consume_quota(&transaction);
+                        }
                         statuses.push((transaction, (mempool_status, None)));
                     },
                     Some(validation_status) => {
@@ -10,7 +10,7 @@ use crate::{
 };
 use anyhow::Result;
 use aptos_config::{
-    config::{MempoolConfig, NodeType, TransactionFilterConfig},
+    config::{MempoolConfig, NodeType, ReloadableFilterConfig, TransactionFilterConfig},
     network_id::PeerNetworkId,
 };
 use aptos_consensus_types::common::{
@@ -56,7 +56,7 @@ pub(crate) struct SharedMempool<NetworkClient, TransactionValidator> {
     pub subscribers: Vec<UnboundedSender<SharedMempoolNotification>>,
     pub broadcast_within_validator_network: Arc<RwLock<bool>>,
     pub use_case_history: Arc<Mutex<UseCaseHistory>>,
-    pub transaction_filter_config: TransactionFilterConfig,
+    pub transaction_filter_config: ReloadableFilterConfig<TransactionFilterConfig>,
 }
 
 impl<
@@ -67,7 +67,7 @@ impl<
     pub fn new(
         mempool: Arc<Mutex<CoreMempool>>,
         config: MempoolConfig,
-        transaction_filter_config: TransactionFilterConfig,
+        transaction_filter_config: ReloadableFilterConfig<TransactionFilterConfig>,
         network_client: NetworkClient,
         db: Arc<dyn DbReader>,
         validator: Arc<RwLock<TransactionValidator>>,
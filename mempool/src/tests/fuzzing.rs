@@ -8,7 +8,7 @@ use crate::{
     shared_mempool::{tasks, types::SharedMempool},
 };
 use aptos_config::{
-    config::{NodeConfig, NodeType},
+    config::{NodeConfig, NodeType, ReloadableFilterConfig},
     network_id::NetworkId,
 };
 use aptos_infallible::{Mutex, RwLock};
@@ -82,7 +82,8 @@ pub fn test_mempool_process_incoming_transactions_impl(
         HashMap::new(),
         PeersAndMetadata::new(&[NetworkId::Validator]),
     );
-    let transaction_filter_config = config.transaction_filters.mempool_filter.clone();
+    let transaction_filter_config =
+        ReloadableFilterConfig::new(config.transaction_filters.mempool_filter.clone());
     let smp: SharedMempool<NetworkClient<MempoolSyncMsg>, MockVMValidator> = SharedMempool::new(
         Arc::new(Mutex::new(CoreMempool::new(&config))),
         config.mempool.clone(),
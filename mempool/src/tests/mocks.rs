@@ -11,7 +11,7 @@ use crate::{
 use anyhow::{format_err, Result};
 use aptos_channels::{self, aptos_channel, message_queues::QueueStyle};
 use aptos_config::{
-    config::{NetworkConfig, NodeConfig},
+    config::{NetworkConfig, NodeConfig, ReloadableFilterConfig},
     network_id::NetworkId,
 };
 use aptos_event_notifications::{ReconfigNotification, ReconfigNotificationListener};
@@ -169,6 +169,7 @@ impl MockSharedMempool {
             Arc::new(RwLock::new(validator)),
             vec![],
             peers_and_metadata,
+            ReloadableFilterConfig::new(config.transaction_filters.mempool_filter.clone()),
         );
 
         (ac_client, mempool, quorum_store_sender, mempool_notifier)
@@ -10,7 +10,7 @@ use crate::{
 };
 use aptos_channels::{aptos_channel, message_queues::QueueStyle};
 use aptos_config::{
-    config::{Identity, NodeConfig, PeerRole},
+    config::{Identity, NodeConfig, PeerRole, ReloadableFilterConfig},
     network_id::{NetworkId, PeerNetworkId},
 };
 use aptos_crypto::{x25519::PrivateKey, Uniform};
@@ -588,6 +588,7 @@ fn start_node_mempool(
         Arc::new(RwLock::new(MockVMValidator)),
         vec![sender],
         peers_and_metadata,
+        ReloadableFilterConfig::new(config.transaction_filters.mempool_filter.clone()),
     );
 
     (mempool, runtime, subscriber)
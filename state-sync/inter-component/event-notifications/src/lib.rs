@@ -7,6 +7,7 @@ use anyhow::{anyhow, Result};
 use aptos_channels::{aptos_channel, message_queues::QueueStyle};
 use aptos_id_generator::{IdGenerator, U64IdGenerator};
 use aptos_infallible::RwLock;
+use aptos_logger::warn;
 use aptos_storage_interface::{
     state_store::state_view::db_state_view::DbStateViewAtVersion, DbReader, DbReaderWriter,
 };
@@ -39,6 +40,7 @@ mod tests;
 // will be retrieved using FIFO ordering.
 const EVENT_NOTIFICATION_CHANNEL_SIZE: usize = 100;
 const RECONFIG_NOTIFICATION_CHANNEL_SIZE: usize = 1; // Note: this should be 1 to ensure only the latest reconfig is consumed
+const COMMIT_NOTIFICATION_CHANNEL_SIZE: usize = 1; // Note: this should be 1 to ensure only the latest commit is consumed
 
 #[derive(Clone, Debug, Deserialize, Error, PartialEq, Eq, Serialize)]
 pub enum Error {
@@ -82,6 +84,9 @@ pub struct EventSubscriptionService {
     // Reconfig subscription registry
     reconfig_subscriptions: HashMap<SubscriptionId, ReconfigSubscription>,
 
+    // Commit subscription registry
+    commit_subscriptions: HashMap<SubscriptionId, CommitSubscription>,
+
     // Database to fetch on-chain configuration data
     storage: Arc<RwLock<DbReaderWriter>>,
 
@@ -96,6 +101,7 @@ impl EventSubscriptionService {
             event_v2_tag_subscriptions: HashMap::new(),
             subscription_id_to_event_subscription: HashMap::new(),
             reconfig_subscriptions: HashMap::new(),
+            commit_subscriptions: HashMap::new(),
             storage,
             subscription_id_generator: U64IdGenerator::new(),
         }
@@ -198,6 +204,38 @@ impl EventSubscriptionService {
         })
     }
 
+    /// Returns a CommitNotificationListener that can be monitored for newly
+    /// committed versions. Subscribers will be sent a notification containing
+    /// the latest committed version every time new transactions are committed.
+    /// Note: only the latest notification is buffered (older notifications are
+    /// dropped), so subscribers should read any intermediate versions from storage.
+    pub fn subscribe_to_commits(&mut self) -> Result<CommitNotificationListener, Error> {
+        let (notification_sender, notification_receiver) =
+            aptos_channel::new(QueueStyle::KLAST, COMMIT_NOTIFICATION_CHANNEL_SIZE, None);
+
+        // Create a new commit subscription
+        let subscription_id = self.get_new_subscription_id();
+        let commit_subscription = CommitSubscription {
+            notification_sender,
+        };
+
+        // Store the new subscription
+        if self
+            .commit_subscriptions
+            .insert(subscription_id, commit_subscription)
+            .is_some()
+        {
+            return Err(Error::UnexpectedErrorEncountered(format!(
+                "Duplicate commit subscription found! This should not occur! ID: {}",
+                subscription_id,
+            )));
+        }
+
+        Ok(CommitNotificationListener {
+            notification_receiver,
+        })
+    }
+
     fn get_new_subscription_id(&mut self) -> u64 {
         self.subscription_id_generator.next()
     }
@@ -260,6 +298,26 @@ impl EventSubscriptionService {
         Ok(reconfig_event_found)
     }
 
+    /// This notifies all the commit subscribers of the newly committed version.
+    /// Subscribers that can no longer be notified (e.g., because the listener
+    /// was dropped) are logged and removed. Commit notifications are best-effort,
+    /// so a failure here must never prevent event or reconfig notifications.
+    fn notify_commit_subscribers(&mut self, version: Version) {
+        self.commit_subscriptions
+            .retain(|subscription_id, commit_subscription| {
+                match commit_subscription.notify_subscriber_of_commit(version) {
+                    Ok(()) => true,
+                    Err(error) => {
+                        warn!(
+                            "Failed to notify the commit subscriber! Removing the subscription. ID: {}, error: {:?}",
+                            subscription_id, error
+                        );
+                        false
+                    },
+                }
+            });
+    }
+
     /// This notifies all the reconfiguration subscribers of the on-chain
     /// configurations at the specified version.
     fn notify_reconfiguration_subscribers(&mut self, version: Version) -> Result<(), Error> {
@@ -310,6 +368,9 @@ impl EventSubscriptionService {
 
 impl EventNotificationSender for EventSubscriptionService {
     fn notify_events(&mut self, version: Version, events: Vec<ContractEvent>) -> Result<(), Error> {
+        // Notify the commit subscribers of the new version (even if there are no events)
+        self.notify_commit_subscribers(version);
+
         if events.is_empty() {
             return Ok(()); // No events!
         }
@@ -383,6 +444,22 @@ impl ReconfigSubscription {
     }
 }
 
+/// A single commit subscription, holding the channel to send the
+/// corresponding notifications.
+struct CommitSubscription {
+    pub notification_sender: aptos_channels::aptos_channel::Sender<(), CommitNotification>,
+}
+
+impl CommitSubscription {
+    fn notify_subscriber_of_commit(&mut self, version: Version) -> Result<(), Error> {
+        let commit_notification = CommitNotification { version };
+
+        self.notification_sender
+            .push((), commit_notification)
+            .map_err(|error| Error::UnexpectedErrorEncountered(format!("{:?}", error)))
+    }
+}
+
 #[derive(Clone)]
 pub struct DbBackedOnChainConfig {
     pub reader: Arc<dyn DbReader>,
@@ -433,12 +510,21 @@ pub struct ReconfigNotification<P: OnChainConfigProvider> {
     pub on_chain_configs: OnChainConfigPayload<P>,
 }
 
+/// A notification for newly committed transactions.
+#[derive(Debug)]
+pub struct CommitNotification {
+    pub version: Version, // The latest committed version
+}
+
 /// A subscription listener for on-chain events.
 pub type EventNotificationListener = NotificationListener<EventNotification>;
 
 /// A subscription listener for reconfigurations.
 pub type ReconfigNotificationListener<P> = NotificationListener<ReconfigNotification<P>>;
 
+/// A subscription listener for commits.
+pub type CommitNotificationListener = NotificationListener<CommitNotification>;
+
 /// The component responsible for listening to subscription notifications.
 #[derive(Debug)]
 pub struct NotificationListener<T> {
@@ -5,8 +5,8 @@
 #![forbid(unsafe_code)]
 
 use crate::{
-    DbBackedOnChainConfig, Error, EventNotificationListener, EventNotificationSender,
-    EventSubscriptionService, ReconfigNotificationListener,
+    CommitNotificationListener, DbBackedOnChainConfig, Error, EventNotificationListener,
+    EventNotificationSender, EventSubscriptionService, ReconfigNotificationListener,
 };
 use aptos_db::AptosDB;
 use aptos_executor_test_helpers::bootstrap_genesis;
@@ -100,6 +100,61 @@ fn test_reconfig_notification_no_queuing() {
     assert_eq!(notification_count, 1);
 }
 
+#[test]
+fn test_commit_subscribers() {
+    // Create subscription service and mock database
+    let mut event_service = create_event_subscription_service();
+
+    // Create several commit subscribers
+    let mut listener_1 = event_service.subscribe_to_commits().unwrap();
+    let mut listener_2 = event_service.subscribe_to_commits().unwrap();
+
+    // Notify the subscription service of a commit with no events
+    let version = 10;
+    notify_events(&mut event_service, version, vec![]);
+
+    // Verify both listeners receive the commit notification
+    verify_commit_notification_received(vec![&mut listener_1, &mut listener_2], version);
+
+    // Notify the subscription service of several commits (with events)
+    for version in 11..20 {
+        let event = create_test_event(create_random_event_key());
+        notify_events(&mut event_service, version, vec![event]);
+    }
+
+    // Verify that only the latest commit notification is received (older ones are dropped)
+    verify_commit_notification_received(vec![&mut listener_1, &mut listener_2], 19);
+    for listener in [&mut listener_1, &mut listener_2] {
+        assert!(listener.select_next_some().now_or_never().is_none());
+    }
+}
+
+#[test]
+fn test_dropped_commit_subscriber() {
+    // Create subscription service and mock database
+    let mut event_service = create_event_subscription_service();
+
+    // Create commit and reconfig subscribers
+    let commit_listener_1 = event_service.subscribe_to_commits().unwrap();
+    let mut commit_listener_2 = event_service.subscribe_to_commits().unwrap();
+    let mut reconfig_listener = event_service.subscribe_to_reconfigurations().unwrap();
+
+    // Drop the first commit listener
+    drop(commit_listener_1);
+
+    // Notify the subscription service of a reconfiguration event
+    let version = 0;
+    let reconfig_event = create_test_reconfig_event();
+    notify_events(&mut event_service, version, vec![reconfig_event]);
+
+    // Verify that the remaining subscribers are still notified
+    verify_commit_notification_received(vec![&mut commit_listener_2], version);
+    verify_reconfig_notifications_received(vec![&mut reconfig_listener], version, 1);
+
+    // Verify that the dropped commit subscription was removed
+    assert_eq!(event_service.commit_subscriptions.len(), 1);
+}
+
 #[test]
 fn test_dynamic_subscribers() {
     // Create subscription service and mock database
@@ -469,6 +524,20 @@ fn count_reconfig_notifications(
     notification_count
 }
 
+// Ensures that the specified listeners have received the expected commit notification
+fn verify_commit_notification_received(
+    listeners: Vec<&mut CommitNotificationListener>,
+    expected_version: Version,
+) {
+    for listener in listeners {
+        if let Some(commit_notification) = listener.select_next_some().now_or_never() {
+            assert_eq!(commit_notification.version, expected_version);
+        } else {
+            panic!("Expected a commit notification but got None!");
+        }
+    }
+}
+
 // Ensures that no event notifications have been received by the listeners
 fn verify_no_event_notifications(listeners: Vec<&mut EventNotificationListener>) {
     for listener in listeners {
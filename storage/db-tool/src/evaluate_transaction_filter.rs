@@ -0,0 +1,309 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{Context, Result};
+use aptos_backup_cli::utils::RocksdbOpt;
+use aptos_config::config::{
+    StorageDirPaths, BUFFERED_STATE_TARGET_ITEMS, DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+    NO_OP_STORAGE_PRUNER_CONFIG,
+};
+use aptos_db::AptosDB;
+use aptos_logger::prelude::*;
+use aptos_storage_interface::{AptosDbError, DbReader};
+use aptos_transaction_filters::transaction_filter::{TransactionFilter, TransactionRule};
+use aptos_types::transaction::{SignedTransaction, Version};
+use clap::Parser;
+use serde::Serialize;
+use std::{fs, path::PathBuf};
+
+/// Evaluates a transaction filter against a range of committed transactions in AptosDB,
+/// and reports how many transactions each rule (and shadow rule) would have matched.
+///
+/// Note: quota rules are not evaluated, as they depend on the time at which each
+/// transaction was received (which is not recorded in AptosDB).
+#[derive(Parser)]
+pub struct Opt {
+    #[clap(long, help = "The first transaction version to evaluate")]
+    start_version: Version,
+
+    #[clap(long, help = "The last transaction version to evaluate (inclusive)")]
+    end_version: Version,
+
+    #[clap(
+        long,
+        value_parser,
+        help = "The YAML file containing the transaction filter to evaluate"
+    )]
+    filter_file: PathBuf,
+
+    #[clap(long = "target-db-dir", value_parser)]
+    db_dir: PathBuf,
+
+    #[clap(flatten)]
+    rocksdb_opt: RocksdbOpt,
+
+    #[clap(
+        long,
+        default_value = "10",
+        help = "The maximum number of matched transaction versions to report for each rule"
+    )]
+    max_sampled_versions: usize,
+}
+
+impl Opt {
+    pub async fn run(self) -> Result<()> {
+        // Load the transaction filter
+        let filter_string = fs::read_to_string(&self.filter_file)
+            .with_context(|| format!("Failed to read the filter file: {:?}", self.filter_file))?;
+        let transaction_filter: TransactionFilter = serde_yaml::from_str(&filter_string)
+            .with_context(|| format!("Failed to parse the filter file: {:?}", self.filter_file))?;
+
+        // Open the DB (in read-only mode)
+        let aptos_db = AptosDB::open(
+            StorageDirPaths::from_path(self.db_dir.as_path()),
+            true,
+            NO_OP_STORAGE_PRUNER_CONFIG,
+            self.rocksdb_opt.clone().into(),
+            false,
+            BUFFERED_STATE_TARGET_ITEMS,
+            DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+            None,
+        )?;
+
+        // Evaluate the filter and print the summary
+        let summary = evaluate_transaction_filter(
+            &aptos_db,
+            &transaction_filter,
+            self.start_version,
+            self.end_version,
+            self.max_sampled_versions,
+        )?;
+        println!("{}", serde_json::to_string_pretty(&summary)?);
+
+        Ok(())
+    }
+}
+
+/// A summary of the evaluation of a transaction filter over a range of versions
+#[derive(Debug, Serialize)]
+struct FilterEvaluationSummary {
+    start_version: Version,                     // The first version evaluated
+    end_version: Version,                       // The last version evaluated
+    num_user_transactions: u64,                 // The number of user transactions evaluated
+    num_denied_transactions: u64,               // The number of transactions denied by the rules
+    num_shadow_denied_transactions: u64, // The number of transactions denied by the shadow rules
+    rule_matches: Vec<RuleMatchSummary>, // The matches for each rule
+    shadow_rule_matches: Vec<RuleMatchSummary>, // The matches for each shadow rule
+}
+
+/// A summary of the transactions matched by a single rule
+#[derive(Debug, Serialize)]
+struct RuleMatchSummary {
+    rule_index: usize,              // The index of the rule in the filter
+    rule_type: String,              // The type of the rule (i.e., allow or deny)
+    num_matches: u64,               // The number of transactions matched by the rule
+    sampled_versions: Vec<Version>, // A sample of the matched transaction versions
+}
+
+impl RuleMatchSummary {
+    fn new(rule_index: usize, transaction_rule: &TransactionRule) -> Self {
+        Self {
+            rule_index,
+            rule_type: transaction_rule.get_label().to_string(),
+            num_matches: 0,
+            sampled_versions: vec![],
+        }
+    }
+
+    /// Records a match for the transaction at the given version
+    fn record_match(&mut self, version: Version, max_sampled_versions: usize) {
+        self.num_matches += 1;
+        if self.sampled_versions.len() < max_sampled_versions {
+            self.sampled_versions.push(version);
+        }
+    }
+}
+
+/// Evaluates the transaction filter against all user transactions in the given range
+fn evaluate_transaction_filter(
+    aptos_db: &dyn DbReader,
+    transaction_filter: &TransactionFilter,
+    start_version: Version,
+    end_version: Version,
+    max_sampled_versions: usize,
+) -> Result<FilterEvaluationSummary> {
+    // Calculate the range of versions to evaluate
+    let db_end_version = aptos_db
+        .get_synced_version()?
+        .ok_or(AptosDbError::NotFound("Synced version is None".to_string()))?;
+    let end_version = std::cmp::min(end_version, db_end_version);
+    let limit = if start_version <= end_version {
+        end_version - start_version + 1
+    } else {
+        warn!(
+            start = start_version,
+            end = end_version,
+            "No transactions to evaluate in the requested range."
+        );
+        0
+    };
+
+    // Create the summary for each rule and shadow rule
+    let mut summary = FilterEvaluationSummary {
+        start_version,
+        end_version,
+        num_user_transactions: 0,
+        num_denied_transactions: 0,
+        num_shadow_denied_transactions: 0,
+        rule_matches: create_rule_match_summaries(transaction_filter.transaction_rules()),
+        shadow_rule_matches: create_rule_match_summaries(transaction_filter.shadow_rules()),
+    };
+
+    // Evaluate the filter against each user transaction
+    let transaction_iterator = aptos_db.get_transaction_iterator(start_version, limit)?;
+    for (version, transaction) in (start_version..).zip(transaction_iterator) {
+        let transaction = transaction?;
+        if let Some(signed_transaction) = transaction.try_as_signed_user_txn() {
+            summary.num_user_transactions += 1;
+            evaluate_transaction(
+                transaction_filter,
+                signed_transaction,
+                version,
+                max_sampled_versions,
+                &mut summary,
+            );
+        }
+    }
+
+    info!(
+        "Evaluated the transaction filter against {} user transactions!",
+        summary.num_user_transactions
+    );
+    Ok(summary)
+}
+
+/// Evaluates the filter rules and shadow rules against the
+/// given transaction, and records any matches in the summary.
+fn evaluate_transaction(
+    transaction_filter: &TransactionFilter,
+    signed_transaction: &SignedTransaction,
+    version: Version,
+    max_sampled_versions: usize,
+    summary: &mut FilterEvaluationSummary,
+) {
+    if let Some(rule_index) = transaction_filter.get_matching_rule_index(signed_transaction) {
+        if let TransactionRule::Deny(_) = transaction_filter.transaction_rules()[rule_index] {
+            summary.num_denied_transactions += 1;
+        }
+        summary.rule_matches[rule_index].record_match(version, max_sampled_versions);
+    }
+
+    if let Some(rule_index) = transaction_filter.get_matching_shadow_rule_index(signed_transaction)
+    {
+        if let TransactionRule::Deny(_) = transaction_filter.shadow_rules()[rule_index] {
+            summary.num_shadow_denied_transactions += 1;
+        }
+        summary.shadow_rule_matches[rule_index].record_match(version, max_sampled_versions);
+    }
+}
+
+/// Creates an empty match summary for each of the given rules
+fn create_rule_match_summaries(transaction_rules: &[TransactionRule]) -> Vec<RuleMatchSummary> {
+    transaction_rules
+        .iter()
+        .enumerate()
+        .map(|(rule_index, transaction_rule)| RuleMatchSummary::new(rule_index, transaction_rule))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_executor_test_helpers::integration_test_impl::test_execution_with_storage_impl;
+    use aptos_transaction_filters::transaction_filter::TransactionMatcher;
+    use aptos_types::account_config::aptos_test_root_address;
+
+    #[test]
+    fn test_evaluate_transaction_filter() {
+        // Create a DB with committed user transactions
+        let aptos_db = test_execution_with_storage_impl();
+        let synced_version = aptos_db.get_synced_version().unwrap().unwrap();
+
+        // Count the user transactions (and those sent by the root account)
+        let mut num_user_transactions = 0;
+        let mut num_root_transactions = 0;
+        for transaction in aptos_db
+            .get_transaction_iterator(0, synced_version + 1)
+            .unwrap()
+        {
+            if let Some(signed_transaction) = transaction.unwrap().try_as_signed_user_txn() {
+                num_user_transactions += 1;
+                if signed_transaction.sender() == aptos_test_root_address() {
+                    num_root_transactions += 1;
+                }
+            }
+        }
+        assert!(num_root_transactions > 0);
+        assert!(num_user_transactions > num_root_transactions);
+
+        // Create a filter that denies the root account, and a shadow rule that denies everything
+        let transaction_filter = TransactionFilter::empty()
+            .add_multiple_matchers_filter(false, vec![TransactionMatcher::Sender(
+                aptos_test_root_address(),
+            )])
+            .add_all_filter(true)
+            .add_multiple_matchers_shadow_filter(false, vec![TransactionMatcher::All]);
+
+        // Evaluate the filter over all versions (with an end version past the synced version)
+        let max_sampled_versions = 2;
+        let summary = evaluate_transaction_filter(
+            aptos_db.as_ref(),
+            &transaction_filter,
+            0,
+            Version::MAX,
+            max_sampled_versions,
+        )
+        .unwrap();
+
+        // Verify the summary
+        assert_eq!(summary.end_version, synced_version);
+        assert_eq!(summary.num_user_transactions, num_user_transactions);
+        assert_eq!(summary.num_denied_transactions, num_root_transactions);
+        assert_eq!(
+            summary.num_shadow_denied_transactions,
+            num_user_transactions
+        );
+        assert_eq!(summary.rule_matches[0].rule_type, "deny");
+        assert_eq!(summary.rule_matches[0].num_matches, num_root_transactions);
+        assert_eq!(summary.rule_matches[1].rule_type, "allow");
+        assert_eq!(
+            summary.rule_matches[1].num_matches,
+            num_user_transactions - num_root_transactions
+        );
+        assert_eq!(
+            summary.shadow_rule_matches[0].num_matches,
+            num_user_transactions
+        );
+        for rule_match in summary
+            .rule_matches
+            .iter()
+            .chain(summary.shadow_rule_matches.iter())
+        {
+            assert!(rule_match.sampled_versions.len() <= max_sampled_versions);
+        }
+
+        // Evaluate the filter over an empty range, and verify that nothing is matched
+        let summary = evaluate_transaction_filter(
+            aptos_db.as_ref(),
+            &transaction_filter,
+            synced_version + 1,
+            synced_version + 10,
+            max_sampled_versions,
+        )
+        .unwrap();
+        assert_eq!(summary.num_user_transactions, 0);
+        assert_eq!(summary.num_denied_transactions, 0);
+        assert_eq!(summary.num_shadow_denied_transactions, 0);
+        assert_eq!(summary.shadow_rule_matches[0].num_matches, 0);
+    }
+}
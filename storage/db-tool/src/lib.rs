@@ -6,6 +6,7 @@ extern crate core;
 mod backup;
 mod backup_maintenance;
 mod bootstrap;
+mod evaluate_transaction_filter;
 mod gen_replay_verify_jobs;
 mod replay_on_archive;
 mod replay_verify;
@@ -41,6 +42,8 @@ pub enum DBTool {
     Restore(restore::Command),
 
     ReplayOnArchive(replay_on_archive::Opt),
+
+    EvaluateTransactionFilter(evaluate_transaction_filter::Opt),
 }
 
 impl DBTool {
@@ -58,6 +61,7 @@ impl DBTool {
             DBTool::GenReplayVerifyJobs(cmd) => cmd.run().await,
             DBTool::Restore(cmd) => cmd.run().await,
             DBTool::ReplayOnArchive(cmd) => cmd.run().await,
+            DBTool::EvaluateTransactionFilter(cmd) => cmd.run().await,
         }
     }
 }
@@ -78,10 +78,27 @@ async fn test_move_compile_flow() {
     }
 
     // Run tests to ensure they work too
-    match cli.test_package(named_addresses.clone(), None).await {
+    match cli.test_package(named_addresses.clone(), None, false).await {
         Ok(result) => assert_eq!("Success", result),
         Err(err) => panic!("Error testing: {:?}", err),
     }
+
+    // Run tests with gas profiling, and check that a gas report is saved for the test
+    let report_dir = move_dir.join("build").join("gas-profiling");
+    match cli.test_package(named_addresses.clone(), None, true).await {
+        Ok(result) => assert_eq!(
+            format!("Success. Gas reports saved to {}", report_dir.display()),
+            result
+        ),
+        Err(err) => panic!("Error testing with gas profiling: {:?}", err),
+    }
+    let report_names: Vec<String> = std::fs::read_dir(&report_dir)
+        .expect("Expect a gas report directory")
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+        .collect();
+    assert!(report_names
+        .iter()
+        .any(|name| name.ends_with("-message_tests-sender_can_set_message")));
 }
 
 #[tokio::test]
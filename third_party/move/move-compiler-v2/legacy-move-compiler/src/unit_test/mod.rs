@@ -44,10 +44,46 @@ pub struct ModuleTestPlan {
 #[derive(Debug, Clone)]
 pub struct TestCase {
     pub test_name: TestName,
+    /// The values of the parameters assigned in the test attribute, in parameter order.
     pub arguments: Vec<MoveValue>,
+    /// The parameters which are not assigned in the test attribute, and whose values are
+    /// generated instead. Only tests attributed as `#[fuzz]` have such parameters, and they are
+    /// run for many generated inputs.
+    pub generated_parameters: Vec<GeneratedParameter>,
     pub expected_failure: Option<ExpectedFailure>,
 }
 
+#[derive(Debug, Clone)]
+pub struct GeneratedParameter {
+    /// The position of the parameter in the test function's signature.
+    pub index: usize,
+    pub name: String,
+    pub ty: GeneratedType,
+}
+
+/// The type of a generated test parameter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GeneratedType {
+    Bool,
+    U8,
+    U16,
+    U32,
+    U64,
+    U128,
+    U256,
+    Address,
+    Vector(Box<GeneratedType>),
+    /// `std::string::String`, which is generated as valid UTF-8.
+    String,
+    /// `std::option::Option<T>`, which is generated with at most one element.
+    Option(Box<GeneratedType>),
+    /// A struct with the `drop` ability, with its fields in declaration order.
+    Struct {
+        name: String,
+        fields: Vec<(String, GeneratedType)>,
+    },
+}
+
 #[derive(Debug, Clone)]
 pub enum ExpectedFailure {
     // expected failure, but codes are not checked
@@ -90,6 +126,29 @@ impl ModuleTestPlan {
     }
 }
 
+impl TestCase {
+    /// Returns the arguments to call the test function with, given values for the generated
+    /// parameters in the order of `generated_parameters`.
+    pub fn arguments_with(&self, generated: Vec<MoveValue>) -> Vec<MoveValue> {
+        let num_params = self.arguments.len() + self.generated_parameters.len();
+        let mut fixed = self.arguments.iter().cloned();
+        let mut generated = self
+            .generated_parameters
+            .iter()
+            .map(|param| param.index)
+            .zip(generated)
+            .peekable();
+        (0..num_params)
+            .filter_map(|index| match generated.peek() {
+                Some((generated_index, _)) if *generated_index == index => {
+                    generated.next().map(|(_, value)| value)
+                },
+                _ => fixed.next(),
+            })
+            .collect()
+    }
+}
+
 impl TestPlan {
     pub fn new(
         tests: Vec<ModuleTestPlan>,
@@ -15,7 +15,10 @@ use crate::options::Options;
 use codespan_reporting::diagnostic::Severity;
 use legacy_move_compiler::{
     shared::known_attributes::{AttributeKind, TestingAttribute},
-    unit_test::{ExpectedFailure, ExpectedMoveError, ModuleTestPlan, TestCase},
+    unit_test::{
+        ExpectedFailure, ExpectedMoveError, GeneratedParameter, GeneratedType, ModuleTestPlan,
+        TestCase,
+    },
 };
 use move_command_line_common::{address::NumericalAddress, parser::NumberFormat};
 use move_core_types::{
@@ -117,9 +120,11 @@ fn build_test_info(
     let expected_failure_name = env.symbol_pool().make(TestingAttribute::EXPECTED_FAILURE);
     let test_name = env.symbol_pool().make(TestingAttribute::TEST);
     let test_only_name = env.symbol_pool().make(TestingAttribute::TEST_ONLY);
+    let fuzz_name = env.symbol_pool().make(TestingAttribute::FUZZ);
 
     let test_attribute_opt = attrs.iter().find(|a| a.name() == test_name);
     let abort_attribute_opt = attrs.iter().find(|a| a.name() == expected_failure_name);
+    let fuzz_attribute_opt = attrs.iter().find(|a| a.name() == fuzz_name);
 
     let test_attribute = match test_attribute_opt {
         None => {
@@ -132,6 +137,14 @@ fn build_test_info(
                 let abort_loc = env.get_node_loc(abort_id);
                 env.error_with_labels(&fn_id_loc, fn_msg, vec![(abort_loc, abort_msg.to_string())]);
             }
+            // neither can generated parameters
+            if let Some(fuzz_attribute) = fuzz_attribute_opt {
+                let fn_msg = "Only functions defined as a test with #[test] can also have a \
+                              #[fuzz] attribute";
+                let fuzz_msg = "Attributed as #[fuzz] here";
+                let fuzz_loc = env.get_node_loc(fuzz_attribute.node_id());
+                env.error_with_labels(&fn_id_loc, fn_msg, vec![(fuzz_loc, fuzz_msg.to_string())]);
+            }
             return None;
         },
         Some(test_attribute) => test_attribute,
@@ -160,7 +173,8 @@ fn build_test_info(
     let test_annotation_params = parse_test_attribute(env, test_attribute, 0);
 
     let mut arguments = Vec::new();
-    for param in function.get_parameters_ref() {
+    let mut generated_parameters = Vec::new();
+    for (index, param) in function.get_parameters_ref().iter().enumerate() {
         let Parameter(var, ty, var_loc) = &param;
 
         match test_annotation_params.get(var) {
@@ -185,9 +199,10 @@ fn build_test_info(
                 },
             },
             Some(value) => arguments.push(value.clone()),
-            None => {
+            None if fuzz_attribute_opt.is_none() => {
                 let missing_param_msg = "Missing test parameter assignment in test. Expected a \
-                                         parameter to be assigned in this attribute";
+                                         parameter to be assigned in this attribute, or the \
+                                         test to be attributed as #[fuzz]";
                 let invalid_test = "unable to generate test";
                 env.error_with_labels(&fn_id_loc, invalid_test, vec![
                     (test_attribute_loc.clone(), missing_param_msg.to_string()),
@@ -197,6 +212,32 @@ fn build_test_info(
                     ),
                 ]);
             },
+            // Parameters of #[fuzz] tests which are not assigned have their values generated, if
+            // possible.
+            None => match generated_type(env, ty) {
+                Ok(ty) => generated_parameters.push(GeneratedParameter {
+                    index,
+                    name: env.symbol_pool().string(*var).to_string(),
+                    ty,
+                }),
+                Err(reason) => {
+                    let missing_param_msg = format!(
+                        "Missing test parameter assignment in test. Expected a parameter to be \
+                         assigned in this attribute, since values of type `{}` cannot be \
+                         generated: {}",
+                        ty.display(&function.get_type_display_ctx()),
+                        reason
+                    );
+                    let invalid_test = "unable to generate test";
+                    env.error_with_labels(&fn_id_loc, invalid_test, vec![
+                        (test_attribute_loc.clone(), missing_param_msg),
+                        (
+                            var_loc.clone(),
+                            "Corresponding to this parameter".to_string(),
+                        ),
+                    ]);
+                },
+            },
         }
     }
 
@@ -208,10 +249,84 @@ fn build_test_info(
     Some(TestCase {
         test_name: fn_name_str.to_string(),
         arguments,
+        generated_parameters,
         expected_failure,
     })
 }
 
+/// Determines how values of type `ty` are generated for a test parameter. Returns the reason if
+/// they cannot be generated.
+fn generated_type(env: &GlobalEnv, ty: &Type) -> Result<GeneratedType, String> {
+    Ok(match ty {
+        Type::Primitive(PrimitiveType::Bool) => GeneratedType::Bool,
+        Type::Primitive(PrimitiveType::U8) => GeneratedType::U8,
+        Type::Primitive(PrimitiveType::U16) => GeneratedType::U16,
+        Type::Primitive(PrimitiveType::U32) => GeneratedType::U32,
+        Type::Primitive(PrimitiveType::U64) => GeneratedType::U64,
+        Type::Primitive(PrimitiveType::U128) => GeneratedType::U128,
+        Type::Primitive(PrimitiveType::U256) => GeneratedType::U256,
+        Type::Primitive(PrimitiveType::Address) => GeneratedType::Address,
+        Type::Vector(elem_ty) => GeneratedType::Vector(Box::new(generated_type(env, elem_ty)?)),
+        Type::Struct(mid, sid, inst) => {
+            let struct_env = env.get_module(*mid).into_struct(*sid);
+            let module_name = struct_env.module_env.get_name();
+            let is_std_struct = |module: &str, name: &str| {
+                *module_name.addr() == env.get_stdlib_address()
+                    && env.symbol_pool().string(module_name.name()).as_str() == module
+                    && env.symbol_pool().string(struct_env.get_name()).as_str() == name
+            };
+            if is_std_struct("string", "String") {
+                return Ok(GeneratedType::String);
+            }
+            if is_std_struct("option", "Option") {
+                let elem_ty = inst.first().expect("option has a type argument");
+                return Ok(GeneratedType::Option(Box::new(generated_type(
+                    env, elem_ty,
+                )?)));
+            }
+            let name = struct_env.get_full_name_str();
+            // Generated values may break the invariants of a struct, which is only acceptable
+            // for structs of the tested package (the primary targets) itself.
+            if !struct_env.module_env.is_primary_target() {
+                return Err(format!(
+                    "struct `{}` is not defined in the package of the test",
+                    name
+                ));
+            }
+            if !struct_env.get_abilities().has_drop() {
+                return Err(format!(
+                    "struct `{}` does not have the `drop` ability",
+                    name
+                ));
+            }
+            if struct_env.has_variants() {
+                return Err(format!("`{}` is an enum", name));
+            }
+            let fields = struct_env
+                .get_fields()
+                .map(|field| {
+                    Ok((
+                        env.symbol_pool().string(field.get_name()).to_string(),
+                        generated_type(env, &field.get_type().instantiate(inst))?,
+                    ))
+                })
+                .collect::<Result<_, String>>()?;
+            GeneratedType::Struct { name, fields }
+        },
+        Type::Primitive(PrimitiveType::Signer) => {
+            return Err("signers must be created from an assigned address".to_string())
+        },
+        Type::Reference(_, inner) if **inner == Type::Primitive(PrimitiveType::Signer) => {
+            return Err("signers must be created from an assigned address".to_string())
+        },
+        _ => {
+            return Err(
+                "only integer, bool, address, vector, and struct types are supported".to_string(),
+            )
+        },
+    })
+}
+
 //***************************************************************************
 // Attribute parsers
 //***************************************************************************
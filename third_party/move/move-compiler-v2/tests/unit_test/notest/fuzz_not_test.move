@@ -0,0 +1,8 @@
+// fuzz attributes can only be placed on #[test] functions
+module 0x1::A {
+    #[fuzz]
+    fun foo() { }
+
+    #[test_only, fuzz]
+    fun bar() { }
+}
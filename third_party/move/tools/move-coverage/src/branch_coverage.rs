@@ -0,0 +1,212 @@
+// Copyright (c) Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+//! Coverage of the conditional branches of functions, i.e., of both sides of each `BrTrue` and
+//! `BrFalse` instruction, located in the source through the source map.
+
+use crate::coverage_map::{ExecCoverageMap, FunctionCoverage};
+use move_binary_format::{
+    access::ModuleAccess,
+    file_format::{Bytecode, CodeOffset, FunctionDefinitionIndex},
+    CompiledModule,
+};
+use move_bytecode_source_map::source_map::SourceMap;
+use move_core_types::{identifier::Identifier, language_storage::ModuleId};
+use move_ir_types::location::Loc;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Coverage of the branches of a module.
+#[derive(Debug, Serialize)]
+pub struct ModuleBranchCoverage {
+    pub module_name: ModuleId,
+    /// The branches of each non-native function, in code order.
+    pub function_branches: BTreeMap<Identifier, Vec<BranchCoverage>>,
+}
+
+/// Coverage of a conditional branch instruction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BranchCoverage {
+    /// The offset of the branch instruction within its function.
+    pub code_offset: CodeOffset,
+    /// The source location of the branch instruction, i.e., of the condition.
+    pub loc: Option<Loc>,
+    /// How often the condition was true, or `None` if the branch was never executed.
+    pub true_count: Option<u64>,
+    /// How often the condition was false, or `None` if the branch was never executed.
+    pub false_count: Option<u64>,
+}
+
+impl BranchCoverage {
+    /// Returns whether both sides of the branch were taken.
+    pub fn is_covered(&self) -> bool {
+        self.true_count.unwrap_or(0) > 0 && self.false_count.unwrap_or(0) > 0
+    }
+}
+
+impl ModuleBranchCoverage {
+    pub fn new(
+        module: &CompiledModule,
+        source_map: &SourceMap,
+        coverage_map: &ExecCoverageMap,
+    ) -> Self {
+        let module_name = module.self_id();
+        let module_map = coverage_map
+            .module_maps
+            .get(&(*module_name.address(), module_name.name().to_owned()));
+        let function_branches = module
+            .function_defs()
+            .iter()
+            .enumerate()
+            .filter_map(|(function_def_idx, function_def)| {
+                let code_unit = function_def.code.as_ref()?;
+                let fn_handle = module.function_handle_at(function_def.function);
+                let fn_name = module.identifier_at(fn_handle.name).to_owned();
+                let empty_coverage = FunctionCoverage::new();
+                let function_coverage = module_map
+                    .and_then(|module_map| module_map.function_maps.get(&fn_name))
+                    .unwrap_or(&empty_coverage);
+                let function_def_idx = FunctionDefinitionIndex(function_def_idx as u16);
+                let branches = function_branch_coverage(&code_unit.code, function_coverage)
+                    .into_iter()
+                    .map(|mut branch| {
+                        branch.loc = source_map
+                            .get_code_location(function_def_idx, branch.code_offset)
+                            .ok();
+                        branch
+                    })
+                    .collect();
+                Some((fn_name, branches))
+            })
+            .collect();
+        Self {
+            module_name,
+            function_branches,
+        }
+    }
+}
+
+/// Computes the coverage of the branches in `code`, without source locations.
+///
+/// The coverage map only records execution counts of instructions. The count of a side of a
+/// branch is exact if its first instruction cannot be reached otherwise, and the count of the
+/// other side then follows from the count of the branch instruction. If neither side is exact,
+/// each side is approximated by the count of its first instruction, capped by the count of the
+/// branch instruction.
+pub fn function_branch_coverage(
+    code: &[Bytecode],
+    function_coverage: &FunctionCoverage,
+) -> Vec<BranchCoverage> {
+    let count =
+        |code_offset: CodeOffset| *function_coverage.get(&(code_offset as u64)).unwrap_or(&0);
+
+    // The function entry is an implicit predecessor of the first instruction.
+    let mut predecessors: BTreeMap<CodeOffset, usize> = BTreeMap::from([(0, 1)]);
+    for code_offset in 0..code.len() as CodeOffset {
+        for successor in Bytecode::get_successors(code_offset, code) {
+            *predecessors.entry(successor).or_default() += 1;
+        }
+    }
+
+    code.iter()
+        .enumerate()
+        .filter_map(|(code_offset, instr)| {
+            let code_offset = code_offset as CodeOffset;
+            let (true_dest, false_dest) = match instr {
+                Bytecode::BrTrue(target) => (*target, code_offset + 1),
+                Bytecode::BrFalse(target) => (code_offset + 1, *target),
+                _ => return None,
+            };
+            let branch_count = count(code_offset);
+            let (true_count, false_count) = if branch_count == 0 {
+                (None, None)
+            } else {
+                let exact_count = |dest: CodeOffset| {
+                    (true_dest != false_dest && predecessors.get(&dest) == Some(&1))
+                        .then(|| count(dest))
+                };
+                let approximate_count = |dest: CodeOffset| count(dest).min(branch_count);
+                let (true_count, false_count) =
+                    match (exact_count(true_dest), exact_count(false_dest)) {
+                        (Some(true_count), Some(false_count)) => (true_count, false_count),
+                        (Some(true_count), None) => {
+                            (true_count, branch_count.saturating_sub(true_count))
+                        },
+                        (None, Some(false_count)) => {
+                            (branch_count.saturating_sub(false_count), false_count)
+                        },
+                        (None, None) => {
+                            (approximate_count(true_dest), approximate_count(false_dest))
+                        },
+                    };
+                (Some(true_count), Some(false_count))
+            };
+            Some(BranchCoverage {
+                code_offset,
+                loc: None,
+                true_count,
+                false_count,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{function_branch_coverage, BranchCoverage};
+    use crate::coverage_map::FunctionCoverage;
+    use move_binary_format::file_format::Bytecode;
+
+    #[test]
+    fn test_function_branch_coverage() {
+        // if (arg) { 1 } else { 2 }, executed 3 times with the condition true once.
+        let code = vec![
+            Bytecode::LdTrue,
+            Bytecode::BrFalse(4),
+            Bytecode::LdU64(1),
+            Bytecode::Branch(5),
+            Bytecode::LdU64(2),
+            Bytecode::Ret,
+        ];
+        let coverage = FunctionCoverage::from([(0, 3), (1, 3), (2, 1), (3, 1), (4, 2), (5, 3)]);
+        assert_eq!(function_branch_coverage(&code, &coverage), vec![
+            BranchCoverage {
+                code_offset: 1,
+                loc: None,
+                true_count: Some(1),
+                false_count: Some(2),
+            }
+        ]);
+
+        // A loop whose exit is shared with a `break`, so that only the body count is exact.
+        let code = vec![
+            Bytecode::LdTrue,
+            Bytecode::BrFalse(6),
+            Bytecode::LdTrue,
+            Bytecode::BrTrue(6),
+            Bytecode::Branch(0),
+            Bytecode::Ret,
+            Bytecode::Ret,
+        ];
+        let coverage = FunctionCoverage::from([(0, 4), (1, 4), (2, 3), (3, 3), (4, 3), (6, 1)]);
+        let branches = function_branch_coverage(&code, &coverage);
+        assert_eq!(
+            branches
+                .iter()
+                .map(|branch| (branch.true_count, branch.false_count))
+                .collect::<Vec<_>>(),
+            vec![(Some(3), Some(1)), (Some(0), Some(3))]
+        );
+        assert!(branches[0].is_covered());
+        assert!(!branches[1].is_covered());
+
+        // Nothing is known about branches which were never executed.
+        let coverage = FunctionCoverage::new();
+        assert_eq!(
+            function_branch_coverage(&code, &coverage)[0].true_count,
+            None
+        );
+    }
+}
@@ -0,0 +1,288 @@
+// Copyright (c) Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+//! Output of coverage in the Cobertura XML format.
+
+use crate::line_coverage::{count_outcomes, PackageLineCoverage, SourceBranchCoverage};
+use std::{
+    collections::BTreeMap,
+    io::{self, Write},
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Writes a Cobertura report with one package, containing one class per module. File names are
+/// made relative to `source_root`, which is listed as the only source directory.
+pub fn output_cobertura<W: Write>(
+    coverage: &PackageLineCoverage,
+    package_name: &str,
+    source_root: &Path,
+    writer: &mut W,
+) -> io::Result<()> {
+    let modules = coverage
+        .files
+        .iter()
+        .flat_map(|file| file.modules.iter().map(move |module| (file, module)));
+    let (mut lines_valid, mut lines_covered, mut branches_valid, mut branches_covered) =
+        (0, 0, 0, 0);
+    for (_, module) in modules.clone() {
+        let (lines, covered) = module.line_summary();
+        lines_valid += lines;
+        lines_covered += covered;
+        let (branches, covered) = module.branch_summary();
+        branches_valid += branches;
+        branches_covered += covered;
+    }
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+
+    writeln!(writer, r#"<?xml version="1.0" ?>"#)?;
+    writeln!(
+        writer,
+        r#"<!DOCTYPE coverage SYSTEM "http://cobertura.sourceforge.net/xml/coverage-04.dtd">"#
+    )?;
+    writeln!(
+        writer,
+        r#"<coverage line-rate="{}" branch-rate="{}" lines-covered="{}" lines-valid="{}" branches-covered="{}" branches-valid="{}" complexity="0" version="0.1" timestamp="{}">"#,
+        rate(lines_covered, lines_valid),
+        rate(branches_covered, branches_valid),
+        lines_covered,
+        lines_valid,
+        branches_covered,
+        branches_valid,
+        timestamp
+    )?;
+    writeln!(writer, "  <sources>")?;
+    writeln!(
+        writer,
+        "    <source>{}</source>",
+        escape(&source_root.display().to_string())
+    )?;
+    writeln!(writer, "  </sources>")?;
+    writeln!(writer, "  <packages>")?;
+    writeln!(
+        writer,
+        r#"    <package name="{}" line-rate="{}" branch-rate="{}" complexity="0">"#,
+        escape(package_name),
+        rate(lines_covered, lines_valid),
+        rate(branches_covered, branches_valid)
+    )?;
+    writeln!(writer, "      <classes>")?;
+    for (file, module) in modules {
+        let file_name = file
+            .source_path
+            .strip_prefix(source_root)
+            .unwrap_or(&file.source_path);
+        let (lines, covered) = module.line_summary();
+        let (branches, branches_taken) = module.branch_summary();
+        writeln!(
+            writer,
+            r#"        <class name="{}" filename="{}" line-rate="{}" branch-rate="{}" complexity="0">"#,
+            escape(&module.module_name),
+            escape(&file_name.display().to_string()),
+            rate(covered, lines),
+            rate(branches_taken, branches)
+        )?;
+        writeln!(writer, "          <methods>")?;
+        for function in &module.functions {
+            let covered = function.lines.values().filter(|hits| **hits > 0).count();
+            let (function_branches, function_branches_taken) = function.branch_summary();
+            writeln!(
+                writer,
+                r#"            <method name="{}" signature="" line-rate="{}" branch-rate="{}" complexity="0">"#,
+                escape(&function.name),
+                rate(covered, function.lines.len()),
+                rate(function_branches_taken, function_branches)
+            )?;
+            output_lines(
+                writer,
+                14,
+                &function.lines,
+                &branches_by_line(function.branches.iter()),
+            )?;
+            writeln!(writer, "            </method>")?;
+        }
+        writeln!(writer, "          </methods>")?;
+        let branches = branches_by_line(
+            module
+                .functions
+                .iter()
+                .flat_map(|function| function.branches.iter()),
+        );
+        output_lines(writer, 10, &module.lines, &branches)?;
+        writeln!(writer, "        </class>")?;
+    }
+    writeln!(writer, "      </classes>")?;
+    writeln!(writer, "    </package>")?;
+    writeln!(writer, "  </packages>")?;
+    writeln!(writer, "</coverage>")
+}
+
+fn output_lines<W: Write>(
+    writer: &mut W,
+    indent: usize,
+    lines: &BTreeMap<u32, u64>,
+    branches: &BTreeMap<u32, Vec<&SourceBranchCoverage>>,
+) -> io::Result<()> {
+    writeln!(writer, "{:indent$}<lines>", "")?;
+    for (line, hits) in lines {
+        match branches.get(line) {
+            Some(line_branches) => {
+                let (total, taken) = count_outcomes(line_branches.iter().copied());
+                writeln!(
+                    writer,
+                    r#"{:indent$}  <line number="{}" hits="{}" branch="true" condition-coverage="{}% ({}/{})"/>"#,
+                    "",
+                    line,
+                    hits,
+                    taken * 100 / total.max(1),
+                    taken,
+                    total
+                )?;
+            },
+            None => writeln!(
+                writer,
+                r#"{:indent$}  <line number="{}" hits="{}" branch="false"/>"#,
+                "", line, hits
+            )?,
+        }
+    }
+    writeln!(writer, "{:indent$}</lines>", "")
+}
+
+fn branches_by_line<'a>(
+    branches: impl Iterator<Item = &'a SourceBranchCoverage>,
+) -> BTreeMap<u32, Vec<&'a SourceBranchCoverage>> {
+    let mut branches_by_line: BTreeMap<_, Vec<_>> = BTreeMap::new();
+    for branch in branches {
+        branches_by_line
+            .entry(branch.line)
+            .or_default()
+            .push(branch);
+    }
+    branches_by_line
+}
+
+/// The fraction of covered items, which is 1 if there are none.
+fn rate(covered: usize, total: usize) -> String {
+    if total == 0 {
+        "1".to_string()
+    } else {
+        format!("{:.4}", covered as f64 / total as f64)
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::output_cobertura;
+    use crate::line_coverage::{
+        FileLineCoverage, FunctionLineCoverage, ModuleLineCoverage, PackageLineCoverage,
+        SourceBranchCoverage,
+    };
+    use std::{
+        collections::BTreeMap,
+        path::{Path, PathBuf},
+    };
+
+    #[test]
+    fn test_output_cobertura() {
+        let bump_lines = BTreeMap::from([(3, 2), (4, 2), (5, 0)]);
+        let reset_lines = BTreeMap::from([(9, 0)]);
+        let coverage = PackageLineCoverage {
+            files: vec![FileLineCoverage {
+                source_path: PathBuf::from("/pkg/sources/counter.move"),
+                modules: vec![ModuleLineCoverage {
+                    module_name: "0x1::counter".to_string(),
+                    functions: vec![
+                        FunctionLineCoverage {
+                            name: "bump".to_string(),
+                            line: 2,
+                            hits: 2,
+                            lines: bump_lines.clone(),
+                            branches: vec![SourceBranchCoverage {
+                                code_offset: 4,
+                                line: 3,
+                                column: 9,
+                                true_count: Some(0),
+                                false_count: Some(2),
+                            }],
+                        },
+                        // Never executed, so nothing is known about its branch.
+                        FunctionLineCoverage {
+                            name: "reset".to_string(),
+                            line: 8,
+                            hits: 0,
+                            lines: reset_lines.clone(),
+                            branches: vec![SourceBranchCoverage {
+                                code_offset: 1,
+                                line: 9,
+                                column: 13,
+                                true_count: None,
+                                false_count: None,
+                            }],
+                        },
+                    ],
+                    lines: bump_lines.into_iter().chain(reset_lines).collect(),
+                }],
+            }],
+        };
+
+        let mut output = vec![];
+        output_cobertura(&coverage, "counter", Path::new("/pkg"), &mut output).unwrap();
+        // The timestamp is the only part of the report which varies between runs.
+        let output = String::from_utf8(output).unwrap();
+        let (before, after) = output.split_once(r#"timestamp=""#).unwrap();
+        let (_, after) = after.split_once('"').unwrap();
+        let output = format!(r#"{}timestamp="0"{}"#, before, after);
+        assert_eq!(
+            output,
+            r#"<?xml version="1.0" ?>
+<!DOCTYPE coverage SYSTEM "http://cobertura.sourceforge.net/xml/coverage-04.dtd">
+<coverage line-rate="0.5000" branch-rate="0.2500" lines-covered="2" lines-valid="4" branches-covered="1" branches-valid="4" complexity="0" version="0.1" timestamp="0">
+  <sources>
+    <source>/pkg</source>
+  </sources>
+  <packages>
+    <package name="counter" line-rate="0.5000" branch-rate="0.2500" complexity="0">
+      <classes>
+        <class name="0x1::counter" filename="sources/counter.move" line-rate="0.5000" branch-rate="0.2500" complexity="0">
+          <methods>
+            <method name="bump" signature="" line-rate="0.6667" branch-rate="0.5000" complexity="0">
+              <lines>
+                <line number="3" hits="2" branch="true" condition-coverage="50% (1/2)"/>
+                <line number="4" hits="2" branch="false"/>
+                <line number="5" hits="0" branch="false"/>
+              </lines>
+            </method>
+            <method name="reset" signature="" line-rate="0.0000" branch-rate="0.0000" complexity="0">
+              <lines>
+                <line number="9" hits="0" branch="true" condition-coverage="0% (0/2)"/>
+              </lines>
+            </method>
+          </methods>
+          <lines>
+            <line number="3" hits="2" branch="true" condition-coverage="50% (1/2)"/>
+            <line number="4" hits="2" branch="false"/>
+            <line number="5" hits="0" branch="false"/>
+            <line number="9" hits="0" branch="true" condition-coverage="0% (0/2)"/>
+          </lines>
+        </class>
+      </classes>
+    </package>
+  </packages>
+</coverage>
+"#
+        );
+    }
+}
@@ -0,0 +1,163 @@
+// Copyright (c) Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+//! Output of coverage in the LCOV tracefile format, as understood by `genhtml` and most CI
+//! coverage services.
+
+use crate::line_coverage::{count_outcomes, PackageLineCoverage};
+use std::{
+    collections::BTreeMap,
+    io::{self, Write},
+};
+
+/// Writes one LCOV record per source file. Functions are named `<module>::<function>`, since a
+/// file may define several modules.
+pub fn output_lcov<W: Write>(
+    coverage: &PackageLineCoverage,
+    test_name: &str,
+    writer: &mut W,
+) -> io::Result<()> {
+    for file in &coverage.files {
+        writeln!(writer, "TN:{}", test_name)?;
+        writeln!(writer, "SF:{}", file.source_path.display())?;
+
+        let functions = file.modules.iter().flat_map(|module| {
+            module
+                .functions
+                .iter()
+                .map(move |function| (&module.module_name, function))
+        });
+        let mut functions_hit = 0;
+        for (module_name, function) in functions.clone() {
+            writeln!(
+                writer,
+                "FN:{},{}::{}",
+                function.line, module_name, function.name
+            )?;
+        }
+        for (module_name, function) in functions.clone() {
+            writeln!(
+                writer,
+                "FNDA:{},{}::{}",
+                function.hits, module_name, function.name
+            )?;
+            if function.hits > 0 {
+                functions_hit += 1;
+            }
+        }
+        writeln!(writer, "FNF:{}", functions.clone().count())?;
+        writeln!(writer, "FNH:{}", functions_hit)?;
+
+        for (_, function) in functions.clone() {
+            for branch in &function.branches {
+                // Each branch instruction forms a block, with the true side listed first.
+                for (index, count) in branch.outcomes().iter().enumerate() {
+                    let taken = match count {
+                        Some(count) => count.to_string(),
+                        None => "-".to_string(),
+                    };
+                    writeln!(
+                        writer,
+                        "BRDA:{},{},{},{}",
+                        branch.line, branch.code_offset, index, taken
+                    )?;
+                }
+            }
+        }
+        let (branches_found, branches_hit) =
+            count_outcomes(functions.flat_map(|(_, function)| function.branches.iter()));
+        writeln!(writer, "BRF:{}", branches_found)?;
+        writeln!(writer, "BRH:{}", branches_hit)?;
+
+        let mut lines: BTreeMap<u32, u64> = BTreeMap::new();
+        for module in &file.modules {
+            for (line, hits) in &module.lines {
+                let file_hits = lines.entry(*line).or_insert(0);
+                *file_hits = (*hits).max(*file_hits);
+            }
+        }
+        for (line, hits) in &lines {
+            writeln!(writer, "DA:{},{}", line, hits)?;
+        }
+        writeln!(writer, "LF:{}", lines.len())?;
+        writeln!(
+            writer,
+            "LH:{}",
+            lines.values().filter(|hits| **hits > 0).count()
+        )?;
+        writeln!(writer, "end_of_record")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::output_lcov;
+    use crate::line_coverage::{
+        FileLineCoverage, FunctionLineCoverage, ModuleLineCoverage, PackageLineCoverage,
+        SourceBranchCoverage,
+    };
+    use std::{collections::BTreeMap, path::PathBuf};
+
+    #[test]
+    fn test_output_lcov() {
+        let lines = BTreeMap::from([(3, 2), (4, 2), (5, 0)]);
+        let coverage = PackageLineCoverage {
+            files: vec![FileLineCoverage {
+                source_path: PathBuf::from("sources/counter.move"),
+                modules: vec![ModuleLineCoverage {
+                    module_name: "0x1::counter".to_string(),
+                    functions: vec![
+                        FunctionLineCoverage {
+                            name: "bump".to_string(),
+                            line: 2,
+                            hits: 2,
+                            lines: lines.clone(),
+                            branches: vec![SourceBranchCoverage {
+                                code_offset: 4,
+                                line: 3,
+                                column: 9,
+                                true_count: Some(0),
+                                false_count: Some(2),
+                            }],
+                        },
+                        FunctionLineCoverage {
+                            name: "reset".to_string(),
+                            line: 8,
+                            hits: 0,
+                            lines: BTreeMap::new(),
+                            branches: vec![],
+                        },
+                    ],
+                    lines,
+                }],
+            }],
+        };
+
+        let mut output = vec![];
+        output_lcov(&coverage, "counter", &mut output).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "TN:counter\n\
+             SF:sources/counter.move\n\
+             FN:2,0x1::counter::bump\n\
+             FN:8,0x1::counter::reset\n\
+             FNDA:2,0x1::counter::bump\n\
+             FNDA:0,0x1::counter::reset\n\
+             FNF:2\n\
+             FNH:1\n\
+             BRDA:3,4,0,0\n\
+             BRDA:3,4,1,2\n\
+             BRF:2\n\
+             BRH:1\n\
+             DA:3,2\n\
+             DA:4,2\n\
+             DA:5,0\n\
+             LF:3\n\
+             LH:2\n\
+             end_of_record\n"
+        );
+    }
+}
@@ -6,7 +6,11 @@ use crate::summary::ModuleSummary;
 use move_binary_format::CompiledModule;
 use std::io::Write;
 
+pub mod branch_coverage;
+pub mod cobertura;
 pub mod coverage_map;
+pub mod lcov;
+pub mod line_coverage;
 pub mod source_coverage;
 pub mod summary;
 
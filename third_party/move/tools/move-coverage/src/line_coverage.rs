@@ -0,0 +1,388 @@
+// Copyright (c) Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+//! Line, function, and branch coverage of source files, as consumed by the LCOV and Cobertura
+//! report formats.
+
+use crate::{
+    branch_coverage::function_branch_coverage,
+    coverage_map::{CoverageMap, FunctionCoverage},
+};
+use anyhow::{bail, Context, Result};
+use codespan::{FileId, Files};
+use move_binary_format::{
+    access::ModuleAccess,
+    file_format::{CodeOffset, FunctionDefinitionIndex},
+    CompiledModule,
+};
+use move_bytecode_source_map::source_map::SourceMap;
+use move_ir_types::location::Loc;
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Coverage of the source files of a package.
+#[derive(Debug, Default, Serialize)]
+pub struct PackageLineCoverage {
+    pub files: Vec<FileLineCoverage>,
+}
+
+/// Coverage of a single source file, which may define several modules.
+#[derive(Debug, Serialize)]
+pub struct FileLineCoverage {
+    pub source_path: PathBuf,
+    pub modules: Vec<ModuleLineCoverage>,
+}
+
+/// Coverage of a module. All line numbers are 1-based.
+#[derive(Debug, Default, Serialize)]
+pub struct ModuleLineCoverage {
+    /// The fully qualified name of the module, e.g. `0x1::coin`.
+    pub module_name: String,
+    pub functions: Vec<FunctionLineCoverage>,
+    /// Execution count of each executable line.
+    pub lines: BTreeMap<u32, u64>,
+}
+
+/// Coverage of a (non-native) function.
+#[derive(Debug, Serialize)]
+pub struct FunctionLineCoverage {
+    pub name: String,
+    /// The line of the function definition.
+    pub line: u32,
+    /// Number of times the function was entered.
+    pub hits: u64,
+    /// Execution count of each executable line of the function.
+    pub lines: BTreeMap<u32, u64>,
+    /// The conditional branches of the function, in code order.
+    pub branches: Vec<SourceBranchCoverage>,
+}
+
+/// Coverage of a conditional branch, see `branch_coverage::BranchCoverage`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SourceBranchCoverage {
+    /// The offset of the branch instruction within its function.
+    pub code_offset: CodeOffset,
+    /// The line and column of the condition.
+    pub line: u32,
+    pub column: u32,
+    /// How often the condition was true, or `None` if the branch was never executed.
+    pub true_count: Option<u64>,
+    /// How often the condition was false, or `None` if the branch was never executed.
+    pub false_count: Option<u64>,
+}
+
+impl SourceBranchCoverage {
+    /// Returns the counts of both sides of the branch, true side first.
+    pub fn outcomes(&self) -> [Option<u64>; 2] {
+        [self.true_count, self.false_count]
+    }
+
+    /// Returns whether both sides of the branch were taken.
+    pub fn is_covered(&self) -> bool {
+        self.outcomes().iter().all(|count| count.unwrap_or(0) > 0)
+    }
+}
+
+impl PackageLineCoverage {
+    /// Computes the coverage of the given modules, each accompanied by its source map and the
+    /// path of the file it was compiled from.
+    pub fn new(
+        coverage_map: &CoverageMap,
+        root_modules: Vec<(&CompiledModule, &SourceMap, &Path)>,
+    ) -> Result<Self> {
+        let unified_exec_map = coverage_map.to_unified_exec_map();
+        let mut files: BTreeMap<PathBuf, (Files<String>, FileId, Vec<ModuleLineCoverage>)> =
+            BTreeMap::new();
+
+        for (module, source_map, source_path) in root_modules {
+            if !files.contains_key(source_path) {
+                let contents = fs::read_to_string(source_path).with_context(|| {
+                    format!("failed to read source file {}", source_path.display())
+                })?;
+                let mut source_files = Files::new();
+                let file_id = source_files.add(source_path.as_os_str().to_os_string(), contents);
+                files.insert(source_path.to_path_buf(), (source_files, file_id, vec![]));
+            }
+            let (source_files, file_id, modules) =
+                files.get_mut(source_path).expect("file added above");
+            let file_id = *file_id;
+            if !source_map.check(source_files.source(file_id)) {
+                bail!(
+                    "file contents {} out of sync with source map",
+                    source_path.display()
+                );
+            }
+            let location_of = |loc: Loc| -> Option<(u32, u32)> {
+                if loc.file_hash() != source_map.definition_location.file_hash() {
+                    return None;
+                }
+                let location = source_files.location(file_id, loc.start()).ok()?;
+                Some((location.line.0 + 1, location.column.0 + 1))
+            };
+            let line_of = |loc: Loc| location_of(loc).map(|(line, _)| line);
+
+            let module_id = module.self_id();
+            let module_map = unified_exec_map
+                .module_maps
+                .get(&(*module_id.address(), module_id.name().to_owned()));
+            let mut module_coverage = ModuleLineCoverage {
+                module_name: format!(
+                    "{}::{}",
+                    module_id.address().to_hex_literal(),
+                    module_id.name()
+                ),
+                ..ModuleLineCoverage::default()
+            };
+
+            for (function_def_idx, function_def) in module.function_defs().iter().enumerate() {
+                let code_unit = match &function_def.code {
+                    Some(code_unit) => code_unit,
+                    None => continue,
+                };
+                let function_def_idx = FunctionDefinitionIndex(function_def_idx as u16);
+                let fn_handle = module.function_handle_at(function_def.function);
+                let fn_name = module.identifier_at(fn_handle.name);
+                let empty_coverage = FunctionCoverage::new();
+                let function_coverage = module_map
+                    .and_then(|module_map| module_map.function_maps.get(fn_name))
+                    .unwrap_or(&empty_coverage);
+                let count = |code_offset: CodeOffset| {
+                    *function_coverage.get(&(code_offset as u64)).unwrap_or(&0)
+                };
+
+                let mut lines = BTreeMap::new();
+                for code_offset in 0..code_unit.code.len() as CodeOffset {
+                    let line = source_map
+                        .get_code_location(function_def_idx, code_offset)
+                        .ok()
+                        .and_then(line_of);
+                    if let Some(line) = line {
+                        let hits = lines.entry(line).or_insert(0);
+                        *hits = count(code_offset).max(*hits);
+                    }
+                }
+
+                let branches = function_branch_coverage(&code_unit.code, function_coverage)
+                    .into_iter()
+                    .filter_map(|branch| {
+                        let (line, column) = source_map
+                            .get_code_location(function_def_idx, branch.code_offset)
+                            .ok()
+                            .and_then(location_of)?;
+                        Some(SourceBranchCoverage {
+                            code_offset: branch.code_offset,
+                            line,
+                            column,
+                            true_count: branch.true_count,
+                            false_count: branch.false_count,
+                        })
+                    })
+                    .collect();
+
+                let line = source_map
+                    .get_function_source_map(function_def_idx)
+                    .ok()
+                    .and_then(|function_map| line_of(function_map.definition_location))
+                    .or_else(|| lines.keys().next().copied())
+                    .unwrap_or(1);
+                for (line, hits) in &lines {
+                    let module_hits = module_coverage.lines.entry(*line).or_insert(0);
+                    *module_hits = (*hits).max(*module_hits);
+                }
+                module_coverage.functions.push(FunctionLineCoverage {
+                    name: fn_name.to_string(),
+                    line,
+                    hits: count(0),
+                    lines,
+                    branches,
+                });
+            }
+            modules.push(module_coverage);
+        }
+
+        Ok(Self {
+            files: files
+                .into_iter()
+                .map(|(source_path, (_, _, modules))| FileLineCoverage {
+                    source_path,
+                    modules,
+                })
+                .collect(),
+        })
+    }
+}
+
+impl ModuleLineCoverage {
+    /// Returns the number of executable lines and the number of covered ones.
+    pub fn line_summary(&self) -> (usize, usize) {
+        (
+            self.lines.len(),
+            self.lines.values().filter(|hits| **hits > 0).count(),
+        )
+    }
+
+    /// Returns the number of branch outcomes (two per branch) and the number of taken ones.
+    pub fn branch_summary(&self) -> (usize, usize) {
+        count_outcomes(
+            self.functions
+                .iter()
+                .flat_map(|function| function.branches.iter()),
+        )
+    }
+}
+
+impl FunctionLineCoverage {
+    /// Returns the number of branch outcomes (two per branch) and the number of taken ones.
+    pub fn branch_summary(&self) -> (usize, usize) {
+        count_outcomes(self.branches.iter())
+    }
+}
+
+/// Returns the number of outcomes of the given branches and the number of taken ones.
+pub fn count_outcomes<'a>(
+    branches: impl Iterator<Item = &'a SourceBranchCoverage>,
+) -> (usize, usize) {
+    branches
+        .flat_map(|branch| branch.outcomes())
+        .fold((0, 0), |(total, taken), count| {
+            (total + 1, taken + (count.unwrap_or(0) > 0) as usize)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PackageLineCoverage, SourceBranchCoverage};
+    use crate::coverage_map::{CoverageMap, ExecCoverageMap};
+    use move_binary_format::file_format::{basic_test_module, Bytecode, FunctionDefinitionIndex};
+    use move_bytecode_source_map::source_map::SourceMap;
+    use move_command_line_common::files::FileHash;
+    use move_core_types::{account_address::AccountAddress, identifier::Identifier};
+    use move_ir_types::location::Loc;
+    use std::{collections::BTreeMap, fs};
+    use tempfile::TempDir;
+
+    const SOURCE: &str = r"module 0x0::counter {
+    fun bump(): u64 {
+        if (true) {
+            1
+        } else 2
+    }
+}
+";
+
+    fn loc_of(needle: &str) -> Loc {
+        let start = SOURCE.find(needle).unwrap();
+        Loc::new(
+            FileHash::new(SOURCE),
+            start as u32,
+            (start + needle.len()) as u32,
+        )
+    }
+
+    fn coverage_map(counts: &[(u64, u64)]) -> CoverageMap {
+        let mut exec_map = ExecCoverageMap::new("test".to_string());
+        for (pc, count) in counts {
+            exec_map.insert_multi(
+                AccountAddress::ZERO,
+                Identifier::new("counter").unwrap(),
+                Identifier::new("bump").unwrap(),
+                *pc,
+                *count,
+            );
+        }
+        CoverageMap {
+            exec_maps: BTreeMap::from([("test".to_string(), exec_map)]),
+        }
+    }
+
+    #[test]
+    fn test_package_line_coverage() {
+        // if (true) { 1 } else { 2 }, with each instruction mapped to the line it is on.
+        let mut module = basic_test_module();
+        module.identifiers[0] = Identifier::new("counter").unwrap();
+        module.identifiers[1] = Identifier::new("bump").unwrap();
+        module.function_defs[0].code.as_mut().unwrap().code = vec![
+            Bytecode::LdTrue,
+            Bytecode::BrFalse(4),
+            Bytecode::LdU64(1),
+            Bytecode::Branch(5),
+            Bytecode::LdU64(2),
+            Bytecode::Ret,
+        ];
+        let function_def_idx = FunctionDefinitionIndex(0);
+        let mut source_map = SourceMap::new(loc_of("counter"), None);
+        source_map
+            .add_top_level_function_mapping(function_def_idx, loc_of("bump"), false)
+            .unwrap();
+        for (code_offset, needle) in [(0, "true"), (2, "1"), (4, "2"), (5, "    }\n}")] {
+            source_map
+                .add_code_mapping(function_def_idx, code_offset, loc_of(needle))
+                .unwrap();
+        }
+        let dir = TempDir::new().unwrap();
+        let source_path = dir.path().join("counter.move");
+        fs::write(&source_path, SOURCE).unwrap();
+        let line_coverage = |coverage_map: &CoverageMap| {
+            let coverage =
+                PackageLineCoverage::new(coverage_map, vec![(&module, &source_map, &source_path)])
+                    .unwrap();
+            assert_eq!(coverage.files.len(), 1);
+            assert_eq!(coverage.files[0].source_path, source_path);
+            assert_eq!(coverage.files[0].modules.len(), 1);
+            coverage.files.into_iter().next().unwrap().modules.remove(0)
+        };
+
+        // Executed 3 times, with the condition true once.
+        let module_coverage = line_coverage(&coverage_map(&[
+            (0, 3),
+            (1, 3),
+            (2, 1),
+            (3, 1),
+            (4, 2),
+            (5, 3),
+        ]));
+        assert_eq!(module_coverage.module_name, "0x0::counter");
+        let function = &module_coverage.functions[0];
+        assert_eq!(
+            (function.name.as_str(), function.line, function.hits),
+            ("bump", 2, 3)
+        );
+        assert_eq!(
+            function.lines,
+            BTreeMap::from([(3, 3), (4, 1), (5, 2), (6, 3)])
+        );
+        assert_eq!(function.branches, vec![SourceBranchCoverage {
+            code_offset: 1,
+            line: 3,
+            column: 13,
+            true_count: Some(1),
+            false_count: Some(2),
+        }]);
+        assert!(function.branches[0].is_covered());
+        assert_eq!(module_coverage.line_summary(), (4, 4));
+        assert_eq!(module_coverage.branch_summary(), (2, 2));
+
+        // Executed twice, with the condition always true.
+        let module_coverage =
+            line_coverage(&coverage_map(&[(0, 2), (1, 2), (2, 2), (3, 2), (5, 2)]));
+        let branch = &module_coverage.functions[0].branches[0];
+        assert_eq!(branch.outcomes(), [Some(2), Some(0)]);
+        assert!(!branch.is_covered());
+        assert_eq!(module_coverage.line_summary(), (4, 3));
+        assert_eq!(module_coverage.branch_summary(), (2, 1));
+
+        // Never executed, so nothing is known about the branch.
+        let module_coverage = line_coverage(&coverage_map(&[]));
+        let function = &module_coverage.functions[0];
+        assert_eq!(function.hits, 0);
+        assert_eq!(function.branches[0].outcomes(), [None, None]);
+        assert_eq!(module_coverage.line_summary(), (4, 0));
+        assert_eq!(module_coverage.branch_summary(), (2, 0));
+    }
+}
@@ -4,7 +4,10 @@
 
 #![forbid(unsafe_code)]
 
-use crate::coverage_map::CoverageMap;
+use crate::{
+    branch_coverage::{BranchCoverage, ModuleBranchCoverage},
+    coverage_map::CoverageMap,
+};
 use clap::ValueEnum;
 use codespan::{FileId, Files, Span};
 use colored::{self, Colorize};
@@ -71,6 +74,9 @@ pub struct SourceCoverageBuilder<'a> {
     /// Source-level uncovered locations.
     pub uncovered_locations: Vec<Loc>,
 
+    /// Conditional branches located in the source file.
+    pub branches: Vec<BranchCoverage>,
+
     source_map: &'a SourceMap,
 }
 
@@ -239,6 +245,8 @@ pub type AnnotatedLine = Vec<StringSegment>;
 #[derive(Debug, Serialize)]
 pub struct SourceCoverage {
     pub annotated_lines: Vec<AnnotatedLine>,
+    /// Conditional branches, keyed by the (0-based) line of their condition.
+    pub branches: BTreeMap<u32, Vec<BranchCoverage>>,
 }
 
 impl<'a> SourceCoverageBuilder<'a> {
@@ -393,8 +401,24 @@ impl<'a> SourceCoverageBuilder<'a> {
             .into_iter()
             .collect::<BTreeSet<_>>();
         let uncovered_locations = subtract_locations(all, &covered);
+
+        let branches = root_modules
+            .iter()
+            .flat_map(|(module, source_map)| {
+                ModuleBranchCoverage::new(module, source_map, &unified_exec_map)
+                    .function_branches
+                    .into_values()
+                    .flatten()
+            })
+            .filter(|branch| {
+                branch
+                    .loc
+                    .is_some_and(|loc| loc.file_hash() == module_file_hash)
+            })
+            .collect();
         Self {
             uncovered_locations,
+            branches,
             source_map,
         }
     }
@@ -417,6 +441,20 @@ impl<'a> SourceCoverageBuilder<'a> {
 
         let uncovered_segments = spans_to_segments(&mut files, file_id, uncovered_spans);
 
+        let mut branches: BTreeMap<u32, Vec<BranchCoverage>> = BTreeMap::new();
+        for branch in &self.branches {
+            // Branches whose location lies outside the file are not annotated.
+            let location = branch
+                .loc
+                .and_then(|loc| files.location(file_id, loc.start()).ok());
+            if let Some(location) = location {
+                branches
+                    .entry(location.line.0)
+                    .or_default()
+                    .push(branch.clone());
+            }
+        }
+
         let mut annotated_lines = Vec::new();
         for (line_number, mut line) in file_contents.lines().map(|x| x.to_owned()).enumerate() {
             match uncovered_segments.get(&(line_number as u32)) {
@@ -460,7 +498,10 @@ impl<'a> SourceCoverageBuilder<'a> {
             }
         }
 
-        SourceCoverage { annotated_lines }
+        SourceCoverage {
+            annotated_lines,
+            branches,
+        }
     }
 }
 
@@ -476,19 +517,26 @@ impl SourceCoverage {
             TextIndicator::Explicit | TextIndicator::On => {
                 write!(
                     output_writer,
-                    "Code coverage per line of code:\n  {} indicates the line is not executable or is fully covered during execution\n  {} indicates the line is executable but NOT fully covered during execution\nSource code follows:\n",
+                    "Code coverage per line of code:\n  {} indicates the line is not executable or is fully covered during execution\n  {} indicates the line is executable but NOT fully covered during execution\n  {} lists how often each side of the conditions on the line was taken\nSource code follows:\n",
                     "+".to_string().green(),
                     "-".to_string().bold().red(),
+                    "[branch ...]".to_string().green(),
                 )?;
                 true
             },
             TextIndicator::None => false,
         };
-        for line in self.annotated_lines.iter() {
+        for (line_number, line) in self.annotated_lines.iter().enumerate() {
+            let branches = self
+                .branches
+                .get(&(line_number as u32))
+                .map(Vec::as_slice)
+                .unwrap_or_default();
             if be_explicit {
                 let has_uncovered = line
                     .iter()
-                    .any(|string_segment| matches!(string_segment, StringSegment::Uncovered(_)));
+                    .any(|string_segment| matches!(string_segment, StringSegment::Uncovered(_)))
+                    || branches.iter().any(|branch| !branch.is_covered());
                 write!(
                     output_writer,
                     "{} ",
@@ -505,6 +553,20 @@ impl SourceCoverage {
                     StringSegment::Uncovered(s) => write!(output_writer, "{}", s.bold().red())?,
                 }
             }
+            if be_explicit {
+                for branch in branches {
+                    let annotation = format!(
+                        " [branch true: {}, false: {}]",
+                        format_branch_count(branch.true_count),
+                        format_branch_count(branch.false_count)
+                    );
+                    if branch.is_covered() {
+                        write!(output_writer, "{}", annotation.green())?;
+                    } else {
+                        write!(output_writer, "{}", annotation.bold().red())?;
+                    }
+                }
+            }
             writeln!(output_writer)?;
         }
         color.undo();
@@ -512,6 +574,13 @@ impl SourceCoverage {
     }
 }
 
+fn format_branch_count(count: Option<u64>) -> String {
+    match count {
+        Some(count) => format!("{}x", count),
+        None => "not executed".to_string(),
+    }
+}
+
 /// Converts a (sorted) list of non-overlapping `Loc` into a map from line number to
 /// set of `AbstractSegment` for each line of the file `file_id` in fileset `files`.
 fn spans_to_segments(
@@ -0,0 +1,92 @@
+// Copyright (c) Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Conversion of compiler diagnostics into LSP diagnostics.
+
+use crate::position::span_to_range;
+use codespan::{FileId, Files};
+use codespan_reporting::diagnostic::{Diagnostic, Label, LabelStyle, Severity};
+use lsp_types::{DiagnosticRelatedInformation, DiagnosticSeverity, Location, Url};
+use move_model::model::GlobalEnv;
+use std::{collections::BTreeMap, path::PathBuf};
+
+/// The name reported as the source of all diagnostics.
+const DIAGNOSTIC_SOURCE: &str = "move";
+
+/// Returns the diagnostics accumulated in the env, grouped by the file of their primary label.
+pub fn collect_diagnostics(env: &GlobalEnv) -> BTreeMap<PathBuf, Vec<lsp_types::Diagnostic>> {
+    let mut diagnostics: BTreeMap<PathBuf, Vec<lsp_types::Diagnostic>> = BTreeMap::new();
+    env.report_diag_with_filter(
+        |files, diag| {
+            if let Some((path, diag)) = to_lsp_diagnostic(files, diag) {
+                diagnostics.entry(path).or_default().push(diag);
+            }
+        },
+        |_| true,
+    );
+    diagnostics
+}
+
+fn to_lsp_diagnostic(
+    files: &Files<String>,
+    diag: &Diagnostic<FileId>,
+) -> Option<(PathBuf, lsp_types::Diagnostic)> {
+    let primary = diag
+        .labels
+        .iter()
+        .find(|label| label.style == LabelStyle::Primary)
+        .or_else(|| diag.labels.first())?;
+
+    let mut message = diag.message.clone();
+    if !primary.message.is_empty() {
+        message = format!("{}: {}", message, primary.message);
+    }
+    for note in &diag.notes {
+        message = format!("{}\n{}", message, note);
+    }
+
+    let related_information = diag
+        .labels
+        .iter()
+        .filter(|label| label.style == LabelStyle::Secondary)
+        .filter_map(|label| {
+            Some(DiagnosticRelatedInformation {
+                location: to_lsp_location(files, label)?,
+                message: label.message.clone(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let diagnostic = lsp_types::Diagnostic {
+        range: span_to_range(
+            files.source(primary.file_id),
+            primary.range.start,
+            primary.range.end,
+        ),
+        severity: Some(match diag.severity {
+            Severity::Bug | Severity::Error => DiagnosticSeverity::ERROR,
+            Severity::Warning => DiagnosticSeverity::WARNING,
+            Severity::Note => DiagnosticSeverity::INFORMATION,
+            Severity::Help => DiagnosticSeverity::HINT,
+        }),
+        code: None,
+        code_description: None,
+        source: Some(DIAGNOSTIC_SOURCE.to_string()),
+        message,
+        related_information: (!related_information.is_empty()).then_some(related_information),
+        tags: None,
+        data: None,
+    };
+    Some((PathBuf::from(files.name(primary.file_id)), diagnostic))
+}
+
+fn to_lsp_location(files: &Files<String>, label: &Label<FileId>) -> Option<Location> {
+    Some(Location::new(
+        Url::from_file_path(files.name(label.file_id)).ok()?,
+        span_to_range(
+            files.source(label.file_id),
+            label.range.start,
+            label.range.end,
+        ),
+    ))
+}
@@ -0,0 +1,29 @@
+// Copyright (c) Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! # Move Language Server
+//!
+//! This crate implements a language server for Move, speaking the Language Server Protocol
+//! (LSP) over any [`lsp_server::Connection`] (stdio for the `move-language-server` binary).
+//!
+//! ## High-level Architecture
+//!
+//! - **Package**: Locates the package enclosing a source file, resolves its dependencies with
+//!   the Move Package Resolver, and type checks it with the Move compiler v2, producing a
+//!   `GlobalEnv`.
+//! - **Diagnostics**: Converts the diagnostics accumulated in the `GlobalEnv` into LSP
+//!   diagnostics, which are published whenever a file is opened or saved.
+//! - **Symbols**: Indexes all definitions and uses of modules, functions, structs, fields,
+//!   constants and locals in the `GlobalEnv`, and answers go-to-definition, find references,
+//!   hover and completion requests from the index.
+//!
+//! Packages are recompiled from disk on save, so positions always refer to the last saved
+//! version of a file.
+
+mod diagnostics;
+mod package;
+mod position;
+mod server;
+mod symbols;
+
+pub use server::{run, ServerConfig};
@@ -0,0 +1,19 @@
+// Copyright (c) Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use lsp_server::Connection;
+use move_language_server::ServerConfig;
+
+fn main() {
+    let (connection, io_threads) = Connection::stdio();
+    if let Err(e) = move_language_server::run(&connection, ServerConfig::default()) {
+        eprintln!("error: {:#}", e);
+        std::process::exit(1)
+    }
+    // Drop the connection first, so the writer thread can finish.
+    drop(connection);
+    if let Err(e) = io_threads.join() {
+        eprintln!("error: {:#}", e);
+        std::process::exit(1)
+    }
+}
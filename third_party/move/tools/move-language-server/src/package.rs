@@ -0,0 +1,150 @@
+// Copyright (c) Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Resolution and type checking of Move packages.
+
+use anyhow::{anyhow, Result};
+use move_command_line_common::files::find_move_filenames;
+use move_compiler_v2::Options;
+use move_core_types::account_address::AccountAddress;
+use move_model::model::GlobalEnv;
+use move_package_cache::PackageCache;
+use move_package_manifest::{AddressAssignment, PackageManifest};
+use move_package_resolver::{resolve, PackageLock};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// A package that has been resolved and type checked.
+pub struct CheckedPackage {
+    /// The canonical path of the package root, i.e., the directory containing `Move.toml`.
+    pub root: PathBuf,
+    /// The canonical paths of the source files of the package (excluding its dependencies).
+    pub source_files: Vec<PathBuf>,
+    /// The global env, including all diagnostics reported while checking the package.
+    pub env: GlobalEnv,
+}
+
+/// Returns the root of the package enclosing the given file, i.e., the closest ancestor
+/// directory containing a `Move.toml`.
+pub fn find_package_root(file: &Path) -> Option<PathBuf> {
+    file.ancestors()
+        .skip(1)
+        .find(|dir| dir.join("Move.toml").is_file())
+        .map(Path::to_path_buf)
+}
+
+/// Resolves the dependencies of the package at `root` and type checks it in dev mode, i.e.,
+/// with dev dependencies, dev addresses and test code included.
+///
+/// Attributes not in `known_attributes` are reported as warnings. Errors are only returned if
+/// the package could not be resolved or read. Compilation errors are accumulated in the
+/// returned env.
+pub fn check_package(
+    root: &Path,
+    package_cache_dir: &Path,
+    known_attributes: &BTreeSet<String>,
+) -> Result<CheckedPackage> {
+    let root = root.canonicalize()?;
+
+    let package_cache = PackageCache::new(package_cache_dir)?;
+    let mut package_lock = PackageLock::load_from_file_or_empty(root.join("Move.lock"))?;
+    let graph = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?
+        .block_on(async { resolve(&package_cache, &mut package_lock, &root, true).await })?;
+
+    let mut root_manifest = None;
+    let mut source_dirs = vec![];
+    let mut dep_source_dirs = vec![];
+    let mut named_addresses = BTreeMap::new();
+    for package in graph.node_weights() {
+        // On-chain packages come without a manifest and sources.
+        if !package.local_path.join("Move.toml").is_file() {
+            continue;
+        }
+        let manifest = read_manifest(&package.local_path)?;
+        add_named_addresses(&mut named_addresses, &manifest);
+
+        if package.local_path.canonicalize()? == root {
+            source_dirs.extend(["sources", "scripts", "tests"].map(|dir| root.join(dir)));
+            root_manifest = Some(manifest);
+        } else {
+            dep_source_dirs.push(package.local_path.join("sources"));
+        }
+    }
+    let root_manifest =
+        root_manifest.ok_or_else(|| anyhow!("root package missing from the resolution graph"))?;
+
+    // Dev addresses override the addresses of the root package.
+    for (name, addr) in &root_manifest.dev_addresses {
+        named_addresses.insert(name.to_string(), Some(*addr));
+    }
+
+    let sources = find_move_filenames(&existing_dirs(source_dirs), false)?;
+    let dependencies = find_move_filenames(&existing_dirs(dep_source_dirs), false)?;
+    let source_files = sources.iter().map(PathBuf::from).collect();
+
+    let options = Options {
+        sources,
+        dependencies,
+        // Addresses left unspecified by all packages are assigned `0x0`, so that packages
+        // meant to be published at a yet unknown address can still be checked.
+        named_address_mapping: named_addresses
+            .into_iter()
+            .map(|(name, addr)| {
+                format!(
+                    "{}={}",
+                    name,
+                    addr.unwrap_or(AccountAddress::ZERO).to_hex_literal()
+                )
+            })
+            .collect(),
+        language_version: root_manifest
+            .build
+            .as_ref()
+            .and_then(|build| build.language_version),
+        compile_test_code: true,
+        known_attributes: known_attributes.clone(),
+        ..Options::default()
+    };
+    let env = move_compiler_v2::run_checker(options)?;
+
+    Ok(CheckedPackage {
+        root,
+        source_files,
+        env,
+    })
+}
+
+fn read_manifest(package_path: &Path) -> Result<PackageManifest> {
+    let manifest_path = package_path.join("Move.toml");
+    let contents = fs::read_to_string(&manifest_path).map_err(|err| {
+        anyhow!(
+            "failed to read package manifest at {}: {}",
+            manifest_path.display(),
+            err
+        )
+    })?;
+    Ok(move_package_manifest::parse_package_manifest(&contents)?)
+}
+
+/// Adds the named addresses declared by the package. Numerical assignments take precedence
+/// over unspecified ones declared by other packages.
+fn add_named_addresses(
+    named_addresses: &mut BTreeMap<String, Option<AccountAddress>>,
+    manifest: &PackageManifest,
+) {
+    for (name, assignment) in &manifest.addresses {
+        let entry = named_addresses.entry(name.to_string()).or_default();
+        if let AddressAssignment::Numerical(addr) = assignment {
+            entry.get_or_insert(*addr);
+        }
+    }
+}
+
+fn existing_dirs(dirs: Vec<PathBuf>) -> Vec<PathBuf> {
+    dirs.into_iter().filter(|dir| dir.is_dir()).collect()
+}
@@ -0,0 +1,82 @@
+// Copyright (c) Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Conversions between byte offsets into a source file and LSP positions.
+//!
+//! LSP positions are zero-based lines and columns, where columns count UTF-16 code units.
+
+use lsp_types::{Position, Range};
+
+/// Converts a byte offset into `source` to an LSP position.
+pub fn offset_to_position(source: &str, offset: usize) -> Position {
+    let offset = floor_char_boundary(source, offset.min(source.len()));
+    let line_start = source[..offset].rfind('\n').map_or(0, |idx| idx + 1);
+    let line = source[..line_start].matches('\n').count();
+    let character = source[line_start..offset].encode_utf16().count();
+    Position::new(line as u32, character as u32)
+}
+
+/// Converts a byte range into `source` to an LSP range.
+pub fn span_to_range(source: &str, start: usize, end: usize) -> Range {
+    Range::new(
+        offset_to_position(source, start),
+        offset_to_position(source, end),
+    )
+}
+
+/// Converts an LSP position to a byte offset into `source`, or returns `None` if the line is
+/// out of bounds. Columns past the end of the line are clamped to the end of the line.
+pub fn position_to_offset(source: &str, position: Position) -> Option<usize> {
+    let line_start = if position.line == 0 {
+        0
+    } else {
+        source
+            .match_indices('\n')
+            .nth(position.line as usize - 1)
+            .map(|(idx, _)| idx + 1)?
+    };
+    let line = source[line_start..].split('\n').next().unwrap_or_default();
+
+    let mut utf16_offset = 0;
+    for (byte_offset, c) in line.char_indices() {
+        if utf16_offset >= position.character as usize {
+            return Some(line_start + byte_offset);
+        }
+        utf16_offset += c.len_utf16();
+    }
+    Some(line_start + line.len())
+}
+
+fn floor_char_boundary(source: &str, mut offset: usize) -> usize {
+    while !source.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position_round_trip() {
+        let source = "module 0x1::m {\n    // ü𝄞\n    fun f() {}\n}\n";
+
+        let offset = source.find("fun").unwrap();
+        let position = offset_to_position(source, offset);
+        assert_eq!(position, Position::new(2, 4));
+        assert_eq!(position_to_offset(source, position), Some(offset));
+
+        // 'ü' is one UTF-16 code unit, '𝄞' is two.
+        let offset = source.find('𝄞').unwrap();
+        let position = offset_to_position(source, offset);
+        assert_eq!(position, Position::new(1, 8));
+        assert_eq!(position_to_offset(source, position), Some(offset));
+
+        assert_eq!(
+            position_to_offset(source, Position::new(0, 100)),
+            Some(source.find('\n').unwrap())
+        );
+        assert_eq!(position_to_offset(source, Position::new(10, 0)), None);
+    }
+}
@@ -0,0 +1,427 @@
+// Copyright (c) Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! The main loop of the language server, dispatching LSP requests and notifications.
+
+use crate::{
+    diagnostics::collect_diagnostics,
+    package::{check_package, find_package_root, CheckedPackage},
+    position::{position_to_offset, span_to_range},
+    symbols::SymbolIndex,
+};
+use anyhow::Result;
+use codespan::FileId;
+use legacy_move_compiler::shared::known_attributes::KnownAttribute;
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, DidSaveTextDocument,
+        Notification as _, PublishDiagnostics, ShowMessage,
+    },
+    request::{Completion, GotoDefinition, HoverRequest, References, Request as _},
+    CompletionItem, CompletionOptions, CompletionParams, CompletionResponse, GotoDefinitionParams,
+    GotoDefinitionResponse, Hover, HoverContents, HoverParams, HoverProviderCapability,
+    InitializeParams, Location, MarkupContent, MarkupKind, MessageType, OneOf,
+    PublishDiagnosticsParams, ReferenceParams, SaveOptions, ServerCapabilities, ShowMessageParams,
+    TextDocumentSyncCapability, TextDocumentSyncKind, TextDocumentSyncOptions,
+    TextDocumentSyncSaveOptions, Url,
+};
+use move_command_line_common::env::MOVE_HOME;
+use move_model::model::{GlobalEnv, Loc};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
+
+/// Configuration of the language server.
+///
+/// The configuration can be overridden by the client with the `initializationOptions` of the
+/// `initialize` request, e.g., `{ "packageCacheDir": "/path/to/cache" }`. Additional known
+/// attributes can be passed as `{ "knownAttributes": ["view"] }`.
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    /// The directory remote dependencies are fetched to.
+    pub package_cache_dir: PathBuf,
+    /// The attributes the compiler accepts without a warning.
+    pub known_attributes: BTreeSet<String>,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            package_cache_dir: PathBuf::from(MOVE_HOME.as_str()).join("package-cache"),
+            known_attributes: KnownAttribute::get_all_attribute_names().clone(),
+        }
+    }
+}
+
+/// Runs the language server on the connection until the client shuts it down.
+pub fn run(connection: &Connection, mut config: ServerConfig) -> Result<()> {
+    let init_params = connection.initialize(serde_json::to_value(server_capabilities())?)?;
+    let init_params: InitializeParams = serde_json::from_value(init_params)?;
+    let options = init_params.initialization_options.as_ref();
+    if let Some(package_cache_dir) = options
+        .and_then(|options| options.get("packageCacheDir"))
+        .and_then(|dir| dir.as_str())
+    {
+        config.package_cache_dir = PathBuf::from(package_cache_dir);
+    }
+    if let Some(known_attributes) = options
+        .and_then(|options| options.get("knownAttributes"))
+        .and_then(|attributes| attributes.as_array())
+    {
+        config.known_attributes.extend(
+            known_attributes
+                .iter()
+                .filter_map(|attribute| attribute.as_str())
+                .map(str::to_string),
+        );
+    }
+
+    let mut server = Server {
+        connection,
+        config,
+        packages: BTreeMap::new(),
+        documents: BTreeMap::new(),
+    };
+    for msg in &connection.receiver {
+        match msg {
+            Message::Request(req) => {
+                if connection.handle_shutdown(&req)? {
+                    return Ok(());
+                }
+                server.handle_request(req)?;
+            },
+            Message::Notification(notification) => server.handle_notification(notification)?,
+            Message::Response(_) => {},
+        }
+    }
+    Ok(())
+}
+
+fn server_capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Options(
+            TextDocumentSyncOptions {
+                open_close: Some(true),
+                change: Some(TextDocumentSyncKind::FULL),
+                save: Some(TextDocumentSyncSaveOptions::SaveOptions(SaveOptions {
+                    include_text: Some(false),
+                })),
+                ..TextDocumentSyncOptions::default()
+            },
+        )),
+        definition_provider: Some(OneOf::Left(true)),
+        references_provider: Some(OneOf::Left(true)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec![":".to_string(), ".".to_string()]),
+            ..CompletionOptions::default()
+        }),
+        ..ServerCapabilities::default()
+    }
+}
+
+/// A checked package together with its symbol index.
+struct Package {
+    checked: CheckedPackage,
+    symbols: SymbolIndex,
+    /// The ids of the files in the env, by canonical path.
+    files: BTreeMap<PathBuf, FileId>,
+    /// The files diagnostics were last published for.
+    published: BTreeSet<PathBuf>,
+}
+
+struct Server<'a> {
+    connection: &'a Connection,
+    config: ServerConfig,
+    /// The checked packages, by canonical root path.
+    packages: BTreeMap<PathBuf, Package>,
+    /// The current contents of the open documents, which may differ from the contents on disk
+    /// until saved.
+    documents: BTreeMap<Url, String>,
+}
+
+impl Server<'_> {
+    fn handle_request(&mut self, req: Request) -> Result<()> {
+        let response = match req.method.as_str() {
+            GotoDefinition::METHOD => self.respond::<GotoDefinition>(req, Self::goto_definition),
+            References::METHOD => self.respond::<References>(req, Self::references),
+            HoverRequest::METHOD => self.respond::<HoverRequest>(req, Self::hover),
+            Completion::METHOD => self.respond::<Completion>(req, Self::completion),
+            _ => Response::new_err(
+                req.id,
+                ErrorCode::MethodNotFound as i32,
+                format!("unsupported request: {}", req.method),
+            ),
+        };
+        self.connection.sender.send(Message::Response(response))?;
+        Ok(())
+    }
+
+    fn respond<R>(
+        &mut self,
+        req: Request,
+        handler: impl FnOnce(&mut Self, R::Params) -> R::Result,
+    ) -> Response
+    where
+        R: lsp_types::request::Request,
+    {
+        match serde_json::from_value::<R::Params>(req.params) {
+            Ok(params) => Response::new_ok(req.id, handler(self, params)),
+            Err(err) => Response::new_err(req.id, ErrorCode::InvalidParams as i32, err.to_string()),
+        }
+    }
+
+    fn handle_notification(&mut self, notification: Notification) -> Result<()> {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let Some(params) = notification_params::<DidOpenTextDocument>(notification) else {
+                    return Ok(());
+                };
+                if let Some(root) = package_root(&params.text_document.uri) {
+                    if !self.packages.contains_key(&root) {
+                        self.check(&root)?;
+                    }
+                }
+                self.documents
+                    .insert(params.text_document.uri, params.text_document.text);
+            },
+            DidChangeTextDocument::METHOD => {
+                let Some(params) = notification_params::<DidChangeTextDocument>(notification)
+                else {
+                    return Ok(());
+                };
+                if let Some(change) = params.content_changes.into_iter().last() {
+                    self.documents.insert(params.text_document.uri, change.text);
+                }
+            },
+            DidSaveTextDocument::METHOD => {
+                let Some(params) = notification_params::<DidSaveTextDocument>(notification) else {
+                    return Ok(());
+                };
+                if let Some(root) = package_root(&params.text_document.uri) {
+                    self.check(&root)?;
+                }
+            },
+            DidCloseTextDocument::METHOD => {
+                let Some(params) = notification_params::<DidCloseTextDocument>(notification) else {
+                    return Ok(());
+                };
+                self.documents.remove(&params.text_document.uri);
+            },
+            _ => {},
+        }
+        Ok(())
+    }
+
+    /// (Re-)checks the package at the root and publishes its diagnostics. If the package cannot
+    /// be resolved, the error is shown to the user and the last successfully checked version of
+    /// the package is kept.
+    fn check(&mut self, root: &Path) -> Result<()> {
+        let checked = match check_package(
+            root,
+            &self.config.package_cache_dir,
+            &self.config.known_attributes,
+        ) {
+            Ok(checked) => checked,
+            Err(err) => {
+                return self.notify::<ShowMessage>(ShowMessageParams {
+                    typ: MessageType::ERROR,
+                    message: format!("Failed to check package at {}: {:#}", root.display(), err),
+                });
+            },
+        };
+
+        // Publish the diagnostics of all source files, to clear the diagnostics of files
+        // without any, as well as of all files diagnostics were last published for.
+        let mut diagnostics = collect_diagnostics(&checked.env);
+        let published = checked
+            .source_files
+            .iter()
+            .cloned()
+            .chain(diagnostics.keys().cloned())
+            .collect::<BTreeSet<_>>();
+        let stale = self
+            .packages
+            .get(&checked.root)
+            .map(|package| &package.published - &published)
+            .unwrap_or_default();
+        for path in published.iter().chain(stale.iter()) {
+            if let Ok(uri) = Url::from_file_path(path) {
+                self.notify::<PublishDiagnostics>(PublishDiagnosticsParams::new(
+                    uri,
+                    diagnostics.remove(path).unwrap_or_default(),
+                    None,
+                ))?;
+            }
+        }
+
+        let env = &checked.env;
+        let files = env
+            .get_source_file_ids()
+            .into_iter()
+            .map(|file_id| {
+                let path = PathBuf::from(env.get_file(file_id));
+                (path.canonicalize().unwrap_or(path), file_id)
+            })
+            .collect();
+        let symbols = SymbolIndex::new(env);
+        self.packages.insert(checked.root.clone(), Package {
+            checked,
+            symbols,
+            files,
+            published,
+        });
+        Ok(())
+    }
+
+    fn notify<N: lsp_types::notification::Notification>(&self, params: N::Params) -> Result<()> {
+        self.connection
+            .sender
+            .send(Message::Notification(Notification::new(
+                N::METHOD.to_string(),
+                params,
+            )))?;
+        Ok(())
+    }
+
+    /// Returns the root of the package containing the document, checking the package if
+    /// needed, together with the id of the document in the env.
+    fn locate(&mut self, uri: &Url) -> Option<(PathBuf, FileId)> {
+        let path = uri.to_file_path().ok()?.canonicalize().ok()?;
+        let root = find_package_root(&path)?;
+        if !self.packages.contains_key(&root) {
+            self.check(&root).ok()?;
+        }
+        let file_id = *self.packages.get(&root)?.files.get(&path)?;
+        Some((root, file_id))
+    }
+
+    fn goto_definition(&mut self, params: GotoDefinitionParams) -> Option<GotoDefinitionResponse> {
+        let params = params.text_document_position_params;
+        let (root, file_id) = self.locate(&params.text_document.uri)?;
+        let package = &self.packages[&root];
+        let env = &package.checked.env;
+        let offset = position_to_offset(env.get_file_source(file_id), params.position)?;
+
+        let occurrence = package.symbols.occurrence_at(file_id, offset)?;
+        let definition = package.symbols.definition(&occurrence.item)?;
+        Some(GotoDefinitionResponse::Scalar(to_location(
+            env, definition,
+        )?))
+    }
+
+    fn references(&mut self, params: ReferenceParams) -> Option<Vec<Location>> {
+        let include_declaration = params.context.include_declaration;
+        let params = params.text_document_position;
+        let (root, file_id) = self.locate(&params.text_document.uri)?;
+        let package = &self.packages[&root];
+        let env = &package.checked.env;
+        let offset = position_to_offset(env.get_file_source(file_id), params.position)?;
+
+        let occurrence = package.symbols.occurrence_at(file_id, offset)?;
+        Some(
+            package
+                .symbols
+                .references(&occurrence.item, include_declaration)
+                .iter()
+                .filter_map(|loc| to_location(env, loc))
+                .collect(),
+        )
+    }
+
+    fn hover(&mut self, params: HoverParams) -> Option<Hover> {
+        let params = params.text_document_position_params;
+        let (root, file_id) = self.locate(&params.text_document.uri)?;
+        let package = &self.packages[&root];
+        let env = &package.checked.env;
+        let offset = position_to_offset(env.get_file_source(file_id), params.position)?;
+
+        let occurrence = package.symbols.occurrence_at(file_id, offset)?;
+        let value = package.symbols.hover(env, &occurrence.item);
+        if value.is_empty() {
+            return None;
+        }
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value,
+            }),
+            range: to_location(env, &occurrence.loc).map(|location| location.range),
+        })
+    }
+
+    fn completion(&mut self, params: CompletionParams) -> Option<CompletionResponse> {
+        let params = params.text_document_position;
+        let uri = params.text_document.uri;
+        let (root, file_id) = self.locate(&uri)?;
+        let package = &self.packages[&root];
+        let env = &package.checked.env;
+
+        // Both the text before the cursor and the offset are taken from the current contents of
+        // the document, as completion is typically requested while typing.
+        let text = self
+            .documents
+            .get(&uri)
+            .map(String::as_str)
+            .unwrap_or_else(|| env.get_file_source(file_id));
+        let offset = position_to_offset(text, params.position)?;
+        let (qualifier, prefix) = completion_context(text, offset);
+
+        let items = package
+            .symbols
+            .completions(env, file_id, offset, qualifier, prefix)
+            .into_iter()
+            .map(|completion| CompletionItem {
+                label: completion.label,
+                kind: Some(completion.kind),
+                detail: completion.detail,
+                ..CompletionItem::default()
+            })
+            .collect();
+        Some(CompletionResponse::Array(items))
+    }
+}
+
+/// Parses the params of the notification. Malformed params are logged and the notification
+/// is skipped, as notifications cannot be answered with an error.
+fn notification_params<N>(notification: Notification) -> Option<N::Params>
+where
+    N: lsp_types::notification::Notification,
+{
+    serde_json::from_value(notification.params)
+        .map_err(|err| eprintln!("skipping malformed `{}` notification: {}", N::METHOD, err))
+        .ok()
+}
+
+/// Returns the canonical root of the package containing the document.
+fn package_root(uri: &Url) -> Option<PathBuf> {
+    find_package_root(&uri.to_file_path().ok()?.canonicalize().ok()?)
+}
+
+fn to_location(env: &GlobalEnv, loc: &Loc) -> Option<Location> {
+    let uri = Url::from_file_path(env.get_file(loc.file_id())).ok()?;
+    let range = span_to_range(
+        env.get_file_source(loc.file_id()),
+        loc.span().start().to_usize(),
+        loc.span().end().to_usize(),
+    );
+    Some(Location::new(uri, range))
+}
+
+/// Returns the qualifier (as in `qualifier::prefix`) and the prefix of the identifier being
+/// typed at the byte offset.
+fn completion_context(text: &str, offset: usize) -> (Option<&str>, &str) {
+    let is_ident_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+
+    let before = &text[..offset];
+    let prefix_start = before.trim_end_matches(is_ident_char).len();
+    let prefix = &before[prefix_start..];
+
+    let qualifier = before[..prefix_start].strip_suffix("::").map(|before| {
+        let qualifier_start = before.trim_end_matches(is_ident_char).len();
+        &before[qualifier_start..]
+    });
+    (qualifier.filter(|qualifier| !qualifier.is_empty()), prefix)
+}
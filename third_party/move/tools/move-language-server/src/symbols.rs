@@ -0,0 +1,670 @@
+// Copyright (c) Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! An index of the definitions and uses of named items in a type checked package.
+//!
+//! Definitions are taken from the declarations in the model, and uses from the expressions of
+//! the function bodies of target modules. Items only referenced from types (e.g., a struct
+//! appearing in a function signature) or `use` declarations are not indexed, since the model
+//! does not keep locations for them.
+
+use codespan::{ByteIndex, FileId, Span};
+use lsp_types::CompletionItemKind;
+use move_model::{
+    ast::{ExpData, NodeId, Operation, Pattern, VisitorPosition},
+    model::{
+        FieldEnv, FieldId, FunId, FunctionEnv, GlobalEnv, Loc, ModuleEnv, ModuleId,
+        NamedConstantId, Parameter, QualifiedId, StructEnv, StructId,
+    },
+    symbol::Symbol,
+    ty::Type,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+};
+
+/// A named item which can be defined and referenced in Move source.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Item {
+    Module(ModuleId),
+    Function(QualifiedId<FunId>),
+    Struct(QualifiedId<StructId>),
+    Field(QualifiedId<StructId>, FieldId),
+    Constant(ModuleId, NamedConstantId),
+    /// A parameter or local variable, identified by the location of its declaration.
+    Local(Loc),
+}
+
+/// An occurrence of the name of an item in the source.
+#[derive(Clone, Debug)]
+pub struct Occurrence {
+    pub item: Item,
+    pub loc: Loc,
+}
+
+/// A parameter or local variable declared in a function.
+struct LocalInfo {
+    name: Symbol,
+    ty: Type,
+    /// The location of the enclosing function.
+    fun_loc: Loc,
+}
+
+/// A candidate returned by [`SymbolIndex::completions`].
+pub struct Completion {
+    pub label: String,
+    pub kind: CompletionItemKind,
+    pub detail: Option<String>,
+}
+
+/// The definitions and uses of all named items in a `GlobalEnv`.
+pub struct SymbolIndex {
+    /// Occurrences of definitions and uses, by file.
+    occurrences: BTreeMap<FileId, Vec<Occurrence>>,
+    /// The location of the name of each item at its definition.
+    definitions: BTreeMap<Item, Loc>,
+    locals: BTreeMap<Loc, LocalInfo>,
+}
+
+impl SymbolIndex {
+    /// Indexes the definitions of all modules in the env, and the uses in target modules.
+    pub fn new(env: &GlobalEnv) -> Self {
+        let mut builder = IndexBuilder {
+            env,
+            index: SymbolIndex {
+                occurrences: BTreeMap::new(),
+                definitions: BTreeMap::new(),
+                locals: BTreeMap::new(),
+            },
+        };
+        for module in env.get_modules() {
+            builder.index_module(&module);
+        }
+        builder.index
+    }
+
+    /// Returns the innermost occurrence at the given byte offset.
+    pub fn occurrence_at(&self, file_id: FileId, offset: usize) -> Option<&Occurrence> {
+        self.occurrences
+            .get(&file_id)?
+            .iter()
+            .filter(|occ| span_contains(occ.loc.span(), offset))
+            .min_by_key(|occ| occ.loc.span().end().to_usize() - occ.loc.span().start().to_usize())
+    }
+
+    /// Returns the location of the name of the item at its definition.
+    pub fn definition(&self, item: &Item) -> Option<&Loc> {
+        self.definitions.get(item)
+    }
+
+    /// Returns the locations of all uses of the item, including its definition if
+    /// `include_definition` is set.
+    pub fn references(&self, item: &Item, include_definition: bool) -> Vec<Loc> {
+        let definition = self.definitions.get(item);
+        self.occurrences
+            .values()
+            .flatten()
+            .filter(|occ| &occ.item == item)
+            .filter(|occ| include_definition || Some(&occ.loc) != definition)
+            .map(|occ| occ.loc.clone())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// Returns a markdown description of the item, showing its declaration, type and
+    /// abilities, as well as its documentation.
+    pub fn hover(&self, env: &GlobalEnv, item: &Item) -> String {
+        let (decl, doc) = match item {
+            Item::Module(mid) => {
+                let module = env.get_module(*mid);
+                (
+                    format!("module {}", module.get_full_name_str()),
+                    module.get_doc().to_string(),
+                )
+            },
+            Item::Function(qid) => {
+                let fun = env.get_function(*qid);
+                (
+                    format!(
+                        "module {}\n{}",
+                        fun.module_env.get_full_name_str(),
+                        fun.get_header_string()
+                    ),
+                    fun.get_doc().to_string(),
+                )
+            },
+            Item::Struct(qid) => {
+                let struct_env = env.get_struct(*qid);
+                (
+                    format!(
+                        "module {}\n{}",
+                        struct_env.module_env.get_full_name_str(),
+                        struct_declaration(&struct_env)
+                    ),
+                    struct_env.get_doc().to_string(),
+                )
+            },
+            Item::Field(qid, fid) => {
+                let struct_env = env.get_struct(*qid);
+                let field = struct_env.get_field(*fid);
+                (
+                    format!(
+                        "{}\n{}: {}",
+                        struct_env.get_full_name_str(),
+                        field.get_name().display(env.symbol_pool()),
+                        field.get_type().display(&struct_env.get_type_display_ctx())
+                    ),
+                    env.get_doc(field.get_loc()).to_string(),
+                )
+            },
+            Item::Constant(mid, cid) => {
+                let module = env.get_module(*mid);
+                let constant = module.get_named_constant(*cid);
+                (
+                    format!(
+                        "module {}\nconst {}: {}",
+                        module.get_full_name_str(),
+                        constant.get_name().display(env.symbol_pool()),
+                        constant.get_type().display(&module.get_type_display_ctx())
+                    ),
+                    constant.get_doc().to_string(),
+                )
+            },
+            Item::Local(loc) => match self.locals.get(loc) {
+                Some(local) => (
+                    format!(
+                        "{}: {}",
+                        local.name.display(env.symbol_pool()),
+                        local.ty.display(&env.get_type_display_ctx())
+                    ),
+                    String::new(),
+                ),
+                None => return String::new(),
+            },
+        };
+
+        let mut hover = format!("```move\n{}\n```", decl);
+        if !doc.is_empty() {
+            write!(hover, "\n\n---\n\n{}", doc).unwrap();
+        }
+        hover
+    }
+
+    /// Returns the completion candidates starting with `prefix` at the given byte offset.
+    ///
+    /// If `qualifier` is given (as in `qualifier::prefix`), the candidates are the members of
+    /// the modules named `qualifier`. Otherwise, they are the members of the enclosing module,
+    /// the names of all modules, and the locals of the enclosing function declared before the
+    /// offset.
+    pub fn completions(
+        &self,
+        env: &GlobalEnv,
+        file_id: FileId,
+        offset: usize,
+        qualifier: Option<&str>,
+        prefix: &str,
+    ) -> Vec<Completion> {
+        let mut completions = vec![];
+        match qualifier {
+            Some(qualifier) => {
+                for module in env.get_modules() {
+                    if module.get_name().display(env).to_string() == qualifier {
+                        add_module_members(&mut completions, &module);
+                    }
+                }
+            },
+            None => {
+                for module in env.get_modules() {
+                    let loc = module.get_loc();
+                    if loc.file_id() == file_id && span_contains(loc.span(), offset) {
+                        add_module_members(&mut completions, &module);
+                    }
+                    completions.push(Completion {
+                        label: module.get_name().display(env).to_string(),
+                        kind: CompletionItemKind::MODULE,
+                        detail: Some(format!("module {}", module.get_full_name_str())),
+                    });
+                }
+                for (def_loc, local) in &self.locals {
+                    if local.fun_loc.file_id() == file_id
+                        && span_contains(local.fun_loc.span(), offset)
+                        && def_loc.span().start().to_usize() < offset
+                    {
+                        completions.push(Completion {
+                            label: local.name.display(env.symbol_pool()).to_string(),
+                            kind: CompletionItemKind::VARIABLE,
+                            detail: Some(local.ty.display(&env.get_type_display_ctx()).to_string()),
+                        });
+                    }
+                }
+            },
+        }
+
+        completions.retain(|completion| completion.label.starts_with(prefix));
+        completions.sort_by(|c1, c2| c1.label.cmp(&c2.label));
+        completions.dedup_by(|c1, c2| c1.label == c2.label);
+        completions
+    }
+}
+
+struct IndexBuilder<'env> {
+    env: &'env GlobalEnv,
+    index: SymbolIndex,
+}
+
+impl IndexBuilder<'_> {
+    fn index_module(&mut self, module: &ModuleEnv) {
+        let pool = self.env.symbol_pool();
+        let mid = module.get_id();
+        let name = module.get_name().name().display(pool).to_string();
+        self.add_definition(Item::Module(mid), &module.get_loc(), &name);
+
+        for struct_env in module.get_structs() {
+            let qid = struct_env.get_qualified_id();
+            let name = struct_env.get_name().display(pool).to_string();
+            self.add_definition(Item::Struct(qid), &struct_env.get_loc(), &name);
+            for field in struct_env.get_fields() {
+                let name = field.get_name().display(pool).to_string();
+                self.add_definition(Item::Field(qid, field.get_id()), field.get_loc(), &name);
+            }
+        }
+        for constant in module.get_named_constants() {
+            let name = constant.get_name().display(pool).to_string();
+            self.add_definition(
+                Item::Constant(mid, constant.get_id()),
+                &constant.get_loc(),
+                &name,
+            );
+        }
+        for fun in module.get_functions() {
+            let name = fun.get_name().display(pool).to_string();
+            self.add_definition(
+                Item::Function(fun.get_qualified_id()),
+                &fun.get_id_loc(),
+                &name,
+            );
+            if module.is_target() {
+                self.index_function_body(&fun);
+            }
+        }
+    }
+
+    fn index_function_body(&mut self, fun: &FunctionEnv) {
+        let fun_loc = fun.get_loc();
+        let params = fun
+            .get_parameters()
+            .into_iter()
+            .map(|Parameter(name, ty, loc)| self.add_local(name, ty, &loc, &fun_loc))
+            .collect::<Vec<_>>();
+        let Some(def) = fun.get_def() else {
+            return;
+        };
+
+        // The locals in scope, innermost scope last.
+        let mut scopes = vec![params.clone()];
+        def.visit_positions(&mut |position, exp| {
+            match (position, exp) {
+                (VisitorPosition::Pre, ExpData::LocalVar(id, name)) => {
+                    if let Some(def_loc) = lookup_local(&scopes, *name) {
+                        self.add_use(Item::Local(def_loc), *id, *name, false);
+                    }
+                },
+                (VisitorPosition::Pre, ExpData::Temporary(id, idx)) => {
+                    if let Some((name, def_loc)) = params.get(*idx) {
+                        self.add_use(Item::Local(def_loc.clone()), *id, *name, false);
+                    }
+                },
+                (VisitorPosition::Pre, ExpData::Call(id, oper, _)) => self.index_call(*id, oper),
+                (VisitorPosition::Pre, ExpData::Value(id, _)) => {
+                    self.index_constant_use(*id, &fun.module_env);
+                },
+                (VisitorPosition::Pre, ExpData::Assign(_, pattern, _)) => {
+                    self.index_assigned_pattern(pattern, &scopes);
+                },
+                (VisitorPosition::BeforeBody, ExpData::Block(_, pattern, _, _))
+                | (VisitorPosition::Pre, ExpData::Lambda(_, pattern, ..)) => {
+                    scopes.push(self.index_bound_pattern(pattern, &fun_loc));
+                },
+                (VisitorPosition::BeforeMatchBody(idx), ExpData::Match(_, _, arms)) => {
+                    scopes.push(self.index_bound_pattern(&arms[idx].pattern, &fun_loc));
+                },
+                (VisitorPosition::Pre, ExpData::Quant(_, _, ranges, ..)) => {
+                    let mut scope = vec![];
+                    for (pattern, _) in ranges {
+                        scope.extend(self.index_bound_pattern(pattern, &fun_loc));
+                    }
+                    scopes.push(scope);
+                },
+                (VisitorPosition::Post, ExpData::Block(..))
+                | (VisitorPosition::Post, ExpData::Lambda(..))
+                | (VisitorPosition::Post, ExpData::Quant(..))
+                | (VisitorPosition::AfterMatchBody(_), ExpData::Match(..)) => {
+                    scopes.pop();
+                },
+                _ => {},
+            }
+            true
+        });
+    }
+
+    fn index_call(&mut self, id: NodeId, oper: &Operation) {
+        match oper {
+            Operation::MoveFunction(mid, fid) | Operation::Closure(mid, fid, _) => {
+                self.add_use(Item::Function(mid.qualified(*fid)), id, fid.symbol(), false);
+            },
+            Operation::Pack(mid, sid, _) => {
+                self.add_use(Item::Struct(mid.qualified(*sid)), id, sid.symbol(), false);
+            },
+            Operation::Select(mid, sid, fid) => {
+                let qid = mid.qualified(*sid);
+                let name = self.env.get_struct(qid).get_field(*fid).get_name();
+                self.add_use(Item::Field(qid, *fid), id, name, true);
+            },
+            Operation::SelectVariants(mid, sid, fids) => {
+                if let Some(fid) = fids.first() {
+                    let qid = mid.qualified(*sid);
+                    let name = self.env.get_struct(qid).get_field(*fid).get_name();
+                    self.add_use(Item::Field(qid, *fid), id, name, true);
+                }
+            },
+            Operation::Exists(_)
+            | Operation::BorrowGlobal(_)
+            | Operation::MoveTo
+            | Operation::MoveFrom => {
+                if let Some(Type::Struct(mid, sid, _)) = self.env.get_node_instantiation(id).first()
+                {
+                    self.add_use(Item::Struct(mid.qualified(*sid)), id, sid.symbol(), false);
+                }
+            },
+            _ => {},
+        }
+    }
+
+    /// Constants are inlined as values by the model builder. Since constants are private to
+    /// their module, a value whose source is the name of a constant of the module is a use of
+    /// that constant.
+    fn index_constant_use(&mut self, id: NodeId, module: &ModuleEnv) {
+        let loc = self.env.get_node_loc(id);
+        let Ok(text) = self.env.get_source(&loc) else {
+            return;
+        };
+        let constant = module.get_named_constants().find(|constant| {
+            constant
+                .get_name()
+                .display(self.env.symbol_pool())
+                .to_string()
+                == text
+        });
+        if let Some(constant) = constant {
+            let item = Item::Constant(module.get_id(), constant.get_id());
+            self.add_use(item, id, constant.get_name(), false);
+        }
+    }
+
+    /// Indexes a pattern binding new locals, and returns them.
+    fn index_bound_pattern(&mut self, pattern: &Pattern, fun_loc: &Loc) -> Vec<(Symbol, Loc)> {
+        let mut bound = vec![];
+        pattern.visit_pre_post(&mut |post, pattern| {
+            if post {
+                return;
+            }
+            match pattern {
+                Pattern::Var(id, name) => {
+                    let loc = self.env.get_node_loc(*id);
+                    let ty = self.env.get_node_type(*id);
+                    bound.push(self.add_local(*name, ty, &loc, fun_loc));
+                },
+                Pattern::Struct(id, qid, _, _) => {
+                    let item = Item::Struct(qid.to_qualified_id());
+                    self.add_use(item, *id, qid.id.symbol(), false);
+                },
+                Pattern::Wildcard(_) | Pattern::Tuple(..) | Pattern::Error(_) => {},
+            }
+        });
+        bound
+    }
+
+    /// Indexes a pattern assigning to locals in scope.
+    fn index_assigned_pattern(&mut self, pattern: &Pattern, scopes: &[Vec<(Symbol, Loc)>]) {
+        pattern.visit_pre_post(&mut |post, pattern| {
+            if post {
+                return;
+            }
+            match pattern {
+                Pattern::Var(id, name) => {
+                    if let Some(def_loc) = lookup_local(scopes, *name) {
+                        self.add_use(Item::Local(def_loc), *id, *name, false);
+                    }
+                },
+                Pattern::Struct(id, qid, _, _) => {
+                    let item = Item::Struct(qid.to_qualified_id());
+                    self.add_use(item, *id, qid.id.symbol(), false);
+                },
+                Pattern::Wildcard(_) | Pattern::Tuple(..) | Pattern::Error(_) => {},
+            }
+        });
+    }
+
+    /// Adds the definition of a parameter or local, and returns its name and the location of
+    /// its name.
+    fn add_local(&mut self, name: Symbol, ty: Type, loc: &Loc, fun_loc: &Loc) -> (Symbol, Loc) {
+        let name_str = name.display(self.env.symbol_pool()).to_string();
+        let def_loc = self.find_name(loc, &name_str, false);
+        self.add_definition(Item::Local(def_loc.clone()), &def_loc, &name_str);
+        self.index.locals.insert(def_loc.clone(), LocalInfo {
+            name,
+            ty,
+            fun_loc: fun_loc.clone(),
+        });
+        (name, def_loc)
+    }
+
+    fn add_definition(&mut self, item: Item, loc: &Loc, name: &str) {
+        let loc = self.find_name(loc, name, false);
+        self.index.definitions.insert(item.clone(), loc.clone());
+        self.add_occurrence(item, loc);
+    }
+
+    /// Adds a use of the item named `name` at the node. If `last` is set, the last occurrence
+    /// of the name in the source of the node is used (e.g., the field in `x.f`), otherwise the
+    /// first one.
+    ///
+    /// For uses qualified with a module name (e.g., `coin::mint`), a use of the module is added
+    /// as well.
+    fn add_use(&mut self, item: Item, id: NodeId, name: Symbol, last: bool) {
+        let node_loc = self.env.get_node_loc(id);
+        // Skip code inlined from elsewhere.
+        if node_loc.is_inlined() {
+            return;
+        }
+        let name = name.display(self.env.symbol_pool()).to_string();
+        let loc = self.find_name(&node_loc, &name, last);
+
+        let module_id = match &item {
+            Item::Function(qid) => Some(qid.module_id),
+            Item::Struct(qid) => Some(qid.module_id),
+            _ => None,
+        };
+        if let Some(mid) = module_id {
+            let module = self.env.get_module(mid);
+            let module_name = module.get_name().name().display(self.env.symbol_pool());
+            let qualifier = format!("{}::", module_name);
+            let source = self.env.get_file_source(loc.file_id());
+            let start = loc.span().start().to_usize();
+            if source[..start].ends_with(&qualifier) {
+                let module_start = start - qualifier.len();
+                let module_loc = Loc::new(
+                    loc.file_id(),
+                    Span::new(
+                        ByteIndex(module_start as u32),
+                        ByteIndex((start - 2) as u32),
+                    ),
+                );
+                self.add_occurrence(Item::Module(mid), module_loc);
+            }
+        }
+
+        self.add_occurrence(item, loc);
+    }
+
+    fn add_occurrence(&mut self, item: Item, loc: Loc) {
+        self.index
+            .occurrences
+            .entry(loc.file_id())
+            .or_default()
+            .push(Occurrence { item, loc });
+    }
+
+    /// Narrows the location down to the first (or last) occurrence of the name as a whole
+    /// identifier, or returns the location as is if the name does not occur.
+    fn find_name(&self, loc: &Loc, name: &str, last: bool) -> Loc {
+        let source = self.env.get_file_source(loc.file_id());
+        let start = loc.span().start().to_usize();
+        let end = loc.span().end().to_usize();
+        let Some(text) = source.get(start..end) else {
+            return loc.clone();
+        };
+
+        let is_ident_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+        let mut matches = text.match_indices(name).filter(|(idx, _)| {
+            !text[..*idx].ends_with(is_ident_char)
+                && !text[idx + name.len()..].starts_with(is_ident_char)
+        });
+        let found = if last { matches.last() } else { matches.next() };
+        match found {
+            Some((idx, _)) => Loc::new(
+                loc.file_id(),
+                Span::new(
+                    ByteIndex((start + idx) as u32),
+                    ByteIndex((start + idx + name.len()) as u32),
+                ),
+            ),
+            None => loc.clone(),
+        }
+    }
+}
+
+/// Returns the declaration of the struct or enum, with its abilities and fields.
+fn struct_declaration(struct_env: &StructEnv) -> String {
+    let env = struct_env.module_env.env;
+    let pool = env.symbol_pool();
+    let ctx = struct_env.get_type_display_ctx();
+
+    let mut decl = format!(
+        "{} {}",
+        if struct_env.has_variants() {
+            "enum"
+        } else {
+            "struct"
+        },
+        struct_env.get_name().display(pool)
+    );
+    let type_params = struct_env.get_type_parameters();
+    if !type_params.is_empty() {
+        let type_params = type_params
+            .iter()
+            .map(|param| param.0.display(pool).to_string())
+            .collect::<Vec<_>>();
+        write!(decl, "<{}>", type_params.join(", ")).unwrap();
+    }
+    let abilities = struct_env
+        .get_abilities()
+        .iter()
+        .map(|ability| ability.to_string())
+        .collect::<Vec<_>>();
+    if !abilities.is_empty() {
+        write!(decl, " has {}", abilities.join(", ")).unwrap();
+    }
+
+    let fmt_fields = |fields: Vec<FieldEnv>, indent: &str| {
+        fields
+            .iter()
+            .map(|field| {
+                format!(
+                    "{}{}: {},\n",
+                    indent,
+                    field.get_name().display(pool),
+                    field.get_type().display(&ctx)
+                )
+            })
+            .collect::<String>()
+    };
+    if struct_env.has_variants() {
+        decl.push_str(" {\n");
+        for variant in struct_env.get_variants() {
+            let fields = struct_env
+                .get_fields_of_variant(variant)
+                .collect::<Vec<_>>();
+            if fields.is_empty() {
+                writeln!(decl, "    {},", variant.display(pool)).unwrap();
+            } else {
+                write!(
+                    decl,
+                    "    {} {{\n{}    }},\n",
+                    variant.display(pool),
+                    fmt_fields(fields, "        ")
+                )
+                .unwrap();
+            }
+        }
+        decl.push('}');
+    } else if !struct_env.is_native() {
+        write!(
+            decl,
+            " {{\n{}}}",
+            fmt_fields(struct_env.get_fields().collect(), "    ")
+        )
+        .unwrap();
+    }
+    decl
+}
+
+fn add_module_members(completions: &mut Vec<Completion>, module: &ModuleEnv) {
+    let pool = module.symbol_pool();
+    for fun in module.get_functions() {
+        completions.push(Completion {
+            label: fun.get_name().display(pool).to_string(),
+            kind: CompletionItemKind::FUNCTION,
+            detail: Some(fun.get_header_string()),
+        });
+    }
+    for struct_env in module.get_structs() {
+        completions.push(Completion {
+            label: struct_env.get_name().display(pool).to_string(),
+            kind: if struct_env.has_variants() {
+                CompletionItemKind::ENUM
+            } else {
+                CompletionItemKind::STRUCT
+            },
+            detail: None,
+        });
+    }
+    for constant in module.get_named_constants() {
+        completions.push(Completion {
+            label: constant.get_name().display(pool).to_string(),
+            kind: CompletionItemKind::CONSTANT,
+            detail: Some(
+                constant
+                    .get_type()
+                    .display(&module.get_type_display_ctx())
+                    .to_string(),
+            ),
+        });
+    }
+}
+
+/// Returns the location of the declaration of the innermost local with the given name.
+fn lookup_local(scopes: &[Vec<(Symbol, Loc)>], name: Symbol) -> Option<Loc> {
+    scopes
+        .iter()
+        .flatten()
+        .rev()
+        .find(|(local, _)| *local == name)
+        .map(|(_, def_loc)| def_loc.clone())
+}
+
+fn span_contains(span: Span, offset: usize) -> bool {
+    span.start().to_usize() <= offset && offset <= span.end().to_usize()
+}
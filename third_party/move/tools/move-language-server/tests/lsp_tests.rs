@@ -0,0 +1,514 @@
+// Copyright (c) Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tests driving the language server with a scripted client over an in-memory connection.
+
+use lsp_server::{Connection, Message, Notification, Request, RequestId};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidOpenTextDocument, DidSaveTextDocument, Exit, Initialized,
+        Notification as _, PublishDiagnostics,
+    },
+    request::{
+        Completion, GotoDefinition, HoverRequest, Initialize, References, Request as _, Shutdown,
+    },
+    CompletionParams, CompletionResponse, Diagnostic, DiagnosticSeverity,
+    DidChangeTextDocumentParams, DidOpenTextDocumentParams, DidSaveTextDocumentParams,
+    GotoDefinitionParams, GotoDefinitionResponse, HoverContents, HoverParams, InitializeParams,
+    InitializedParams, Location, Position, PublishDiagnosticsParams, ReferenceContext,
+    ReferenceParams, TextDocumentContentChangeEvent, TextDocumentIdentifier, TextDocumentItem,
+    TextDocumentPositionParams, Url, VersionedTextDocumentIdentifier,
+};
+use move_language_server::ServerConfig;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+use tempfile::TempDir;
+
+const TIMEOUT: Duration = Duration::from_secs(120);
+
+/// A scripted LSP client, running the server in a separate thread.
+struct TestClient {
+    connection: Connection,
+    server: JoinHandle<()>,
+    next_id: i32,
+    /// Notifications received from the server which have not been consumed yet.
+    notifications: Vec<Notification>,
+    _package_cache_dir: TempDir,
+}
+
+impl TestClient {
+    fn start() -> Self {
+        Self::start_with_options(None)
+    }
+
+    /// Starts the server, passing the `initializationOptions` to the `initialize` request.
+    fn start_with_options(initialization_options: Option<serde_json::Value>) -> Self {
+        let package_cache_dir = TempDir::new().unwrap();
+        let config = ServerConfig {
+            package_cache_dir: package_cache_dir.path().to_path_buf(),
+            ..ServerConfig::default()
+        };
+        let (server_connection, connection) = Connection::memory();
+        let server =
+            thread::spawn(move || move_language_server::run(&server_connection, config).unwrap());
+
+        let mut client = Self {
+            connection,
+            server,
+            next_id: 0,
+            notifications: vec![],
+            _package_cache_dir: package_cache_dir,
+        };
+        client.request::<Initialize>(InitializeParams {
+            initialization_options,
+            ..InitializeParams::default()
+        });
+        client.notify::<Initialized>(InitializedParams {});
+        client
+    }
+
+    fn shutdown(mut self) {
+        self.request::<Shutdown>(());
+        self.notify::<Exit>(());
+        self.server.join().unwrap();
+    }
+
+    fn request<R: lsp_types::request::Request>(&mut self, params: R::Params) -> R::Result {
+        self.next_id += 1;
+        let id = RequestId::from(self.next_id);
+        self.connection
+            .sender
+            .send(Message::Request(Request::new(
+                id.clone(),
+                R::METHOD.to_string(),
+                params,
+            )))
+            .unwrap();
+        loop {
+            match self.connection.receiver.recv_timeout(TIMEOUT).unwrap() {
+                Message::Response(response) if response.id == id => {
+                    assert!(response.error.is_none(), "{:?}", response.error);
+                    return serde_json::from_value(response.result.unwrap_or_default()).unwrap();
+                },
+                Message::Notification(notification) => self.notifications.push(notification),
+                msg => panic!("unexpected message: {:?}", msg),
+            }
+        }
+    }
+
+    fn notify<N: lsp_types::notification::Notification>(&self, params: N::Params) {
+        self.connection
+            .sender
+            .send(Message::Notification(Notification::new(
+                N::METHOD.to_string(),
+                params,
+            )))
+            .unwrap();
+    }
+
+    /// Waits for the next diagnostics published for the document.
+    fn diagnostics(&mut self, uri: &Url) -> Vec<Diagnostic> {
+        loop {
+            let idx = self.notifications.iter().position(|notification| {
+                notification.method == PublishDiagnostics::METHOD
+                    && notification.params["uri"] == uri.as_str()
+            });
+            if let Some(idx) = idx {
+                let notification = self.notifications.remove(idx);
+                let params: PublishDiagnosticsParams =
+                    serde_json::from_value(notification.params).unwrap();
+                return params.diagnostics;
+            }
+            match self.connection.receiver.recv_timeout(TIMEOUT).unwrap() {
+                Message::Notification(notification) => self.notifications.push(notification),
+                msg => panic!("unexpected message: {:?}", msg),
+            }
+        }
+    }
+
+    fn open(&mut self, path: &Path) -> Url {
+        let uri = Url::from_file_path(path).unwrap();
+        self.notify::<DidOpenTextDocument>(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem::new(
+                uri.clone(),
+                "move".to_string(),
+                0,
+                fs::read_to_string(path).unwrap(),
+            ),
+        });
+        uri
+    }
+
+    fn goto_definition(&mut self, uri: &Url, position: Position) -> Option<Location> {
+        let response = self.request::<GotoDefinition>(GotoDefinitionParams {
+            text_document_position_params: position_params(uri, position),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        });
+        match response? {
+            GotoDefinitionResponse::Scalar(location) => Some(location),
+            response => panic!("unexpected response: {:?}", response),
+        }
+    }
+
+    fn references(&mut self, uri: &Url, position: Position) -> Vec<Location> {
+        let mut references = self
+            .request::<References>(ReferenceParams {
+                text_document_position: position_params(uri, position),
+                work_done_progress_params: Default::default(),
+                partial_result_params: Default::default(),
+                context: ReferenceContext {
+                    include_declaration: true,
+                },
+            })
+            .unwrap_or_default();
+        references.sort_by_key(|location| (location.uri.to_string(), location.range.start));
+        references
+    }
+
+    fn hover(&mut self, uri: &Url, position: Position) -> String {
+        let hover = self
+            .request::<HoverRequest>(HoverParams {
+                text_document_position_params: position_params(uri, position),
+                work_done_progress_params: Default::default(),
+            })
+            .expect("hover expected");
+        match hover.contents {
+            HoverContents::Markup(content) => content.value,
+            contents => panic!("unexpected hover contents: {:?}", contents),
+        }
+    }
+
+    fn completion(&mut self, uri: &Url, position: Position) -> Vec<String> {
+        let response = self.request::<Completion>(CompletionParams {
+            text_document_position: position_params(uri, position),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+            context: None,
+        });
+        match response {
+            Some(CompletionResponse::Array(items)) => {
+                items.into_iter().map(|item| item.label).collect()
+            },
+            response => panic!("unexpected response: {:?}", response),
+        }
+    }
+}
+
+fn position_params(uri: &Url, position: Position) -> TextDocumentPositionParams {
+    TextDocumentPositionParams::new(TextDocumentIdentifier::new(uri.clone()), position)
+}
+
+fn testdata(path: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/testdata")
+        .join(path)
+        .canonicalize()
+        .unwrap()
+}
+
+/// Returns the position of the start of the `nth` (zero-based) occurrence of `needle`.
+fn position_of(path: &Path, needle: &str, nth: usize) -> Position {
+    let source = fs::read_to_string(path).unwrap();
+    let (offset, _) = source
+        .match_indices(needle)
+        .nth(nth)
+        .unwrap_or_else(|| panic!("`{}` not found in {}", needle, path.display()));
+    let line = source[..offset].matches('\n').count();
+    let line_start = source[..offset].rfind('\n').map_or(0, |idx| idx + 1);
+    Position::new(line as u32, (offset - line_start) as u32)
+}
+
+fn location_of(path: &Path, needle: &str, nth: usize) -> (Url, Position) {
+    (
+        Url::from_file_path(path).unwrap(),
+        position_of(path, needle, nth),
+    )
+}
+
+fn start_of(location: &Location) -> (Url, Position) {
+    (location.uri.clone(), location.range.start)
+}
+
+#[test]
+fn test_diagnostics_on_save() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().canonicalize().unwrap();
+    fs::create_dir(root.join("sources")).unwrap();
+    fs::copy(testdata("broken/Move.toml"), root.join("Move.toml")).unwrap();
+    let path = root.join("sources/counter.move");
+    fs::copy(testdata("broken/sources/counter.move"), &path).unwrap();
+
+    let mut client = TestClient::start();
+    let uri = client.open(&path);
+
+    let diagnostics = client.diagnostics(&uri);
+    assert!(!diagnostics.is_empty());
+    for diag in &diagnostics {
+        assert_eq!(diag.severity, Some(DiagnosticSeverity::ERROR), "{:?}", diag);
+        assert_eq!(diag.range.start.line, 2, "{:?}", diag);
+    }
+
+    // Fix the error; the diagnostics are only updated once the file is saved.
+    let fixed = fs::read_to_string(&path)
+        .unwrap()
+        .replace("counter + true", "counter + 1");
+    client.notify::<DidChangeTextDocument>(DidChangeTextDocumentParams {
+        text_document: VersionedTextDocumentIdentifier::new(uri.clone(), 1),
+        content_changes: vec![TextDocumentContentChangeEvent {
+            range: None,
+            range_length: None,
+            text: fixed.clone(),
+        }],
+    });
+    fs::write(&path, fixed).unwrap();
+    client.notify::<DidSaveTextDocument>(DidSaveTextDocumentParams {
+        text_document: TextDocumentIdentifier::new(uri.clone()),
+        text: None,
+    });
+    assert_eq!(client.diagnostics(&uri), vec![]);
+
+    client.shutdown();
+}
+
+#[test]
+fn test_known_attributes() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().canonicalize().unwrap();
+    fs::create_dir(root.join("sources")).unwrap();
+    fs::copy(testdata("broken/Move.toml"), root.join("Move.toml")).unwrap();
+    let path = root.join("sources/view.move");
+    fs::write(
+        &path,
+        "module broken::view {\n    #[view]\n    public fun get(): u64 { 1 }\n}\n",
+    )
+    .unwrap();
+
+    // Unknown attributes are reported as warnings.
+    let mut client = TestClient::start();
+    let uri = client.open(&path);
+    let diagnostics = client.diagnostics(&uri);
+    assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+    assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::WARNING));
+    assert_eq!(diagnostics[0].range.start.line, 1);
+    client.shutdown();
+
+    // Attributes passed by the client are known.
+    let mut client =
+        TestClient::start_with_options(Some(serde_json::json!({ "knownAttributes": ["view"] })));
+    let uri = client.open(&path);
+    assert_eq!(client.diagnostics(&uri), vec![]);
+    client.shutdown();
+}
+
+#[test]
+fn test_malformed_notification() {
+    let coin = testdata("basic/sources/coin.move");
+
+    let mut client = TestClient::start();
+    // The malformed notification is skipped, and the server keeps running.
+    client
+        .connection
+        .sender
+        .send(Message::Notification(Notification::new(
+            DidOpenTextDocument::METHOD.to_string(),
+            serde_json::json!({ "textDocument": "coin.move" }),
+        )))
+        .unwrap();
+    let uri = client.open(&coin);
+    let hover = client.hover(&uri, position_of(&coin, "mint(", 0));
+    assert!(hover.contains("public fun mint("), "{}", hover);
+
+    client.shutdown();
+}
+
+#[test]
+fn test_goto_definition() {
+    let coin = testdata("basic/sources/coin.move");
+    let wallet = testdata("basic/sources/wallet.move");
+    let math = testdata("util/sources/math.move");
+
+    let mut client = TestClient::start();
+    let uri = client.open(&wallet);
+    let diagnostics = client.diagnostics(&uri);
+    assert!(
+        diagnostics
+            .iter()
+            .all(|diag| diag.severity != Some(DiagnosticSeverity::ERROR)),
+        "{:?}",
+        diagnostics
+    );
+
+    // Function in another module
+    let location = client
+        .goto_definition(&uri, position_of(&wallet, "mint", 0))
+        .unwrap();
+    assert_eq!(start_of(&location), location_of(&coin, "mint(", 0));
+
+    // Module qualifying a call
+    let location = client
+        .goto_definition(&uri, position_of(&wallet, "coin::mint", 0))
+        .unwrap();
+    assert_eq!(start_of(&location), location_of(&coin, "coin", 0));
+
+    // Struct in the same module
+    let location = client
+        .goto_definition(&uri, position_of(&wallet, "Wallet { coin }", 0))
+        .unwrap();
+    assert_eq!(start_of(&location), location_of(&wallet, "Wallet", 0));
+
+    // Local shadowing the module name
+    let location = client
+        .goto_definition(&uri, position_of(&wallet, "coin }", 0))
+        .unwrap();
+    assert_eq!(start_of(&location), location_of(&wallet, "coin = ", 0));
+
+    // Field
+    let location = client
+        .goto_definition(&uri, position_of(&wallet, "coin)", 0))
+        .unwrap();
+    assert_eq!(start_of(&location), location_of(&wallet, "coin: Coin", 0));
+
+    // Function in a dependency
+    let uri = client.open(&coin);
+    let location = client
+        .goto_definition(&uri, position_of(&coin, "double(coin.value)", 0))
+        .unwrap();
+    assert_eq!(start_of(&location), location_of(&math, "double", 0));
+
+    // Nothing to go to
+    assert_eq!(
+        client.goto_definition(&uri, position_of(&coin, "public", 0)),
+        None
+    );
+
+    client.shutdown();
+}
+
+#[test]
+fn test_references() {
+    let coin = testdata("basic/sources/coin.move");
+    let wallet = testdata("basic/sources/wallet.move");
+
+    let mut client = TestClient::start();
+    let uri = client.open(&coin);
+
+    let references = client
+        .references(&uri, position_of(&coin, "value(coin", 0))
+        .iter()
+        .map(start_of)
+        .collect::<Vec<_>>();
+    assert_eq!(references, vec![
+        location_of(&coin, "value(coin", 0),
+        location_of(&wallet, "value", 0),
+    ]);
+
+    let references = client
+        .references(&uri, position_of(&coin, "value: u64", 0))
+        .iter()
+        .map(start_of)
+        .collect::<Vec<_>>();
+    assert_eq!(references, vec![
+        location_of(&coin, "value: u64", 0),
+        location_of(&coin, "value\n", 0),
+        location_of(&coin, "value)", 0),
+    ]);
+
+    // Parameter
+    let references = client
+        .references(&uri, position_of(&coin, "value: u64): Coin", 0))
+        .iter()
+        .map(start_of)
+        .collect::<Vec<_>>();
+    assert_eq!(references, vec![
+        location_of(&coin, "value: u64): Coin", 0),
+        location_of(&coin, "value <=", 0),
+        location_of(&coin, "value }", 0),
+    ]);
+
+    client.shutdown();
+}
+
+#[test]
+fn test_hover() {
+    let coin = testdata("basic/sources/coin.move");
+
+    let mut client = TestClient::start();
+    let uri = client.open(&coin);
+
+    let hover = client.hover(&uri, position_of(&coin, "Coin { value }", 0));
+    assert!(hover.contains("struct Coin has drop, store"), "{}", hover);
+    assert!(hover.contains("value: u64"), "{}", hover);
+    assert!(hover.contains("A coin holding some value."), "{}", hover);
+
+    let hover = client.hover(&uri, position_of(&coin, "mint(", 0));
+    assert!(hover.contains("public fun mint("), "{}", hover);
+    assert!(
+        hover.contains("Mints a coin of the given value."),
+        "{}",
+        hover
+    );
+
+    let hover = client.hover(&uri, position_of(&coin, "MAX_VALUE, 1", 0));
+    assert!(hover.contains("const MAX_VALUE: u64"), "{}", hover);
+    assert!(
+        hover.contains("The maximum value of a freshly minted coin."),
+        "{}",
+        hover
+    );
+
+    let hover = client.hover(&uri, position_of(&coin, "doubled }", 0));
+    assert!(hover.contains("doubled: u64"), "{}", hover);
+
+    client.shutdown();
+}
+
+#[test]
+fn test_completion() {
+    let wallet = testdata("basic/sources/wallet.move");
+
+    let mut client = TestClient::start();
+    let uri = client.open(&wallet);
+
+    // Simulate typing a qualified name, without saving.
+    let text = fs::read_to_string(&wallet)
+        .unwrap()
+        .replace("move_to(", "coin::m\n        move_to(");
+    client.notify::<DidChangeTextDocument>(DidChangeTextDocumentParams {
+        text_document: VersionedTextDocumentIdentifier::new(uri.clone(), 1),
+        content_changes: vec![TextDocumentContentChangeEvent {
+            range: None,
+            range_length: None,
+            text,
+        }],
+    });
+
+    let position = position_of(&wallet, "move_to(", 0);
+    assert_eq!(
+        client.completion(&uri, Position::new(position.line, position.character + 7)),
+        vec!["mint".to_string()]
+    );
+    assert_eq!(
+        client.completion(&uri, Position::new(position.line, position.character + 6)),
+        vec![
+            "Coin".to_string(),
+            "MAX_VALUE".to_string(),
+            "double".to_string(),
+            "mint".to_string(),
+            "value".to_string(),
+        ]
+    );
+
+    // Unqualified names include the locals in scope.
+    let labels = client.completion(&uri, position_of(&wallet, "amount);", 0));
+    assert!(labels.contains(&"amount".to_string()), "{:?}", labels);
+    assert!(labels.contains(&"account".to_string()), "{:?}", labels);
+    assert!(labels.contains(&"coin".to_string()), "{:?}", labels);
+    assert!(labels.contains(&"deposit".to_string()), "{:?}", labels);
+
+    client.shutdown();
+}
@@ -0,0 +1,26 @@
+module basic::coin {
+    use util::math;
+
+    /// The maximum value of a freshly minted coin.
+    const MAX_VALUE: u64 = 1000;
+
+    /// A coin holding some value.
+    struct Coin has store, drop {
+        value: u64,
+    }
+
+    /// Mints a coin of the given value.
+    public fun mint(value: u64): Coin {
+        assert!(value <= MAX_VALUE, 1);
+        Coin { value }
+    }
+
+    public fun value(coin: &Coin): u64 {
+        coin.value
+    }
+
+    public fun double(coin: Coin): Coin {
+        let doubled = math::double(coin.value);
+        Coin { value: doubled }
+    }
+}
@@ -0,0 +1,16 @@
+module basic::wallet {
+    use basic::coin::{Self, Coin};
+
+    struct Wallet has key {
+        coin: Coin,
+    }
+
+    public fun deposit(account: &signer, amount: u64) {
+        let coin = coin::mint(amount);
+        move_to(account, Wallet { coin });
+    }
+
+    public fun balance(addr: address): u64 acquires Wallet {
+        coin::value(&borrow_global<Wallet>(addr).coin)
+    }
+}
@@ -0,0 +1,5 @@
+module broken::counter {
+    public fun increment(counter: u64): u64 {
+        counter + true
+    }
+}
@@ -0,0 +1,6 @@
+module util::math {
+    /// Returns twice the given value.
+    public fun double(x: u64): u64 {
+        x * 2
+    }
+}
@@ -0,0 +1,496 @@
+// Copyright (c) Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Generation and shrinking of values for the generated parameters of `#[fuzz]` tests, i.e., the
+//! parameters which are not assigned in the `#[test]` attribute (see
+//! `TestCase::generated_parameters`).
+//!
+//! A test with generated parameters is run with inputs drawn from a pseudo-random generator,
+//! which is seeded from the fuzzing seed and the name of the test. Running the tests again with
+//! the same seed therefore replays the same inputs, independently of which other tests are run.
+//! Once an input makes the test fail, it is shrunk to a simpler input which still fails.
+
+use legacy_move_compiler::unit_test::GeneratedType;
+use move_core_types::{
+    account_address::AccountAddress,
+    language_storage::ModuleId,
+    u256::U256,
+    value::{MoveStruct, MoveValue},
+};
+use std::{
+    fmt,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The default number of inputs a test with generated parameters is run with.
+pub const DEFAULT_FUZZ_RUNS: u64 = 100;
+
+/// The maximal number of elements of generated vectors and characters of generated strings.
+const MAX_GENERATED_LEN: u64 = 16;
+
+/// The maximal number of test executions spent on shrinking a failing input.
+const MAX_SHRINK_RUNS: usize = 1_000;
+
+/// Non-ASCII characters which are mixed into generated strings.
+const NON_ASCII_CHARS: &[char] = &['é', 'ß', 'λ', 'Ж', '中', '€', '🦀'];
+
+/// Details of a failing test with generated parameters, for reporting and replay.
+#[derive(Debug, Clone, Ord, PartialOrd, PartialEq, Eq)]
+pub struct FuzzFailure {
+    /// The fuzzing seed the tests were run with.
+    pub seed: u64,
+    /// The number of the run which failed first, starting at 1.
+    pub run: u64,
+    /// The number of times the failing input was successfully shrunk.
+    pub shrinks: usize,
+    /// The name and value of each generated parameter in the shrunk input.
+    pub input: Vec<(String, String)>,
+}
+
+impl fmt::Display for FuzzFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Failed on run {} with fuzzing seed {}. The input was shrunk {} times to:",
+            self.run, self.seed, self.shrinks
+        )?;
+        for (name, value) in &self.input {
+            writeln!(f, "    {} = {}", name, value)?;
+        }
+        write!(
+            f,
+            "Rerun with `--fuzz-seed {}` to reproduce this failure.",
+            self.seed
+        )
+    }
+}
+
+/// A SplitMix64 generator. It is used instead of a library generator so that a seed produces the
+/// same inputs on all platforms and across releases.
+pub struct FuzzRng(u64);
+
+impl FuzzRng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    /// Creates the generator for the given test, seeded from the fuzzing seed and the fully
+    /// qualified name of the test.
+    pub fn for_test(seed: u64, module_id: &ModuleId, function_name: &str) -> Self {
+        // FNV-1a, which unlike the hashers of the standard library is stable across releases.
+        let name = format!("{}::{}", module_id, function_name);
+        let name_hash = name.bytes().fold(0xCBF2_9CE4_8422_2325u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0100_0000_01B3)
+        });
+        Self::new(seed ^ name_hash)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a number in `0..bound`, which must be positive.
+    pub fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+
+    fn next_u256(&mut self) -> U256 {
+        let mut bytes = [0u8; 32];
+        for chunk in bytes.chunks_mut(8) {
+            chunk.copy_from_slice(&self.next_u64().to_le_bytes());
+        }
+        U256::from_le_bytes(&bytes)
+    }
+}
+
+/// Returns a seed for a run of tests for which none was given.
+pub fn random_seed() -> u64 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos() as u64)
+        .unwrap_or_default();
+    FuzzRng::new(nanos ^ std::process::id() as u64).next_u64()
+}
+
+/// Generates a value of type `ty`. Generated values favor edge cases such as zero, one, and the
+/// maximal value of integers, and empty vectors.
+pub fn generate(rng: &mut FuzzRng, ty: &GeneratedType) -> MoveValue {
+    match ty {
+        GeneratedType::Bool => MoveValue::Bool(rng.below(2) == 1),
+        GeneratedType::Address => {
+            let address = match rng.below(4) {
+                0 => AccountAddress::ZERO,
+                1 => AccountAddress::ONE,
+                2 => {
+                    let mut bytes = [0u8; AccountAddress::LENGTH];
+                    bytes[AccountAddress::LENGTH - 1] = rng.below(256) as u8;
+                    AccountAddress::new(bytes)
+                },
+                _ => {
+                    let mut bytes = [0u8; AccountAddress::LENGTH];
+                    bytes.fill_with(|| rng.below(256) as u8);
+                    AccountAddress::new(bytes)
+                },
+            };
+            MoveValue::Address(address)
+        },
+        GeneratedType::Vector(elem_ty) => {
+            let len = generate_len(rng);
+            MoveValue::Vector((0..len).map(|_| generate(rng, elem_ty)).collect())
+        },
+        GeneratedType::String => {
+            let len = generate_len(rng);
+            let string: String = (0..len)
+                .map(|_| match rng.below(8) {
+                    0 => NON_ASCII_CHARS[rng.below(NON_ASCII_CHARS.len() as u64) as usize],
+                    // Printable ASCII characters
+                    _ => (b' ' + rng.below(95) as u8) as char,
+                })
+                .collect();
+            string_value(&string)
+        },
+        GeneratedType::Option(elem_ty) => {
+            let elems = if rng.below(2) == 0 {
+                vec![]
+            } else {
+                vec![generate(rng, elem_ty)]
+            };
+            MoveValue::Struct(MoveStruct::Runtime(vec![MoveValue::Vector(elems)]))
+        },
+        GeneratedType::Struct { fields, .. } => MoveValue::Struct(MoveStruct::Runtime(
+            fields.iter().map(|(_, ty)| generate(rng, ty)).collect(),
+        )),
+        _ => {
+            let bits = int_bits(ty);
+            let max = int_max(bits);
+            let value = match rng.below(8) {
+                0 => U256::zero(),
+                1 => U256::one(),
+                2 => max,
+                3 => max - U256::one(),
+                4 => U256::from(rng.below(256)).min(max),
+                // Random values of random bit length, so that all magnitudes are covered
+                _ => (rng.next_u256() & max) >> (rng.below(bits as u64) as u8),
+            };
+            int_value(ty, value)
+        },
+    }
+}
+
+fn generate_len(rng: &mut FuzzRng) -> u64 {
+    match rng.below(4) {
+        0 => 0,
+        1 => 1,
+        _ => rng.below(MAX_GENERATED_LEN + 1),
+    }
+}
+
+/// Returns simpler values of type `ty` than `value`, simplest first.
+pub fn shrink_candidates(ty: &GeneratedType, value: &MoveValue) -> Vec<MoveValue> {
+    match (ty, value) {
+        (GeneratedType::Bool, MoveValue::Bool(true)) => vec![MoveValue::Bool(false)],
+        (GeneratedType::Bool, _) => vec![],
+        (GeneratedType::Address, MoveValue::Address(address)) => {
+            [AccountAddress::ZERO, AccountAddress::ONE]
+                .into_iter()
+                .filter(|candidate| candidate < address)
+                .map(MoveValue::Address)
+                .collect()
+        },
+        (GeneratedType::Vector(elem_ty), MoveValue::Vector(elems)) => {
+            shrink_vector(elems, |elem| shrink_candidates(elem_ty, elem))
+                .into_iter()
+                .map(MoveValue::Vector)
+                .collect()
+        },
+        (GeneratedType::String, _) => {
+            let chars: Vec<char> = string_of(value).chars().collect();
+            let shrink_char = |c: &char| if *c == 'a' { vec![] } else { vec!['a'] };
+            shrink_vector(&chars, shrink_char)
+                .into_iter()
+                .map(|chars| string_value(&chars.into_iter().collect::<String>()))
+                .collect()
+        },
+        (GeneratedType::Option(elem_ty), MoveValue::Struct(MoveStruct::Runtime(fields))) => {
+            match fields.as_slice() {
+                [MoveValue::Vector(elems)] if !elems.is_empty() => {
+                    let mut candidates = vec![vec![]];
+                    candidates.extend(
+                        shrink_candidates(elem_ty, &elems[0])
+                            .into_iter()
+                            .map(|elem| vec![elem]),
+                    );
+                    candidates
+                        .into_iter()
+                        .map(|elems| {
+                            MoveValue::Struct(MoveStruct::Runtime(vec![MoveValue::Vector(elems)]))
+                        })
+                        .collect()
+                },
+                _ => vec![],
+            }
+        },
+        (
+            GeneratedType::Struct {
+                fields: field_tys, ..
+            },
+            MoveValue::Struct(MoveStruct::Runtime(fields)),
+        ) => {
+            let mut candidates = vec![];
+            for (index, ((_, field_ty), field)) in field_tys.iter().zip(fields).enumerate() {
+                for candidate in shrink_candidates(field_ty, field) {
+                    let mut fields = fields.clone();
+                    fields[index] = candidate;
+                    candidates.push(MoveValue::Struct(MoveStruct::Runtime(fields)));
+                }
+            }
+            candidates
+        },
+        _ => {
+            let value = int_of(value);
+            let mut candidates = vec![];
+            if value > U256::zero() {
+                candidates.push(U256::zero());
+                // Approach the value from below in halving steps.
+                let mut delta = value >> 1u8;
+                while delta > U256::zero() {
+                    candidates.push(value - delta);
+                    delta = delta >> 1u8;
+                }
+            }
+            candidates.dedup();
+            candidates
+                .into_iter()
+                .map(|candidate| int_value(ty, candidate))
+                .collect()
+        },
+    }
+}
+
+/// Returns shorter vectors than `elems` first, and then vectors with one element shrunk.
+fn shrink_vector<T: Clone>(elems: &[T], shrink_elem: impl Fn(&T) -> Vec<T>) -> Vec<Vec<T>> {
+    if elems.is_empty() {
+        return vec![];
+    }
+    let mut candidates = vec![vec![]];
+    if elems.len() > 2 {
+        candidates.push(elems[..elems.len() / 2].to_vec());
+        candidates.push(elems[elems.len() / 2..].to_vec());
+    }
+    if elems.len() > 1 {
+        for index in 0..elems.len() {
+            let mut candidate = elems.to_vec();
+            candidate.remove(index);
+            candidates.push(candidate);
+        }
+    }
+    for (index, elem) in elems.iter().enumerate() {
+        for shrunk in shrink_elem(elem) {
+            let mut candidate = elems.to_vec();
+            candidate[index] = shrunk;
+            candidates.push(candidate);
+        }
+    }
+    candidates
+}
+
+/// Shrinks a failing input, given the types of its values, by repeatedly replacing one of its
+/// values with the first simpler value for which the input still fails. Returns the shrunk input
+/// and how often it was shrunk.
+pub fn shrink(
+    types: &[GeneratedType],
+    mut input: Vec<MoveValue>,
+    mut fails: impl FnMut(&[MoveValue]) -> bool,
+) -> (Vec<MoveValue>, usize) {
+    let mut runs = 0;
+    let mut shrinks = 0;
+    'shrink: loop {
+        for (index, ty) in types.iter().enumerate() {
+            for candidate in shrink_candidates(ty, &input[index]) {
+                if runs == MAX_SHRINK_RUNS {
+                    break 'shrink;
+                }
+                runs += 1;
+                let mut candidate_input = input.clone();
+                candidate_input[index] = candidate;
+                if fails(&candidate_input) {
+                    input = candidate_input;
+                    shrinks += 1;
+                    continue 'shrink;
+                }
+            }
+        }
+        break;
+    }
+    (input, shrinks)
+}
+
+/// Formats a generated value in Move syntax.
+pub fn display_value(ty: &GeneratedType, value: &MoveValue) -> String {
+    match (ty, value) {
+        (GeneratedType::Address, MoveValue::Address(address)) => {
+            format!("@{}", address.to_hex_literal())
+        },
+        (GeneratedType::Vector(elem_ty), MoveValue::Vector(elems)) => format!(
+            "vector[{}]",
+            elems
+                .iter()
+                .map(|elem| display_value(elem_ty, elem))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        (GeneratedType::String, _) => format!("string::utf8(b{:?})", string_of(value)),
+        (GeneratedType::Option(elem_ty), MoveValue::Struct(MoveStruct::Runtime(fields))) => {
+            match fields.as_slice() {
+                [MoveValue::Vector(elems)] if !elems.is_empty() => {
+                    format!("option::some({})", display_value(elem_ty, &elems[0]))
+                },
+                _ => "option::none()".to_string(),
+            }
+        },
+        (
+            GeneratedType::Struct {
+                name,
+                fields: field_tys,
+            },
+            MoveValue::Struct(MoveStruct::Runtime(fields)),
+        ) => format!(
+            "{} {{ {} }}",
+            name,
+            field_tys
+                .iter()
+                .zip(fields)
+                .map(|((field_name, field_ty), field)| format!(
+                    "{}: {}",
+                    field_name,
+                    display_value(field_ty, field)
+                ))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        (_, MoveValue::Bool(value)) => value.to_string(),
+        _ => int_of(value).to_string(),
+    }
+}
+
+fn string_value(string: &str) -> MoveValue {
+    MoveValue::Struct(MoveStruct::Runtime(vec![MoveValue::vector_u8(
+        string.as_bytes().to_vec(),
+    )]))
+}
+
+fn string_of(value: &MoveValue) -> String {
+    let bytes = match value {
+        MoveValue::Struct(MoveStruct::Runtime(fields)) => match fields.as_slice() {
+            [MoveValue::Vector(bytes)] => bytes
+                .iter()
+                .filter_map(|byte| match byte {
+                    MoveValue::U8(byte) => Some(*byte),
+                    _ => None,
+                })
+                .collect(),
+            _ => vec![],
+        },
+        _ => vec![],
+    };
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+fn int_bits(ty: &GeneratedType) -> u32 {
+    match ty {
+        GeneratedType::U8 => 8,
+        GeneratedType::U16 => 16,
+        GeneratedType::U32 => 32,
+        GeneratedType::U64 => 64,
+        GeneratedType::U128 => 128,
+        GeneratedType::U256 => 256,
+        _ => unreachable!("not an integer type: {:?}", ty),
+    }
+}
+
+fn int_max(bits: u32) -> U256 {
+    U256::max_value() >> ((256 - bits) as u8)
+}
+
+fn int_value(ty: &GeneratedType, value: U256) -> MoveValue {
+    match ty {
+        GeneratedType::U8 => MoveValue::U8(value.unchecked_as_u8()),
+        GeneratedType::U16 => MoveValue::U16(value.unchecked_as_u16()),
+        GeneratedType::U32 => MoveValue::U32(value.unchecked_as_u32()),
+        GeneratedType::U64 => MoveValue::U64(value.unchecked_as_u64()),
+        GeneratedType::U128 => MoveValue::U128(value.unchecked_as_u128()),
+        GeneratedType::U256 => MoveValue::U256(value),
+        _ => unreachable!("not an integer type: {:?}", ty),
+    }
+}
+
+fn int_of(value: &MoveValue) -> U256 {
+    match value {
+        MoveValue::U8(value) => U256::from(*value),
+        MoveValue::U16(value) => U256::from(*value),
+        MoveValue::U32(value) => U256::from(*value),
+        MoveValue::U64(value) => U256::from(*value),
+        MoveValue::U128(value) => U256::from(*value),
+        MoveValue::U256(value) => *value,
+        _ => unreachable!("not an integer value: {:?}", value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{display_value, generate, shrink, FuzzRng};
+    use legacy_move_compiler::unit_test::GeneratedType;
+    use move_core_types::value::MoveValue;
+
+    #[test]
+    fn test_generate_is_reproducible() {
+        let ty = GeneratedType::Vector(Box::new(GeneratedType::Struct {
+            name: "0x42::m::S".to_string(),
+            fields: vec![
+                ("a".to_string(), GeneratedType::U8),
+                ("b".to_string(), GeneratedType::String),
+                (
+                    "c".to_string(),
+                    GeneratedType::Option(Box::new(GeneratedType::Address)),
+                ),
+            ],
+        }));
+        let generate_all = |seed| {
+            let mut rng = FuzzRng::new(seed);
+            (0..50).map(|_| generate(&mut rng, &ty)).collect::<Vec<_>>()
+        };
+        assert_eq!(generate_all(7), generate_all(7));
+        assert_ne!(generate_all(7), generate_all(8));
+    }
+
+    #[test]
+    fn test_shrink() {
+        // Fails for all inputs with `x >= 1000` and a vector containing `true`.
+        let types = [
+            GeneratedType::U64,
+            GeneratedType::Vector(Box::new(GeneratedType::Bool)),
+        ];
+        let input = vec![
+            MoveValue::U64(123_456_789),
+            MoveValue::Vector(vec![
+                MoveValue::Bool(false),
+                MoveValue::Bool(true),
+                MoveValue::Bool(true),
+            ]),
+        ];
+        let fails = |input: &[MoveValue]| match input {
+            [MoveValue::U64(x), MoveValue::Vector(elems)] => {
+                *x >= 1000 && elems.contains(&MoveValue::Bool(true))
+            },
+            _ => false,
+        };
+        let (shrunk, shrinks) = shrink(&types, input, fails);
+        assert!(shrinks > 0);
+        assert_eq!(display_value(&types[0], &shrunk[0]), "1000");
+        assert_eq!(display_value(&types[1], &shrunk[1]), "vector[true]");
+    }
+}
@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod extensions;
+pub mod fuzz;
 pub mod test_reporter;
 pub mod test_runner;
 
@@ -21,8 +22,10 @@ use move_package::compilation::compiled_package::build_and_report_v2_driver;
 use move_vm_runtime::native_functions::NativeFunctionTable;
 use std::{
     collections::BTreeMap,
-    io::{Result, Write},
+    fs::File,
+    io::{BufWriter, Result, Write},
     marker::Send,
+    path::PathBuf,
     sync::Mutex,
 };
 use test_reporter::UnitTestFactory;
@@ -97,6 +100,24 @@ pub struct UnitTestingConfig {
     /// Verbose mode
     #[clap(short = 'v', long = "verbose")]
     pub verbose: bool,
+
+    /// Write a JUnit XML report of the test results to the given file
+    #[clap(name = "junit_report", long = "junit-report")]
+    pub junit_report: Option<PathBuf>,
+
+    /// Write the test results to the given file as line-delimited JSON, one test per line
+    #[clap(name = "json_report", long = "json-report")]
+    pub json_report: Option<PathBuf>,
+
+    /// Number of generated inputs to run each test with generated parameters with
+    #[clap(name = "fuzz_runs", long = "fuzz-runs", default_value_t = fuzz::DEFAULT_FUZZ_RUNS)]
+    pub fuzz_runs: u64,
+
+    /// Seed for generating the inputs of tests with generated parameters. A random seed is used
+    /// if none is given; the seed is reported along with failing inputs, so that they can be
+    /// reproduced.
+    #[clap(name = "fuzz_seed", long = "fuzz-seed")]
+    pub fuzz_seed: Option<u64>,
 }
 
 fn format_module_id(module_id: &ModuleId) -> String {
@@ -121,6 +142,10 @@ impl Default for UnitTestingConfig {
             verbose: false,
             list: false,
             named_address_values: vec![],
+            junit_report: None,
+            json_report: None,
+            fuzz_runs: fuzz::DEFAULT_FUZZ_RUNS,
+            fuzz_seed: None,
         }
     }
 }
@@ -213,6 +238,8 @@ impl UnitTestingConfig {
             native_function_table,
             genesis_state,
             self.verbose,
+            self.fuzz_runs,
+            self.fuzz_seed.unwrap_or_else(fuzz::random_seed),
         )
         .unwrap();
 
@@ -229,6 +256,17 @@ impl UnitTestingConfig {
             test_results.report_goldens(&shared_writer)?;
         }
 
+        if let Some(path) = &self.junit_report {
+            let mut report_writer = BufWriter::new(File::create(path)?);
+            test_results.report_junit(&mut report_writer)?;
+            report_writer.flush()?;
+        }
+        if let Some(path) = &self.json_report {
+            let mut report_writer = BufWriter::new(File::create(path)?);
+            test_results.report_json(&mut report_writer)?;
+            report_writer.flush()?;
+        }
+
         let ok = test_results.summarize(&shared_writer)?;
 
         let writer = shared_writer.into_inner().unwrap();
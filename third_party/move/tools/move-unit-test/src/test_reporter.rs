@@ -2,7 +2,7 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{format_module_id, DEFAULT_EXECUTION_BOUND};
+use crate::{format_module_id, fuzz::FuzzFailure, DEFAULT_EXECUTION_BOUND};
 use codespan_reporting::files::{Files, SimpleFiles};
 use colored::{control, Colorize};
 pub use legacy_move_compiler::unit_test::ExpectedMoveError as MoveError;
@@ -22,6 +22,7 @@ use move_vm_runtime::native_extensions::NativeContextExtensions;
 use move_vm_test_utils::gas_schedule::{zero_cost_schedule, CostTable, GasCost, GasStatus};
 use move_vm_types::gas::GasMeter;
 use once_cell::sync::Lazy;
+use serde::Serialize;
 use std::{
     collections::{BTreeMap, BTreeSet, HashMap},
     io::{Result, Write},
@@ -42,6 +43,18 @@ fn unit_cost_table() -> CostTable {
 pub trait UnitTestFactory {
     type GasMeter: GasMeter;
     fn new_gas_meter(&self) -> Self::GasMeter;
+
+    /// Creates a gas meter for running the given test function. Factories which need to know
+    /// which test is run (e.g., to attribute gas costs to the test) can override this. If the
+    /// gas meter cannot be created, the test fails with the returned error.
+    fn new_gas_meter_for_test(
+        &self,
+        _module_id: &ModuleId,
+        _function_name: &str,
+    ) -> anyhow::Result<Self::GasMeter> {
+        Ok(self.new_gas_meter())
+    }
+
     fn finalize_test_run_info(
         &self,
         change_set: &ChangeSet,
@@ -115,6 +128,8 @@ pub struct TestFailure {
     pub vm_error: Option<VMError>,
     pub failure_reason: FailureReason,
     pub storage_state: Option<String>,
+    /// The failing input, if the test has generated parameters.
+    pub fuzz_failure: Option<FuzzFailure>,
 }
 
 #[derive(Debug, Clone, Ord, PartialOrd, PartialEq, Eq)]
@@ -122,6 +137,8 @@ pub struct TestRunInfo {
     pub function_ident: String,
     pub elapsed_time: Duration,
     pub gas_used: u64,
+    /// The abort code, if the test aborted (whether expected or not).
+    pub abort_code: Option<u64>,
 }
 
 #[derive(Debug, Clone)]
@@ -143,6 +160,7 @@ impl TestRunInfo {
             function_ident,
             elapsed_time,
             gas_used: 0,
+            abort_code: None,
         }
     }
 }
@@ -193,6 +211,34 @@ impl FailureReason {
     pub fn property(details: String) -> Self {
         FailureReason::Property(details)
     }
+
+    /// A short, single-line description of the failure.
+    pub fn summary(&self) -> &str {
+        match self {
+            FailureReason::NoError(message)
+            | FailureReason::WrongError(message, ..)
+            | FailureReason::WrongAbortDEPRECATED(message, ..)
+            | FailureReason::UnexpectedError(message, _)
+            | FailureReason::Timeout(message) => message,
+            FailureReason::Mismatch { .. } => {
+                "Executions via Move VM and stackless VM yield different results"
+            },
+            FailureReason::Property(_) => "Property check failed",
+        }
+    }
+
+    /// The kind of the failure, as a `snake_case` identifier.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            FailureReason::NoError(_) => "no_error",
+            FailureReason::WrongError(..) => "wrong_error",
+            FailureReason::WrongAbortDEPRECATED(..) => "wrong_abort",
+            FailureReason::UnexpectedError(..) => "unexpected_error",
+            FailureReason::Timeout(_) => "timeout",
+            FailureReason::Mismatch { .. } => "mismatch",
+            FailureReason::Property(_) => "property",
+        }
+    }
 }
 
 impl TestFailure {
@@ -207,9 +253,15 @@ impl TestFailure {
             vm_error,
             failure_reason,
             storage_state,
+            fuzz_failure: None,
         }
     }
 
+    pub fn with_fuzz_failure(mut self, fuzz_failure: FuzzFailure) -> Self {
+        self.fuzz_failure = Some(fuzz_failure);
+        self
+    }
+
     pub fn render_error(&self, test_plan: &TestPlan) -> String {
         let error_string = match &self.failure_reason {
             FailureReason::NoError(message) => message.to_string(),
@@ -277,6 +329,10 @@ impl TestFailure {
             },
             FailureReason::Property(message) => message.clone(),
         };
+        let error_string = match &self.fuzz_failure {
+            None => error_string,
+            Some(fuzz_failure) => format!("{}\n{}", error_string, fuzz_failure),
+        };
 
         match &self.storage_state {
             None => error_string,
@@ -493,6 +549,28 @@ impl TestStatistics {
     }
 }
 
+/// The result of a single test, as written to machine-readable reports.
+#[derive(Debug, Serialize)]
+pub struct TestCaseReport {
+    /// The fully qualified name of the test, e.g. `0x1::coin_tests::test_mint`.
+    pub name: String,
+    pub module: String,
+    pub function: String,
+    /// One of `passed`, `failed` or `timeout`.
+    pub status: &'static str,
+    pub duration_secs: f64,
+    pub abort_code: Option<u64>,
+    /// The gas used, or the number of instructions executed when running with a unit cost table.
+    pub gas_used: u64,
+    /// A short description of the failure, if the test failed.
+    pub failure: Option<String>,
+    /// The kind of the failure (see `FailureReason::kind`), if the test failed.
+    pub failure_kind: Option<&'static str>,
+    /// The rendered failure including the location and stack trace for failed tests, and the
+    /// recorded write set in verbose mode. Output printed by the test itself is not included.
+    pub output: Option<String>,
+}
+
 impl TestResults {
     pub fn new(final_statistics: TestStatistics, test_plan: TestPlan) -> Self {
         Self {
@@ -501,6 +579,142 @@ impl TestResults {
         }
     }
 
+    /// Returns the results of all tests, grouped by module and sorted by name.
+    pub fn test_case_reports(&self) -> BTreeMap<ModuleId, Vec<TestCaseReport>> {
+        // Failures are rendered without color escapes, since reports are not shown in a terminal.
+        control::set_override(false);
+        let mut reports: BTreeMap<ModuleId, Vec<TestCaseReport>> = BTreeMap::new();
+        let mut add_report = |module_id: &ModuleId,
+                              test_run_info: &TestRunInfo,
+                              failure: Option<&TestFailure>| {
+            let module = format_module_id(module_id);
+            let recorded_output = self
+                .final_statistics
+                .output
+                .get(module_id)
+                .and_then(|outputs| outputs.get(&test_run_info.function_ident));
+            let output = match (failure, recorded_output) {
+                (Some(failure), Some(recorded)) => Some(format!(
+                    "{}\n{}",
+                    failure.render_error(&self.test_plan),
+                    recorded
+                )),
+                (Some(failure), None) => Some(failure.render_error(&self.test_plan)),
+                (None, recorded) => recorded.cloned(),
+            };
+            reports
+                .entry(module_id.clone())
+                .or_default()
+                .push(TestCaseReport {
+                    name: format!("{}::{}", module, test_run_info.function_ident),
+                    module,
+                    function: test_run_info.function_ident.clone(),
+                    status: match failure.map(|failure| &failure.failure_reason) {
+                        None => "passed",
+                        Some(FailureReason::Timeout(_)) => "timeout",
+                        Some(_) => "failed",
+                    },
+                    duration_secs: test_run_info.elapsed_time.as_secs_f64(),
+                    abort_code: test_run_info.abort_code,
+                    gas_used: test_run_info.gas_used,
+                    failure: failure.map(|failure| failure.failure_reason.summary().to_string()),
+                    failure_kind: failure.map(|failure| failure.failure_reason.kind()),
+                    output,
+                });
+        };
+        for (module_id, test_results) in &self.final_statistics.passed {
+            for test_run_info in test_results {
+                add_report(module_id, test_run_info, None);
+            }
+        }
+        for (module_id, test_failures) in &self.final_statistics.failed {
+            for test_failure in test_failures {
+                add_report(module_id, &test_failure.test_run_info, Some(test_failure));
+            }
+        }
+        control::unset_override();
+
+        for module_reports in reports.values_mut() {
+            module_reports.sort_by(|report1, report2| report1.function.cmp(&report2.function));
+        }
+        reports
+    }
+
+    /// Writes the results of all tests as line-delimited JSON, one `TestCaseReport` per line.
+    pub fn report_json<W: Write>(&self, writer: &mut W) -> Result<()> {
+        for report in self.test_case_reports().values().flatten() {
+            serde_json::to_writer(&mut *writer, report)?;
+            writeln!(writer)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the results of all tests in the JUnit XML format, with one test suite per module.
+    pub fn report_junit<W: Write>(&self, writer: &mut W) -> Result<()> {
+        let reports = self.test_case_reports();
+        let all_reports = || reports.values().flatten();
+
+        writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(
+            writer,
+            r#"<testsuites name="Move unit tests" tests="{}" failures="{}" errors="0" time="{:.3}">"#,
+            all_reports().count(),
+            count_failures(all_reports()),
+            total_time(all_reports())
+        )?;
+        for (module_id, module_reports) in &reports {
+            writeln!(
+                writer,
+                r#"  <testsuite name="{}" tests="{}" failures="{}" errors="0" skipped="0" time="{:.3}">"#,
+                escape_xml(&format_module_id(module_id)),
+                module_reports.len(),
+                count_failures(module_reports),
+                total_time(module_reports)
+            )?;
+            for report in module_reports {
+                writeln!(
+                    writer,
+                    r#"    <testcase name="{}" classname="{}" time="{:.3}">"#,
+                    escape_xml(&report.function),
+                    escape_xml(&report.module),
+                    report.duration_secs
+                )?;
+                writeln!(writer, "      <properties>")?;
+                writeln!(
+                    writer,
+                    r#"        <property name="gas_used" value="{}"/>"#,
+                    report.gas_used
+                )?;
+                if let Some(abort_code) = report.abort_code {
+                    writeln!(
+                        writer,
+                        r#"        <property name="abort_code" value="{}"/>"#,
+                        abort_code
+                    )?;
+                }
+                writeln!(writer, "      </properties>")?;
+                if let Some(failure) = &report.failure {
+                    writeln!(
+                        writer,
+                        r#"      <failure message="{}" type="{}">{}</failure>"#,
+                        escape_xml(failure),
+                        report.failure_kind.unwrap_or_default(),
+                        escape_xml(report.output.as_deref().unwrap_or_default())
+                    )?;
+                } else if let Some(output) = &report.output {
+                    writeln!(
+                        writer,
+                        "      <system-out>{}</system-out>",
+                        escape_xml(output)
+                    )?;
+                }
+                writeln!(writer, "    </testcase>")?;
+            }
+            writeln!(writer, "  </testsuite>")?;
+        }
+        writeln!(writer, "</testsuites>")
+    }
+
     pub fn report_goldens<W: Write>(&self, writer: &Mutex<W>) -> Result<()> {
         for (module_name, test_outputs) in self.final_statistics.output.iter() {
             for (test_name, write_set) in test_outputs.iter() {
@@ -659,3 +873,97 @@ impl TestResults {
         Ok(num_failed_tests == 0)
     }
 }
+
+fn count_failures<'a>(reports: impl IntoIterator<Item = &'a TestCaseReport>) -> usize {
+    reports
+        .into_iter()
+        .filter(|report| report.failure.is_some())
+        .count()
+}
+
+fn total_time<'a>(reports: impl IntoIterator<Item = &'a TestCaseReport>) -> f64 {
+    reports.into_iter().map(|report| report.duration_secs).sum()
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FailureReason, TestFailure, TestResults, TestRunInfo, TestStatistics};
+    use legacy_move_compiler::unit_test::{ModuleTestPlan, TestPlan};
+    use move_core_types::{
+        account_address::AccountAddress, identifier::Identifier, language_storage::ModuleId,
+    };
+    use serde_json::{json, Value};
+    use std::{
+        collections::{BTreeMap, HashMap},
+        time::Duration,
+    };
+
+    #[test]
+    fn test_failure_kinds_in_reports() {
+        let module_id = ModuleId::new(AccountAddress::TWO, Identifier::new("tests").unwrap());
+        let module_plan = ModuleTestPlan {
+            module_id: module_id.clone(),
+            tests: BTreeMap::new(),
+        };
+        let test_run_info =
+            |name: &str| TestRunInfo::new(name.to_string(), Duration::from_millis(1));
+        let mut statistics = TestStatistics::new();
+        statistics.test_success(test_run_info("passes"), &module_plan);
+        for (name, failure_reason) in [
+            ("does_not_abort", FailureReason::no_error()),
+            ("times_out", FailureReason::timeout()),
+        ] {
+            statistics.test_failure(
+                TestFailure::new(failure_reason, test_run_info(name), None, None),
+                &module_plan,
+            );
+        }
+        let results = TestResults::new(statistics, TestPlan {
+            files: HashMap::new(),
+            module_tests: BTreeMap::from([(module_id, module_plan)]),
+            module_info: BTreeMap::new(),
+        });
+
+        // The failure type is the kind of the failure, not the status of the test.
+        let mut junit = vec![];
+        results.report_junit(&mut junit).unwrap();
+        let junit = String::from_utf8(junit).unwrap();
+        assert!(
+            junit.contains(r#"<failure message="Test did not error as expected" type="no_error">"#),
+            "{}",
+            junit
+        );
+        assert!(
+            junit.contains(r#"<failure message="Test timed out" type="timeout">"#),
+            "{}",
+            junit
+        );
+
+        let mut json = vec![];
+        results.report_json(&mut json).unwrap();
+        let reports = String::from_utf8(json)
+            .unwrap()
+            .lines()
+            .map(|line| {
+                let report: Value = serde_json::from_str(line).unwrap();
+                (
+                    report["function"].clone(),
+                    report["status"].clone(),
+                    report["failure_kind"].clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(reports, vec![
+            (json!("does_not_abort"), json!("failed"), json!("no_error")),
+            (json!("passes"), json!("passed"), Value::Null),
+            (json!("times_out"), json!("timeout"), json!("timeout")),
+        ]);
+    }
+}
@@ -4,6 +4,7 @@
 
 use crate::{
     extensions, format_module_id,
+    fuzz::{self, FuzzFailure, FuzzRng},
     test_reporter::{
         FailureReason, MoveError, TestFailure, TestResults, TestRunInfo, TestStatistics,
         UnitTestFactory,
@@ -15,7 +16,7 @@ use legacy_move_compiler::unit_test::{
     ExpectedFailure, ModuleTestPlan, NamedOrBytecodeModule, TestCase, TestPlan,
 };
 use move_binary_format::{
-    errors::{Location, VMResult},
+    errors::{Location, PartialVMError, VMError, VMResult},
     file_format::CompiledModule,
 };
 use move_bytecode_utils::Modules;
@@ -23,7 +24,7 @@ use move_core_types::{
     account_address::AccountAddress,
     effects::{ChangeSet, Op},
     identifier::IdentStr,
-    value::serialize_values,
+    value::{serialize_values, MoveValue},
     vm_status::StatusCode,
 };
 use move_resource_viewer::MoveValueAnnotator;
@@ -39,7 +40,12 @@ use move_vm_runtime::{
 };
 use move_vm_test_utils::InMemoryStorage;
 use rayon::prelude::*;
-use std::{io::Write, marker::Send, sync::Mutex, time::Instant};
+use std::{
+    io::Write,
+    marker::Send,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
 
 /// Test state common to all tests
 pub struct SharedTestingConfig {
@@ -49,6 +55,10 @@ pub struct SharedTestingConfig {
     #[allow(dead_code)] // used by some features
     source_files: Vec<String>,
     record_writeset: bool,
+    /// The number of inputs tests with generated parameters are run with.
+    fuzz_runs: u64,
+    /// The seed from which the inputs of tests with generated parameters are generated.
+    fuzz_seed: u64,
 }
 
 pub struct TestRunner {
@@ -118,6 +128,8 @@ impl TestRunner {
         native_function_table: Option<NativeFunctionTable>,
         genesis_state: Option<ChangeSet>,
         record_writeset: bool,
+        fuzz_runs: u64,
+        fuzz_seed: u64,
     ) -> Result<Self> {
         let native_function_table = native_function_table.unwrap_or_else(|| {
             move_stdlib::natives::all_natives(
@@ -148,6 +160,8 @@ impl TestRunner {
                 starting_storage_state,
                 source_files,
                 record_writeset,
+                fuzz_runs,
+                fuzz_seed,
             },
             num_threads,
             tests,
@@ -244,7 +258,7 @@ impl SharedTestingConfig {
         &self,
         test_plan: &ModuleTestPlan,
         function_name: &str,
-        test_info: &TestCase,
+        arguments: &[MoveValue],
         factory: &Mutex<F>,
     ) -> (
         VMResult<ChangeSet>,
@@ -255,7 +269,24 @@ impl SharedTestingConfig {
         let module_storage = self.starting_storage_state.as_unsync_module_storage();
 
         let mut extensions = extensions::new_extensions();
-        let mut gas_meter = factory.lock().unwrap().new_gas_meter();
+        let gas_meter = factory
+            .lock()
+            .unwrap()
+            .new_gas_meter_for_test(&test_plan.module_id, function_name);
+        let mut gas_meter = match gas_meter {
+            Ok(gas_meter) => gas_meter,
+            Err(err) => {
+                let err = PartialVMError::new(StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR)
+                    .with_message(format!("Failed to create the gas meter: {:#}", err))
+                    .finish(Location::Undefined);
+                return (
+                    Err(err.clone()),
+                    Err(err.clone()),
+                    Err(err),
+                    TestRunInfo::new(function_name.to_string(), Duration::ZERO),
+                );
+            },
+        };
         let traversal_storage = TraversalStorage::new();
         let mut traversal_context = TraversalContext::new(&traversal_storage);
         let mut data_cache = TransactionDataCache::empty();
@@ -275,7 +306,7 @@ impl SharedTestingConfig {
                     &[],
                 )
                 .and_then(|function| {
-                    let args = serialize_values(test_info.arguments.iter());
+                    let args = serialize_values(arguments);
                     MoveVM::execute_loaded_function(
                         function,
                         args,
@@ -326,6 +357,107 @@ impl SharedTestingConfig {
         }
     }
 
+    /// Checks the result of executing a test against its expected failure, returning the reason
+    /// and the error of the execution if the test failed.
+    fn check_test_result(
+        test_info: &TestCase,
+        exec_result: &VMResult<Vec<Vec<u8>>>,
+    ) -> Result<(), (FailureReason, Option<VMError>)> {
+        match exec_result {
+            Err(err) => {
+                let actual_err = MoveError(
+                    err.major_status(),
+                    err.sub_status(),
+                    err.location().clone(),
+                    err.message().cloned(),
+                );
+                assert!(err.major_status() != StatusCode::EXECUTED);
+                let failure_reason = match test_info.expected_failure.as_ref() {
+                    Some(ExpectedFailure::Expected) => return Ok(()),
+                    Some(ExpectedFailure::ExpectedWithError(expected_err))
+                        if expected_err == &actual_err =>
+                    {
+                        return Ok(())
+                    },
+                    Some(ExpectedFailure::ExpectedWithCodeDEPRECATED(code))
+                        if actual_err.0 == StatusCode::ABORTED
+                            && actual_err.1.is_some()
+                            && actual_err.1.unwrap() == *code =>
+                    {
+                        return Ok(())
+                    },
+                    // incorrect cases
+                    Some(ExpectedFailure::ExpectedWithError(expected_err)) => {
+                        FailureReason::wrong_error(expected_err.clone(), actual_err)
+                    },
+                    Some(ExpectedFailure::ExpectedWithCodeDEPRECATED(expected_code)) => {
+                        FailureReason::wrong_abort_deprecated(*expected_code, actual_err)
+                    },
+                    // Ran out of ticks, report a test timeout
+                    None if err.major_status() == StatusCode::OUT_OF_GAS => {
+                        FailureReason::timeout()
+                    },
+                    None => FailureReason::unexpected_error(actual_err),
+                };
+                Err((failure_reason, Some(err.clone())))
+            },
+            // Expected the test to fail, but it executed
+            Ok(_) if test_info.expected_failure.is_some() => Err((FailureReason::no_error(), None)),
+            // Expected the test to execute fully and it did
+            Ok(_) => Ok(()),
+        }
+    }
+
+    /// Runs a test with generated parameters for `fuzz_runs` generated inputs, until one of them
+    /// makes the test fail. Returns the arguments to report the test result for, which is either
+    /// the shrunk failing input or the last input, and the details of the failure, if any.
+    fn fuzz_test<F: UnitTestFactory>(
+        &self,
+        test_plan: &ModuleTestPlan,
+        function_name: &str,
+        test_info: &TestCase,
+        factory: &Mutex<F>,
+    ) -> (Vec<MoveValue>, Option<FuzzFailure>) {
+        let types: Vec<_> = test_info
+            .generated_parameters
+            .iter()
+            .map(|param| param.ty.clone())
+            .collect();
+        let fails = |input: &[MoveValue]| {
+            let arguments = test_info.arguments_with(input.to_vec());
+            let (_, _, exec_result, _) =
+                self.execute_via_move_vm(test_plan, function_name, &arguments, factory);
+            Self::check_test_result(test_info, &exec_result).is_err()
+        };
+
+        let mut rng = FuzzRng::for_test(self.fuzz_seed, &test_plan.module_id, function_name);
+        let mut input = vec![];
+        for run in 1..=self.fuzz_runs.max(1) {
+            input = types
+                .iter()
+                .map(|ty| fuzz::generate(&mut rng, ty))
+                .collect();
+            if fails(&input) {
+                let (input, shrinks) = fuzz::shrink(&types, input, &fails);
+                let fuzz_failure = FuzzFailure {
+                    seed: self.fuzz_seed,
+                    run,
+                    shrinks,
+                    input: test_info
+                        .generated_parameters
+                        .iter()
+                        .zip(&input)
+                        .map(|(param, value)| {
+                            (param.name.clone(), fuzz::display_value(&param.ty, value))
+                        })
+                        .collect(),
+                };
+                return (test_info.arguments_with(input), Some(fuzz_failure));
+            }
+        }
+        (test_info.arguments_with(input), None)
+    }
+
     fn exec_module_tests_move_vm_and_stackless_vm<F: UnitTestFactory>(
         &self,
         test_plan: &ModuleTestPlan,
@@ -335,8 +467,18 @@ impl SharedTestingConfig {
         let mut stats = TestStatistics::new();
 
         for (function_name, test_info) in &test_plan.tests {
-            let (cs_result, ext_result, exec_result, test_run_info) =
-                self.execute_via_move_vm(test_plan, function_name, test_info, factory);
+            let (arguments, fuzz_failure) = if test_info.generated_parameters.is_empty() {
+                (test_info.arguments.clone(), None)
+            } else {
+                self.fuzz_test(test_plan, function_name, test_info, factory)
+            };
+            let (cs_result, ext_result, exec_result, mut test_run_info) =
+                self.execute_via_move_vm(test_plan, function_name, &arguments, factory);
+            if let Err(err) = &exec_result {
+                if err.major_status() == StatusCode::ABORTED {
+                    test_run_info.abort_code = err.sub_status();
+                }
+            }
 
             if self.record_writeset {
                 stats.test_output(
@@ -363,107 +505,30 @@ impl SharedTestingConfig {
                 }
             };
 
-            match exec_result {
-                Err(err) => {
-                    let actual_err = MoveError(
-                        err.major_status(),
-                        err.sub_status(),
-                        err.location().clone(),
-                        err.message().cloned(),
-                    );
-                    assert!(err.major_status() != StatusCode::EXECUTED);
-                    match test_info.expected_failure.as_ref() {
-                        Some(ExpectedFailure::Expected) => {
-                            output.pass(function_name);
-                            stats.test_success(test_run_info, test_plan);
-                        },
-                        Some(ExpectedFailure::ExpectedWithError(expected_err))
-                            if expected_err == &actual_err =>
-                        {
-                            output.pass(function_name);
-                            stats.test_success(test_run_info, test_plan);
-                        },
-                        Some(ExpectedFailure::ExpectedWithCodeDEPRECATED(code))
-                            if actual_err.0 == StatusCode::ABORTED
-                                && actual_err.1.is_some()
-                                && actual_err.1.unwrap() == *code =>
-                        {
-                            output.pass(function_name);
-                            stats.test_success(test_run_info, test_plan);
-                        },
-                        // incorrect cases
-                        Some(ExpectedFailure::ExpectedWithError(expected_err)) => {
-                            output.fail(function_name);
-                            stats.test_failure(
-                                TestFailure::new(
-                                    FailureReason::wrong_error(expected_err.clone(), actual_err),
-                                    test_run_info,
-                                    Some(err),
-                                    save_session_state(),
-                                ),
-                                test_plan,
-                            )
-                        },
-                        Some(ExpectedFailure::ExpectedWithCodeDEPRECATED(expected_code)) => {
-                            output.fail(function_name);
-                            stats.test_failure(
-                                TestFailure::new(
-                                    FailureReason::wrong_abort_deprecated(
-                                        *expected_code,
-                                        actual_err,
-                                    ),
-                                    test_run_info,
-                                    Some(err),
-                                    save_session_state(),
-                                ),
-                                test_plan,
-                            )
-                        },
-                        None if err.major_status() == StatusCode::OUT_OF_GAS => {
-                            // Ran out of ticks, report a test timeout and log a test failure
-                            output.timeout(function_name);
-                            stats.test_failure(
-                                TestFailure::new(
-                                    FailureReason::timeout(),
-                                    test_run_info,
-                                    Some(err),
-                                    save_session_state(),
-                                ),
-                                test_plan,
-                            )
-                        },
-                        None => {
-                            output.fail(function_name);
-                            stats.test_failure(
-                                TestFailure::new(
-                                    FailureReason::unexpected_error(actual_err),
-                                    test_run_info,
-                                    Some(err),
-                                    save_session_state(),
-                                ),
-                                test_plan,
-                            )
-                        },
-                    }
+            match Self::check_test_result(test_info, &exec_result) {
+                Ok(()) => {
+                    output.pass(function_name);
+                    stats.test_success(test_run_info, test_plan);
                 },
-                Ok(_) => {
-                    // Expected the test to fail, but it executed
-                    if test_info.expected_failure.is_some() {
-                        output.fail(function_name);
-                        stats.test_failure(
-                            TestFailure::new(
-                                FailureReason::no_error(),
-                                test_run_info,
-                                None,
-                                save_session_state(),
-                            ),
-                            test_plan,
-                        )
+                Err((failure_reason, vm_error)) => {
+                    if let FailureReason::Timeout(_) = failure_reason {
+                        output.timeout(function_name);
                     } else {
-                        // Expected the test to execute fully and it did
-                        output.pass(function_name);
-                        stats.test_success(test_run_info, test_plan);
+                        output.fail(function_name);
                     }
+                    let failure = TestFailure::new(
+                        failure_reason,
+                        test_run_info,
+                        vm_error,
+                        save_session_state(),
+                    );
+                    stats.test_failure(
+                        match fuzz_failure {
+                            Some(fuzz_failure) => failure.with_fuzz_failure(fuzz_failure),
+                            None => failure,
+                        },
+                        test_plan,
+                    )
                 },
             }
         }
@@ -95,6 +95,8 @@ fn run_test_impl(path: &Path) -> anyhow::Result<()> {
             .into_iter()
             .collect(),
         verbose: true,
+        // Use a fixed seed so that the inputs of fuzzed tests are the same in every run
+        fuzz_seed: Some(42),
 
         ..UnitTestingConfig::default()
     };
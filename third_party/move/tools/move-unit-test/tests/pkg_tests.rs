@@ -20,6 +20,14 @@ where
 }
 
 fn run_tests_for_pkg(path_to_pkg: impl Into<String>, v2: bool) {
+    run_tests_for_pkg_with_config(path_to_pkg, v2, UnitTestingConfig::default())
+}
+
+fn run_tests_for_pkg_with_config(
+    path_to_pkg: impl Into<String>,
+    v2: bool,
+    unit_test_config: UnitTestingConfig,
+) {
     let pkg_path = path_in_crate(path_to_pkg);
 
     let natives = all_natives(
@@ -42,7 +50,7 @@ fn run_tests_for_pkg(path_to_pkg: impl Into<String>, v2: bool) {
             },
             ..Default::default()
         },
-        UnitTestingConfig::default(),
+        unit_test_config,
         natives,
         ChangeSet::new(),
         /* gas_limit */ Some(100_000),
@@ -61,3 +69,31 @@ fn one_bytecode_dep() {
     run_tests_for_pkg("tests/packages/one-bytecode-dep", true);
     run_tests_for_pkg("tests/packages/one-bytecode-dep", false);
 }
+
+#[test]
+fn junit_and_json_reports() {
+    let report_dir = tempdir().unwrap();
+    let junit_report = report_dir.path().join("report.xml");
+    let json_report = report_dir.path().join("report.jsonl");
+    run_tests_for_pkg_with_config("tests/packages/one-bytecode-dep", true, UnitTestingConfig {
+        junit_report: Some(junit_report.clone()),
+        json_report: Some(json_report.clone()),
+        ..UnitTestingConfig::default()
+    });
+
+    let junit = std::fs::read_to_string(junit_report).unwrap();
+    assert!(junit.contains(r#"<testsuites name="Move unit tests" tests="1" failures="0""#));
+    assert!(junit.contains(r#"<testsuite name="0x42::test" tests="1" failures="0""#));
+    assert!(junit.contains(r#"<testcase name="test" classname="0x42::test""#));
+
+    let json = std::fs::read_to_string(json_report).unwrap();
+    let reports: Vec<serde_json::Value> = json
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(reports.len(), 1);
+    assert_eq!(reports[0]["name"], "0x42::test::test");
+    assert_eq!(reports[0]["module"], "0x42::test");
+    assert_eq!(reports[0]["status"], "passed");
+    assert!(reports[0]["failure"].is_null());
+}
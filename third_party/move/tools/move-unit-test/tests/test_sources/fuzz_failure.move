@@ -0,0 +1,7 @@
+module 0x1::M {
+    #[test]
+    #[fuzz]
+    fun below_limit(x: u64) {
+        if (x >= 1000) abort 0
+    }
+}
@@ -0,0 +1,60 @@
+module 0x1::M {
+    use std::option::{Self, Option};
+    use std::signer;
+    use std::string::{Self, String};
+    use std::vector;
+
+    struct Point has drop { x: u8, y: u8 }
+
+    #[test]
+    #[fuzz]
+    fun add_commutes(a: u64, b: u64) {
+        let (a, b) = (a / 2, b / 2);
+        assert!(a + b == b + a, 0);
+    }
+
+    #[test]
+    #[fuzz]
+    #[expected_failure(arithmetic_error, location = Self)]
+    fun always_overflows(x: u8) {
+        (x | 1) + 255;
+    }
+
+    #[test(s = @0x42)]
+    #[fuzz]
+    fun assigned_and_generated(s: signer, flag: bool, a: address) {
+        assert!(signer::address_of(&s) == @0x42, 0);
+        assert!(flag || !flag, 1);
+        assert!(a == @0x0 || a != @0x0, 2);
+    }
+
+    #[test]
+    #[fuzz]
+    fun option_has_at_most_one_element(o: Option<u128>) {
+        if (option::is_some(&o)) {
+            option::destroy_some(o);
+        }
+    }
+
+    #[test]
+    #[fuzz]
+    fun reverse_twice(v: vector<u16>) {
+        let w = copy v;
+        vector::reverse(&mut w);
+        vector::reverse(&mut w);
+        assert!(w == v, 0);
+    }
+
+    #[test]
+    #[fuzz]
+    fun string_is_utf8(s: String) {
+        assert!(string::utf8(*string::bytes(&s)) == s, 0);
+    }
+
+    #[test]
+    #[fuzz]
+    fun struct_fields(p: Point, ps: vector<Point>) {
+        assert!((p.x as u16) + (p.y as u16) <= 510, 0);
+        assert!(vector::length(&ps) <= 16, 1);
+    }
+}